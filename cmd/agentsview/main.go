@@ -1,16 +1,23 @@
 package main
 
 import (
+	"context"
 	"encoding/base64"
+	"encoding/json"
 	"errors"
 	"flag"
 	"fmt"
+	"io"
 	"log"
+	"net"
 	"net/http"
 	"os"
 	"os/exec"
+	"os/signal"
 	"path/filepath"
 	"runtime"
+	"strconv"
+	"syscall"
 	"time"
 	_ "time/tzdata"
 
@@ -35,6 +42,10 @@ const (
 	browserPollAttempts   = 60
 )
 
+// shutdownTimeout bounds how long open requests get to finish
+// once the server is asked to stop.
+const shutdownTimeout = 2 * time.Second
+
 func main() {
 	if len(os.Args) > 1 {
 		switch os.Args[1] {
@@ -79,6 +90,7 @@ Server flags:
   -host string        Host to bind to (default "127.0.0.1")
   -port int           Port to listen on (default 8080)
   -no-browser         Don't open browser on startup
+  -socket string      Serve on this Unix socket instead of host:port
 
 Prune flags:
   -project string     Sessions whose project contains this substring
@@ -179,11 +191,34 @@ func runServe(args []string) {
 		go startUnwatchedPoll(engine)
 	}
 
-	port := server.FindAvailablePort(cfg.Host, cfg.Port)
-	if port != cfg.Port {
-		fmt.Printf("Port %d in use, using %d\n", cfg.Port, port)
+	// Bind before announcing the address, so the ready event
+	// means the port already accepts connections. The listener is
+	// kept open from here on, so no other process can take the
+	// port between choosing and serving it.
+	var (
+		ln  net.Listener
+		err error
+	)
+	if cfg.Socket != "" {
+		// Host and Port are still what clients put in the Host
+		// header, via the desktop app's proxy.
+		ln, err = server.ListenSocket(cfg.Socket)
+		if err != nil {
+			fatal("listening on %s: %v", cfg.Socket, err)
+		}
+		defer os.Remove(cfg.Socket)
+	} else {
+		var port int
+		ln, port, err = server.ListenAvailable(cfg.Host, cfg.Port)
+		if err != nil {
+			addr := net.JoinHostPort(cfg.Host, strconv.Itoa(cfg.Port))
+			fatal("listening on %s: %v", addr, err)
+		}
+		if cfg.Port != 0 && port != cfg.Port {
+			fmt.Printf("Port %d in use, using %d\n", cfg.Port, port)
+		}
+		cfg.Port = port
 	}
-	cfg.Port = port
 
 	srv := server.New(cfg, database, engine,
 		server.WithVersion(server.VersionInfo{
@@ -193,7 +228,13 @@ func runServe(args []string) {
 		}),
 	)
 
-	url := fmt.Sprintf("http://%s:%d", cfg.Host, cfg.Port)
+	addr := net.JoinHostPort(cfg.Host, strconv.Itoa(cfg.Port))
+	url := "http://" + addr
+	if os.Getenv(readyEventEnv) != "" {
+		if err := writeReadyEvent(os.Stdout, cfg.Host, cfg.Port); err != nil {
+			log.Printf("writing ready event: %v", err)
+		}
+	}
 	fmt.Printf(
 		"agentsview %s listening at %s (started in %s)\n",
 		version, url,
@@ -204,10 +245,60 @@ func runServe(args []string) {
 		go openBrowser(url)
 	}
 
-	addr := fmt.Sprintf("%s:%d", cfg.Host, cfg.Port)
-	if err := http.ListenAndServe(addr, srv.Handler()); err != nil {
+	httpSrv := &http.Server{Addr: addr, Handler: srv.Handler()}
+	ctx, stop := signal.NotifyContext(
+		context.Background(), os.Interrupt, syscall.SIGTERM,
+	)
+	defer stop()
+	serveErr := make(chan error, 1)
+	go func() { serveErr <- httpSrv.Serve(ln) }()
+	select {
+	case err := <-serveErr:
 		fatal("server error: %v", err)
+	case <-ctx.Done():
 	}
+
+	// Stop taking requests, then let any sync in progress finish
+	// so the database is closed between writes.
+	fmt.Println("Shutting down...")
+	shutdownCtx, cancel := context.WithTimeout(
+		context.Background(), shutdownTimeout,
+	)
+	defer cancel()
+	if err := httpSrv.Shutdown(shutdownCtx); err != nil {
+		log.Printf("server shutdown: %v", err)
+	}
+	stopWatcher()
+	engine.Stop()
+}
+
+// readyEventEnv asks serve to announce its address as a JSON line
+// on stdout once it accepts connections, for the desktop app to
+// read instead of scraping the human-readable "listening at" line
+// and polling the port.
+const readyEventEnv = "AGENT_VIEWER_READY_EVENT"
+
+// readyEvent is the startup handshake printed when readyEventEnv
+// is set.
+type readyEvent struct {
+	Event   string `json:"event"`
+	Host    string `json:"host"`
+	Port    int    `json:"port"`
+	Version string `json:"version"`
+}
+
+func writeReadyEvent(w io.Writer, host string, port int) error {
+	line, err := json.Marshal(readyEvent{
+		Event:   "ready",
+		Host:    host,
+		Port:    port,
+		Version: version,
+	})
+	if err != nil {
+		return err
+	}
+	_, err = fmt.Fprintf(w, "%s\n", line)
+	return err
 }
 
 func mustLoadConfig(args []string) config.Config {
@@ -238,7 +329,7 @@ func mustLoadConfig(args []string) config.Config {
 const maxLogSize = 10 * 1024 * 1024 // 10 MB
 
 func setupLogFile(dataDir string) {
-	logPath := filepath.Join(dataDir, "debug.log")
+	logPath := logFilePath(dataDir)
 	truncateLogFile(logPath, maxLogSize)
 	f, err := os.OpenFile(
 		logPath, os.O_CREATE|os.O_WRONLY|os.O_APPEND, 0o644,
@@ -250,6 +341,20 @@ func setupLogFile(dataDir string) {
 	log.SetOutput(f)
 }
 
+// logFilePath returns AGENT_VIEWER_LOG_FILE when set, so users
+// sharing a data directory (e.g. the desktop app on a shared
+// workstation) keep separate logs, and debug.log in the data
+// directory otherwise.
+func logFilePath(dataDir string) string {
+	if v := os.Getenv("AGENT_VIEWER_LOG_FILE"); v != "" {
+		if err := os.MkdirAll(filepath.Dir(v), 0o755); err != nil {
+			log.Printf("warning: cannot create log directory: %v", err)
+		}
+		return v
+	}
+	return filepath.Join(dataDir, "debug.log")
+}
+
 // truncateLogFile truncates the log file if it exceeds limit
 // bytes. Symlinks are skipped to avoid truncating unrelated
 // files. Errors are silently ignored since logging is
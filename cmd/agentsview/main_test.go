@@ -69,6 +69,18 @@ func TestMustLoadConfig(t *testing.T) {
 	}
 }
 
+func TestWriteReadyEvent(t *testing.T) {
+	var buf bytes.Buffer
+	if err := writeReadyEvent(&buf, "127.0.0.1", 8081); err != nil {
+		t.Fatalf("writeReadyEvent: %v", err)
+	}
+	want := `{"event":"ready","host":"127.0.0.1","port":8081,` +
+		`"version":"dev"}` + "\n"
+	if got := buf.String(); got != want {
+		t.Errorf("ready event = %q, want %q", got, want)
+	}
+}
+
 func TestSetupLogFile(t *testing.T) {
 	origOutput := log.Writer()
 
@@ -100,6 +112,23 @@ func TestSetupLogFile(t *testing.T) {
 	}
 }
 
+func TestLogFilePath(t *testing.T) {
+	dir := t.TempDir()
+	t.Setenv("AGENT_VIEWER_LOG_FILE", "")
+	if got, want := logFilePath(dir), filepath.Join(dir, "debug.log"); got != want {
+		t.Errorf("logFilePath() = %q, want %q", got, want)
+	}
+
+	custom := filepath.Join(dir, "logs", "uid-501", "debug.log")
+	t.Setenv("AGENT_VIEWER_LOG_FILE", custom)
+	if got := logFilePath(dir); got != custom {
+		t.Errorf("logFilePath() = %q, want %q", got, custom)
+	}
+	if _, err := os.Stat(filepath.Dir(custom)); err != nil {
+		t.Errorf("log directory not created: %v", err)
+	}
+}
+
 func TestSetupLogFileOpenFailure(t *testing.T) {
 	origOutput := log.Writer()
 	t.Cleanup(func() { log.SetOutput(origOutput) })
@@ -27,6 +27,7 @@ assert_fails() {
 assert_eq "$(map_go_target aarch64-apple-darwin)" "darwin arm64" "map darwin arm64"
 assert_eq "$(map_go_target x86_64-apple-darwin)" "darwin amd64" "map darwin amd64"
 assert_eq "$(map_go_target x86_64-pc-windows-msvc)" "windows amd64" "map windows amd64"
+assert_eq "$(map_go_target aarch64-pc-windows-msvc)" "windows arm64" "map windows arm64"
 assert_eq "$(map_go_target x86_64-unknown-linux-gnu)" "linux amd64" "map linux amd64"
 assert_fails "unsupported triple rejected" map_go_target "weird-target"
 
@@ -1,3 +1,92 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+// APP_COMMANDS are the shell's commands. Listing them makes Tauri
+// generate an allow-<command> permission for each and refuse any
+// command a window's capabilities do not grant, so the backend's
+// web UI only reaches the commands capabilities/backend.json lists.
+// Keep this in sync with the invoke handler in src/lib.rs.
+const APP_COMMANDS: &[&str] = &[
+    "acknowledge_cost_alerts",
+    "add_external_url_rule",
+    "annotate_close",
+    "annotate_submit",
+    "annotate_target",
+    "backend_output",
+    "capability_status",
+    "change_zoom",
+    "connect_remote",
+    "connection_picker",
+    "copy_api_url",
+    "cost_alerts",
+    "external_url_lists",
+    "get_env_overrides",
+    "invoke_desktop_action",
+    "list_desktop_actions",
+    "load_ui_state",
+    "locale_info",
+    "localize",
+    "locate_sidecar",
+    "managed_policy",
+    "missing_sidecar",
+    "open_path",
+    "open_session_window",
+    "palette_close",
+    "palette_open",
+    "palette_search",
+    "reload_environment",
+    "remove_env_override",
+    "remove_external_url_rule",
+    "request_capability",
+    "reset_capability",
+    "restart_backend",
+    "retry_sidecar",
+    "run_doctor",
+    "save_ui_state",
+    "set_always_on_top",
+    "set_cost_limits",
+    "set_env_override",
+    "set_presentation_mode",
+    "sidecar_environment",
+    "sidecar_resources",
+    "snooze_cost_alerts",
+    "toggle_presentation_mode",
+];
+
 fn main() {
-    tauri_build::build()
+    embed_sidecar_manifest();
+    tauri_build::try_build(
+        tauri_build::Attributes::new()
+            .app_manifest(tauri_build::AppManifest::new().commands(APP_COMMANDS)),
+    )
+    .expect("failed to run tauri-build");
+}
+
+// embed_sidecar_manifest records the SHA-256 and size of the
+// sidecar prepare-sidecar built, which the shell checks before
+// spawning it (see src/integrity.rs). Builds that sign the sidecar
+// while bundling change its bytes, so they embed nothing and rely
+// on the platform's signature check instead: signed macOS builds,
+// and any build with AGENTSVIEW_SIDECAR_SIGNED set.
+fn embed_sidecar_manifest() {
+    let target = env::var("TARGET").unwrap_or_default();
+    let ext = if target.contains("windows") {
+        ".exe"
+    } else {
+        ""
+    };
+    let path = Path::new("binaries").join(format!("agentsview-{target}{ext}"));
+    println!("cargo:rerun-if-changed={}", path.display());
+    println!("cargo:rerun-if-env-changed=APPLE_SIGNING_IDENTITY");
+    println!("cargo:rerun-if-env-changed=AGENTSVIEW_SIDECAR_SIGNED");
+    let signed = env::var_os("AGENTSVIEW_SIDECAR_SIGNED").is_some()
+        || (target.contains("apple") && env::var_os("APPLE_SIGNING_IDENTITY").is_some());
+    let manifest = match fs::read(&path) {
+        Ok(bytes) if !signed => format!("{:x}:{}", Sha256::digest(&bytes), bytes.len()),
+        _ => String::new(),
+    };
+    println!("cargo:rustc-env=AGENTSVIEW_SIDECAR_MANIFEST={manifest}");
 }
@@ -0,0 +1,266 @@
+# Shell-owned strings for AgentsView Desktop. Keys follow Fluent
+# syntax; `{ $name }` placeables are filled in by the shell.
+
+## Splash screen
+
+splash-heading = Preparing your workspace
+splash-status-starting = Starting local backend and loading the interface.
+splash-stage-launch = Launching local service
+splash-stage-database = Connecting to session database
+splash-stage-shell = Opening app shell
+splash-foot = Everything stays local to this machine.
+
+## Remote connection picker
+
+connect-heading = Connect to AgentsView
+connect-status = Enter the address of an agentsview server on your network.
+connect-label = Server address
+connect-button = Connect
+connect-foot = Sessions stay on the machine running agentsview serve.
+connect-status-connecting = Connecting to { $url }...
+
+## Instance lock
+
+instance-running-title = AgentsView is already running
+instance-running = AgentsView is already running (process { $pid }).
+instance-running-port = Its backend is serving { $url }.
+instance-running-switch = Switch to the open AgentsView window instead of starting another copy.
+
+## Missing backend binary
+
+sidecar-missing-title = AgentsView backend not found
+sidecar-missing-intro = AgentsView could not start its bundled backend ({ $reason }).
+sidecar-missing-checked = Locations checked:
+sidecar-missing-platform = Platform: { $platform }
+sidecar-missing-fixes = Try this:
+sidecar-fix-damaged = { $path } exists but could not be run. It may be damaged or built for another platform.
+sidecar-fix-chmod = { $path } is not executable. Run chmod +x on it, or reinstall AgentsView.
+sidecar-fix-macos = If macOS blocked the binary, allow agentsview in System Settings > Privacy & Security, then retry.
+sidecar-fix-windows = Security software may have quarantined agentsview.exe. Check Windows Security > Protection history, restore it, then retry.
+sidecar-fix-reinstall = Reinstall AgentsView from the latest release.
+sidecar-fix-locate = Or locate an agentsview binary yourself, such as one from go install. The choice is remembered for future launches.
+sidecar-status-missing = missing
+sidecar-status-not-executable = not executable
+sidecar-status-found = found
+sidecar-locate-button = Locate Binary...
+sidecar-locate-title = Locate the agentsview binary
+
+## Startup failures
+
+error-sidecar-spawn = AgentsView could not start its local backend.
+error-sidecar-integrity = AgentsView did not start its backend because the bundled agentsview binary has been modified or is incomplete.
+error-port-unavailable-any = AgentsView could not open a local port.
+error-port-unavailable = AgentsView could not use local port { $port }.
+error-env-probe = AgentsView could not read your shell environment.
+error-readiness = AgentsView backend did not start within { $seconds } seconds.
+error-sidecar-exited = AgentsView backend exited before startup completed.
+error-remote-connect = AgentsView could not connect to { $url }.
+error-backend-request = AgentsView could not get a response from its backend.
+error-backend-unsupported = This AgentsView backend does not support this feature.
+error-action-unavailable = This action is not available: { $reason }
+error-path-not-allowed = AgentsView only opens files in the session's project or agent log folders.
+error-connection-blocked = The AgentsView backend started on port { $port }, but AgentsView cannot connect to it.
+error-internal = AgentsView hit an internal error while starting.
+
+hint-sidecar-spawn = Reinstall AgentsView, or check that security software has not quarantined the bundled agentsview binary.
+hint-sidecar-integrity = Reinstall AgentsView. If you replaced the binary yourself, set AGENTSVIEW_DESKTOP_SKIP_SIDECAR_CHECK=1.
+hint-port-unavailable = Close other programs using the port (including another agentsview serve) and relaunch.
+hint-env-probe = Check your shell startup files, or set AGENTSVIEW_DESKTOP_SKIP_LOGIN_SHELL_ENV=1 and use ~/.agentsview/desktop.env.
+hint-readiness = Large session archives can take a while to index. Relaunch, or run agentsview serve in a terminal to see its output.
+hint-sidecar-exited = Run agentsview serve in a terminal to see why the backend stopped.
+hint-remote-connect = Check that agentsview serve is running on that machine with -host set to an address this device can reach.
+hint-backend-request = Wait for the backend to finish starting. If this keeps happening, relaunch AgentsView.
+hint-backend-unsupported = Update agentsview on the machine running the backend, then try again.
+hint-action-unavailable = Pick another action, or check that the local backend is running.
+hint-path-not-allowed = Open the file from your file manager or editor instead.
+hint-connection-blocked-windows = Windows Defender Firewall or endpoint protection may be blocking local connections. Choose Allow access if a firewall prompt is open, or allow agentsview.exe in Windows Security > Firewall & network protection > Allow an app through firewall. On managed PCs, ask IT to allow agentsview on 127.0.0.1.
+hint-connection-blocked-macos = A firewall such as Little Snitch or LuLu, or endpoint protection, may be blocking local connections. Allow AgentsView and agentsview to connect to 127.0.0.1 in its rules. On managed Macs, ask IT to allow agentsview.
+hint-connection-blocked-linux = A firewall rule or endpoint protection may be blocking the loopback interface. Check that your iptables or nftables rules accept traffic on lo, or ask IT to allow agentsview on 127.0.0.1.
+hint-internal = Relaunch AgentsView. If this keeps happening, please report an issue.
+
+startup-failed-title = AgentsView could not start
+dialog-retry = Retry
+dialog-retest = Re-test
+dialog-open-logs = Open Logs
+dialog-report-issue = Report Issue
+
+## Capability explanations
+
+capability-allow = Allow
+capability-deny = Not Now
+capability-notifications-title = Allow AgentsView notifications?
+capability-notifications-explanation = AgentsView can notify you when an agent is waiting for your input or a usage alert fires. Your system will ask for notification permission next. Notification text is built on this machine and never leaves it.
+capability-autostart-title = Start AgentsView at login?
+capability-autostart-explanation = AgentsView can add itself to your login items so sessions are indexed before you open it. You can turn this off at any time in AgentsView or your system settings.
+capability-global-shortcuts-title = Allow AgentsView keyboard shortcuts?
+capability-global-shortcuts-explanation = AgentsView can register system-wide shortcuts that work while another app is focused. A registered shortcut is unavailable to other apps until AgentsView releases it.
+
+## Quick-switcher palette
+
+palette-placeholder = Search sessions, projects, or dates (YYYY-MM-DD)
+palette-empty = No matches
+palette-kind-session = Session
+palette-kind-project = Project
+palette-kind-date = Date
+palette-project-sessions = Sessions: { $count }
+
+## Session annotation
+
+annotate-target = Note on the live { $project } session
+annotate-placeholder = What did you notice?
+annotate-no-session = No session has been active in the last 10 minutes.
+annotate-hint = Enter saves, Shift+Enter adds a line, Esc cancels.
+
+## Tray
+
+tray-turn-running = { $project }: turn running for { $elapsed }
+tray-clean-restart = Restart Backend with Clean Environment
+tray-refresh-environment = Reload Environment & Restart Backend
+tray-copy-api-url = Copy API URL & curl Example
+tray-always-on-top = Keep Window on Top
+tray-software-rendering = Relaunch with Software Rendering
+tray-hardware-rendering = Relaunch with Hardware Acceleration
+tray-connect = Connect to…
+tray-connect-none = No servers found on the LAN
+
+## Notifications
+
+notify-waiting-title = Agent is waiting for you
+notify-waiting-body = The { $project } session needs your input.
+weekly-summary-title = Your week in AgentsView
+weekly-summary-totals = Sessions: { $sessions } · Messages: { $messages } · Active days: { $days }
+weekly-summary-top = Top projects: { $projects }
+
+## Cost alerts
+
+cost-alert-title = Spending limit reached
+cost-alert-daily = Today's spending is { $spent }, over your { $limit } limit.
+cost-alert-weekly = This week's spending is { $spent }, over your { $limit } limit.
+tray-cost-acknowledge = Acknowledge cost alert
+tray-cost-snooze = Snooze cost alerts for an hour
+
+## Diagnostics
+
+doctor-button = Run diagnostics
+doctor-running = Running diagnostics...
+doctor-sidecar = Backend binary
+doctor-sidecar-found = Found at { $path }
+doctor-sidecar-not-executable = { $path } is not executable
+doctor-sidecar-missing = Not found in any of the { $count } searched locations
+doctor-port = Backend port
+doctor-port-listening = The backend is listening on port { $port }
+doctor-port-free = Port { $port } is available
+doctor-port-busy = Port { $port } is in use; the backend will pick another one
+doctor-data-dir = Data directory
+doctor-data-dir-writable = { $path } is writable
+doctor-data-dir-unwritable = { $path } is not writable: { $error }
+doctor-data-dir-unknown = Could not find your home directory
+doctor-agents = Agent CLIs
+doctor-agents-found = Found on PATH: { $found }
+doctor-agents-partial = Found: { $found }. Not on PATH: { $missing }
+doctor-agents-none = None on PATH: { $missing }
+doctor-webview = Webview runtime
+doctor-webview-version = Version { $version }
+doctor-webview-error = Could not read the version: { $error }
+doctor-clock = System clock
+doctor-clock-ok = { $time }
+doctor-clock-wrong = { $time } looks wrong; check the date and time settings
+doctor-arch = Architecture
+doctor-arch-native = Running natively on { $native }
+doctor-arch-emulated = An { $machine } build is running under emulation on { $native }; install the { $native } build of AgentsView
+doctor-arch-webview = The WebView2 runtime is { $machine } but Windows is { $native }; reinstall the WebView2 runtime
+
+## Desktop actions
+
+desktop-action-open-in-editor = Open Transcript in Editor
+desktop-action-reveal-file = Reveal Transcript File
+desktop-action-resume-session = Resume Session in Terminal
+desktop-action-export-pdf = Export as PDF
+desktop-action-open-in-new-window = Open Session in New Window
+desktop-action-restart-backend = Restart Backend
+desktop-action-restart-backend-clean = Restart Backend with Clean Environment
+desktop-action-refresh-environment = Reload Environment & Restart Backend
+desktop-action-unknown = this version of AgentsView does not have it
+desktop-action-needs-session = open a session first
+desktop-action-needs-local = it needs the local backend, not a remote server
+desktop-action-no-terminal = no terminal emulator was found
+desktop-action-no-transcript = the session has no transcript file
+desktop-action-unsupported-agent = the session's agent cannot resume sessions from the command line
+desktop-action-unsafe-session-id = the session id has characters a terminal command line cannot safely carry
+desktop-action-superseded = another restart replaced this one
+desktop-action-kiosk = kiosk mode shows a single window
+
+## Rendering
+
+rendering-trial-title = Software rendering
+rendering-trial-message = AgentsView started in safe mode with hardware acceleration turned off. If the window now displays correctly, keep software rendering for future launches?
+rendering-trial-keep = Keep
+rendering-trial-discard = Not Now
+
+## Architecture
+
+arch-emulated-title = AgentsView is running under emulation
+arch-emulated-message = This is the { $machine } build of AgentsView, running under emulation on an ARM64 PC. The ARM64 build starts faster and uses less battery.
+arch-emulated-download = Download ARM64 Build
+arch-emulated-continue = Continue
+webview2-missing-title = WebView2 runtime required
+webview2-missing-message = AgentsView needs the Microsoft Edge WebView2 runtime ({ $machine }), which is not installed. Open the download page now?
+webview2-missing-offline = AgentsView needs the Microsoft Edge WebView2 runtime ({ $machine }), which is not installed. Ask your administrator to install it.
+
+## Backend health
+
+backend-unresponsive-title = AgentsView backend is not responding
+backend-unresponsive-message = The local backend stopped answering health checks, so the window may stop updating. Restarting it usually fixes this; the page reloads once it is back.
+backend-unresponsive-restart = Restart Backend
+backend-unresponsive-wait = Keep Waiting
+
+## Backend crash
+
+backend-crash-title = AgentsView backend stopped
+backend-crash-startup = The AgentsView backend exited before startup completed ({ $status }).
+backend-crash-running = The AgentsView backend stopped unexpectedly ({ $status }).
+backend-crash-status-code = exit code { $code }
+backend-crash-status-signal = signal { $signal }
+backend-crash-status-unknown = unknown exit status
+backend-crash-output = Last backend output:
+backend-crash-no-output = The backend wrote nothing to stderr. Run agentsview serve in a terminal to see why it stopped.
+backend-crash-restart = Restart
+backend-crash-quit = Quit
+
+## Backend resources
+
+memory-alert-title = AgentsView backend is using a lot of memory
+memory-alert-message = The local backend is using { $used } MB of memory, over the { $limit } MB limit. Restarting it frees the memory; the page reloads once it is back.
+memory-alert-restart = Restart Backend
+memory-alert-ignore = Ignore
+
+## Backend version
+
+backend-version-title = AgentsView backend version mismatch
+backend-version-old = The AgentsView backend is version { $backend }, older than this app supports ({ $min } or later). Some panels may stay empty or fail to load.
+backend-version-new = The AgentsView backend is version { $backend }, newer than this app supports (before { $max }). Some panels may stay empty or fail to load.
+backend-version-advice = This usually follows a partial update. Install the latest AgentsView so the app and its backend match, or update agentsview if you run agentsview serve yourself.
+backend-version-download = Download AgentsView
+backend-version-continue = Continue
+
+## Port conflict
+
+port-conflict-title = Backend port in use
+port-conflict-owner = AgentsView could not start its backend because port { $port } is in use by { $name } (process { $pid }).
+port-conflict-unknown = AgentsView could not start its backend because port { $port } is in use by another program.
+port-conflict-advice = Start the backend on another port, or close that program and retry.
+port-conflict-stale = This looks like an agentsview backend left running by an earlier session. Stop it to free the port, or start the backend on another port.
+port-conflict-stop = Stop agentsview
+port-conflict-other-port = Use Another Port
+port-conflict-cancel = Cancel
+
+## Connectivity
+
+connectivity-reconnecting = Lost connection to the AgentsView backend. Reconnecting…
+
+## Downloads
+
+download-saved-title = Download complete
+download-saved-body = Saved { $name } to { $folder }.
+download-failed-title = Download failed
+download-failed-body = AgentsView could not save { $name }.
@@ -0,0 +1,265 @@
+# Textos del shell de AgentsView Desktop en español.
+
+## Splash screen
+
+splash-heading = Preparando tu espacio de trabajo
+splash-status-starting = Iniciando el backend local y cargando la interfaz.
+splash-stage-launch = Iniciando el servicio local
+splash-stage-database = Conectando con la base de datos de sesiones
+splash-stage-shell = Abriendo la aplicación
+splash-foot = Todo se queda en este equipo.
+
+## Remote connection picker
+
+connect-heading = Conectar con AgentsView
+connect-status = Introduce la dirección de un servidor agentsview de tu red.
+connect-label = Dirección del servidor
+connect-button = Conectar
+connect-foot = Las sesiones se quedan en el equipo que ejecuta agentsview serve.
+connect-status-connecting = Conectando con { $url }...
+
+## Instance lock
+
+instance-running-title = AgentsView ya se está ejecutando
+instance-running = AgentsView ya se está ejecutando (proceso { $pid }).
+instance-running-port = Su backend está disponible en { $url }.
+instance-running-switch = Cambia a la ventana de AgentsView abierta en lugar de iniciar otra copia.
+
+## Missing backend binary
+
+sidecar-missing-title = No se encontró el backend de AgentsView
+sidecar-missing-intro = AgentsView no pudo iniciar su backend incluido ({ $reason }).
+sidecar-missing-checked = Ubicaciones revisadas:
+sidecar-missing-platform = Plataforma: { $platform }
+sidecar-missing-fixes = Prueba lo siguiente:
+sidecar-fix-damaged = { $path } existe pero no se pudo ejecutar. Puede estar dañado o compilado para otra plataforma.
+sidecar-fix-chmod = { $path } no es ejecutable. Ejecuta chmod +x sobre él o reinstala AgentsView.
+sidecar-fix-macos = Si macOS bloqueó el binario, permite agentsview en Ajustes del Sistema > Privacidad y seguridad y vuelve a intentarlo.
+sidecar-fix-windows = Puede que un software de seguridad haya puesto en cuarentena agentsview.exe. Revisa Seguridad de Windows > Historial de protección, restáuralo y vuelve a intentarlo.
+sidecar-fix-reinstall = Reinstala AgentsView desde la última versión publicada.
+sidecar-fix-locate = O localiza tú mismo un binario agentsview, por ejemplo uno instalado con go install. La elección se recordará en los próximos inicios.
+sidecar-status-missing = no existe
+sidecar-status-not-executable = no es ejecutable
+sidecar-status-found = encontrado
+sidecar-locate-button = Localizar binario...
+sidecar-locate-title = Localiza el binario agentsview
+
+## Startup failures
+
+error-sidecar-spawn = AgentsView no pudo iniciar su backend local.
+error-sidecar-integrity = AgentsView no inició su backend porque el binario agentsview incluido se modificó o está incompleto.
+error-port-unavailable-any = AgentsView no pudo abrir un puerto local.
+error-port-unavailable = AgentsView no pudo usar el puerto local { $port }.
+error-env-probe = AgentsView no pudo leer el entorno de tu shell.
+error-readiness = El backend de AgentsView no se inició en { $seconds } segundos.
+error-sidecar-exited = El backend de AgentsView terminó antes de completar el inicio.
+error-remote-connect = AgentsView no pudo conectarse a { $url }.
+error-backend-request = AgentsView no obtuvo respuesta de su servicio local.
+error-backend-unsupported = Este backend de AgentsView no admite esta función.
+error-action-unavailable = Esta acción no está disponible: { $reason }
+error-path-not-allowed = AgentsView solo abre archivos de las carpetas del proyecto de la sesión o de los registros de agentes.
+error-connection-blocked = El backend de AgentsView se inició en el puerto { $port }, pero AgentsView no puede conectarse a él.
+error-internal = AgentsView sufrió un error interno durante el inicio.
+
+hint-sidecar-spawn = Reinstala AgentsView o comprueba que ningún software de seguridad haya puesto en cuarentena el binario agentsview incluido.
+hint-sidecar-integrity = Reinstala AgentsView. Si reemplazaste el binario tú mismo, define AGENTSVIEW_DESKTOP_SKIP_SIDECAR_CHECK=1.
+hint-port-unavailable = Cierra otros programas que usen el puerto (incluido otro agentsview serve) y vuelve a abrir la aplicación.
+hint-env-probe = Revisa los archivos de inicio de tu shell, o define AGENTSVIEW_DESKTOP_SKIP_LOGIN_SHELL_ENV=1 y usa ~/.agentsview/desktop.env.
+hint-readiness = Los archivos de sesiones grandes pueden tardar en indexarse. Vuelve a abrir la aplicación o ejecuta agentsview serve en una terminal para ver su salida.
+hint-sidecar-exited = Ejecuta agentsview serve en una terminal para ver por qué se detuvo el backend.
+hint-remote-connect = Comprueba que agentsview serve se esté ejecutando en ese equipo con -host configurado en una dirección accesible desde este dispositivo.
+hint-backend-request = Espera a que el servicio termine de iniciarse. Si sigue ocurriendo, reinicia AgentsView.
+hint-backend-unsupported = Actualiza agentsview en la máquina que ejecuta el backend y vuelve a intentarlo.
+hint-action-unavailable = Elige otra acción o comprueba que el backend local está en ejecución.
+hint-path-not-allowed = Abre el archivo desde tu gestor de archivos o tu editor.
+hint-connection-blocked-windows = Es posible que el Firewall de Windows Defender o un software de protección esté bloqueando las conexiones locales. Elige Permitir acceso si hay un aviso del firewall abierto, o permite agentsview.exe en Seguridad de Windows > Firewall y protección de red > Permitir una aplicación a través del firewall. En equipos administrados, pide a TI que permita agentsview en 127.0.0.1.
+hint-connection-blocked-macos = Es posible que un firewall como Little Snitch o LuLu, o un software de protección, esté bloqueando las conexiones locales. Permite que AgentsView y agentsview se conecten a 127.0.0.1 en sus reglas. En Macs administrados, pide a TI que permita agentsview.
+hint-connection-blocked-linux = Es posible que una regla de firewall o un software de protección esté bloqueando la interfaz de loopback. Comprueba que tus reglas de iptables o nftables aceptan el tráfico en lo, o pide a TI que permita agentsview en 127.0.0.1.
+hint-internal = Vuelve a abrir AgentsView. Si sigue ocurriendo, informa del problema.
+
+startup-failed-title = AgentsView no pudo iniciarse
+dialog-retry = Reintentar
+dialog-retest = Volver a probar
+dialog-open-logs = Abrir registros
+dialog-report-issue = Informar del problema
+
+## Capability explanations
+
+capability-allow = Permitir
+capability-deny = Ahora no
+capability-notifications-title = ¿Permitir notificaciones de AgentsView?
+capability-notifications-explanation = AgentsView puede avisarte cuando un agente espera tu respuesta o salta una alerta de uso. A continuación, el sistema te pedirá permiso para mostrar notificaciones. El texto de las notificaciones se genera en este equipo y nunca sale de él.
+capability-autostart-title = ¿Iniciar AgentsView al iniciar sesión?
+capability-autostart-explanation = AgentsView puede añadirse a tus elementos de inicio para indexar las sesiones antes de que lo abras. Puedes desactivarlo en cualquier momento desde AgentsView o los ajustes del sistema.
+capability-global-shortcuts-title = ¿Permitir atajos de teclado de AgentsView?
+capability-global-shortcuts-explanation = AgentsView puede registrar atajos globales que funcionan aunque otra aplicación esté activa. Mientras AgentsView lo tenga registrado, ese atajo no estará disponible para otras aplicaciones.
+
+## Quick-switcher palette
+
+palette-placeholder = Buscar sesiones, proyectos o fechas (AAAA-MM-DD)
+palette-empty = Sin resultados
+palette-kind-session = Sesión
+palette-kind-project = Proyecto
+palette-kind-date = Fecha
+palette-project-sessions = Sesiones: { $count }
+
+## Session annotation
+
+annotate-target = Nota en la sesión activa de { $project }
+annotate-placeholder = ¿Qué has observado?
+annotate-no-session = Ninguna sesión ha tenido actividad en los últimos 10 minutos.
+annotate-hint = Intro guarda, Mayús+Intro añade una línea, Esc cancela.
+
+## Tray
+
+tray-turn-running = { $project }: turno en curso desde hace { $elapsed }
+tray-clean-restart = Reiniciar el backend con un entorno limpio
+tray-refresh-environment = Recargar el entorno y reiniciar el backend
+tray-copy-api-url = Copiar la URL de la API y un ejemplo de curl
+tray-always-on-top = Mantener la ventana encima
+tray-software-rendering = Reiniciar con renderizado por software
+tray-hardware-rendering = Reiniciar con aceleración por hardware
+tray-connect = Conectar a…
+tray-connect-none = No se encontraron servidores en la red local
+
+## Notifications
+
+notify-waiting-title = El agente te está esperando
+notify-waiting-body = La sesión de { $project } necesita tu respuesta.
+weekly-summary-title = Tu semana en AgentsView
+weekly-summary-totals = Sesiones: { $sessions } · Mensajes: { $messages } · Días activos: { $days }
+weekly-summary-top = Proyectos principales: { $projects }
+
+## Cost alerts
+
+cost-alert-title = Límite de gasto alcanzado
+cost-alert-daily = El gasto de hoy es { $spent }, por encima de tu límite de { $limit }.
+cost-alert-weekly = El gasto de esta semana es { $spent }, por encima de tu límite de { $limit }.
+tray-cost-acknowledge = Confirmar alerta de gasto
+tray-cost-snooze = Posponer las alertas de gasto una hora
+
+## Diagnostics
+
+doctor-button = Ejecutar diagnóstico
+doctor-running = Ejecutando diagnóstico...
+doctor-sidecar = Binario del backend
+doctor-sidecar-found = Encontrado en { $path }
+doctor-sidecar-not-executable = { $path } no es ejecutable
+doctor-sidecar-missing = No se encontró en ninguna de las { $count } ubicaciones buscadas
+doctor-port = Puerto del backend
+doctor-port-listening = El backend escucha en el puerto { $port }
+doctor-port-free = El puerto { $port } está disponible
+doctor-port-busy = El puerto { $port } está en uso; el backend elegirá otro
+doctor-data-dir = Directorio de datos
+doctor-data-dir-writable = Se puede escribir en { $path }
+doctor-data-dir-unwritable = No se puede escribir en { $path }: { $error }
+doctor-data-dir-unknown = No se encontró tu directorio personal
+doctor-agents = CLI de agentes
+doctor-agents-found = En el PATH: { $found }
+doctor-agents-partial = Encontrados: { $found }. Fuera del PATH: { $missing }
+doctor-agents-none = Ninguno en el PATH: { $missing }
+doctor-webview = Motor del webview
+doctor-webview-version = Versión { $version }
+doctor-webview-error = No se pudo leer la versión: { $error }
+doctor-clock = Reloj del sistema
+doctor-clock-ok = { $time }
+doctor-clock-wrong = { $time } parece incorrecto; revisa la fecha y la hora del sistema
+doctor-arch = Arquitectura
+doctor-arch-native = Se ejecuta de forma nativa en { $native }
+doctor-arch-emulated = Una versión { $machine } se ejecuta con emulación en { $native }; instala la versión { $native } de AgentsView
+doctor-arch-webview = El runtime de WebView2 es { $machine } pero Windows es { $native }; reinstala el runtime de WebView2
+
+## Desktop actions
+
+desktop-action-open-in-editor = Abrir la transcripción en el editor
+desktop-action-reveal-file = Mostrar el archivo de la transcripción
+desktop-action-resume-session = Reanudar la sesión en una terminal
+desktop-action-export-pdf = Exportar como PDF
+desktop-action-open-in-new-window = Abrir la sesión en una ventana nueva
+desktop-action-restart-backend = Reiniciar el backend
+desktop-action-restart-backend-clean = Reiniciar el backend con un entorno limpio
+desktop-action-refresh-environment = Recargar el entorno y reiniciar el backend
+desktop-action-unknown = esta versión de AgentsView no la incluye
+desktop-action-needs-session = abre primero una sesión
+desktop-action-needs-local = requiere el backend local, no un servidor remoto
+desktop-action-no-terminal = no se encontró ningún emulador de terminal
+desktop-action-no-transcript = la sesión no tiene archivo de transcripción
+desktop-action-unsupported-agent = el agente de la sesión no puede reanudar sesiones desde la línea de comandos
+desktop-action-unsafe-session-id = el id de la sesión tiene caracteres que una línea de comandos no puede llevar con seguridad
+desktop-action-superseded = otro reinicio reemplazó a este
+desktop-action-kiosk = el modo quiosco muestra una sola ventana
+
+## Rendering
+
+rendering-trial-title = Renderizado por software
+rendering-trial-message = AgentsView se inició en modo seguro con la aceleración por hardware desactivada. Si la ventana se ve correctamente, ¿quieres mantener el renderizado por software en los próximos inicios?
+rendering-trial-keep = Mantener
+rendering-trial-discard = Ahora no
+
+## Architecture
+
+arch-emulated-title = AgentsView se ejecuta con emulación
+arch-emulated-message = Esta es la versión { $machine } de AgentsView, que se ejecuta con emulación en un PC ARM64. La versión ARM64 se inicia más rápido y consume menos batería.
+arch-emulated-download = Descargar versión ARM64
+arch-emulated-continue = Continuar
+webview2-missing-title = Se necesita el runtime de WebView2
+webview2-missing-message = AgentsView necesita el runtime de Microsoft Edge WebView2 ({ $machine }), que no está instalado. ¿Abrir ahora la página de descarga?
+webview2-missing-offline = AgentsView necesita el runtime de Microsoft Edge WebView2 ({ $machine }), que no está instalado. Pide a tu administrador que lo instale.
+
+## Backend health
+
+backend-unresponsive-title = El backend de AgentsView no responde
+backend-unresponsive-message = El backend local dejó de responder a las comprobaciones de estado, así que la ventana puede dejar de actualizarse. Reiniciarlo suele solucionarlo; la página se recarga cuando vuelve a estar disponible.
+backend-unresponsive-restart = Reiniciar backend
+backend-unresponsive-wait = Seguir esperando
+
+## Backend crash
+
+backend-crash-title = El backend de AgentsView se detuvo
+backend-crash-startup = El backend de AgentsView terminó antes de completar el inicio ({ $status }).
+backend-crash-running = El backend de AgentsView se detuvo de forma inesperada ({ $status }).
+backend-crash-status-code = código de salida { $code }
+backend-crash-status-signal = señal { $signal }
+backend-crash-status-unknown = estado de salida desconocido
+backend-crash-output = Última salida del backend:
+backend-crash-no-output = El backend no escribió nada en stderr. Ejecuta agentsview serve en una terminal para ver por qué se detuvo.
+backend-crash-restart = Reiniciar
+backend-crash-quit = Salir
+
+## Backend resources
+
+memory-alert-title = El backend de AgentsView está usando mucha memoria
+memory-alert-message = El backend local está usando { $used } MB de memoria, por encima del límite de { $limit } MB. Reiniciarlo libera la memoria; la página se recarga cuando vuelve a estar disponible.
+memory-alert-restart = Reiniciar backend
+memory-alert-ignore = Ignorar
+
+## Backend version
+
+backend-version-title = La versión del backend de AgentsView no coincide
+backend-version-old = El backend de AgentsView tiene la versión { $backend }, anterior a las que admite esta aplicación ({ $min } o posterior). Algunos paneles pueden quedar vacíos o no cargarse.
+backend-version-new = El backend de AgentsView tiene la versión { $backend }, posterior a las que admite esta aplicación (anterior a { $max }). Algunos paneles pueden quedar vacíos o no cargarse.
+backend-version-advice = Esto suele deberse a una actualización incompleta. Instala la última versión de AgentsView para que la aplicación y su backend coincidan, o actualiza agentsview si ejecutas agentsview serve por tu cuenta.
+backend-version-download = Descargar AgentsView
+backend-version-continue = Continuar
+
+## Port conflict
+
+port-conflict-title = Puerto del backend en uso
+port-conflict-owner = AgentsView no pudo iniciar su backend porque { $name } (proceso { $pid }) está usando el puerto { $port }.
+port-conflict-unknown = AgentsView no pudo iniciar su backend porque otro programa está usando el puerto { $port }.
+port-conflict-advice = Inicia el backend en otro puerto, o cierra ese programa y vuelve a intentarlo.
+port-conflict-stale = Parece un backend de agentsview que quedó en ejecución de una sesión anterior. Detenlo para liberar el puerto, o inicia el backend en otro puerto.
+port-conflict-stop = Detener agentsview
+port-conflict-other-port = Usar otro puerto
+port-conflict-cancel = Cancelar
+
+## Connectivity
+
+connectivity-reconnecting = Se perdió la conexión con el backend de AgentsView. Reconectando…
+
+## Downloads
+
+download-saved-title = Descarga completada
+download-saved-body = Se guardó { $name } en { $folder }.
+download-failed-title = Error en la descarga
+download-failed-body = AgentsView no pudo guardar { $name }.
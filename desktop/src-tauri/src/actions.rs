@@ -0,0 +1,574 @@
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::backend_client::{encode_query_component, BackendClient};
+use crate::doctor;
+use crate::error::DesktopError;
+use crate::i18n;
+use crate::kiosk;
+use crate::policy;
+use crate::remote::RemoteState;
+use crate::session_window;
+use crate::shutdown::ShutdownSignal;
+use crate::startup_dialog;
+use crate::{Probe, SidecarState};
+
+// CWD_SCAN_LINES bounds how far into a transcript the working
+// directory is looked for; agents record it in the first entries.
+const CWD_SCAN_LINES: usize = 50;
+
+// Action is a desktop capability the web UI can offer, such as
+// its command palette. Actions describe themselves, so the UI
+// lists what the current platform and connection support without
+// knowing about either.
+struct Action {
+    id: &'static str,
+    title_key: &'static str,
+    // needs_session marks actions that act on the session passed
+    // to invoke_desktop_action.
+    needs_session: bool,
+    // availability returns the localization key of the reason an
+    // action cannot run here.
+    availability: fn(&AppHandle) -> Result<(), &'static str>,
+    run: fn(&AppHandle, Option<&str>) -> Result<(), DesktopError>,
+}
+
+const ACTIONS: &[Action] = &[
+    Action {
+        id: "open_in_editor",
+        title_key: "desktop-action-open-in-editor",
+        needs_session: true,
+        availability: local_backend,
+        run: open_in_editor,
+    },
+    Action {
+        id: "reveal_file",
+        title_key: "desktop-action-reveal-file",
+        needs_session: true,
+        availability: local_backend,
+        run: reveal_file,
+    },
+    Action {
+        id: "resume_session",
+        title_key: "desktop-action-resume-session",
+        needs_session: true,
+        availability: terminal,
+        run: resume_session,
+    },
+    Action {
+        id: "export_pdf",
+        title_key: "desktop-action-export-pdf",
+        needs_session: false,
+        availability: always,
+        run: export_pdf,
+    },
+    Action {
+        id: "open_in_new_window",
+        title_key: "desktop-action-open-in-new-window",
+        needs_session: true,
+        availability: multiple_windows,
+        run: open_in_new_window,
+    },
+    Action {
+        id: "restart_backend",
+        title_key: "desktop-action-restart-backend",
+        needs_session: false,
+        availability: local_backend,
+        run: restart,
+    },
+    Action {
+        id: "restart_backend_clean",
+        title_key: "desktop-action-restart-backend-clean",
+        needs_session: false,
+        availability: local_backend,
+        run: restart_clean,
+    },
+    Action {
+        id: "refresh_environment",
+        title_key: "desktop-action-refresh-environment",
+        needs_session: false,
+        availability: local_backend,
+        run: refresh_environment,
+    },
+];
+
+// DesktopAction is an action as listed to the frontend. reason
+// explains why an unavailable action is disabled.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct DesktopAction {
+    id: &'static str,
+    title: String,
+    needs_session: bool,
+    available: bool,
+    reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SessionFile {
+    id: String,
+    #[serde(default)]
+    agent: String,
+    pub(crate) file_path: Option<String>,
+}
+
+#[tauri::command]
+pub(crate) fn list_desktop_actions(app: AppHandle) -> Vec<DesktopAction> {
+    ACTIONS
+        .iter()
+        .map(|action| {
+            let reason = (action.availability)(&app).err().map(i18n::tr);
+            DesktopAction {
+                id: action.id,
+                title: i18n::tr(action.title_key),
+                needs_session: action.needs_session,
+                available: reason.is_none(),
+                reason,
+            }
+        })
+        .collect()
+}
+
+// invoke_desktop_action runs the action with id, on session_id
+// for actions that need a session.
+#[tauri::command]
+pub(crate) async fn invoke_desktop_action(
+    app: AppHandle,
+    id: String,
+    session_id: Option<String>,
+) -> Result<(), DesktopError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let action = ACTIONS
+            .iter()
+            .find(|action| action.id == id)
+            .ok_or_else(|| unavailable(&id, "desktop-action-unknown"))?;
+        (action.availability)(&app).map_err(|reason| unavailable(action.id, reason))?;
+        if action.needs_session && session_id.is_none() {
+            return Err(unavailable(action.id, "desktop-action-needs-session"));
+        }
+        (action.run)(&app, session_id.as_deref())
+    })
+    .await
+    .map_err(|_| DesktopError::StatePoisoned {
+        what: "desktop action",
+    })?
+}
+
+fn unavailable(action: &str, reason: &str) -> DesktopError {
+    DesktopError::ActionUnavailable {
+        action: action.to_string(),
+        reason: i18n::tr(reason),
+    }
+}
+
+fn always(_app: &AppHandle) -> Result<(), &'static str> {
+    Ok(())
+}
+
+// local_backend limits actions to the bundled backend, whose
+// transcript paths exist on this machine.
+fn local_backend(app: &AppHandle) -> Result<(), &'static str> {
+    if policy::managed().remote_url.is_some() || app.state::<RemoteState>().origin().is_some() {
+        return Err("desktop-action-needs-local");
+    }
+    Ok(())
+}
+
+// multiple_windows rules out kiosk mode, which keeps to the main
+// window.
+fn multiple_windows(_app: &AppHandle) -> Result<(), &'static str> {
+    if kiosk::config().is_some() {
+        return Err("desktop-action-kiosk");
+    }
+    Ok(())
+}
+
+fn terminal(app: &AppHandle) -> Result<(), &'static str> {
+    local_backend(app)?;
+    if cfg!(target_os = "linux") {
+        let path = std::env::var_os("PATH").unwrap_or_default();
+        doctor::find_on_path(&path, "x-terminal-emulator", false)
+            .ok_or("desktop-action-no-terminal")?;
+    }
+    Ok(())
+}
+
+pub(crate) fn session_file(
+    app: &AppHandle,
+    session_id: Option<&str>,
+) -> Result<SessionFile, DesktopError> {
+    let id = session_id.unwrap_or_default();
+    let path = format!("/api/v1/sessions/{}", encode_query_component(id));
+    let client = BackendClient::for_app(app).ok_or_else(|| DesktopError::BackendRequest {
+        path: path.clone(),
+        reason: "backend is not running".to_string(),
+    })?;
+    client
+        .get_json_until::<SessionFile>(&path, &|| false)?
+        .ok_or(DesktopError::BackendRequest {
+            path,
+            reason: "cancelled".to_string(),
+        })
+}
+
+fn transcript(action: &str, session: &SessionFile) -> Result<PathBuf, DesktopError> {
+    session
+        .file_path
+        .as_deref()
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .ok_or_else(|| unavailable(action, "desktop-action-no-transcript"))
+}
+
+// open_in_editor opens the session's transcript in the system
+// text editor.
+fn open_in_editor(app: &AppHandle, session_id: Option<&str>) -> Result<(), DesktopError> {
+    let path = transcript("open_in_editor", &session_file(app, session_id)?)?;
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = Command::new("open");
+        command.arg("-t");
+        command
+    } else if cfg!(windows) {
+        Command::new("notepad")
+    } else {
+        Command::new("xdg-open")
+    };
+    command.arg(&path).spawn()?;
+    Ok(())
+}
+
+fn reveal_file(app: &AppHandle, session_id: Option<&str>) -> Result<(), DesktopError> {
+    let path = transcript("reveal_file", &session_file(app, session_id)?)?;
+    startup_dialog::reveal(&path);
+    Ok(())
+}
+
+// resume_session continues the session with its agent's CLI in a
+// new terminal window, started in the directory the session ran
+// in when the transcript records it.
+fn resume_session(app: &AppHandle, session_id: Option<&str>) -> Result<(), DesktopError> {
+    let session = session_file(app, session_id)?;
+    if !is_plain_session_id(&session.id) {
+        return Err(unavailable(
+            "resume_session",
+            "desktop-action-unsafe-session-id",
+        ));
+    }
+    let argv = resume_command(&session.agent, &session.id)
+        .ok_or_else(|| unavailable("resume_session", "desktop-action-unsupported-agent"))?;
+    let dir = session
+        .file_path
+        .as_deref()
+        .and_then(|path| transcript_cwd(Path::new(path)));
+    open_terminal(dir.as_deref(), &argv)?;
+    Ok(())
+}
+
+// resume_command is the CLI invocation that resumes a session, for
+// agents whose CLI can. Session ids of agents other than Claude
+// carry the agent's prefix.
+fn resume_command(agent: &str, id: &str) -> Option<Vec<String>> {
+    match agent {
+        "claude" => Some(vec!["claude".into(), "--resume".into(), id.into()]),
+        "codex" => {
+            let id = id.strip_prefix("codex:").unwrap_or(id);
+            Some(vec!["codex".into(), "resume".into(), id.into()])
+        }
+        _ => None,
+    }
+}
+
+// is_plain_session_id accepts the characters agents use in session
+// ids. On Windows the resume command line goes through cmd, where
+// characters such as & | ^ and " would start a command of their
+// own.
+fn is_plain_session_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | ':' | '-'))
+}
+
+// transcript_cwd reads the working directory agents record in
+// their transcript entries.
+fn transcript_cwd(path: &Path) -> Option<PathBuf> {
+    let file = std::fs::File::open(path).ok()?;
+    BufReader::new(file)
+        .lines()
+        .take(CWD_SCAN_LINES)
+        .map_while(Result::ok)
+        .find_map(|line| line_cwd(&line))
+        .filter(|dir| dir.is_dir())
+}
+
+pub(crate) fn line_cwd(line: &str) -> Option<PathBuf> {
+    let entry: serde_json::Value = serde_json::from_str(line).ok()?;
+    let cwd = entry
+        .get("cwd")
+        .or_else(|| entry.get("payload")?.get("cwd"))?
+        .as_str()?;
+    Some(PathBuf::from(cwd))
+}
+
+fn open_terminal(dir: Option<&Path>, argv: &[String]) -> io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = Command::new("osascript");
+        command.args([
+            "-e",
+            &format!(
+                "tell application \"Terminal\" to do script {}",
+                applescript_string(&terminal_script(dir, argv))
+            ),
+            "-e",
+            "tell application \"Terminal\" to activate",
+        ]);
+        command
+    } else if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", "", "cmd", "/K"]).args(argv);
+        command
+    } else {
+        let mut command = Command::new("x-terminal-emulator");
+        command.arg("-e").args(argv);
+        command
+    };
+    if let Some(dir) = dir {
+        command.current_dir(dir);
+    }
+    command.spawn().map(drop)
+}
+
+// terminal_script is argv as a POSIX shell command line, run in
+// dir. Terminal.app starts scripts in the home directory.
+fn terminal_script(dir: Option<&Path>, argv: &[String]) -> String {
+    let command = argv
+        .iter()
+        .map(|arg| shell_quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+    match dir {
+        Some(dir) => format!("cd {} && {command}", shell_quote(&dir.to_string_lossy())),
+        None => command,
+    }
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn applescript_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', r"\\").replace('"', "\\\""))
+}
+
+fn export_pdf(app: &AppHandle, _session_id: Option<&str>) -> Result<(), DesktopError> {
+    let main = app
+        .get_webview_window("main")
+        .ok_or(DesktopError::MissingWindow { label: "main" })?;
+    // The print dialog offers saving as PDF on every desktop OS.
+    main.print().map_err(io::Error::other)?;
+    Ok(())
+}
+
+fn open_in_new_window(app: &AppHandle, session_id: Option<&str>) -> Result<(), DesktopError> {
+    session_window::open(app, session_id.unwrap_or_default())
+}
+
+fn restart(app: &AppHandle, _session_id: Option<&str>) -> Result<(), DesktopError> {
+    let main = app
+        .get_webview_window("main")
+        .ok_or(DesktopError::MissingWindow { label: "main" })?;
+    crate::restart_backend(app, &main);
+    Ok(())
+}
+
+fn restart_clean(app: &AppHandle, _session_id: Option<&str>) -> Result<(), DesktopError> {
+    crate::restart_backend_clean(app);
+    Ok(())
+}
+
+fn refresh_environment(app: &AppHandle, _session_id: Option<&str>) -> Result<(), DesktopError> {
+    crate::refresh_environment(app);
+    Ok(())
+}
+
+// BackendStatus is where a restarted backend serves.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct BackendStatus {
+    port: u16,
+    url: String,
+}
+
+// restart_backend stops the local backend, starts it again with
+// the same environment and arguments, and resolves once it
+// answers. Unlike the restart_backend action, it leaves the page
+// alone unless the backend comes back on another port, so the
+// caller gets the result. A failed launch is also reported in the
+// startup failure dialog, as at startup.
+#[tauri::command]
+pub(crate) async fn restart_backend(app: AppHandle) -> Result<BackendStatus, DesktopError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        local_backend(&app).map_err(|reason| unavailable("restart_backend", reason))?;
+        let main = app
+            .get_webview_window("main")
+            .ok_or(DesktopError::MissingWindow { label: "main" })?;
+        let attempt = crate::relaunch_backend(&app, &main, true);
+        let port = wait_for_relaunch(&app, attempt)?;
+        Ok(BackendStatus {
+            port,
+            url: crate::local_url(port),
+        })
+    })
+    .await
+    .map_err(|_| DesktopError::StatePoisoned {
+        what: "backend restart",
+    })?
+}
+
+// reload_environment probes the login shell again, re-reads
+// desktop.env and restarts the local backend with the result,
+// resolving once it answers, as restart_backend does. Use it after
+// installing an agent CLI or editing a shell profile.
+#[tauri::command]
+pub(crate) async fn reload_environment(app: AppHandle) -> Result<BackendStatus, DesktopError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        local_backend(&app).map_err(|reason| unavailable("reload_environment", reason))?;
+        let main = app
+            .get_webview_window("main")
+            .ok_or(DesktopError::MissingWindow { label: "main" })?;
+        let attempt = crate::reload_environment(&app, &main, true);
+        let port = wait_for_relaunch(&app, attempt)?;
+        Ok(BackendStatus {
+            port,
+            url: crate::local_url(port),
+        })
+    })
+    .await
+    .map_err(|_| DesktopError::StatePoisoned {
+        what: "environment reload",
+    })?
+}
+
+// wait_for_relaunch waits up to READY_TIMEOUT for launch attempt
+// to report its port and answer there, classifying failures as
+// startup does.
+fn wait_for_relaunch(app: &AppHandle, attempt: u64) -> Result<u16, DesktopError> {
+    let shutdown = app.state::<ShutdownSignal>().inner().clone();
+    let deadline = Instant::now() + crate::READY_TIMEOUT;
+    let timed_out = || DesktopError::Readiness {
+        timeout: crate::READY_TIMEOUT,
+    };
+    loop {
+        if crate::superseded(app, attempt) {
+            return Err(unavailable("restart_backend", "desktop-action-superseded"));
+        }
+        if let Some(port) = crate::current_sidecar_port(app) {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let failure = match crate::wait_for_server(port, remaining, &shutdown) {
+                Probe::Ready => return Ok(port),
+                _ if crate::current_sidecar_port(app) != Some(port) => {
+                    return Err(DesktopError::SidecarExited {
+                        code: sidecar_exit(app).flatten(),
+                    })
+                }
+                Probe::Refused => "were refused",
+                Probe::TimedOut => "timed out",
+                Probe::Unhealthy => return Err(timed_out()),
+            };
+            return Err(DesktopError::ConnectionBlocked { port, failure });
+        }
+        if let Some(code) = sidecar_exit(app) {
+            return Err(DesktopError::SidecarExited { code });
+        }
+        if Instant::now() >= deadline || shutdown.wait(crate::READY_POLL_INTERVAL) {
+            return Err(timed_out());
+        }
+    }
+}
+
+// sidecar_exit returns the exit code of a sidecar that has exited,
+// None while it runs.
+fn sidecar_exit(app: &AppHandle) -> Option<Option<i32>> {
+    let state = app.state::<SidecarState>();
+    let guard = state.child.lock().ok()?;
+    guard.as_ref()?.exit_status().map(|status| status.code())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn action_ids_are_unique() {
+        let mut ids: Vec<&str> = ACTIONS.iter().map(|action| action.id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), ACTIONS.len());
+    }
+
+    #[test]
+    fn resume_command_strips_agent_prefixes() {
+        assert_eq!(
+            resume_command("claude", "4f1c"),
+            Some(vec!["claude".into(), "--resume".into(), "4f1c".into()])
+        );
+        assert_eq!(
+            resume_command("codex", "codex:019a"),
+            Some(vec!["codex".into(), "resume".into(), "019a".into()])
+        );
+        assert_eq!(resume_command("gemini", "gemini:1"), None);
+    }
+
+    #[test]
+    fn is_plain_session_id_rejects_shell_metacharacters() {
+        assert!(is_plain_session_id("4f1c9a2e-7b1d-4c1e-9f7a-0e3b5c2d1a6f"));
+        assert!(is_plain_session_id("codex:019a.rollout_2"));
+        for id in [
+            "",
+            "4f1c & calc",
+            "4f1c|calc",
+            "4f1c^",
+            "4f1c\"",
+            "4f1c%PATH%",
+            "4f1c>out",
+            "4f1c\ncalc",
+        ] {
+            assert!(!is_plain_session_id(id), "{id:?}");
+        }
+    }
+
+    #[test]
+    fn line_cwd_reads_claude_and_codex_entries() {
+        assert_eq!(
+            line_cwd(r#"{"type":"user","cwd":"/work/app"}"#),
+            Some(PathBuf::from("/work/app"))
+        );
+        assert_eq!(
+            line_cwd(r#"{"type":"session_meta","payload":{"cwd":"/work/api"}}"#),
+            Some(PathBuf::from("/work/api"))
+        );
+        assert_eq!(line_cwd(r#"{"type":"summary"}"#), None);
+        assert_eq!(line_cwd("not json"), None);
+    }
+
+    #[test]
+    fn terminal_script_quotes_for_the_shell_and_applescript() {
+        let argv = vec![
+            "claude".to_string(),
+            "--resume".to_string(),
+            "it's".to_string(),
+        ];
+        let script = terminal_script(Some(Path::new("/work/my app")), &argv);
+        assert_eq!(
+            script,
+            r#"cd '/work/my app' && 'claude' '--resume' 'it'\''s'"#
+        );
+        assert_eq!(
+            applescript_string(&script),
+            r#""cd '/work/my app' && 'claude' '--resume' 'it'\\''s'""#
+        );
+    }
+}
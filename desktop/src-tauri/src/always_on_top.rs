@@ -0,0 +1,57 @@
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+use crate::error::DesktopError;
+use crate::kiosk;
+use crate::tray;
+
+// set_always_on_top pins the calling window above other apps, or
+// unpins it, so a running session stays in view while the user
+// works in their editor. It returns whether the window is pinned.
+#[tauri::command]
+pub(crate) fn set_always_on_top(window: WebviewWindow, pinned: bool) -> Result<bool, DesktopError> {
+    set(&window, pinned)?;
+    Ok(pinned)
+}
+
+// toggle_main flips the main window's pin, for the tray menu.
+pub(crate) fn toggle_main(app: &AppHandle) {
+    let Some(main) = app.get_webview_window("main") else {
+        return;
+    };
+    if let Err(err) = set(&main, !is_pinned(&main)) {
+        eprintln!("[agentsview] could not change keep on top: {err}");
+        tray::refresh_menu(app);
+    }
+}
+
+// main_pinned reports whether the main window is pinned, for the
+// tray menu's check mark.
+pub(crate) fn main_pinned(app: &AppHandle) -> bool {
+    app.get_webview_window("main")
+        .is_some_and(|main| is_pinned(&main))
+}
+
+fn is_pinned(window: &WebviewWindow) -> bool {
+    window.is_always_on_top().unwrap_or(false)
+}
+
+// set applies a pin. Kiosk windows are fullscreen already and stay
+// as they are.
+fn set(window: &WebviewWindow, pinned: bool) -> Result<(), DesktopError> {
+    if kiosk::config().is_some() {
+        return Err(DesktopError::ActionUnavailable {
+            action: "set_always_on_top".to_string(),
+            reason: "kiosk mode keeps the window fullscreen".to_string(),
+        });
+    }
+    window
+        .set_always_on_top(pinned)
+        .map_err(|err| DesktopError::ActionUnavailable {
+            action: "set_always_on_top".to_string(),
+            reason: err.to_string(),
+        })?;
+    if window.label() == "main" {
+        tray::refresh_menu(window.app_handle());
+    }
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::backend_client::{encode_query_component, BackendClient};
+use crate::error::DesktopError;
+use crate::i18n;
+use crate::live::{self, SessionSummary};
+use crate::shortcuts::{self, Popup};
+use crate::timestamps;
+
+const MAX_TITLE_CHARS: usize = 80;
+
+// POPUP is the note-entry window opened by the annotate shortcut.
+pub(crate) const POPUP: Popup = Popup {
+    label: "annotate",
+    page: "annotate.html",
+    width: 480.0,
+    height: 180.0,
+};
+
+// AnnotateTarget is the session a note will be attached to, shown
+// in the note window so the user knows where it goes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct AnnotateTarget {
+    session_id: String,
+    label: String,
+    title: String,
+}
+
+#[derive(Serialize)]
+struct NewAnnotation<'a> {
+    body: &'a str,
+    source: &'static str,
+}
+
+// open_annotate shows the note-entry window over the focused app.
+pub(crate) fn open_annotate(app: &AppHandle) {
+    shortcuts::show_popup(app, &POPUP);
+}
+
+// annotate_target finds the live session a note would be attached
+// to, or None when no session has had recent activity.
+#[tauri::command]
+pub(crate) async fn annotate_target(
+    app: AppHandle,
+) -> Result<Option<AnnotateTarget>, DesktopError> {
+    let client = backend_client(&app)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let session = live::latest_live_session(&client, timestamps::unix_now())?;
+        Ok(session.map(describe_target))
+    })
+    .await
+    .map_err(|_| DesktopError::StatePoisoned {
+        what: "annotation target",
+    })?
+}
+
+// annotate_submit attaches note to session_id through the
+// backend's annotation API and hides the note window.
+#[tauri::command]
+pub(crate) async fn annotate_submit(
+    app: AppHandle,
+    session_id: String,
+    note: String,
+) -> Result<(), DesktopError> {
+    let note = note.trim().to_string();
+    if !note.is_empty() {
+        let client = backend_client(&app)?;
+        let path = format!(
+            "/api/v1/sessions/{}/annotations",
+            encode_query_component(&session_id)
+        );
+        tauri::async_runtime::spawn_blocking(move || {
+            client.post_json::<serde_json::Value>(
+                &path,
+                &NewAnnotation {
+                    body: &note,
+                    source: "desktop",
+                },
+            )
+        })
+        .await
+        .map_err(|_| DesktopError::StatePoisoned {
+            what: "annotation request",
+        })??;
+    }
+    shortcuts::hide_popup(&app, &POPUP);
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn annotate_close(app: AppHandle) {
+    shortcuts::hide_popup(&app, &POPUP);
+}
+
+fn backend_client(app: &AppHandle) -> Result<BackendClient, DesktopError> {
+    BackendClient::for_app(app).ok_or_else(|| DesktopError::BackendRequest {
+        path: "/api/v1/sessions".to_string(),
+        reason: "no backend is running".to_string(),
+    })
+}
+
+// describe_target labels session with its project and a one-line
+// excerpt of its first message.
+fn describe_target(session: SessionSummary) -> AnnotateTarget {
+    let title = session
+        .first_message
+        .as_deref()
+        .map(|message| message.split_whitespace().collect::<Vec<_>>().join(" "))
+        .map(
+            |message| match message.char_indices().nth(MAX_TITLE_CHARS) {
+                Some((cut, _)) => format!("{}…", &message[..cut]),
+                None => message,
+            },
+        )
+        .unwrap_or_default();
+    AnnotateTarget {
+        label: i18n::tr_args("annotate-target", &[("project", &session.project)]),
+        session_id: session.id,
+        title,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(first_message: Option<&str>) -> SessionSummary {
+        SessionSummary {
+            id: "root".to_string(),
+            project: "agentsview".to_string(),
+            first_message: first_message.map(str::to_string),
+            parent_session_id: None,
+        }
+    }
+
+    #[test]
+    fn describe_target_collapses_whitespace() {
+        let target = describe_target(summary(Some("fix the\n  flaky test")));
+        assert_eq!(target.session_id, "root");
+        assert_eq!(target.title, "fix the flaky test");
+        assert_eq!(describe_target(summary(None)).title, "");
+    }
+
+    #[test]
+    fn describe_target_truncates_long_titles() {
+        let long = "é".repeat(MAX_TITLE_CHARS + 5);
+        let target = describe_target(summary(Some(&long)));
+        assert_eq!(target.title.chars().count(), MAX_TITLE_CHARS + 1);
+        assert!(target.title.ends_with('…'));
+    }
+}
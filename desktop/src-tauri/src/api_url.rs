@@ -0,0 +1,125 @@
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::error::DesktopError;
+use crate::ports;
+use crate::remote::RemoteState;
+
+// EXAMPLE_PATH is the endpoint the curl example calls.
+const EXAMPLE_PATH: &str = "/api/v1/sessions";
+// TOKEN_VAR is the shell variable the curl example reads the
+// backend's auth token from; the token itself is never copied.
+const TOKEN_VAR: &str = "AGENTSVIEW_AUTH_TOKEN";
+
+// ApiEndpoint is what Copy API URL puts on the clipboard: the base
+// URL of the backend the window shows and a curl command that
+// calls it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct ApiEndpoint {
+    base_url: String,
+    curl: String,
+}
+
+impl ApiEndpoint {
+    fn clipboard_text(&self) -> String {
+        format!("{}\n{}\n", self.base_url, self.curl)
+    }
+}
+
+// copy_api_url copies the backend's base URL and a curl example
+// for scripting against it, and returns both.
+#[tauri::command]
+pub(crate) fn copy_api_url(app: AppHandle) -> Result<ApiEndpoint, DesktopError> {
+    copy(&app)
+}
+
+pub(crate) fn copy(app: &AppHandle) -> Result<ApiEndpoint, DesktopError> {
+    let endpoint = current(app).ok_or_else(|| DesktopError::ActionUnavailable {
+        action: "copy_api_url".to_string(),
+        reason: "the backend is not running".to_string(),
+    })?;
+    app.clipboard()
+        .write_text(endpoint.clipboard_text())
+        .map_err(|err| DesktopError::ActionUnavailable {
+            action: "copy_api_url".to_string(),
+            reason: err.to_string(),
+        })?;
+    Ok(endpoint)
+}
+
+// current describes the attached remote server, or else the local
+// backend: the port it serves the window on, or its Unix socket
+// when it serves on one, which curl can reach without the proxy's
+// secret.
+fn current(app: &AppHandle) -> Option<ApiEndpoint> {
+    if let Some(origin) = app.state::<RemoteState>().origin() {
+        let base_url = origin.as_str().trim_end_matches('/').to_string();
+        let needs_token = !origin.host_str().is_some_and(ports::is_loopback_host);
+        return Some(endpoint(base_url, None, needs_token));
+    }
+    let port = crate::current_sidecar_port(app)?;
+    let socket = crate::sidecar_socket(app).map(|path| path.display().to_string());
+    Some(endpoint(crate::local_url(port), socket.as_deref(), false))
+}
+
+// endpoint builds the copied text. Servers on other machines need
+// the auth token, which the example leaves as $TOKEN_VAR.
+fn endpoint(base_url: String, socket: Option<&str>, needs_token: bool) -> ApiEndpoint {
+    let mut curl = "curl -s".to_string();
+    if let Some(socket) = socket {
+        curl.push_str(&format!(" --unix-socket {}", shell_quote(socket)));
+    }
+    if needs_token {
+        curl.push_str(&format!(" -H \"Authorization: Bearer ${TOKEN_VAR}\""));
+    }
+    let url = match socket {
+        Some(_) => format!("http://localhost{EXAMPLE_PATH}"),
+        None => format!("{base_url}{EXAMPLE_PATH}"),
+    };
+    curl.push_str(&format!(" {}", shell_quote(&url)));
+    ApiEndpoint { base_url, curl }
+}
+
+// shell_quote single-quotes text for a POSIX shell when it holds
+// anything but plain URL and path characters.
+fn shell_quote(text: &str) -> String {
+    let plain = text
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "/:._-@%+=,".contains(c));
+    if plain {
+        text.to_string()
+    } else {
+        format!("'{}'", text.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoint_builds_curl_for_each_backend() {
+        let local = endpoint("http://127.0.0.1:18080".to_string(), None, false);
+        assert_eq!(
+            local.clipboard_text(),
+            "http://127.0.0.1:18080\ncurl -s http://127.0.0.1:18080/api/v1/sessions\n"
+        );
+
+        let socket = endpoint(
+            "http://127.0.0.1:18080".to_string(),
+            Some("/tmp/agentsview dir/backend.sock"),
+            false,
+        );
+        assert_eq!(
+            socket.curl,
+            "curl -s --unix-socket '/tmp/agentsview dir/backend.sock' http://localhost/api/v1/sessions"
+        );
+
+        let remote = endpoint("https://agents.example.com".to_string(), None, true);
+        assert_eq!(
+            remote.curl,
+            "curl -s -H \"Authorization: Bearer $AGENTSVIEW_AUTH_TOKEN\" https://agents.example.com/api/v1/sessions"
+        );
+    }
+}
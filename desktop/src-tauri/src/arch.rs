@@ -0,0 +1,385 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use tauri::AppHandle;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::i18n;
+use crate::settings;
+use crate::sidecar::{self, CandidateStatus};
+
+#[cfg(not(feature = "no-network"))]
+const RELEASES_URL: &str = "https://github.com/wesm/agentsview/releases/latest";
+#[cfg(all(windows, not(feature = "no-network")))]
+const WEBVIEW2_DOWNLOAD_URL: &str = "https://go.microsoft.com/fwlink/p/?LinkId=2124703";
+// PE_HEADER_BYTES is enough of an executable to hold the DOS stub
+// and the PE file header of anything a linker produces.
+const PE_HEADER_BYTES: u64 = 4096;
+
+// Machine is the CPU architecture a Windows executable targets, or
+// the one the OS runs on natively.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Machine {
+    X86,
+    X64,
+    Arm64,
+    Other(u16),
+}
+
+impl Machine {
+    fn from_code(code: u16) -> Self {
+        match code {
+            0x014c => Self::X86,
+            0x8664 => Self::X64,
+            0xaa64 => Self::Arm64,
+            other => Self::Other(other),
+        }
+    }
+
+    // current is the architecture this shell was built for.
+    pub(crate) fn current() -> Self {
+        match std::env::consts::ARCH {
+            "x86" => Self::X86,
+            "x86_64" => Self::X64,
+            "aarch64" => Self::Arm64,
+            _ => Self::Other(0),
+        }
+    }
+
+    pub(crate) fn name(self) -> String {
+        match self {
+            Self::X86 => "x86".to_string(),
+            Self::X64 => "x64".to_string(),
+            Self::Arm64 => "ARM64".to_string(),
+            Self::Other(code) => format!("machine 0x{code:04x}"),
+        }
+    }
+}
+
+// Architectures describes what runs where: the OS's native
+// machine and the machines of the shell, the sidecar, and the
+// WebView2 runtime, each None when it could not be read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Architectures {
+    pub(crate) native: Option<Machine>,
+    pub(crate) shell: Machine,
+    pub(crate) sidecar: Option<Machine>,
+    pub(crate) webview: Option<Machine>,
+}
+
+impl Architectures {
+    // emulated returns the machine of an x64 or x86 build of the
+    // sidecar or the shell running under emulation on ARM64
+    // Windows.
+    pub(crate) fn emulated(&self) -> Option<Machine> {
+        if self.native != Some(Machine::Arm64) {
+            return None;
+        }
+        self.sidecar
+            .into_iter()
+            .chain([self.shell])
+            .find(|machine| *machine != Machine::Arm64)
+    }
+
+    // webview_mismatch reports a WebView2 runtime that does not
+    // match the OS, e.g. an x64 runtime copied onto ARM64 Windows.
+    pub(crate) fn webview_mismatch(&self) -> bool {
+        matches!((self.native, self.webview), (Some(native), Some(webview)) if native != webview)
+    }
+}
+
+// detect reads the architectures of this process, the sidecar it
+// would launch, and the WebView2 runtime. Only Windows reports a
+// native machine; elsewhere nothing counts as emulated.
+pub(crate) fn detect() -> Architectures {
+    let override_path = settings::load_settings().sidecar_path;
+    let sidecar = sidecar::checked_sidecar_paths(override_path.as_deref())
+        .into_iter()
+        .find(|(_, status)| *status == CandidateStatus::Present)
+        .and_then(|(path, _)| file_machine(&path));
+    let webview = tauri::webview_version().ok().and_then(|version| {
+        webview2_dirs(&version)
+            .into_iter()
+            .find_map(|dir| file_machine(&dir.join("msedgewebview2.exe")))
+    });
+    Architectures {
+        native: native_machine(),
+        shell: Machine::current(),
+        sidecar,
+        webview,
+    }
+}
+
+// file_machine reads the target machine from a PE executable's
+// file header, or None if path is not one.
+fn file_machine(path: &Path) -> Option<Machine> {
+    let mut header = Vec::new();
+    File::open(path)
+        .ok()?
+        .take(PE_HEADER_BYTES)
+        .read_to_end(&mut header)
+        .ok()?;
+    pe_machine(&header)
+}
+
+fn pe_machine(header: &[u8]) -> Option<Machine> {
+    if !header.starts_with(b"MZ") {
+        return None;
+    }
+    let offset_bytes = header.get(0x3c..0x40)?;
+    let offset = u32::from_le_bytes(offset_bytes.try_into().ok()?) as usize;
+    let signature = header.get(offset..offset.checked_add(6)?)?;
+    if &signature[..4] != b"PE\0\0" {
+        return None;
+    }
+    Some(Machine::from_code(u16::from_le_bytes([
+        signature[4],
+        signature[5],
+    ])))
+}
+
+// webview2_dirs lists where the Evergreen WebView2 runtime keeps
+// a version: machine-wide installs, then per-user ones.
+fn webview2_dirs(version: &str) -> Vec<PathBuf> {
+    ["ProgramFiles(x86)", "ProgramFiles", "LOCALAPPDATA"]
+        .iter()
+        .filter_map(|key| std::env::var_os(key))
+        .map(|root| {
+            PathBuf::from(root)
+                .join("Microsoft")
+                .join("EdgeWebView")
+                .join("Application")
+                .join(version)
+        })
+        .collect()
+}
+
+#[cfg(windows)]
+fn native_machine() -> Option<Machine> {
+    use std::ffi::c_void;
+
+    type IsWow64Process2 = unsafe extern "system" fn(*mut c_void, *mut u16, *mut u16) -> i32;
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetCurrentProcess() -> *mut c_void;
+        fn GetModuleHandleW(name: *const u16) -> *mut c_void;
+        fn GetProcAddress(module: *mut c_void, name: *const u8) -> *mut c_void;
+    }
+
+    let kernel32: Vec<u16> = "kernel32.dll\0".encode_utf16().collect();
+    // IsWow64Process2 is looked up at runtime because Windows 10
+    // before 1709 does not have it.
+    // SAFETY: both names are NUL-terminated, kernel32 is always
+    // loaded, and a non-null address has IsWow64Process2's
+    // signature. GetCurrentProcess returns a pseudo-handle that
+    // needs no closing, and the out-pointers are valid for writes.
+    unsafe {
+        let function = GetProcAddress(
+            GetModuleHandleW(kernel32.as_ptr()),
+            b"IsWow64Process2\0".as_ptr(),
+        );
+        if function.is_null() {
+            return Some(Machine::current());
+        }
+        let is_wow64_process2 = std::mem::transmute::<*mut c_void, IsWow64Process2>(function);
+        let (mut process, mut native) = (0u16, 0u16);
+        if is_wow64_process2(GetCurrentProcess(), &mut process, &mut native) == 0 {
+            return None;
+        }
+        Some(Machine::from_code(native))
+    }
+}
+
+#[cfg(not(windows))]
+fn native_machine() -> Option<Machine> {
+    None
+}
+
+// warn_if_emulated tells the user, once, when an x64 build of the
+// app runs under emulation on ARM64 Windows, offering the ARM64
+// download. no-network builds only explain.
+pub(crate) fn warn_if_emulated(app: &AppHandle) {
+    if settings::load_settings().emulation_warning_dismissed {
+        return;
+    }
+    let Some(machine) = detect().emulated() else {
+        return;
+    };
+    eprintln!(
+        "[agentsview] running the {} build under emulation on ARM64",
+        machine.name()
+    );
+    let buttons = if cfg!(feature = "no-network") {
+        MessageDialogButtons::OkCustom(i18n::tr("arch-emulated-continue"))
+    } else {
+        MessageDialogButtons::OkCancelCustom(
+            i18n::tr("arch-emulated-download"),
+            i18n::tr("arch-emulated-continue"),
+        )
+    };
+    let handle = app.clone();
+    app.dialog()
+        .message(i18n::tr_args(
+            "arch-emulated-message",
+            &[("machine", &machine.name())],
+        ))
+        .title(i18n::tr("arch-emulated-title"))
+        .kind(MessageDialogKind::Warning)
+        .buttons(buttons)
+        .show(move |chose_download| {
+            if chose_download && !cfg!(feature = "no-network") {
+                open_releases(&handle);
+                return;
+            }
+            if let Err(err) = settings::update_settings(|s| s.emulation_warning_dismissed = true) {
+                eprintln!("[agentsview] failed to save emulation warning setting: {err}");
+            }
+        });
+}
+
+// open_releases opens the latest release's download page.
+#[cfg(not(feature = "no-network"))]
+pub(crate) fn open_releases(app: &AppHandle) {
+    use tauri_plugin_shell::ShellExt;
+
+    #[allow(deprecated)]
+    if let Err(err) = app.shell().open(RELEASES_URL, None) {
+        eprintln!("[agentsview] failed to open the releases page: {err}");
+    }
+}
+
+#[cfg(feature = "no-network")]
+pub(crate) fn open_releases(_app: &AppHandle) {}
+
+// ensure_webview2 stops startup with a native prompt when the
+// WebView2 runtime is missing, since Tauri cannot create a window
+// without it. It runs before the Tauri builder, so the prompt is a
+// plain Win32 message box. Yes opens the runtime download, except
+// in no-network builds.
+#[cfg(windows)]
+pub(crate) fn ensure_webview2() {
+    let Err(err) = tauri::webview_version() else {
+        return;
+    };
+    eprintln!("[agentsview] WebView2 runtime not found: {err}");
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn MessageBoxW(
+            window: *mut std::ffi::c_void,
+            text: *const u16,
+            caption: *const u16,
+            style: u32,
+        ) -> i32;
+    }
+    const MB_OK: u32 = 0x0;
+    const MB_YESNO: u32 = 0x4;
+    const MB_ICONERROR: u32 = 0x10;
+    const IDYES: i32 = 6;
+
+    let key = if cfg!(feature = "no-network") {
+        "webview2-missing-offline"
+    } else {
+        "webview2-missing-message"
+    };
+    let wide = |text: String| -> Vec<u16> { text.encode_utf16().chain([0]).collect() };
+    let text = wide(i18n::tr_args(
+        key,
+        &[(
+            "machine",
+            &native_machine().unwrap_or(Machine::current()).name(),
+        )],
+    ));
+    let caption = wide(i18n::tr("webview2-missing-title"));
+    let buttons = if cfg!(feature = "no-network") {
+        MB_OK
+    } else {
+        MB_YESNO
+    };
+    // SAFETY: text and caption are NUL-terminated UTF-16 buffers
+    // that outlive the call, and a null owner window is allowed.
+    let choice = unsafe {
+        MessageBoxW(
+            std::ptr::null_mut(),
+            text.as_ptr(),
+            caption.as_ptr(),
+            buttons | MB_ICONERROR,
+        )
+    };
+    if choice == IDYES {
+        open_webview2_download();
+    }
+    std::process::exit(1);
+}
+
+#[cfg(all(windows, not(feature = "no-network")))]
+fn open_webview2_download() {
+    if let Err(err) = std::process::Command::new("explorer")
+        .arg(WEBVIEW2_DOWNLOAD_URL)
+        .spawn()
+    {
+        eprintln!("[agentsview] failed to open the WebView2 download: {err}");
+    }
+}
+
+#[cfg(all(windows, feature = "no-network"))]
+fn open_webview2_download() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // pe_header builds the smallest header pe_machine accepts.
+    fn pe_header(machine: u16) -> Vec<u8> {
+        let mut header = vec![0u8; 0x86];
+        header[..2].copy_from_slice(b"MZ");
+        header[0x3c..0x40].copy_from_slice(&0x80u32.to_le_bytes());
+        header[0x80..0x84].copy_from_slice(b"PE\0\0");
+        header[0x84..0x86].copy_from_slice(&machine.to_le_bytes());
+        header
+    }
+
+    #[test]
+    fn pe_machine_reads_the_file_header() {
+        assert_eq!(pe_machine(&pe_header(0x8664)), Some(Machine::X64));
+        assert_eq!(pe_machine(&pe_header(0xaa64)), Some(Machine::Arm64));
+        assert_eq!(pe_machine(&pe_header(0x01c4)), Some(Machine::Other(0x01c4)));
+        assert_eq!(pe_machine(b"\x7fELF"), None);
+        assert_eq!(pe_machine(&pe_header(0x8664)[..0x84]), None);
+    }
+
+    #[test]
+    fn architectures_flag_emulation_only_on_arm64() {
+        let native_arm64 = Architectures {
+            native: Some(Machine::Arm64),
+            shell: Machine::Arm64,
+            sidecar: Some(Machine::Arm64),
+            webview: Some(Machine::Arm64),
+        };
+        assert_eq!(native_arm64.emulated(), None);
+        assert!(!native_arm64.webview_mismatch());
+
+        let x64_sidecar = Architectures {
+            sidecar: Some(Machine::X64),
+            ..native_arm64
+        };
+        assert_eq!(x64_sidecar.emulated(), Some(Machine::X64));
+
+        let x64_app = Architectures {
+            shell: Machine::X64,
+            sidecar: Some(Machine::X64),
+            webview: Some(Machine::X64),
+            ..native_arm64
+        };
+        assert_eq!(x64_app.emulated(), Some(Machine::X64));
+        assert!(x64_app.webview_mismatch());
+
+        let x64_pc = Architectures {
+            native: Some(Machine::X64),
+            ..x64_app
+        };
+        assert_eq!(x64_pc.emulated(), None);
+        assert!(!x64_pc.webview_mismatch());
+    }
+}
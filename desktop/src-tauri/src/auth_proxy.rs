@@ -0,0 +1,380 @@
+use std::io::{self, Write};
+use std::net::{IpAddr, Ipv4Addr, Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+
+use tauri::AppHandle;
+
+use crate::settings::{self, DesktopSettings};
+use crate::sidecar::MIN_AUTH_TOKEN_LEN;
+use crate::socket_proxy::{self, Admission};
+
+// COOKIE holds the token once a browser has presented it in the
+// URL, as the backend's own LAN auth does.
+const COOKIE: &str = "agentsview_token";
+const TOKEN_PARAM: &str = "token";
+const UNAUTHORIZED: &[u8] = b"HTTP/1.1 401 Unauthorized\r\nWWW-Authenticate: Bearer\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const UNAVAILABLE: &[u8] = b"HTTP/1.1 503 Service Unavailable\r\nRetry-After: 2\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+// ProxyConfig is where the auth proxy listens and the token it
+// requires.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ProxyConfig {
+    addr: SocketAddr,
+    token: String,
+}
+
+// start opens the auth proxy when auth_proxy_port is set: a
+// listener on its own port that admits only clients presenting
+// auth_proxy_token and relays them to the local backend, wherever
+// the sidecar currently listens. It is the way to share the backend
+// with other machines without the backend's own LAN mode.
+pub(crate) fn start(app: &AppHandle) {
+    let config = match config(&settings::load_settings()) {
+        Ok(Some(config)) => config,
+        Ok(None) => return,
+        Err(reason) => {
+            eprintln!("[agentsview] not starting the auth proxy: {reason}");
+            return;
+        }
+    };
+    let listener = match TcpListener::bind(config.addr) {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!(
+                "[agentsview] auth proxy could not listen on {}: {err}",
+                config.addr
+            );
+            return;
+        }
+    };
+    eprintln!("[agentsview] auth proxy listening on {}", config.addr);
+    let token: Arc<str> = config.token.into();
+    let app = app.clone();
+    thread::spawn(move || {
+        for conn in listener.incoming() {
+            let Ok(conn) = conn else {
+                continue;
+            };
+            let app = app.clone();
+            let token = token.clone();
+            thread::spawn(move || {
+                if let Err(err) = forward(&app, conn, &token) {
+                    eprintln!("[agentsview] auth proxy request failed: {err}");
+                }
+            });
+        }
+    });
+}
+
+// config reads the proxy settings. The host defaults to loopback,
+// so exposing the backend to the network takes an explicit
+// auth_proxy_host such as 0.0.0.0.
+fn config(settings: &DesktopSettings) -> Result<Option<ProxyConfig>, &'static str> {
+    let Some(port) = settings.auth_proxy_port else {
+        return Ok(None);
+    };
+    let host = match settings.auth_proxy_host.as_deref().map(str::trim) {
+        None | Some("") => IpAddr::V4(Ipv4Addr::LOCALHOST),
+        Some(host) => host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse()
+            .map_err(|_| "auth_proxy_host must be an IP address")?,
+    };
+    let token = settings
+        .auth_proxy_token
+        .as_deref()
+        .map(str::trim)
+        .unwrap_or_default();
+    if token.len() < MIN_AUTH_TOKEN_LEN {
+        return Err("auth_proxy_token must be at least 16 characters");
+    }
+    Ok(Some(ProxyConfig {
+        addr: SocketAddr::new(host, port),
+        token: token.to_string(),
+    }))
+}
+
+// forward relays conn to the local backend, wherever the sidecar
+// currently listens.
+fn forward(app: &AppHandle, conn: TcpStream, token: &str) -> io::Result<()> {
+    let backend = crate::current_sidecar_port(app).map(crate::local_addr);
+    relay(conn, token, backend, socket_proxy::cookie().as_deref())
+}
+
+// relay checks the first request on conn and relays the connection
+// to backend in both directions until either side closes it. Only
+// that first head is rewritten, so rewrite_head asks the backend to
+// close the connection after answering it: a second request on the
+// connection would otherwise reach the backend with the client's
+// Host and Origin. Clients open a new connection for the next one.
+fn relay(
+    mut conn: TcpStream,
+    token: &str,
+    backend: Option<SocketAddr>,
+    cookie: Option<&str>,
+) -> io::Result<()> {
+    let head = socket_proxy::read_head(&mut conn)?;
+    match socket_proxy::admit(&head, COOKIE, TOKEN_PARAM, token) {
+        Admission::Allowed => {}
+        Admission::Login(location) => {
+            let response = format!(
+                "HTTP/1.1 302 Found\r\nLocation: {location}\r\nSet-Cookie: {COOKIE}={token}; Path=/; HttpOnly; SameSite=Strict\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            );
+            return conn.write_all(response.as_bytes());
+        }
+        Admission::Denied => return conn.write_all(UNAUTHORIZED),
+    }
+    let Some(addr) = backend else {
+        return conn.write_all(UNAVAILABLE);
+    };
+    let mut backend = match TcpStream::connect(addr) {
+        Ok(backend) => backend,
+        Err(_) => return conn.write_all(UNAVAILABLE),
+    };
+    backend.write_all(&rewrite_head(&head, addr, cookie))?;
+    let mut upstream = backend.try_clone()?;
+    let mut downstream = conn.try_clone()?;
+    let pump = thread::spawn(move || {
+        let _ = io::copy(&mut downstream, &mut upstream);
+        let _ = upstream.shutdown(Shutdown::Write);
+    });
+    let _ = io::copy(&mut backend, &mut conn);
+    let _ = conn.shutdown(Shutdown::Both);
+    let _ = pump.join();
+    Ok(())
+}
+
+// rewrite_head points a request head at the backend: its API only
+// answers a loopback Host, and checks Origin against the same
+// address. The socket proxy's cookie is added when the sidecar
+// serves behind it, so admitted clients get through, and
+// Connection: close replaces any keep-alive.
+fn rewrite_head(head: &[u8], backend: SocketAddr, cookie: Option<&str>) -> Vec<u8> {
+    let Some(end) = head.windows(4).position(|window| window == b"\r\n\r\n") else {
+        return head.to_vec();
+    };
+    let text = String::from_utf8_lossy(&head[..end]);
+    let mut lines = text.split("\r\n");
+    let mut rewritten = String::new();
+    rewritten.push_str(lines.next().unwrap_or_default());
+    rewritten.push_str("\r\n");
+    if let Some(cookie) = cookie {
+        rewritten.push_str(&format!("Cookie: {cookie}\r\n"));
+    }
+    for line in lines {
+        let name = line.split_once(':').map(|(name, _)| name.trim());
+        match name {
+            Some(name) if name.eq_ignore_ascii_case("host") => {
+                rewritten.push_str(&format!("Host: {backend}\r\n"));
+            }
+            Some(name) if name.eq_ignore_ascii_case("origin") => {
+                rewritten.push_str(&format!("Origin: http://{backend}\r\n"));
+            }
+            Some(name)
+                if name.eq_ignore_ascii_case("connection")
+                    || name.eq_ignore_ascii_case("keep-alive") => {}
+            _ => {
+                rewritten.push_str(line);
+                rewritten.push_str("\r\n");
+            }
+        }
+    }
+    rewritten.push_str("Connection: close\r\n");
+    rewritten.push_str("\r\n");
+    let mut bytes = rewritten.into_bytes();
+    bytes.extend_from_slice(&head[end + 4..]);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    const TOKEN: &str = "0123456789abcdef";
+
+    #[test]
+    fn admit_takes_the_token_as_bearer_cookie_or_query() {
+        let request = |target: &str, header: &str| {
+            format!("GET {target} HTTP/1.1\r\nHost: 192.168.1.20:8443\r\n{header}\r\n")
+        };
+        let admit = |target: &str, header: &str| {
+            socket_proxy::admit(
+                request(target, header).as_bytes(),
+                COOKIE,
+                TOKEN_PARAM,
+                TOKEN,
+            )
+        };
+        assert_eq!(
+            admit(
+                "/api/v1/sessions",
+                "Authorization: Bearer 0123456789abcdef\r\n"
+            ),
+            Admission::Allowed
+        );
+        assert_eq!(
+            admit(
+                "/",
+                "Cookie: theme=dark; agentsview_token=0123456789abcdef\r\n"
+            ),
+            Admission::Allowed
+        );
+        assert_eq!(
+            admit("/sessions?q=1&token=0123456789abcdef", ""),
+            Admission::Login("/sessions?q=1".to_string())
+        );
+        assert_eq!(admit("/api/v1/sessions", ""), Admission::Denied);
+        assert_eq!(
+            admit("/", "Authorization: Bearer 0123456789abcdeX\r\n"),
+            Admission::Denied
+        );
+        assert_eq!(admit("/?token=", ""), Admission::Denied);
+    }
+
+    #[test]
+    fn relay_points_host_and_origin_at_the_backend() {
+        // The backend stands in for hostCheckMiddleware: API requests
+        // with any other Host are forbidden.
+        let backend = TcpListener::bind("127.0.0.1:0").expect("bind backend");
+        let backend_addr = backend.local_addr().expect("backend address");
+        let server = thread::spawn(move || {
+            let (mut conn, _) = backend.accept().expect("accept proxied request");
+            let head = socket_proxy::read_head(&mut conn).expect("read proxied head");
+            let head = String::from_utf8_lossy(&head).to_string();
+            let expected_host = format!("\r\nHost: {backend_addr}\r\n");
+            let status = if head.contains(&expected_host) {
+                "200 OK"
+            } else {
+                "403 Forbidden"
+            };
+            let response =
+                format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+            conn.write_all(response.as_bytes()).expect("respond");
+            head
+        });
+
+        let proxy = TcpListener::bind("127.0.0.1:0").expect("bind proxy");
+        let proxy_addr = proxy.local_addr().expect("proxy address");
+        let relayed = thread::spawn(move || {
+            let (conn, _) = proxy.accept().expect("accept client");
+            relay(
+                conn,
+                TOKEN,
+                Some(backend_addr),
+                Some("agentsview_proxy=s3cret"),
+            )
+        });
+
+        let mut client = TcpStream::connect(proxy_addr).expect("connect to proxy");
+        client
+            .write_all(
+                b"GET /api/v1/version HTTP/1.1\r\nHost: 192.168.1.20:8443\r\nOrigin: http://192.168.1.20:8443\r\nAuthorization: Bearer 0123456789abcdef\r\n\r\n",
+            )
+            .expect("send request");
+        let mut response = String::new();
+        client.read_to_string(&mut response).expect("read response");
+        relayed.join().expect("relay thread").expect("relay");
+        let seen = server.join().expect("backend thread");
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        assert!(seen.contains(&format!("\r\nOrigin: http://{backend_addr}\r\n")));
+        assert!(
+            seen.starts_with("GET /api/v1/version HTTP/1.1\r\nCookie: agentsview_proxy=s3cret\r\n")
+        );
+        assert!(!seen.contains("192.168.1.20"));
+    }
+
+    #[test]
+    fn relay_closes_the_connection_after_the_rewritten_request() {
+        // The backend stands in for Go's server: it answers requests
+        // on a connection until one asks it to close, and forbids any
+        // whose Host is not its own.
+        let backend = TcpListener::bind("127.0.0.1:0").expect("bind backend");
+        let backend_addr = backend.local_addr().expect("backend address");
+        let server = thread::spawn(move || {
+            let (mut conn, _) = backend.accept().expect("accept proxied request");
+            let mut buffered = Vec::new();
+            let mut heads = Vec::new();
+            while heads.len() < 2 {
+                let Some(end) = buffered.windows(4).position(|window| window == b"\r\n\r\n") else {
+                    let mut chunk = [0u8; 4096];
+                    match conn.read(&mut chunk) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => buffered.extend_from_slice(&chunk[..n]),
+                    }
+                    continue;
+                };
+                let head = String::from_utf8_lossy(&buffered[..end + 4]).to_string();
+                buffered.drain(..end + 4);
+                let status = if head.contains(&format!("\r\nHost: {backend_addr}\r\n")) {
+                    "200 OK"
+                } else {
+                    "403 Forbidden"
+                };
+                let close = head.contains("\r\nConnection: close\r\n");
+                let response = format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\n\r\n");
+                conn.write_all(response.as_bytes()).expect("respond");
+                heads.push(head);
+                if close {
+                    break;
+                }
+            }
+            heads
+        });
+
+        let proxy = TcpListener::bind("127.0.0.1:0").expect("bind proxy");
+        let proxy_addr = proxy.local_addr().expect("proxy address");
+        let relayed = thread::spawn(move || {
+            let (conn, _) = proxy.accept().expect("accept client");
+            relay(conn, TOKEN, Some(backend_addr), None)
+        });
+
+        let request = b"GET /api/v1/version HTTP/1.1\r\nHost: 192.168.1.20:8443\r\nConnection: keep-alive\r\nAuthorization: Bearer 0123456789abcdef\r\n\r\n";
+        let mut client = TcpStream::connect(proxy_addr).expect("connect to proxy");
+        client.write_all(request).expect("send first request");
+        let mut first = Vec::new();
+        let mut byte = [0u8; 1];
+        while !first.ends_with(b"\r\n\r\n") {
+            client.read_exact(&mut byte).expect("read first response");
+            first.push(byte[0]);
+        }
+        // The second request finds the connection closing; a client
+        // would retry it on a new one.
+        let _ = client.write_all(request);
+        let mut rest = Vec::new();
+        let _ = client.read_to_end(&mut rest);
+        relayed.join().expect("relay thread").expect("relay");
+        let seen = server.join().expect("backend thread");
+
+        let first = String::from_utf8_lossy(&first);
+        assert!(first.starts_with("HTTP/1.1 200 OK"), "{first}");
+        assert!(rest.is_empty(), "{}", String::from_utf8_lossy(&rest));
+        assert_eq!(seen.len(), 1, "{seen:?}");
+        assert!(seen[0].contains("\r\nConnection: close\r\n"));
+        assert!(!seen[0].contains("keep-alive"));
+    }
+
+    #[test]
+    fn config_needs_a_port_and_a_long_token() {
+        let mut settings = DesktopSettings::default();
+        assert_eq!(config(&settings), Ok(None));
+
+        settings.auth_proxy_port = Some(8443);
+        settings.auth_proxy_token = Some("short".to_string());
+        assert!(config(&settings).is_err());
+
+        settings.auth_proxy_token = Some(TOKEN.to_string());
+        let local = config(&settings).unwrap().unwrap();
+        assert_eq!(local.addr, "127.0.0.1:8443".parse().unwrap());
+
+        settings.auth_proxy_host = Some("0.0.0.0".to_string());
+        let shared = config(&settings).unwrap().unwrap();
+        assert_eq!(shared.addr, "0.0.0.0:8443".parse().unwrap());
+
+        settings.auth_proxy_host = Some("lan.example".to_string());
+        assert!(config(&settings).is_err());
+    }
+}
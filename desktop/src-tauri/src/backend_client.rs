@@ -0,0 +1,406 @@
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::error::DesktopError;
+use crate::hardening;
+use crate::ports;
+use crate::proxy::{self, ProxyServer};
+use crate::remote::RemoteState;
+use crate::socket_proxy;
+use crate::SidecarState;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(500);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_RESPONSE_BYTES: usize = 4 * 1024 * 1024;
+// CANCEL_CHECK_INTERVAL bounds each blocking read so a cancelled
+// request stops promptly.
+const CANCEL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+// BackendClient sends small JSON API requests from the shell to
+// the backend the main window shows: the local sidecar or an
+// attached http remote server. Requests use HTTP/1.0 so bodies
+// arrive unchunked and end when the server closes the connection.
+// Requests to a remote go through the system proxy, if one applies
+// (see proxy::for_url); loopback requests never do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BackendClient {
+    host: String,
+    port: u16,
+    // cookie admits the client to socket_proxy; see
+    // socket_proxy::cookie.
+    cookie: Option<String>,
+    proxy: Option<ProxyServer>,
+}
+
+impl BackendClient {
+    pub(crate) fn local(port: u16) -> Self {
+        Self {
+            host: ports::loopback_host(),
+            port,
+            cookie: socket_proxy::cookie(),
+            proxy: None,
+        }
+    }
+
+    // for_app returns a client for the running sidecar, falling
+    // back to the attached remote server. https remotes are not
+    // supported since the client speaks plain HTTP only.
+    pub(crate) fn for_app(app: &AppHandle) -> Option<Self> {
+        let local_port = app
+            .state::<SidecarState>()
+            .backend_port
+            .lock()
+            .ok()
+            .and_then(|guard| *guard);
+        if let Some(port) = local_port {
+            return Some(Self::local(port));
+        }
+        let origin = app.state::<RemoteState>().origin()?;
+        if origin.scheme() != "http" {
+            return None;
+        }
+        Some(Self {
+            host: origin.host_str()?.to_string(),
+            port: origin.port_or_known_default()?,
+            cookie: None,
+            proxy: proxy::for_url(&origin),
+        })
+    }
+
+    // get_json_until fetches and decodes path, giving up and
+    // returning None once cancelled reports true. It is checked
+    // between reads.
+    pub(crate) fn get_json_until<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        cancelled: &dyn Fn() -> bool,
+    ) -> Result<Option<T>, DesktopError> {
+        let Some(response) = self.send("GET", path, None, cancelled)? else {
+            return Ok(None);
+        };
+        parse_json_response(path, &response).map(Some)
+    }
+
+    // post_json sends body as JSON and decodes the response.
+    pub(crate) fn post_json<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+    ) -> Result<T, DesktopError> {
+        let body = serde_json::to_vec(body).map_err(|err| request_error(path, &err.to_string()))?;
+        let response = self
+            .send("POST", path, Some(&body), &|| false)?
+            .ok_or_else(|| request_error(path, "cancelled"))?;
+        parse_json_response(path, &response)
+    }
+
+    // watch_events follows the server-sent events at path and calls
+    // on_event with each event's name. It returns once the server
+    // closes the stream, or fails when nothing, heartbeats
+    // included, arrives for idle.
+    pub(crate) fn watch_events(
+        &self,
+        path: &str,
+        idle: Duration,
+        on_event: &mut dyn FnMut(&str),
+    ) -> Result<(), DesktopError> {
+        let fail = |reason: &str| request_error(path, reason);
+        let mut stream = self.open("GET", path, "text/event-stream", None)?;
+        let _ = stream.set_read_timeout(Some(idle));
+        let mut pending = Vec::with_capacity(4096);
+        let mut streaming = false;
+        let mut chunk = [0u8; 4096];
+        loop {
+            match stream.read(&mut chunk) {
+                Ok(0) => return Ok(()),
+                Ok(n) => pending.extend_from_slice(&chunk[..n]),
+                Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    return Err(fail("event stream went quiet"))
+                }
+                Err(err) => return Err(fail(&err.to_string())),
+            }
+            if !streaming {
+                let Some(end) = pending.windows(4).position(|w| w == b"\r\n\r\n") else {
+                    continue;
+                };
+                check_status(path, &pending[..end])?;
+                pending.drain(..end + 4);
+                streaming = true;
+            }
+            while let Some(newline) = pending.iter().position(|&byte| byte == b'\n') {
+                let line: Vec<u8> = pending.drain(..=newline).collect();
+                let line = String::from_utf8_lossy(&line);
+                if let Some(name) = line.trim_end().strip_prefix("event:") {
+                    on_event(name.trim());
+                }
+            }
+            if pending.len() > MAX_RESPONSE_BYTES {
+                return Err(fail("event too large"));
+            }
+        }
+    }
+
+    fn send(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<&[u8]>,
+        cancelled: &dyn Fn() -> bool,
+    ) -> Result<Option<Vec<u8>>, DesktopError> {
+        let fail = |reason: String| request_error(path, &reason);
+        let mut stream = self.open(method, path, "application/json", body)?;
+        let deadline = Instant::now() + REQUEST_TIMEOUT;
+        let mut response = Vec::with_capacity(8192);
+        let mut chunk = [0u8; 8192];
+        loop {
+            if cancelled() {
+                return Ok(None);
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(fail("timed out".to_string()));
+            }
+            let _ = stream.set_read_timeout(Some(remaining.min(CANCEL_CHECK_INTERVAL)));
+            match stream.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(n) => response.extend_from_slice(&chunk[..n]),
+                Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+                Err(err) => return Err(fail(err.to_string())),
+            }
+            if response.len() > MAX_RESPONSE_BYTES {
+                return Err(fail("response too large".to_string()));
+            }
+        }
+        Ok(Some(response))
+    }
+
+    // open connects and writes the request, leaving the response
+    // to be read from the returned stream.
+    fn open(
+        &self,
+        method: &str,
+        path: &str,
+        accept: &str,
+        body: Option<&[u8]>,
+    ) -> Result<TcpStream, DesktopError> {
+        let fail = |reason: String| request_error(path, &reason);
+        // host is as written in URLs, with IPv6 addresses bracketed.
+        let unbracketed = self.host.trim_start_matches('[').trim_end_matches(']');
+        let (connect_host, connect_port) = match &self.proxy {
+            Some(proxy) => (proxy.host.as_str(), proxy.port),
+            None => (unbracketed, self.port),
+        };
+        let addr = (connect_host, connect_port)
+            .to_socket_addrs()
+            .map_err(|err| fail(err.to_string()))?
+            .next()
+            .ok_or_else(|| fail("host did not resolve".to_string()))?;
+        let mut stream = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)
+            .map_err(|err| fail(err.to_string()))?;
+        let _ = stream.set_write_timeout(Some(CONNECT_TIMEOUT));
+        let authority = format!("{}:{}", self.host, self.port);
+        // A proxy takes the full URL in place of the path.
+        let target = match &self.proxy {
+            Some(_) => format!("http://{authority}{path}"),
+            None => path.to_string(),
+        };
+        let mut request = format!(
+            "{method} {target} HTTP/1.0\r\nHost: {authority}\r\nUser-Agent: {}\r\nAccept: {accept}\r\n",
+            hardening::user_agent()
+        );
+        if let Some(proxy) = &self.proxy {
+            request.push_str(&proxy.authorization());
+        }
+        if let Some(cookie) = &self.cookie {
+            request.push_str(&format!("Cookie: {cookie}\r\n"));
+        }
+        if let Some(body) = body {
+            request.push_str(&format!(
+                "Content-Type: application/json\r\nContent-Length: {}\r\n",
+                body.len()
+            ));
+        }
+        request.push_str("\r\n");
+        let mut request = request.into_bytes();
+        request.extend_from_slice(body.unwrap_or_default());
+        stream
+            .write_all(&request)
+            .map_err(|err| fail(err.to_string()))?;
+        Ok(stream)
+    }
+}
+
+fn request_error(path: &str, reason: &str) -> DesktopError {
+    DesktopError::BackendRequest {
+        path: path.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+// parse_json_response checks the status and decodes the body
+// after the header block.
+fn parse_json_response<T: DeserializeOwned>(
+    path: &str,
+    response: &[u8],
+) -> Result<T, DesktopError> {
+    let Some(end) = response.windows(4).position(|w| w == b"\r\n\r\n") else {
+        return Err(request_error(path, "incomplete response"));
+    };
+    check_status(path, &response[..end])?;
+    serde_json::from_slice(&response[end + 4..])
+        .map_err(|err| request_error(path, &err.to_string()))
+}
+
+// check_status accepts a header block with a 2xx status.
+// Backends that predate an endpoint answer 404 or 405, or serve
+// the frontend's index.html for unknown paths; both are reported
+// as BackendUnsupported.
+fn check_status(path: &str, head: &[u8]) -> Result<(), DesktopError> {
+    let head = String::from_utf8_lossy(head).to_ascii_lowercase();
+    let mut lines = head.lines();
+    let status_line = lines.next().unwrap_or_default();
+    let status = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse::<u16>().ok());
+    let html = lines.any(|line| {
+        line.strip_prefix("content-type:")
+            .is_some_and(|value| value.trim().starts_with("text/html"))
+    });
+    match status {
+        Some(404 | 405) => Err(DesktopError::BackendUnsupported {
+            path: path.to_string(),
+        }),
+        Some(200..=299) if html => Err(DesktopError::BackendUnsupported {
+            path: path.to_string(),
+        }),
+        Some(200..=299) => Ok(()),
+        _ => Err(request_error(
+            path,
+            &format!("unexpected status: {status_line}"),
+        )),
+    }
+}
+
+// encode_query_component percent-encodes a query parameter value.
+pub(crate) fn encode_query_component(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{Ipv4Addr, SocketAddrV4, TcpListener};
+    use std::thread;
+
+    fn serve_once(response: &'static [u8], delay: Duration) -> u16 {
+        let listener =
+            TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0)).expect("bind listener");
+        let port = listener.local_addr().expect("local addr").port();
+        thread::spawn(move || {
+            let (mut conn, _) = listener.accept().expect("accept");
+            let mut request = [0u8; 1024];
+            let _ = conn.read(&mut request);
+            thread::sleep(delay);
+            let _ = conn.write_all(response);
+        });
+        port
+    }
+
+    #[test]
+    fn get_json_until_decodes_ok_responses() {
+        let port = serve_once(
+            b"HTTP/1.0 200 OK\r\nContent-Type: application/json\r\n\r\n{\"count\":2}",
+            Duration::ZERO,
+        );
+        let body: Option<serde_json::Value> = BackendClient::local(port)
+            .get_json_until("/api/v1/stats", &|| false)
+            .expect("json response");
+        assert_eq!(body.expect("not cancelled")["count"], 2);
+
+        let port = serve_once(
+            b"HTTP/1.0 501 Not Implemented\r\n\r\n{\"error\":\"search not available\"}",
+            Duration::ZERO,
+        );
+        let err = BackendClient::local(port)
+            .get_json_until::<serde_json::Value>("/api/v1/search?q=x", &|| false)
+            .expect_err("non-200 status");
+        assert_eq!(err.kind(), "backend_request");
+    }
+
+    #[test]
+    fn post_json_reports_missing_endpoints_as_unsupported() {
+        let port = serve_once(
+            b"HTTP/1.0 201 Created\r\nContent-Type: application/json\r\n\r\n{\"id\":7}",
+            Duration::ZERO,
+        );
+        let created: serde_json::Value = BackendClient::local(port)
+            .post_json("/api/v1/notes", &serde_json::json!({"body": "x"}))
+            .expect("json response");
+        assert_eq!(created["id"], 7);
+
+        for response in [
+            &b"HTTP/1.0 404 Not Found\r\n\r\n"[..],
+            &b"HTTP/1.0 200 OK\r\nContent-Type: text/html; charset=utf-8\r\n\r\n<!doctype html>"[..],
+        ] {
+            let port = serve_once(response, Duration::ZERO);
+            let err = BackendClient::local(port)
+                .post_json::<serde_json::Value>("/api/v1/notes", &serde_json::json!({}))
+                .expect_err("missing endpoint");
+            assert_eq!(err.kind(), "backend_unsupported");
+        }
+    }
+
+    #[test]
+    fn get_json_until_stops_when_cancelled() {
+        let port = serve_once(b"HTTP/1.0 200 OK\r\n\r\n{}", Duration::from_secs(3));
+        let started = Instant::now();
+        let result = BackendClient::local(port)
+            .get_json_until::<serde_json::Value>("/api/v1/stats", &|| {
+                started.elapsed() > Duration::from_millis(50)
+            })
+            .expect("no error");
+        assert!(result.is_none());
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn watch_events_reports_event_names_until_closed() {
+        let port = serve_once(
+            b"HTTP/1.0 200 OK\r\nContent-Type: text/event-stream\r\n\r\nevent: heartbeat\ndata: now\n\nevent: sessions_changed\ndata: \n\n",
+            Duration::ZERO,
+        );
+        let mut events = Vec::new();
+        BackendClient::local(port)
+            .watch_events("/api/v1/events", Duration::from_secs(5), &mut |name| {
+                events.push(name.to_string())
+            })
+            .expect("stream closes cleanly");
+        assert_eq!(events, ["heartbeat", "sessions_changed"]);
+
+        let port = serve_once(b"HTTP/1.0 404 Not Found\r\n\r\n", Duration::ZERO);
+        let err = BackendClient::local(port)
+            .watch_events("/api/v1/events", Duration::from_secs(5), &mut |_| {})
+            .expect_err("missing endpoint");
+        assert_eq!(err.kind(), "backend_unsupported");
+    }
+
+    #[test]
+    fn encode_query_component_escapes_reserved_bytes() {
+        assert_eq!(encode_query_component("fix bug&x=1"), "fix%20bug%26x%3D1");
+        assert_eq!(encode_query_component("café"), "caf%C3%A9");
+    }
+}
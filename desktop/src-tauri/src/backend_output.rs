@@ -0,0 +1,110 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+// CAPACITY is how many lines of sidecar output are kept, across
+// launches. Older lines are dropped first.
+const CAPACITY: usize = 2_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Stream {
+    Stdout,
+    Stderr,
+}
+
+// OutputLine is one line the sidecar wrote, tagged with the launch
+// attempt that wrote it so restarts can be told apart.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct OutputLine {
+    pub(crate) attempt: u64,
+    pub(crate) stream: Stream,
+    pub(crate) text: String,
+}
+
+// BackendOutput is a ring buffer of recent sidecar output, kept
+// for crash reports and diagnostics after the lines have been
+// written to the shell's own stderr.
+#[derive(Debug, Default)]
+pub(crate) struct BackendOutput {
+    lines: Mutex<VecDeque<OutputLine>>,
+}
+
+impl BackendOutput {
+    pub(crate) fn push(&self, attempt: u64, stream: Stream, text: &str) {
+        let Ok(mut lines) = self.lines.lock() else {
+            return;
+        };
+        if lines.len() == CAPACITY {
+            lines.pop_front();
+        }
+        lines.push_back(OutputLine {
+            attempt,
+            stream,
+            text: text.trim_end().to_string(),
+        });
+    }
+
+    pub(crate) fn snapshot(&self) -> Vec<OutputLine> {
+        self.lines
+            .lock()
+            .map(|lines| lines.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    // tail returns up to limit of the newest lines attempt wrote to
+    // stream, oldest first.
+    pub(crate) fn tail(&self, attempt: u64, stream: Stream, limit: usize) -> Vec<String> {
+        let Ok(lines) = self.lines.lock() else {
+            return Vec::new();
+        };
+        let mut tail: Vec<String> = lines
+            .iter()
+            .rev()
+            .filter(|line| line.attempt == attempt && line.stream == stream)
+            .take(limit)
+            .map(|line| line.text.clone())
+            .collect();
+        tail.reverse();
+        tail
+    }
+}
+
+// backend_output returns the buffered sidecar output, oldest line
+// first.
+#[tauri::command]
+pub(crate) fn backend_output(state: tauri::State<'_, BackendOutput>) -> Vec<OutputLine> {
+    state.snapshot()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_newest_lines() {
+        let output = BackendOutput::default();
+        for n in 0..CAPACITY + 5 {
+            output.push(1, Stream::Stdout, &format!("line {n}\n"));
+        }
+        let lines = output.snapshot();
+        assert_eq!(lines.len(), CAPACITY);
+        assert_eq!(lines[0].text, "line 5");
+        assert_eq!(lines[CAPACITY - 1].text, format!("line {}", CAPACITY + 4));
+    }
+
+    #[test]
+    fn tail_filters_by_launch_and_stream() {
+        let output = BackendOutput::default();
+        output.push(1, Stream::Stderr, "old launch");
+        output.push(2, Stream::Stderr, "first");
+        output.push(2, Stream::Stdout, "listening");
+        output.push(2, Stream::Stderr, "second");
+        output.push(2, Stream::Stderr, "third");
+
+        assert_eq!(output.tail(2, Stream::Stderr, 2), ["second", "third"]);
+        assert_eq!(output.tail(2, Stream::Stderr, 10).len(), 3);
+        assert_eq!(output.tail(3, Stream::Stderr, 10), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,145 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+use serde::Deserialize;
+use tauri::{AppHandle, Manager};
+
+use crate::backend_client::BackendClient;
+use crate::i18n;
+use crate::shutdown::ShutdownSignal;
+use crate::{arch, startup_dialog};
+
+// MIN_BACKEND and MAX_BACKEND bound the backend releases this
+// shell is built against: MIN_BACKEND is the oldest that serves
+// every API the shell and its pages call, and MAX_BACKEND is the
+// first release line that may change them.
+const MIN_BACKEND: Version = Version(0, 1, 0);
+const MAX_BACKEND: Version = Version(1, 0, 0);
+
+// WARNED is set once the mismatch warning has been shown, so a
+// restarted backend of the same version does not repeat it.
+static WARNED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Version(u64, u64, u64);
+
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.0, self.1, self.2)
+    }
+}
+
+#[derive(Deserialize)]
+struct VersionInfo {
+    version: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mismatch {
+    TooOld,
+    TooNew,
+}
+
+// spawn_check compares the version of the backend on port with the
+// range this shell supports once it is ready, and warns when they
+// diverge, as after a partial update, before mismatched APIs show
+// up as empty panels. It warns once per launch.
+pub(crate) fn spawn_check(app: AppHandle, port: u16) {
+    if WARNED.load(Ordering::SeqCst) {
+        return;
+    }
+    let shutdown = app.state::<ShutdownSignal>().inner().clone();
+    thread::spawn(move || {
+        let info = match BackendClient::local(port)
+            .get_json_until::<VersionInfo>("/api/v1/version", &|| shutdown.is_triggered())
+        {
+            Ok(Some(info)) => info,
+            Ok(None) => return,
+            Err(err) => {
+                eprintln!("[agentsview] failed to read the backend version: {err}");
+                return;
+            }
+        };
+        let Some(mismatch) = parse_version(&info.version).and_then(mismatch) else {
+            return;
+        };
+        if WARNED.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        eprintln!(
+            "[agentsview] backend {} is outside the supported range {MIN_BACKEND} to {MAX_BACKEND}",
+            info.version
+        );
+        let key = match mismatch {
+            Mismatch::TooOld => "backend-version-old",
+            Mismatch::TooNew => "backend-version-new",
+        };
+        let message = format!(
+            "{}\n\n{}",
+            i18n::tr_args(
+                key,
+                &[
+                    ("backend", &info.version),
+                    ("min", &MIN_BACKEND),
+                    ("max", &MAX_BACKEND),
+                ],
+            ),
+            i18n::tr("backend-version-advice")
+        );
+        let download_app = app.clone();
+        startup_dialog::show_version_mismatch(&app, message, move || {
+            arch::open_releases(&download_app)
+        });
+    });
+}
+
+// parse_version reads the release from a backend version string
+// such as v0.12.0 or v0.12.0-3-gabc1234-dirty. Development builds,
+// reporting dev or a bare commit hash, have none and are not
+// checked.
+fn parse_version(text: &str) -> Option<Version> {
+    let text = text.trim();
+    let text = text.strip_prefix('v').unwrap_or(text);
+    let end = text
+        .find(|ch: char| !ch.is_ascii_digit() && ch != '.')
+        .unwrap_or(text.len());
+    let mut parts = text[..end].split('.').map(|part| part.parse::<u64>().ok());
+    let version = Version(parts.next()??, parts.next()??, parts.next()??);
+    parts.next().is_none().then_some(version)
+}
+
+fn mismatch(version: Version) -> Option<Mismatch> {
+    if version < MIN_BACKEND {
+        Some(Mismatch::TooOld)
+    } else if version >= MAX_BACKEND {
+        Some(Mismatch::TooNew)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_version_reads_release_tags() {
+        assert_eq!(parse_version("v0.12.3"), Some(Version(0, 12, 3)));
+        assert_eq!(
+            parse_version("v0.12.3-4-gabc1234-dirty"),
+            Some(Version(0, 12, 3))
+        );
+        assert_eq!(parse_version("1.0.0"), Some(Version(1, 0, 0)));
+        for dev in ["dev", "abc1234", "1234abc", "mock", "v1.2", "1.2.3.4", ""] {
+            assert_eq!(parse_version(dev), None, "version {dev:?}");
+        }
+    }
+
+    #[test]
+    fn mismatch_checks_the_supported_range() {
+        assert_eq!(mismatch(Version(0, 0, 9)), Some(Mismatch::TooOld));
+        assert_eq!(mismatch(MIN_BACKEND), None);
+        assert_eq!(mismatch(Version(0, 99, 0)), None);
+        assert_eq!(mismatch(MAX_BACKEND), Some(Mismatch::TooNew));
+    }
+}
@@ -0,0 +1,157 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use tauri::{Manager, Url, WebviewWindow};
+
+use crate::i18n;
+use crate::keepalive::{self, ActivityState, KeepAliveSchedule};
+use crate::remote::{self, RemoteState};
+use crate::shutdown::ShutdownSignal;
+
+// LOST_AFTER consecutive failed probes mean the backend is gone
+// rather than slow to answer one request.
+const LOST_AFTER: u32 = 2;
+// RETRY_INTERVAL is how often a backend that is away is probed.
+const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+const BANNER_ID: &str = "agentsview-reconnecting";
+
+// REMOTE_WATCH is bumped on every remote attach, ending the watch
+// of the previous connection.
+static REMOTE_WATCH: AtomicU64 = AtomicU64::new(0);
+
+// LinkChange is what the main window should show after a probe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LinkChange {
+    // Lost is repeated for every failed probe while the backend is
+    // away, so the banner comes back on a page that replaced it.
+    Lost,
+    Restored,
+}
+
+// Link follows whether the backend the main window shows is
+// reachable, from a series of probes.
+#[derive(Debug, Default)]
+pub(crate) struct Link {
+    failures: u32,
+}
+
+impl Link {
+    pub(crate) fn observe(&mut self, reachable: bool) -> Option<LinkChange> {
+        if reachable {
+            let lost = self.is_lost();
+            self.failures = 0;
+            return lost.then_some(LinkChange::Restored);
+        }
+        self.failures = self.failures.saturating_add(1);
+        self.is_lost().then_some(LinkChange::Lost)
+    }
+
+    pub(crate) fn is_lost(&self) -> bool {
+        self.failures >= LOST_AFTER
+    }
+
+    // next_delay shortens the scheduled delay after a failed probe,
+    // so the page is reloaded soon after the backend is back.
+    pub(crate) fn next_delay(&self, scheduled: Duration) -> Duration {
+        if self.failures > 0 {
+            scheduled.min(RETRY_INTERVAL)
+        } else {
+            scheduled
+        }
+    }
+}
+
+// update shows the reconnecting banner while the backend at origin
+// is lost, and reloads the page once it is back. The page is
+// reloaded by navigating to it again, which also replaces an error
+// page the webview showed in its place.
+pub(crate) fn update(window: &WebviewWindow, change: LinkChange, origin: &Url) {
+    match change {
+        LinkChange::Lost => {
+            let _ = window.eval(&banner_script(&i18n::tr("connectivity-reconnecting")));
+        }
+        LinkChange::Restored => {
+            eprintln!("[agentsview] backend at {origin} is reachable again; reloading");
+            let target = window
+                .url()
+                .ok()
+                .filter(|url| remote::same_origin(url, origin))
+                .unwrap_or_else(|| origin.clone());
+            let _ = window.navigate(target);
+        }
+    }
+}
+
+// banner_script adds a banner across the top of the page unless
+// it is already there.
+fn banner_script(text: &str) -> String {
+    format!(
+        r#"(() => {{
+  if (!document.body || document.getElementById({BANNER_ID:?})) return;
+  const banner = document.createElement("div");
+  banner.id = {BANNER_ID:?};
+  banner.setAttribute("role", "status");
+  banner.textContent = {text:?};
+  banner.style.cssText = "position:fixed;top:0;left:0;right:0;z-index:2147483647;padding:6px 12px;text-align:center;font:13px system-ui,sans-serif;background:#b45309;color:#fff;";
+  document.body.appendChild(banner);
+}})();"#
+    )
+}
+
+// watch_remote probes the remote backend at origin on the
+// keep-alive schedule and updates the main window when it goes
+// away or comes back, such as across a server restart. It ends
+// when another server is attached or the app shuts down.
+pub(crate) fn watch_remote(window: &WebviewWindow, origin: Url) {
+    let Some(schedule) = KeepAliveSchedule::from_env() else {
+        return;
+    };
+    let watch = REMOTE_WATCH.fetch_add(1, Ordering::SeqCst) + 1;
+    let window = window.clone();
+    let shutdown = window.state::<ShutdownSignal>().inner().clone();
+    thread::spawn(move || {
+        let mut link = Link::default();
+        loop {
+            let delay = schedule.next_delay(
+                keepalive::on_battery_power(),
+                window.state::<ActivityState>().is_idle(),
+                keepalive::jitter_sample(),
+            );
+            if shutdown.wait(link.next_delay(delay)) {
+                return;
+            }
+            let attached = window.state::<RemoteState>().origin();
+            if REMOTE_WATCH.load(Ordering::SeqCst) != watch || attached.as_ref() != Some(&origin) {
+                return;
+            }
+            if let Some(change) = link.observe(remote::reachable(&origin)) {
+                if change == LinkChange::Lost && link.failures == LOST_AFTER {
+                    eprintln!("[agentsview] remote backend at {origin} stopped responding");
+                }
+                update(&window, change, &origin);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn link_reports_loss_after_repeated_failures_and_one_restore() {
+        let mut link = Link::default();
+        assert_eq!(link.observe(true), None);
+        assert_eq!(link.observe(false), None);
+        assert_eq!(link.observe(true), None);
+        for _ in 1..LOST_AFTER {
+            assert_eq!(link.observe(false), None);
+        }
+        assert_eq!(link.observe(false), Some(LinkChange::Lost));
+        assert_eq!(link.observe(false), Some(LinkChange::Lost));
+        assert_eq!(link.next_delay(Duration::from_secs(30)), RETRY_INTERVAL);
+        assert_eq!(link.observe(true), Some(LinkChange::Restored));
+        assert_eq!(link.observe(true), None);
+    }
+}
@@ -0,0 +1,352 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::backend_client::BackendClient;
+use crate::error::DesktopError;
+use crate::i18n::{self, Localizer};
+use crate::notify::{self, Notice};
+use crate::settings::{self, DesktopSettings};
+use crate::shutdown::ShutdownSignal;
+use crate::timestamps;
+use crate::tray;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(15 * 60);
+const SNOOZE_SECS: u64 = 60 * 60;
+// MAX_ACKNOWLEDGED bounds the saved acknowledgements; only the
+// current day and week can still alert.
+const MAX_ACKNOWLEDGED: usize = 8;
+const DASHBOARD_ROUTE: &str = "#/sessions";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Period {
+    Daily,
+    Weekly,
+}
+
+// CostAlert is a spending limit exceeded in the current period.
+// key names the period, e.g. "weekly:2026-03-09", and is what an
+// acknowledgement records.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct CostAlert {
+    key: String,
+    period: Period,
+    spent_usd: f64,
+    limit_usd: f64,
+}
+
+#[derive(Deserialize)]
+struct CostTotal {
+    #[serde(default)]
+    total_usd: f64,
+}
+
+// CostState holds the alerts found by the last poll and which of
+// them the user has already been notified about this run.
+#[derive(Default)]
+pub(crate) struct CostState {
+    active: Mutex<Vec<CostAlert>>,
+    notified: Mutex<HashSet<String>>,
+}
+
+// spawn_poller checks spending against the daily_cost_limit and
+// weekly_cost_limit settings every POLL_INTERVAL. It stops if the
+// backend does not report costs.
+pub(crate) fn spawn_poller(app: AppHandle) {
+    let shutdown = app.state::<ShutdownSignal>().inner().clone();
+    thread::spawn(move || loop {
+        let saved = settings::load_settings();
+        if saved.daily_cost_limit.is_some() || saved.weekly_cost_limit.is_some() {
+            if let Some(client) = BackendClient::for_app(&app) {
+                match poll(&app, &client, &saved, timestamps::unix_now()) {
+                    Ok(()) => {}
+                    Err(err @ DesktopError::BackendUnsupported { .. }) => {
+                        eprintln!("[agentsview] cost alerts disabled: {err}");
+                        return;
+                    }
+                    Err(err) => eprintln!("[agentsview] cost check failed: {err}"),
+                }
+            }
+        }
+        if shutdown.wait(POLL_INTERVAL) {
+            return;
+        }
+    });
+}
+
+fn poll(
+    app: &AppHandle,
+    client: &BackendClient,
+    saved: &DesktopSettings,
+    now: u64,
+) -> Result<(), DesktopError> {
+    let mut spending = Vec::new();
+    for (period, limit) in [
+        (Period::Daily, saved.daily_cost_limit),
+        (Period::Weekly, saved.weekly_cost_limit),
+    ] {
+        if limit.is_some() {
+            let (from, to) = period_range(period, now);
+            let path = format!("/api/v1/analytics/cost?from={from}&to={to}");
+            let total = client
+                .get_json_until::<CostTotal>(&path, &|| false)?
+                .map_or(0.0, |cost| cost.total_usd);
+            spending.push((period, total));
+        }
+    }
+    let alerts = exceeded_limits(saved, &spending, now);
+    show_alerts(app, alerts);
+    Ok(())
+}
+
+// show_alerts records alerts as active, notifies once per alert,
+// and marks the tray while any is active.
+fn show_alerts(app: &AppHandle, alerts: Vec<CostAlert>) {
+    let state = app.state::<CostState>();
+    let localizer = i18n::localizer();
+    if let Ok(mut notified) = state.notified.lock() {
+        for alert in &alerts {
+            if notified.insert(alert.key.clone()) {
+                notify::post(
+                    app,
+                    Notice {
+                        title: i18n::tr("cost-alert-title"),
+                        body: describe(localizer, alert),
+                        sound: None,
+                        route: Some(DASHBOARD_ROUTE.to_string()),
+                    },
+                );
+            }
+        }
+    }
+    let summary = alerts.first().map(|alert| describe(localizer, alert));
+    if let Ok(mut active) = state.active.lock() {
+        *active = alerts;
+    }
+    tray::show_cost_alert(app, summary);
+}
+
+// acknowledge silences the active alerts until their period ends.
+pub(crate) fn acknowledge(app: &AppHandle) {
+    let keys: Vec<String> = take_active(app)
+        .into_iter()
+        .map(|alert| alert.key)
+        .collect();
+    if let Err(err) = settings::update_settings(|s| {
+        s.acknowledged_cost_alerts.extend(keys);
+        let excess = s
+            .acknowledged_cost_alerts
+            .len()
+            .saturating_sub(MAX_ACKNOWLEDGED);
+        s.acknowledged_cost_alerts.drain(..excess);
+    }) {
+        eprintln!("[agentsview] failed to save cost alert acknowledgement: {err}");
+    }
+    tray::show_cost_alert(app, None);
+}
+
+// snooze silences cost alerts for SNOOZE_SECS; alerts still
+// active afterwards are notified again.
+pub(crate) fn snooze(app: &AppHandle) {
+    take_active(app);
+    if let Ok(mut notified) = app.state::<CostState>().notified.lock() {
+        notified.clear();
+    }
+    let until = timestamps::unix_now() + SNOOZE_SECS;
+    if let Err(err) = settings::update_settings(|s| s.cost_alerts_snoozed_until = Some(until)) {
+        eprintln!("[agentsview] failed to save cost alert snooze: {err}");
+    }
+    tray::show_cost_alert(app, None);
+}
+
+fn take_active(app: &AppHandle) -> Vec<CostAlert> {
+    app.state::<CostState>()
+        .active
+        .lock()
+        .map(|mut active| std::mem::take(&mut *active))
+        .unwrap_or_default()
+}
+
+// cost_alerts lists the spending limits currently exceeded.
+#[tauri::command]
+pub(crate) fn cost_alerts(app: AppHandle) -> Vec<CostAlert> {
+    app.state::<CostState>()
+        .active
+        .lock()
+        .map(|active| active.clone())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub(crate) fn acknowledge_cost_alerts(app: AppHandle) {
+    acknowledge(&app);
+}
+
+#[tauri::command]
+pub(crate) fn snooze_cost_alerts(app: AppHandle) {
+    snooze(&app);
+}
+
+// set_cost_limits saves the daily and weekly limits in US
+// dollars; None removes a limit.
+#[tauri::command]
+pub(crate) fn set_cost_limits(
+    daily_usd: Option<f64>,
+    weekly_usd: Option<f64>,
+) -> Result<(), DesktopError> {
+    let positive = |limit: Option<f64>| limit.filter(|usd| usd.is_finite() && *usd > 0.0);
+    settings::update_settings(|s| {
+        s.daily_cost_limit = positive(daily_usd);
+        s.weekly_cost_limit = positive(weekly_usd);
+    })?;
+    Ok(())
+}
+
+// exceeded_limits compares spending in each period with its
+// limit, leaving out acknowledged periods and everything while
+// alerts are snoozed.
+fn exceeded_limits(
+    saved: &DesktopSettings,
+    spending: &[(Period, f64)],
+    now: u64,
+) -> Vec<CostAlert> {
+    if saved
+        .cost_alerts_snoozed_until
+        .is_some_and(|until| now < until)
+    {
+        return Vec::new();
+    }
+    spending
+        .iter()
+        .filter_map(|&(period, spent_usd)| {
+            let limit_usd = match period {
+                Period::Daily => saved.daily_cost_limit,
+                Period::Weekly => saved.weekly_cost_limit,
+            }?;
+            let key = period_key(period, now);
+            let acknowledged = saved.acknowledged_cost_alerts.contains(&key);
+            (spent_usd >= limit_usd && !acknowledged).then_some(CostAlert {
+                key,
+                period,
+                spent_usd,
+                limit_usd,
+            })
+        })
+        .collect()
+}
+
+// period_range returns the first and last UTC dates of the period
+// containing now; weeks start on Monday.
+fn period_range(period: Period, now: u64) -> (String, String) {
+    let today = now / 86_400;
+    let first = match period {
+        Period::Daily => today,
+        // 1970-01-01 was a Thursday, three days after a Monday.
+        Period::Weekly => today - (today + 3) % 7,
+    };
+    (
+        timestamps::date_utc(first * 86_400),
+        timestamps::date_utc(now),
+    )
+}
+
+fn period_key(period: Period, now: u64) -> String {
+    let (first, _) = period_range(period, now);
+    match period {
+        Period::Daily => format!("daily:{first}"),
+        Period::Weekly => format!("weekly:{first}"),
+    }
+}
+
+fn describe(localizer: &Localizer, alert: &CostAlert) -> String {
+    let key = match alert.period {
+        Period::Daily => "cost-alert-daily",
+        Period::Weekly => "cost-alert-weekly",
+    };
+    localizer.format(
+        key,
+        &[
+            ("spent", &format!("${:.2}", alert.spent_usd)),
+            ("limit", &format!("${:.2}", alert.limit_usd)),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_773_480_413; // Saturday 2026-03-14T09:26:53Z
+
+    fn limits() -> DesktopSettings {
+        DesktopSettings {
+            daily_cost_limit: Some(10.0),
+            weekly_cost_limit: Some(50.0),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn period_range_starts_weeks_on_monday() {
+        assert_eq!(
+            period_range(Period::Daily, NOW),
+            ("2026-03-14".to_string(), "2026-03-14".to_string())
+        );
+        assert_eq!(
+            period_range(Period::Weekly, NOW),
+            ("2026-03-09".to_string(), "2026-03-14".to_string())
+        );
+        // 2026-03-09 is itself a Monday.
+        let monday = NOW - 5 * 86_400;
+        assert_eq!(period_range(Period::Weekly, monday).0, "2026-03-09");
+        assert_eq!(period_key(Period::Weekly, NOW), "weekly:2026-03-09");
+    }
+
+    #[test]
+    fn exceeded_limits_reports_crossed_thresholds() {
+        let alerts = exceeded_limits(
+            &limits(),
+            &[(Period::Daily, 12.5), (Period::Weekly, 40.0)],
+            NOW,
+        );
+        assert_eq!(
+            alerts,
+            vec![CostAlert {
+                key: "daily:2026-03-14".to_string(),
+                period: Period::Daily,
+                spent_usd: 12.5,
+                limit_usd: 10.0,
+            }]
+        );
+        assert_eq!(
+            describe(&Localizer::for_locale("en"), &alerts[0]),
+            "Today's spending is $12.50, over your $10.00 limit."
+        );
+    }
+
+    #[test]
+    fn exceeded_limits_honours_acknowledgements_and_snoozes() {
+        let spending = [(Period::Daily, 12.5), (Period::Weekly, 60.0)];
+
+        let mut acknowledged = limits();
+        acknowledged.acknowledged_cost_alerts = vec!["daily:2026-03-14".to_string()];
+        let alerts = exceeded_limits(&acknowledged, &spending, NOW);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].period, Period::Weekly);
+        // The acknowledgement lapses with the day.
+        assert_eq!(
+            exceeded_limits(&acknowledged, &spending, NOW + 86_400).len(),
+            2
+        );
+
+        let mut snoozed = limits();
+        snoozed.cost_alerts_snoozed_until = Some(NOW + 60);
+        assert!(exceeded_limits(&snoozed, &spending, NOW).is_empty());
+        assert_eq!(exceeded_limits(&snoozed, &spending, NOW + 60).len(), 2);
+    }
+}
@@ -0,0 +1,73 @@
+use crate::backend_output::{BackendOutput, Stream};
+use crate::i18n;
+
+// TAIL_LINES is how much of the sidecar's stderr the crash dialog
+// shows. A panic or fatal log line is at the end, and the lines
+// before it usually say what the backend was doing.
+const TAIL_LINES: usize = 200;
+// TAIL_CHARS caps the tail shown in the dialog, which native
+// dialogs do not scroll; the newest output is kept.
+const TAIL_CHARS: usize = 8_000;
+
+// tail_text joins the output lines, dropping the oldest
+// characters beyond TAIL_CHARS.
+fn tail_text(lines: &[String]) -> String {
+    let text = lines.join("\n");
+    let excess = text.chars().count().saturating_sub(TAIL_CHARS);
+    if excess == 0 {
+        return text;
+    }
+    let start = text
+        .char_indices()
+        .nth(excess)
+        .map_or(text.len(), |(index, _)| index);
+    format!("…{}", &text[start..])
+}
+
+// message describes a sidecar that exited on its own: whether it
+// got through startup, how it exited, and the end of what launch
+// attempt wrote to stderr.
+pub(crate) fn message(
+    code: Option<i32>,
+    signal: Option<i32>,
+    during_startup: bool,
+    output: &BackendOutput,
+    attempt: u64,
+) -> String {
+    let status = match (code, signal) {
+        (Some(code), _) => i18n::tr_args("backend-crash-status-code", &[("code", &code)]),
+        (None, Some(signal)) => {
+            i18n::tr_args("backend-crash-status-signal", &[("signal", &signal)])
+        }
+        (None, None) => i18n::tr("backend-crash-status-unknown"),
+    };
+    let summary_key = if during_startup {
+        "backend-crash-startup"
+    } else {
+        "backend-crash-running"
+    };
+    let summary = i18n::tr_args(summary_key, &[("status", &status)]);
+    let output = tail_text(&output.tail(attempt, Stream::Stderr, TAIL_LINES));
+    if output.trim().is_empty() {
+        return format!("{summary}\n\n{}", i18n::tr("backend-crash-no-output"));
+    }
+    format!(
+        "{summary}\n\n{}\n{output}",
+        i18n::tr("backend-crash-output")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tail_text_keeps_the_newest_output() {
+        let lines = vec!["first".to_string(), "second".to_string()];
+        assert_eq!(tail_text(&lines), "first\nsecond");
+
+        let text = tail_text(&["é".repeat(TAIL_CHARS + 10)]);
+        assert_eq!(text.chars().count(), TAIL_CHARS + 1);
+        assert!(text.starts_with('…'));
+    }
+}
@@ -0,0 +1,43 @@
+// Backend page hardening, injected into every page. The backend
+// serves its UI without a Content-Security-Policy, so this adds one
+// as a <meta> tag at the top of <head>, along with a referrer
+// policy. It applies to everything the page loads after that,
+// including eval() and new Function().
+(() => {
+  const POLICY = __POLICY__;
+
+  // The bundled splash page has its own policy from tauri.conf.json.
+  const { protocol, hostname } = window.location;
+  const bundled = protocol === "tauri:" || hostname === "tauri.localhost";
+  if (bundled || !["http:", "https:"].includes(protocol) || window !== window.top) {
+    return;
+  }
+
+  const meta = (attribute, value, content) => {
+    const element = document.createElement("meta");
+    element.setAttribute(attribute, value);
+    element.setAttribute("content", content);
+    return element;
+  };
+  const tags = [
+    meta("http-equiv", "Content-Security-Policy", POLICY),
+    meta("name", "referrer", "same-origin"),
+  ];
+
+  const insert = () => {
+    if (!document.head) {
+      return false;
+    }
+    document.head.prepend(...tags);
+    return true;
+  };
+  // Injected scripts can run before the parser has created <head>.
+  if (!insert()) {
+    const observer = new MutationObserver(() => {
+      if (insert()) {
+        observer.disconnect();
+      }
+    });
+    observer.observe(document, { childList: true, subtree: true });
+  }
+})();
@@ -0,0 +1,102 @@
+use tauri::plugin::{Builder as PluginBuilder, TauriPlugin};
+use tauri::Runtime;
+
+const HARDENING_SCRIPT: &str = include_str!("csp.js");
+// DISABLE_ENV turns the policy off, for debugging a frontend change
+// against a release build.
+const DISABLE_ENV: &str = "AGENTSVIEW_DESKTOP_DISABLE_CSP";
+// STYLE_SRC and FONT_SRC admit the UI's web fonts, which no-network
+// builds leave out.
+#[cfg(not(feature = "no-network"))]
+const STYLE_SRC: &str = "'self' 'unsafe-inline' https://fonts.googleapis.com";
+#[cfg(not(feature = "no-network"))]
+const FONT_SRC: &str = "'self' data: https://fonts.gstatic.com";
+#[cfg(feature = "no-network")]
+const STYLE_SRC: &str = "'self' 'unsafe-inline'";
+#[cfg(feature = "no-network")]
+const FONT_SRC: &str = "'self' data:";
+
+// init_plugin injects the Content-Security-Policy into backend
+// pages. `tauri dev` builds and DISABLE_ENV skip it, so a frontend
+// dev server's tooling keeps working.
+//
+// The shell never sees the backend's responses, local or remote,
+// so csp.js adds the policy as a <meta> tag rather than a header,
+// which enforces less. It only covers what the page loads once the
+// tag is in <head>, not anything the parser fetched before.
+// Browsers ignore frame-ancestors in a <meta> policy, so other
+// origins can still frame the page, and they ignore report-uri and
+// report-to, so violations only reach the web inspector's console.
+pub(crate) fn init_plugin<R: Runtime>() -> TauriPlugin<R> {
+    let mut builder = PluginBuilder::new("csp");
+    if cfg!(dev) {
+        eprintln!("[agentsview] dev build; not enforcing the backend Content-Security-Policy");
+    } else if disabled() {
+        eprintln!(
+            "[agentsview] {DISABLE_ENV} is set; not enforcing the backend Content-Security-Policy"
+        );
+    } else {
+        builder = builder.js_init_script(hardening_script(&policy()));
+    }
+    builder.build()
+}
+
+// disabled reports whether DISABLE_ENV turns the policy off.
+// no-network builds ignore it, since the policy is what keeps the
+// UI from fetching its web fonts.
+#[cfg(not(feature = "no-network"))]
+fn disabled() -> bool {
+    std::env::var_os(DISABLE_ENV).is_some()
+}
+
+#[cfg(feature = "no-network")]
+fn disabled() -> bool {
+    if std::env::var_os(DISABLE_ENV).is_some() {
+        eprintln!("[agentsview] {DISABLE_ENV} is ignored in no-network builds");
+    }
+    false
+}
+
+fn hardening_script(policy: &str) -> String {
+    let literal = serde_json::to_string(policy).unwrap_or_else(|_| "\"\"".to_string());
+    HARDENING_SCRIPT.replace("__POLICY__", &literal)
+}
+
+// policy is the backend UI's Content-Security-Policy. Scripts only
+// come from the backend's own origin, without eval. Connections may
+// also reach Tauri's IPC endpoint, which the shell's commands use.
+fn policy() -> String {
+    [
+        "default-src 'self'".to_string(),
+        "script-src 'self'".to_string(),
+        format!("style-src {STYLE_SRC}"),
+        format!("font-src {FONT_SRC}"),
+        "img-src 'self' data: blob:".to_string(),
+        "connect-src 'self' ipc: http://ipc.localhost".to_string(),
+        "object-src 'none'".to_string(),
+        "base-uri 'none'".to_string(),
+        "form-action 'self'".to_string(),
+    ]
+    .join("; ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policy_blocks_eval_and_plugins() {
+        let policy = policy();
+        assert!(policy.contains("script-src 'self';"), "{policy}");
+        assert!(!policy.contains("unsafe-eval"), "{policy}");
+        assert!(policy.contains("object-src 'none'"), "{policy}");
+        assert_eq!(
+            policy.contains("fonts.googleapis.com"),
+            !cfg!(feature = "no-network")
+        );
+
+        let script = hardening_script(&policy);
+        assert!(!script.contains("__POLICY__"));
+        assert!(script.contains(&format!("const POLICY = \"{policy}\";")));
+    }
+}
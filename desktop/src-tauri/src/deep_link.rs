@@ -0,0 +1,139 @@
+use std::sync::Mutex;
+
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Manager, Url, Webview, Wry};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::backend_client::encode_query_component;
+use crate::palette;
+
+// SCHEME is the URL scheme registered with the OS; see the
+// deep-link plugin config in tauri.conf.json.
+const SCHEME: &str = "agentsview";
+
+// DeepLinkState holds the route of a link that arrived before the
+// window showed the backend, such as the one that launched the app.
+#[derive(Default)]
+pub(crate) struct DeepLinkState {
+    pending: Mutex<Option<String>>,
+}
+
+pub(crate) fn init_plugin() -> TauriPlugin<Wry> {
+    tauri_plugin_deep_link::init()
+}
+
+// install handles agentsview:// links opened while the app runs,
+// forwarded from a second launch by the single-instance plugin on
+// Windows and Linux, and the link the app was launched with.
+pub(crate) fn install(app: &AppHandle) {
+    // Installers register the scheme; `tauri dev` builds have to do
+    // it themselves.
+    #[cfg(any(windows, target_os = "linux"))]
+    if cfg!(debug_assertions) {
+        if let Err(err) = app.deep_link().register_all() {
+            eprintln!("[agentsview] could not register the {SCHEME}:// scheme: {err}");
+        }
+    }
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            open(&handle, &url);
+        }
+    });
+    if let Ok(Some(urls)) = app.deep_link().get_current() {
+        for url in urls {
+            open(app, &url);
+        }
+    }
+}
+
+// open shows the session url names. Until the backend page has
+// loaded, the route waits for open_pending.
+fn open(app: &AppHandle, url: &Url) {
+    let Some(route) = session_route(url) else {
+        eprintln!("[agentsview] ignoring unsupported link {url}");
+        return;
+    };
+    let on_backend = app
+        .get_webview_window("main")
+        .and_then(|main| main.url().ok())
+        .is_some_and(|url| palette::is_backend_page(&url));
+    if !on_backend {
+        if let Ok(mut pending) = app.state::<DeepLinkState>().pending.lock() {
+            *pending = Some(route.clone());
+        }
+    }
+    if let Err(err) = palette::open_route(app, &route) {
+        eprintln!("[agentsview] failed to open {url}: {err}");
+    }
+}
+
+// open_pending opens a waiting link once the main window has loaded
+// a backend page, that is, once the backend is ready.
+pub(crate) fn open_pending(webview: &Webview) {
+    if webview.label() != "main"
+        || !webview
+            .url()
+            .is_ok_and(|url| palette::is_backend_page(&url))
+    {
+        return;
+    }
+    let route = webview
+        .state::<DeepLinkState>()
+        .pending
+        .lock()
+        .ok()
+        .and_then(|mut pending| pending.take());
+    if let Some(route) = route {
+        if let Err(err) = palette::open_route(webview.app_handle(), &route) {
+            eprintln!("[agentsview] failed to open a waiting link: {err}");
+        }
+    }
+}
+
+// session_route maps agentsview://session/<id> to the SPA route of
+// that session. An optional ?msg=<ordinal> scrolls to a message.
+fn session_route(url: &Url) -> Option<String> {
+    if url.scheme() != SCHEME || url.host_str() != Some("session") {
+        return None;
+    }
+    let id = url.path().strip_prefix('/')?.trim_end_matches('/');
+    let valid_id = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'));
+    if !valid_id {
+        return None;
+    }
+    let mut route = format!("#/sessions?session={}", encode_query_component(id));
+    let ordinal = url
+        .query_pairs()
+        .find(|(key, _)| key == "msg")
+        .and_then(|(_, value)| value.parse::<u64>().ok());
+    if let Some(ordinal) = ordinal {
+        route.push_str(&format!("&msg={ordinal}"));
+    }
+    Some(route)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_route_reads_session_links_only() {
+        let route = |link: &str| session_route(&Url::parse(link).expect("valid link"));
+        assert_eq!(
+            route("agentsview://session/mock-claude-1").as_deref(),
+            Some("#/sessions?session=mock-claude-1")
+        );
+        assert_eq!(
+            route("agentsview://session/codex:abc/?msg=12").as_deref(),
+            Some("#/sessions?session=codex%3Aabc&msg=12")
+        );
+        assert_eq!(route("agentsview://session/"), None);
+        assert_eq!(route("agentsview://session/a/b"), None);
+        assert_eq!(route("agentsview://settings/abc"), None);
+        assert_eq!(route("https://session/abc"), None);
+    }
+}
@@ -0,0 +1,82 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::{timestamps, user};
+
+// DESKTOP_LOG_FILE is the shell's own log in the data dir, next to
+// the backend's debug.log.
+const DESKTOP_LOG_FILE: &str = "desktop.log";
+// MAX_LOG_BYTES is the size at which the log is moved to
+// desktop.log.1, replacing the previous one, and started afresh.
+const MAX_LOG_BYTES: u64 = 1 << 20;
+
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+// record appends a timestamped line to the desktop log, for things
+// users should be able to attach to a bug report that stderr loses
+// once the app is launched from the OS.
+pub(crate) fn record(line: &str) {
+    let Some(path) = log_path() else {
+        return;
+    };
+    let stamped = format!(
+        "{} {line}\n",
+        timestamps::rfc3339_utc(timestamps::unix_now())
+    );
+    if let Err(err) = append(&path, &stamped) {
+        eprintln!("[agentsview] failed to write {}: {err}", path.display());
+    }
+}
+
+// log_path is the desktop log, scoped per user in shared data dirs
+// like the backend's.
+pub(crate) fn log_path() -> Option<PathBuf> {
+    let dir = crate::data_dir()?;
+    let name = user::scoped_file_name(DESKTOP_LOG_FILE, &dir);
+    Some(dir.join(name))
+}
+
+fn append(path: &Path, text: &str) -> io::Result<()> {
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+    if fs::metadata(path).is_ok_and(|meta| meta.len() >= MAX_LOG_BYTES) {
+        let mut rotated = path.as_os_str().to_owned();
+        rotated.push(".1");
+        fs::rename(path, rotated)?;
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(text.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn append_rotates_a_full_log() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("logs").join(DESKTOP_LOG_FILE);
+        append(&path, "first\n").expect("append first line");
+        append(&path, "second\n").expect("append second line");
+        assert_eq!(
+            fs::read_to_string(&path).expect("read log"),
+            "first\nsecond\n"
+        );
+
+        fs::write(&path, vec![b'x'; MAX_LOG_BYTES as usize]).expect("fill log");
+        append(&path, "third\n").expect("append after rotation");
+        assert_eq!(fs::read_to_string(&path).expect("read new log"), "third\n");
+        let rotated = dir.path().join("logs").join("desktop.log.1");
+        assert_eq!(
+            fs::metadata(rotated).expect("stat rotated log").len(),
+            MAX_LOG_BYTES
+        );
+    }
+}
@@ -0,0 +1,356 @@
+use std::collections::BTreeMap;
+use std::io::{self, ErrorKind};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddrV4, UdpSocket};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Manager};
+
+use crate::settings;
+use crate::shutdown::ShutdownSignal;
+use crate::tray;
+
+// SERVICE is the DNS-SD service type agentsview servers are
+// advertised under.
+const SERVICE: &str = "_agentsview._tcp.local";
+const MDNS_GROUP: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(224, 0, 0, 251), 5353);
+// BROWSE_TIME is how long one browse collects answers.
+const BROWSE_TIME: Duration = Duration::from_secs(2);
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+const TYPE_A: u16 = 1;
+const TYPE_PTR: u16 = 12;
+const TYPE_TXT: u16 = 16;
+const TYPE_AAAA: u16 = 28;
+const TYPE_SRV: u16 = 33;
+const CLASS_IN: u16 = 1;
+
+// Server is an agentsview server advertised on the LAN.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Server {
+    pub(crate) name: String,
+    pub(crate) url: String,
+}
+
+// DiscoveryState holds the servers found by the last browse.
+#[derive(Default)]
+pub(crate) struct DiscoveryState(Mutex<Vec<Server>>);
+
+impl DiscoveryState {
+    pub(crate) fn servers(&self) -> Vec<Server> {
+        self.0
+            .lock()
+            .map(|servers| servers.clone())
+            .unwrap_or_default()
+    }
+}
+
+// enabled reports whether the tray browses for servers, which
+// lan_discovery opts into.
+pub(crate) fn enabled() -> bool {
+    settings::load_settings().lan_discovery
+}
+
+// spawn_browser looks for agentsview servers on the LAN every
+// REFRESH_INTERVAL and lists them in the tray's Connect to menu.
+pub(crate) fn spawn_browser(app: AppHandle) {
+    if !enabled() {
+        return;
+    }
+    let shutdown = app.state::<ShutdownSignal>().inner().clone();
+    thread::spawn(move || loop {
+        match browse() {
+            Ok(servers) => {
+                let changed = app
+                    .state::<DiscoveryState>()
+                    .0
+                    .lock()
+                    .map(|mut known| std::mem::replace(&mut *known, servers.clone()) != servers)
+                    .unwrap_or(false);
+                if changed {
+                    tray::refresh_menu(&app);
+                }
+            }
+            Err(err) => eprintln!("[agentsview] LAN server discovery failed: {err}"),
+        }
+        if shutdown.wait(REFRESH_INTERVAL) {
+            return;
+        }
+    });
+}
+
+// connect attaches the main window to a server picked from the
+// tray, the same way as a configured remote backend.
+pub(crate) fn connect(app: &AppHandle, url: &str) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let _ = window.unminimize();
+    let _ = window.show();
+    let _ = window.set_focus();
+    eprintln!("[agentsview] connecting to discovered server {url}");
+    crate::attach_remote_backend(&window, url.to_string());
+}
+
+// browse asks the LAN for agentsview servers. The query comes from
+// an ephemeral port, which makes it a legacy unicast query:
+// responders answer this socket directly, so the browser neither
+// binds 5353 nor joins the multicast group.
+fn browse() -> io::Result<Vec<Server>> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+    socket.send_to(&query(SERVICE), MDNS_GROUP)?;
+    let deadline = Instant::now() + BROWSE_TIME;
+    let mut records = Records::default();
+    let mut packet = [0u8; 9000];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        socket.set_read_timeout(Some(remaining))?;
+        match socket.recv_from(&mut packet) {
+            Ok((n, _)) => records.add(&packet[..n]),
+            Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => break,
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(records.servers())
+}
+
+// query builds a DNS question for the PTR records of service.
+fn query(service: &str) -> Vec<u8> {
+    let mut packet = vec![0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0];
+    for label in service.split('.') {
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.push(0);
+    packet.extend_from_slice(&TYPE_PTR.to_be_bytes());
+    packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+    packet
+}
+
+// Records collects the answers to a browse. Names are keyed in
+// lowercase since DNS names compare case-insensitively.
+#[derive(Debug, Default)]
+struct Records {
+    // instances maps an instance's full name to its first label,
+    // the name it is shown under.
+    instances: BTreeMap<String, String>,
+    srv: BTreeMap<String, (String, u16)>,
+    txt: BTreeMap<String, Vec<String>>,
+    addrs: BTreeMap<String, Vec<IpAddr>>,
+}
+
+impl Records {
+    // add reads the records of one response. Malformed packets and
+    // unrelated records are skipped.
+    fn add(&mut self, packet: &[u8]) {
+        let _ = self.try_add(packet);
+    }
+
+    fn try_add(&mut self, packet: &[u8]) -> Option<()> {
+        let header = packet.get(..12)?;
+        if header[2] & 0x80 == 0 {
+            return None;
+        }
+        let count = |at: usize| usize::from(u16::from_be_bytes([header[at], header[at + 1]]));
+        let mut pos = 12;
+        for _ in 0..count(4) {
+            pos = read_name(packet, pos)?.1 + 4;
+        }
+        for _ in 0..count(6) + count(8) + count(10) {
+            let (name, next) = read_name(packet, pos)?;
+            let fixed = packet.get(next..next + 10)?;
+            let kind = u16::from_be_bytes([fixed[0], fixed[1]]);
+            let len = usize::from(u16::from_be_bytes([fixed[8], fixed[9]]));
+            let start = next + 10;
+            let data = packet.get(start..start + len)?;
+            let key = join(&name);
+            match kind {
+                TYPE_PTR if key == SERVICE => {
+                    let (instance, _) = read_name(packet, start)?;
+                    let label = instance.first()?.clone();
+                    self.instances.insert(join(&instance), label);
+                }
+                TYPE_SRV if len > 6 => {
+                    let port = u16::from_be_bytes([data[4], data[5]]);
+                    let (target, _) = read_name(packet, start + 6)?;
+                    self.srv.insert(key, (join(&target), port));
+                }
+                TYPE_TXT => {
+                    let mut entries = Vec::new();
+                    let mut at = 0;
+                    while let Some(&size) = data.get(at) {
+                        let entry = data.get(at + 1..at + 1 + usize::from(size))?;
+                        entries.push(String::from_utf8_lossy(entry).into_owned());
+                        at += 1 + usize::from(size);
+                    }
+                    self.txt.insert(key, entries);
+                }
+                TYPE_A if len == 4 => {
+                    let ip = Ipv4Addr::new(data[0], data[1], data[2], data[3]);
+                    self.addrs.entry(key).or_default().push(ip.into());
+                }
+                TYPE_AAAA if len == 16 => {
+                    let octets: [u8; 16] = data.try_into().ok()?;
+                    self.addrs
+                        .entry(key)
+                        .or_default()
+                        .push(Ipv6Addr::from(octets).into());
+                }
+                _ => {}
+            }
+            pos = start + len;
+        }
+        Some(())
+    }
+
+    // servers turns the collected records into connectable URLs.
+    // An IPv4 address is preferred; link-local IPv6 addresses need
+    // a zone a URL cannot carry and are skipped. A TXT "scheme=https"
+    // entry marks servers behind TLS.
+    fn servers(&self) -> Vec<Server> {
+        let mut servers: Vec<Server> = self
+            .instances
+            .iter()
+            .filter_map(|(instance, name)| {
+                let (target, port) = self.srv.get(instance)?;
+                let addrs = self.addrs.get(target)?;
+                let ip = addrs.iter().find(|ip| ip.is_ipv4()).or_else(|| {
+                    addrs.iter().find(|ip| match ip {
+                        IpAddr::V6(v6) => (v6.segments()[0] & 0xffc0) != 0xfe80,
+                        IpAddr::V4(_) => false,
+                    })
+                })?;
+                let host = match ip {
+                    IpAddr::V4(v4) => v4.to_string(),
+                    IpAddr::V6(v6) => format!("[{v6}]"),
+                };
+                let https = self
+                    .txt
+                    .get(instance)
+                    .is_some_and(|entries| entries.iter().any(|entry| entry == "scheme=https"));
+                let scheme = if https { "https" } else { "http" };
+                Some(Server {
+                    name: name.clone(),
+                    url: format!("{scheme}://{host}:{port}/"),
+                })
+            })
+            .collect();
+        servers.sort();
+        servers.dedup();
+        servers
+    }
+}
+
+fn join(labels: &[String]) -> String {
+    labels.join(".").to_ascii_lowercase()
+}
+
+// read_name reads the name at pos, following compression
+// pointers, and returns its labels with the position after it.
+fn read_name(packet: &[u8], mut pos: usize) -> Option<(Vec<String>, usize)> {
+    let mut labels = Vec::new();
+    let mut end = None;
+    // Each pointer must go backwards, which rules out loops.
+    let mut limit = pos;
+    loop {
+        let len = *packet.get(pos)?;
+        match len {
+            0 => break,
+            len if len & 0xc0 == 0xc0 => {
+                let target = usize::from(u16::from_be_bytes([len & 0x3f, *packet.get(pos + 1)?]));
+                if target >= limit {
+                    return None;
+                }
+                end.get_or_insert(pos + 2);
+                limit = target;
+                pos = target;
+            }
+            len if len & 0xc0 == 0 => {
+                let label = packet.get(pos + 1..pos + 1 + usize::from(len))?;
+                labels.push(String::from_utf8_lossy(label).into_owned());
+                pos += 1 + usize::from(len);
+            }
+            _ => return None,
+        }
+    }
+    Some((labels, end.unwrap_or(pos + 1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn name(out: &mut Vec<u8>, name: &str) {
+        for label in name.split('.') {
+            out.push(label.len() as u8);
+            out.extend_from_slice(label.as_bytes());
+        }
+        out.push(0);
+    }
+
+    fn record(out: &mut Vec<u8>, owner: &str, kind: u16, data: &[u8]) {
+        name(out, owner);
+        out.extend_from_slice(&kind.to_be_bytes());
+        out.extend_from_slice(&[0x80, 0x01, 0, 0, 0x11, 0x94]);
+        out.extend_from_slice(&(data.len() as u16).to_be_bytes());
+        out.extend_from_slice(data);
+    }
+
+    #[test]
+    fn records_read_a_dns_sd_response() {
+        let mut packet = vec![0, 0, 0x84, 0, 0, 0, 0, 1, 0, 0, 0, 4];
+        let mut ptr = Vec::new();
+        name(&mut ptr, "Team Box._agentsview._tcp.local");
+        record(&mut packet, "_agentsview._tcp.local", TYPE_PTR, &ptr);
+        let mut srv = vec![0, 0, 0, 0, 0x1f, 0x90];
+        // The target is compressed to a pointer at "local" in the
+        // PTR owner name.
+        srv.extend_from_slice(b"\x07teambox\xc0\x1d");
+        record(
+            &mut packet,
+            "Team Box._agentsview._tcp.local",
+            TYPE_SRV,
+            &srv,
+        );
+        record(
+            &mut packet,
+            "Team Box._agentsview._tcp.local",
+            TYPE_TXT,
+            b"\x0cscheme=https",
+        );
+        record(
+            &mut packet,
+            "teambox.local",
+            TYPE_AAAA,
+            &[0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+        );
+        record(&mut packet, "teambox.local", TYPE_A, &[192, 168, 1, 20]);
+
+        let mut records = Records::default();
+        records.add(&packet);
+        assert_eq!(
+            records.servers(),
+            vec![Server {
+                name: "Team Box".to_string(),
+                url: "https://192.168.1.20:8080/".to_string(),
+            }]
+        );
+
+        // Queries and truncated packets add nothing.
+        let mut ignored = Records::default();
+        ignored.add(&query(SERVICE));
+        ignored.add(&packet[..packet.len() - 3]);
+        assert!(ignored.srv.is_empty() || ignored.servers().is_empty());
+    }
+
+    #[test]
+    fn read_name_rejects_pointer_loops() {
+        let packet = [0u8, 0, 0xc0, 0x02];
+        assert_eq!(read_name(&packet, 2), None);
+        let packet = b"\x03abc\x00\xc0\x00";
+        assert_eq!(read_name(packet, 5), Some((vec!["abc".to_string()], 7)));
+    }
+}
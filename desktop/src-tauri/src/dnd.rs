@@ -0,0 +1,77 @@
+use std::fs;
+
+// do_not_disturb reports whether the user has silenced
+// notifications (Focus on macOS, Focus Assist or quiet hours on
+// Windows, GNOME's Do Not Disturb). Scheduled, non-urgent
+// notifications wait until it is off. Unknown states count as off.
+pub(crate) fn do_not_disturb() -> bool {
+    if cfg!(target_os = "macos") {
+        return crate::resolve_home_dir()
+            .and_then(|home| {
+                fs::read_to_string(home.join("Library/DoNotDisturb/DB/Assertions.json")).ok()
+            })
+            .is_some_and(|json| focus_assertions_active(&json));
+    }
+    if cfg!(target_os = "linux") {
+        return std::process::Command::new("gsettings")
+            .args(["get", "org.gnome.desktop.notifications", "show-banners"])
+            .output()
+            .ok()
+            .is_some_and(|out| String::from_utf8_lossy(&out.stdout).trim() == "false");
+    }
+    windows_quiet()
+}
+
+// focus_assertions_active reads macOS's Focus assertion store: a
+// manually enabled Focus is recorded as a store assertion.
+fn focus_assertions_active(json: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(json)
+        .ok()
+        .and_then(|value| {
+            value
+                .get("data")?
+                .as_array()?
+                .iter()
+                .find_map(|entry| entry.get("storeAssertionRecords")?.as_array().cloned())
+        })
+        .is_some_and(|records| !records.is_empty())
+}
+
+#[cfg(windows)]
+fn windows_quiet() -> bool {
+    // QUNS_ACCEPTS_NOTIFICATIONS; every other state (busy,
+    // presentation mode, quiet time, ...) holds notifications back.
+    const ACCEPTS_NOTIFICATIONS: i32 = 5;
+
+    #[link(name = "shell32")]
+    extern "system" {
+        fn SHQueryUserNotificationState(state: *mut i32) -> i32;
+    }
+
+    let mut state = ACCEPTS_NOTIFICATIONS;
+    // SAFETY: state is a valid, properly aligned out-pointer.
+    let ok = unsafe { SHQueryUserNotificationState(&mut state) } == 0;
+    ok && state != ACCEPTS_NOTIFICATIONS
+}
+
+#[cfg(not(windows))]
+fn windows_quiet() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn focus_assertions_active_reads_store_records() {
+        let on = r#"{"data":[{"storeAssertionRecords":[{"assertionDetails":
+            {"assertionDetailsModeIdentifier":"com.apple.donotdisturb.mode.default"}}]}]}"#;
+        assert!(focus_assertions_active(on));
+        assert!(!focus_assertions_active(
+            r#"{"data":[{"storeAssertionRecords":[]}]}"#
+        ));
+        assert!(!focus_assertions_active(r#"{"data":[{}]}"#));
+        assert!(!focus_assertions_active("not json"));
+    }
+}
@@ -0,0 +1,480 @@
+use std::collections::BTreeMap;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::arch::{self, Architectures};
+use crate::env::{self, EnvSource};
+use crate::error::DesktopError;
+use crate::i18n::{self, Localizer};
+use crate::ports;
+use crate::settings;
+use crate::sidecar::{self, CandidateStatus};
+use crate::timestamps;
+use crate::SidecarState;
+
+// AGENT_CLIS are the agent command-line tools whose sessions the
+// backend indexes and whose output it may need to run.
+const AGENT_CLIS: &[&str] = &["claude", "codex", "gemini"];
+// CLOCK_FLOOR and CLOCK_CEILING bound a plausible system clock
+// (2025-01-01 and 2100-01-01 UTC).
+const CLOCK_FLOOR: u64 = 1_735_689_600;
+const CLOCK_CEILING: u64 = 4_102_444_800;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+// DoctorCheck is one diagnostic result. name and detail are
+// localized for display; id is stable for tooling.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct DoctorCheck {
+    id: &'static str,
+    status: CheckStatus,
+    name: String,
+    detail: String,
+}
+
+// DoctorReport is the result of run_doctor; status is the worst
+// status of any check.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct DoctorReport {
+    status: CheckStatus,
+    checks: Vec<DoctorCheck>,
+}
+
+impl DoctorReport {
+    fn new(checks: Vec<DoctorCheck>) -> Self {
+        let status = checks
+            .iter()
+            .map(|check| check.status)
+            .max()
+            .unwrap_or(CheckStatus::Pass);
+        Self { status, checks }
+    }
+
+    // to_text renders the report as plain lines, e.g. for issue
+    // reports.
+    pub(crate) fn to_text(&self) -> String {
+        self.checks
+            .iter()
+            .map(|check| {
+                let mark = match check.status {
+                    CheckStatus::Pass => "PASS",
+                    CheckStatus::Warn => "WARN",
+                    CheckStatus::Fail => "FAIL",
+                };
+                format!("[{mark}] {}: {}", check.name, check.detail)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+// run_doctor checks the local setup the backend depends on. The
+// login-shell probe for the agent CLI check can take seconds, so
+// it runs off the main thread.
+#[tauri::command]
+pub(crate) async fn run_doctor(app: AppHandle) -> Result<DoctorReport, DesktopError> {
+    tauri::async_runtime::spawn_blocking(move || diagnose(&app))
+        .await
+        .map_err(|_| DesktopError::StatePoisoned { what: "doctor" })
+}
+
+// EnvVar is one variable of the backend's environment as exported
+// for troubleshooting. source is the layer whose value won, and
+// override_file the desktop.env file for the desktop_env layer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct EnvVar {
+    key: String,
+    value: String,
+    source: EnvSource,
+    override_file: Option<String>,
+}
+
+// sidecar_environment exports the environment the backend is
+// launched with, with secret values redacted; see env::Redactor.
+#[tauri::command]
+pub(crate) async fn sidecar_environment(app: AppHandle) -> Result<Vec<EnvVar>, DesktopError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let traced = launch_env(&app);
+        let sources: BTreeMap<String, EnvSource> = traced
+            .iter()
+            .map(|(key, _, source)| (key.to_string_lossy().into_owned(), *source))
+            .collect();
+        let files = env::desktop_env_sources();
+        let pairs = traced
+            .into_iter()
+            .map(|(key, value, _)| (key, value))
+            .collect();
+        env::redacted_env(pairs, &env::Redactor::from_settings())
+            .into_iter()
+            .map(|(key, value)| {
+                let source = sources.get(&key).copied().unwrap_or(EnvSource::Inherited);
+                let override_file = (source == EnvSource::DesktopEnv)
+                    .then(|| files.get(OsStr::new(&key)))
+                    .flatten()
+                    .map(|path| path.display().to_string());
+                EnvVar {
+                    key,
+                    value,
+                    source,
+                    override_file,
+                }
+            })
+            .collect()
+    })
+    .await
+    .map_err(|_| DesktopError::StatePoisoned {
+        what: "sidecar environment",
+    })
+}
+
+// launch_env is the environment the next sidecar launch gets,
+// with the layer each variable came from.
+fn launch_env(app: &AppHandle) -> Vec<(OsString, OsString, EnvSource)> {
+    if app.state::<SidecarState>().clean_env.load(Ordering::SeqCst) {
+        env::clean_sidecar_env()
+            .into_iter()
+            .map(|(key, value)| (key, value, EnvSource::Clean))
+            .collect()
+    } else {
+        env::traced_sidecar_env()
+    }
+}
+
+// diagnose runs every check in the order the backend needs them.
+pub(crate) fn diagnose(app: &AppHandle) -> DoctorReport {
+    let localizer = i18n::localizer();
+    let override_path = settings::load_settings().sidecar_path;
+    let backend_port = app
+        .state::<SidecarState>()
+        .backend_port
+        .lock()
+        .ok()
+        .and_then(|guard| *guard);
+    let sidecar_env = launch_env(app);
+    let path = sidecar_env
+        .iter()
+        .find(|(key, _, _)| key.eq_ignore_ascii_case("PATH"))
+        .map(|(_, value, _)| value.clone())
+        .unwrap_or_default();
+
+    DoctorReport::new(vec![
+        sidecar_check(
+            localizer,
+            &sidecar::checked_sidecar_paths(override_path.as_deref()),
+        ),
+        port_check(localizer, backend_port, ports::range().first),
+        data_dir_check(localizer, crate::data_dir().as_deref()),
+        agents_check(localizer, &path),
+        webview_check(
+            localizer,
+            tauri::webview_version().map_err(|err| err.to_string()),
+        ),
+        arch_check(localizer, &arch::detect()),
+        clock_check(localizer, timestamps::unix_now()),
+    ])
+}
+
+fn check(
+    localizer: &Localizer,
+    id: &'static str,
+    status: CheckStatus,
+    detail: String,
+) -> DoctorCheck {
+    DoctorCheck {
+        id,
+        status,
+        name: localizer.format(&format!("doctor-{id}"), &[]),
+        detail,
+    }
+}
+
+fn sidecar_check(localizer: &Localizer, checked: &[(PathBuf, CandidateStatus)]) -> DoctorCheck {
+    let found = |wanted: CandidateStatus| {
+        checked
+            .iter()
+            .find(|(_, status)| *status == wanted)
+            .map(|(path, _)| path.display().to_string())
+    };
+    let (status, detail) = if let Some(path) = found(CandidateStatus::Present) {
+        (
+            CheckStatus::Pass,
+            localizer.format("doctor-sidecar-found", &[("path", &path)]),
+        )
+    } else if let Some(path) = found(CandidateStatus::NotExecutable) {
+        (
+            CheckStatus::Fail,
+            localizer.format("doctor-sidecar-not-executable", &[("path", &path)]),
+        )
+    } else {
+        (
+            CheckStatus::Fail,
+            localizer.format("doctor-sidecar-missing", &[("count", &checked.len())]),
+        )
+    };
+    check(localizer, "sidecar", status, detail)
+}
+
+// port_check reports the running backend's port, or whether the
+// port the backend tries first is free. A busy port only warns:
+// the backend searches for the next free one.
+fn port_check(localizer: &Localizer, backend_port: Option<u16>, preferred: u16) -> DoctorCheck {
+    let (status, detail) = match backend_port {
+        Some(port) => (
+            CheckStatus::Pass,
+            localizer.format("doctor-port-listening", &[("port", &port)]),
+        ),
+        None if ports::port_free(preferred) => (
+            CheckStatus::Pass,
+            localizer.format("doctor-port-free", &[("port", &preferred)]),
+        ),
+        None => (
+            CheckStatus::Warn,
+            localizer.format("doctor-port-busy", &[("port", &preferred)]),
+        ),
+    };
+    check(localizer, "port", status, detail)
+}
+
+fn data_dir_check(localizer: &Localizer, dir: Option<&Path>) -> DoctorCheck {
+    let (status, detail) = match dir {
+        None => (
+            CheckStatus::Fail,
+            localizer.format("doctor-data-dir-unknown", &[]),
+        ),
+        Some(dir) => {
+            let path = dir.display().to_string();
+            match fs::create_dir_all(dir).and_then(|()| tempfile::tempfile_in(dir)) {
+                Ok(_) => (
+                    CheckStatus::Pass,
+                    localizer.format("doctor-data-dir-writable", &[("path", &path)]),
+                ),
+                Err(err) => (
+                    CheckStatus::Fail,
+                    localizer.format(
+                        "doctor-data-dir-unwritable",
+                        &[("path", &path), ("error", &err)],
+                    ),
+                ),
+            }
+        }
+    };
+    check(localizer, "data-dir", status, detail)
+}
+
+// agents_check looks for the agent CLIs on the PATH the sidecar
+// gets. Missing ones only warn, since users rarely run all of
+// them.
+fn agents_check(localizer: &Localizer, path: &OsStr) -> DoctorCheck {
+    let (found, missing): (Vec<&str>, Vec<&str>) = AGENT_CLIS
+        .iter()
+        .partition(|name| find_on_path(path, name, cfg!(windows)).is_some());
+    let (found, missing) = (found.join(", "), missing.join(", "));
+    let (status, detail) = if missing.is_empty() {
+        (
+            CheckStatus::Pass,
+            localizer.format("doctor-agents-found", &[("found", &found)]),
+        )
+    } else if found.is_empty() {
+        (
+            CheckStatus::Warn,
+            localizer.format("doctor-agents-none", &[("missing", &missing)]),
+        )
+    } else {
+        (
+            CheckStatus::Warn,
+            localizer.format(
+                "doctor-agents-partial",
+                &[("found", &found), ("missing", &missing)],
+            ),
+        )
+    };
+    check(localizer, "agents", status, detail)
+}
+
+// find_on_path resolves a command name the way a shell would,
+// trying the usual executable extensions on Windows.
+pub(crate) fn find_on_path(path: &OsStr, name: &str, is_windows: bool) -> Option<PathBuf> {
+    let extensions: &[&str] = if is_windows {
+        &[".exe", ".cmd", ".bat"]
+    } else {
+        &[""]
+    };
+    std::env::split_paths(path).find_map(|dir| {
+        extensions
+            .iter()
+            .map(|ext| dir.join(format!("{name}{ext}")))
+            .find(|candidate| sidecar::candidate_status(candidate) == CandidateStatus::Present)
+    })
+}
+
+fn webview_check(localizer: &Localizer, version: Result<String, String>) -> DoctorCheck {
+    let (status, detail) = match version {
+        Ok(version) => (
+            CheckStatus::Pass,
+            localizer.format("doctor-webview-version", &[("version", &version)]),
+        ),
+        Err(error) => (
+            CheckStatus::Fail,
+            localizer.format("doctor-webview-error", &[("error", &error)]),
+        ),
+    };
+    check(localizer, "webview", status, detail)
+}
+
+// arch_check warns when the app runs under emulation on ARM64
+// Windows, or when the WebView2 runtime does not match the OS.
+fn arch_check(localizer: &Localizer, architectures: &Architectures) -> DoctorCheck {
+    let native = architectures.native.unwrap_or(architectures.shell).name();
+    let (status, detail) = match (architectures.emulated(), architectures.webview) {
+        (Some(machine), _) => (
+            CheckStatus::Warn,
+            localizer.format(
+                "doctor-arch-emulated",
+                &[("machine", &machine.name()), ("native", &native)],
+            ),
+        ),
+        (None, Some(webview)) if architectures.webview_mismatch() => (
+            CheckStatus::Warn,
+            localizer.format(
+                "doctor-arch-webview",
+                &[("machine", &webview.name()), ("native", &native)],
+            ),
+        ),
+        (None, _) => (
+            CheckStatus::Pass,
+            localizer.format("doctor-arch-native", &[("native", &native)]),
+        ),
+    };
+    check(localizer, "arch", status, detail)
+}
+
+// clock_check flags a system clock outside CLOCK_FLOOR and
+// CLOCK_CEILING; session times and live turn timers depend on it.
+fn clock_check(localizer: &Localizer, now: u64) -> DoctorCheck {
+    let time = timestamps::rfc3339_utc(now);
+    let (status, key) = if (CLOCK_FLOOR..CLOCK_CEILING).contains(&now) {
+        (CheckStatus::Pass, "doctor-clock-ok")
+    } else {
+        (CheckStatus::Warn, "doctor-clock-wrong")
+    };
+    let detail = localizer.format(key, &[("time", &time)]);
+    check(localizer, "clock", status, detail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_status_is_the_worst_check() {
+        let english = Localizer::for_locale("en");
+        let report = DoctorReport::new(vec![
+            clock_check(&english, CLOCK_FLOOR),
+            port_check(&english, Some(8080), 8080),
+            clock_check(&english, 0),
+        ]);
+        assert_eq!(report.status, CheckStatus::Warn);
+        assert_eq!(
+            report.to_text(),
+            "[PASS] System clock: 2025-01-01T00:00:00Z\n\
+             [PASS] Backend port: The backend is listening on port 8080\n\
+             [WARN] System clock: 1970-01-01T00:00:00Z looks wrong; check the date and time settings"
+        );
+        assert_eq!(DoctorReport::new(Vec::new()).status, CheckStatus::Pass);
+    }
+
+    #[test]
+    fn sidecar_check_prefers_an_executable_candidate() {
+        let english = Localizer::for_locale("en");
+        let checked = vec![
+            (
+                PathBuf::from("/opt/agentsview"),
+                CandidateStatus::NotExecutable,
+            ),
+            (PathBuf::from("/app/agentsview"), CandidateStatus::Present),
+        ];
+        let pass = sidecar_check(&english, &checked);
+        assert_eq!(pass.status, CheckStatus::Pass);
+        assert_eq!(pass.detail, "Found at /app/agentsview");
+
+        assert_eq!(
+            sidecar_check(&english, &checked[..1]).status,
+            CheckStatus::Fail
+        );
+        assert_eq!(sidecar_check(&english, &[]).status, CheckStatus::Fail);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn find_on_path_requires_an_executable() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let bin = dir.path().join("bin");
+        fs::create_dir(&bin).unwrap();
+        let claude = bin.join("claude");
+        fs::write(&claude, "#!/bin/sh\n").unwrap();
+        fs::write(bin.join("codex"), "").unwrap();
+        fs::set_permissions(&claude, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let path = std::env::join_paths([dir.path(), &bin]).unwrap();
+        assert_eq!(find_on_path(&path, "claude", false), Some(claude));
+        assert_eq!(find_on_path(&path, "codex", false), None);
+
+        let english = Localizer::for_locale("en");
+        let agents = agents_check(&english, &path);
+        assert_eq!(agents.status, CheckStatus::Warn);
+        assert_eq!(agents.detail, "Found: claude. Not on PATH: codex, gemini");
+    }
+
+    #[test]
+    fn data_dir_check_reports_unwritable_dirs() {
+        let english = Localizer::for_locale("en");
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            data_dir_check(&english, Some(dir.path())).status,
+            CheckStatus::Pass
+        );
+        let file = dir.path().join("file");
+        fs::write(&file, "").unwrap();
+        assert_eq!(
+            data_dir_check(&english, Some(&file.join("data"))).status,
+            CheckStatus::Fail
+        );
+        assert_eq!(data_dir_check(&english, None).status, CheckStatus::Fail);
+    }
+
+    #[test]
+    fn arch_check_warns_about_emulation() {
+        use crate::arch::Machine;
+
+        let english = Localizer::for_locale("en");
+        let native = Architectures {
+            native: Some(Machine::Arm64),
+            shell: Machine::Arm64,
+            sidecar: Some(Machine::Arm64),
+            webview: Some(Machine::Arm64),
+        };
+        assert_eq!(arch_check(&english, &native).status, CheckStatus::Pass);
+        let emulated = arch_check(
+            &english,
+            &Architectures {
+                sidecar: Some(Machine::X64),
+                ..native
+            },
+        );
+        assert_eq!(emulated.status, CheckStatus::Warn);
+        assert!(emulated.detail.contains("x64"));
+    }
+}
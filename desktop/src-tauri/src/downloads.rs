@@ -0,0 +1,322 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::webview::DownloadEvent;
+use tauri::{Emitter, Manager, Webview};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::web_permissions::{self, Verdict, WebPermission};
+
+// DOWNLOAD_EVENT tells the page that a download it started has
+// finished, with a DownloadFinished payload.
+pub(crate) const DOWNLOAD_EVENT: &str = "agentsview://download-finished";
+
+// STAGING_DIR, under the system temp dir, holds downloads while
+// the save dialog is open.
+const STAGING_DIR: &str = "agentsview-downloads";
+
+static STAGED: AtomicU64 = AtomicU64::new(0);
+
+// DownloadState tracks, by URL, the downloads being written to a
+// staging file while the user picks where they go.
+#[derive(Default)]
+pub(crate) struct DownloadState {
+    pending: Mutex<HashMap<String, PendingDownload>>,
+}
+
+// PendingDownload is one staged download. choice is None until
+// the dialog closes, then the picked path or None when it was
+// cancelled; success is None until the webview finishes writing.
+struct PendingDownload {
+    staging: PathBuf,
+    choice: Option<Option<PathBuf>>,
+    success: Option<bool>,
+}
+
+// Completed is a staged download whose file and dialog are both
+// done, ready for deliver.
+#[derive(Debug, PartialEq, Eq)]
+struct Completed {
+    staging: PathBuf,
+    path: Option<PathBuf>,
+    success: bool,
+}
+
+impl DownloadState {
+    fn stage(&self, url: String, staging: PathBuf) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.insert(
+                url,
+                PendingDownload {
+                    staging,
+                    choice: None,
+                    success: None,
+                },
+            );
+        }
+    }
+
+    // choose records the dialog's answer for url.
+    fn choose(&self, url: &str, path: Option<PathBuf>) -> Option<Completed> {
+        self.update(url, |download| download.choice = Some(path))
+    }
+
+    // finish records that the webview is done writing url.
+    fn finish(&self, url: &str, success: bool) -> Option<Completed> {
+        self.update(url, |download| download.success = Some(success))
+    }
+
+    // update applies change to url's download and takes it out of
+    // the pending set once both halves are in, whichever order
+    // they arrive in.
+    fn update(&self, url: &str, change: impl FnOnce(&mut PendingDownload)) -> Option<Completed> {
+        let mut pending = self.pending.lock().ok()?;
+        let download = pending.get_mut(url)?;
+        change(download);
+        if download.choice.is_none() || download.success.is_none() {
+            return None;
+        }
+        let download = pending.remove(url)?;
+        Some(Completed {
+            staging: download.staging,
+            path: download.choice.flatten(),
+            success: download.success.unwrap_or(false),
+        })
+    }
+}
+
+// DownloadFinished is the DOWNLOAD_EVENT payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct DownloadFinished {
+    url: String,
+    path: String,
+    success: bool,
+}
+
+// on_download is the main window's download handler, so exports
+// behave the same on every platform. A new download is written to
+// a staging file while a native save dialog asks where it goes:
+// the page may revoke a blob URL as soon as it has clicked it, so
+// the download cannot be held back and repeated. Once both are
+// done the file is moved to the chosen path, the page gets
+// DOWNLOAD_EVENT and the user a notification.
+pub(crate) fn on_download(webview: Webview, event: DownloadEvent<'_>) -> bool {
+    match event {
+        DownloadEvent::Requested { url, destination } => {
+            match web_permissions::verdict(WebPermission::Download) {
+                Verdict::Allow => return true,
+                Verdict::Deny => return false,
+                Verdict::Prompt => {}
+            }
+            let staging = match staging_path(destination) {
+                Ok(staging) => staging,
+                Err(err) => {
+                    eprintln!("[agentsview] could not stage the download: {err}");
+                    return false;
+                }
+            };
+            let url = url.to_string();
+            webview
+                .state::<DownloadState>()
+                .stage(url.clone(), staging.clone());
+            ask_destination(webview, url, destination);
+            *destination = staging;
+            true
+        }
+        DownloadEvent::Finished { url, success, .. } => {
+            let completed = webview
+                .state::<DownloadState>()
+                .finish(url.as_str(), success);
+            if let Some(completed) = completed {
+                report(&webview, url.as_str(), completed);
+            }
+            true
+        }
+        _ => true,
+    }
+}
+
+// staging_path is a fresh file in STAGING_DIR carrying the name
+// the webview would have used.
+fn staging_path(suggested: &Path) -> io::Result<PathBuf> {
+    let dir = std::env::temp_dir().join(STAGING_DIR);
+    fs::create_dir_all(&dir)?;
+    let name = suggested
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "download".to_string());
+    let n = STAGED.fetch_add(1, Ordering::Relaxed);
+    Ok(dir.join(format!("{}-{n}-{name}", std::process::id())))
+}
+
+// ask_destination shows the save dialog, starting from the name
+// and folder the webview would have used.
+fn ask_destination(webview: Webview, url: String, suggested: &Path) {
+    let name = suggested
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "download".to_string());
+    let mut dialog = webview.dialog().file().set_file_name(&name);
+    if let Some(dir) = suggested.parent().filter(|dir| dir.is_dir()) {
+        dialog = dialog.set_directory(dir);
+    }
+    dialog.save_file(move |picked| {
+        let path = picked.and_then(|picked| picked.into_path().ok());
+        let completed = webview.state::<DownloadState>().choose(&url, path);
+        if let Some(completed) = completed {
+            report(&webview, &url, completed);
+        }
+    });
+}
+
+// deliver moves a completed download to the chosen path and
+// returns that path and whether the file got there. A download
+// whose dialog was cancelled is discarded and reports nothing.
+fn deliver(completed: Completed) -> Option<(PathBuf, bool)> {
+    let Some(path) = completed.path else {
+        let _ = fs::remove_file(&completed.staging);
+        return None;
+    };
+    if !completed.success {
+        let _ = fs::remove_file(&completed.staging);
+        return Some((path, false));
+    }
+    // The temp dir may be on another volume than the destination,
+    // where a rename fails.
+    let moved = fs::rename(&completed.staging, &path).is_ok()
+        || fs::copy(&completed.staging, &path).is_ok();
+    let _ = fs::remove_file(&completed.staging);
+    Some((path, moved))
+}
+
+// report delivers a download the user saved and tells the page
+// and the user how it went.
+fn report(webview: &Webview, url: &str, completed: Completed) {
+    let Some((path, success)) = deliver(completed) else {
+        return;
+    };
+    if success {
+        eprintln!("[agentsview] saved download to {}", path.display());
+    } else {
+        eprintln!("[agentsview] download to {} failed", path.display());
+    }
+    let payload = DownloadFinished {
+        url: url.to_string(),
+        path: path.display().to_string(),
+        success,
+    };
+    if let Err(err) = webview.emit(DOWNLOAD_EVENT, payload) {
+        eprintln!("[agentsview] failed to emit download event: {err}");
+    }
+    #[cfg(desktop)]
+    notify_finished(webview.app_handle(), &path, success);
+}
+
+#[cfg(desktop)]
+fn notify_finished(app: &tauri::AppHandle, path: &Path, success: bool) {
+    use crate::i18n;
+    use crate::notify::{self, Notice};
+
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let folder = path
+        .parent()
+        .map(|dir| dir.display().to_string())
+        .unwrap_or_default();
+    let (title, body) = if success {
+        (
+            i18n::tr("download-saved-title"),
+            i18n::tr_args(
+                "download-saved-body",
+                &[("name", &name), ("folder", &folder)],
+            ),
+        )
+    } else {
+        (
+            i18n::tr("download-failed-title"),
+            i18n::tr_args("download-failed-body", &[("name", &name)]),
+        )
+    };
+    notify::post(
+        app,
+        Notice {
+            title,
+            body,
+            sound: None,
+            route: None,
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URL: &str = "blob:http://127.0.0.1:18080/5b1c2e2a";
+    const CSV: &str = "session,messages\ns1,4\n";
+
+    // stage_blob_export stands in for the webview saving an
+    // analytics CSV export from a blob URL to its staging file.
+    fn stage_blob_export(dir: &Path) -> (DownloadState, PathBuf) {
+        let staging = dir.join("staging-analytics.csv");
+        let state = DownloadState::default();
+        state.stage(URL.to_string(), staging.clone());
+        fs::write(&staging, CSV).expect("write staged export");
+        (state, staging)
+    }
+
+    #[test]
+    fn blob_export_finished_before_the_dialog_is_saved() {
+        // The page revokes the blob 100 ms after clicking it, long
+        // before the user answers the dialog.
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let (state, staging) = stage_blob_export(dir.path());
+        assert_eq!(state.finish(URL, true), None, "dialog still open");
+
+        let chosen = dir.path().join("analytics.csv");
+        let completed = state
+            .choose(URL, Some(chosen.clone()))
+            .expect("download and dialog both done");
+        assert_eq!(deliver(completed), Some((chosen.clone(), true)));
+        assert_eq!(fs::read_to_string(&chosen).expect("read export"), CSV);
+        assert!(!staging.exists());
+        assert_eq!(state.finish(URL, true), None, "no longer pending");
+    }
+
+    #[test]
+    fn blob_export_saved_before_it_finishes_writing() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let (state, _) = stage_blob_export(dir.path());
+        let chosen = dir.path().join("analytics.csv");
+        assert_eq!(state.choose(URL, Some(chosen.clone())), None);
+
+        let completed = state.finish(URL, true).expect("download done");
+        assert_eq!(deliver(completed), Some((chosen.clone(), true)));
+        assert_eq!(fs::read_to_string(&chosen).expect("read export"), CSV);
+    }
+
+    #[test]
+    fn cancelled_and_failed_exports_leave_no_staging_file() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let (state, staging) = stage_blob_export(dir.path());
+        assert_eq!(state.finish(URL, true), None);
+        let completed = state.choose(URL, None).expect("dialog cancelled");
+        assert_eq!(deliver(completed), None);
+        assert!(!staging.exists());
+
+        let (state, staging) = stage_blob_export(dir.path());
+        let chosen = dir.path().join("analytics.csv");
+        assert_eq!(state.choose(URL, Some(chosen.clone())), None);
+        let completed = state.finish(URL, false).expect("download failed");
+        assert_eq!(deliver(completed), Some((chosen.clone(), false)));
+        assert!(!staging.exists());
+        assert!(!chosen.exists());
+    }
+}
@@ -0,0 +1,253 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Webview};
+
+use crate::env::{self, Redactor};
+use crate::error::DesktopError;
+use crate::SidecarState;
+
+// FILE_LOCK serializes edits to desktop.env, which are a read,
+// rewrite and rename.
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+
+// EnvOverride is one desktop.env line as shown to a settings page.
+// Secret values are redacted; see env::Redactor. Only overrides in
+// desktop.env itself are editable, not those in fragments.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct EnvOverride {
+    key: String,
+    value: String,
+    mode: &'static str,
+    secret: bool,
+    file: String,
+    editable: bool,
+}
+
+// EnvOverrides lists the overrides in the order they apply.
+// restart_needed is set once an override changed after the running
+// sidecar was launched.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct EnvOverrides {
+    overrides: Vec<EnvOverride>,
+    restart_needed: bool,
+}
+
+#[tauri::command]
+pub(crate) fn get_env_overrides(app: AppHandle) -> EnvOverrides {
+    overrides(&app)
+}
+
+// set_env_override sets key to value in desktop.env. value may
+// reference other variables as ${VAR}. desktop.env reaches the
+// sidecar's environment, so only the shell's own pages may edit it.
+#[tauri::command]
+pub(crate) fn set_env_override(
+    webview: Webview,
+    app: AppHandle,
+    key: String,
+    value: String,
+) -> Result<EnvOverrides, DesktopError> {
+    crate::require_bundled_page(&webview, "set_env_override")?;
+    if !valid_key(&key) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{key:?} is not a valid environment variable name"),
+        )
+        .into());
+    }
+    edit_file(&app, |content| Some(set_override(content, &key, &value)))
+}
+
+#[tauri::command]
+pub(crate) fn remove_env_override(
+    webview: Webview,
+    app: AppHandle,
+    key: String,
+) -> Result<EnvOverrides, DesktopError> {
+    crate::require_bundled_page(&webview, "remove_env_override")?;
+    edit_file(&app, |content| remove_override(content, &key))
+}
+
+// edit_file rewrites desktop.env with edit, which returns None when
+// nothing changes, and flags a running sidecar for restart.
+fn edit_file(
+    app: &AppHandle,
+    edit: impl FnOnce(&str) -> Option<String>,
+) -> Result<EnvOverrides, DesktopError> {
+    let path = env::desktop_env_path()
+        .ok_or_else(|| io::Error::other("could not resolve the home directory"))?;
+    {
+        let _guard = FILE_LOCK.lock().map_err(|_| DesktopError::StatePoisoned {
+            what: "desktop.env",
+        })?;
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err.into()),
+        };
+        if let Some(updated) = edit(&content) {
+            write_atomically(&path, &updated)?;
+            let state = app.state::<SidecarState>();
+            if state.child.lock().is_ok_and(|child| child.is_some()) {
+                state.env_changed.store(true, Ordering::SeqCst);
+            }
+        }
+    }
+    Ok(overrides(app))
+}
+
+fn overrides(app: &AppHandle) -> EnvOverrides {
+    let editable = env::desktop_env_path();
+    let redactor = Redactor::from_settings();
+    let overrides = env::desktop_env_layers()
+        .into_iter()
+        .flat_map(|layer| {
+            let file = layer.path.display().to_string();
+            let is_editable = editable.as_deref() == Some(layer.path.as_path());
+            let redactor = &redactor;
+            layer.vars.into_iter().map(move |(key, edit)| {
+                let secret = redactor.is_secret(&key);
+                EnvOverride {
+                    key: key.to_string_lossy().into_owned(),
+                    value: if secret {
+                        "<redacted>".to_string()
+                    } else {
+                        edit.value()
+                            .map(|value| value.template())
+                            .unwrap_or_default()
+                    },
+                    mode: edit.mode(),
+                    secret,
+                    file: file.clone(),
+                    editable: is_editable,
+                }
+            })
+        })
+        .collect();
+    EnvOverrides {
+        overrides,
+        restart_needed: app
+            .state::<SidecarState>()
+            .env_changed
+            .load(Ordering::SeqCst),
+    }
+}
+
+fn write_atomically(path: &Path, content: &str) -> io::Result<()> {
+    let dir = path
+        .parent()
+        .ok_or_else(|| io::Error::other("desktop.env has no parent directory"))?;
+    fs::create_dir_all(dir)?;
+    let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+    tmp.write_all(content.as_bytes())?;
+    tmp.as_file().sync_all()?;
+    tmp.persist(path).map_err(|err| err.error)?;
+    Ok(())
+}
+
+fn valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn same_key(line: &str, key: &str) -> bool {
+    env::desktop_env_line_key(line).is_some_and(|line_key| {
+        if cfg!(windows) {
+            line_key.eq_ignore_ascii_case(key)
+        } else {
+            line_key == key
+        }
+    })
+}
+
+// set_override replaces the first line for key and drops any
+// others, such as PATH+= additions, so value is what key ends up
+// with. A new key goes at the end. Comments are kept.
+fn set_override(content: &str, key: &str, value: &str) -> String {
+    let line = override_line(key, value);
+    let mut lines = Vec::new();
+    let mut replaced = false;
+    for existing in content.lines() {
+        if !same_key(existing, key) {
+            lines.push(existing.to_string());
+        } else if !replaced {
+            lines.push(line.clone());
+            replaced = true;
+        }
+    }
+    if !replaced {
+        lines.push(line);
+    }
+    lines.join("\n") + "\n"
+}
+
+// remove_override drops every line for key, or returns None when
+// there is none.
+fn remove_override(content: &str, key: &str) -> Option<String> {
+    let kept: Vec<&str> = content
+        .lines()
+        .filter(|line| !same_key(line, key))
+        .collect();
+    if kept.len() == content.lines().count() {
+        return None;
+    }
+    Some(kept.iter().map(|line| format!("{line}\n")).collect())
+}
+
+// override_line writes value bare when it reads back unchanged, and
+// double-quoted otherwise. ${VAR} references stay references.
+fn override_line(key: &str, value: &str) -> String {
+    let bare = !value.is_empty()
+        && !value
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '"' | '\'' | '#' | '\\'));
+    if bare {
+        return format!("{key}={value}");
+    }
+    let mut quoted = String::with_capacity(value.len() + 2);
+    for c in value.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    format!("{key}=\"{quoted}\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_override_replaces_lines_for_the_key_and_keeps_the_rest() {
+        let content = "# proxy\nHTTP_PROXY=http://proxy\nPATH^=/opt/bin\nexport PATH=/bin\n";
+        assert_eq!(
+            set_override(content, "PATH", "/usr/bin:${HOME}/bin"),
+            "# proxy\nHTTP_PROXY=http://proxy\nPATH=/usr/bin:${HOME}/bin\n"
+        );
+        assert_eq!(
+            set_override("", "GREETING", "say \"hi\"\tnow"),
+            "GREETING=\"say \\\"hi\\\"\\tnow\"\n"
+        );
+        assert_eq!(
+            remove_override(content, "PATH").as_deref(),
+            Some("# proxy\nHTTP_PROXY=http://proxy\n")
+        );
+        assert_eq!(remove_override(content, "MISSING"), None);
+        assert!(valid_key("_MY_VAR2"));
+        assert!(!valid_key("2FAST"));
+        assert!(!valid_key("PATH+"));
+    }
+}
@@ -0,0 +1,200 @@
+use std::io;
+use std::time::Duration;
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::i18n::{self, Localizer};
+
+// DesktopError classifies failures in the desktop shell. Each
+// variant carries enough context to render a user-facing message
+// and a remediation hint instead of an opaque error string.
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum DesktopError {
+    #[error("failed to start the agentsview sidecar: {reason}")]
+    SidecarSpawn { reason: String },
+    #[error("the agentsview sidecar at {path} failed its integrity check: {reason}")]
+    SidecarIntegrity { path: String, reason: String },
+    #[error("backend port {port} is not available: {reason}")]
+    PortUnavailable { port: u16, reason: String },
+    #[error("login shell environment probe failed for {shell}")]
+    EnvProbe { shell: String },
+    #[error("backend did not become ready within {}s", timeout.as_secs())]
+    Readiness { timeout: Duration },
+    #[error("backend exited before startup completed (code: {code:?})")]
+    SidecarExited { code: Option<i32> },
+    #[error("could not connect to {url}: {reason}")]
+    RemoteConnect { url: String, reason: String },
+    #[error("backend request {path} failed: {reason}")]
+    BackendRequest { path: String, reason: String },
+    #[error("backend does not serve {path}")]
+    BackendUnsupported { path: String },
+    #[error("desktop action {action} is unavailable: {reason}")]
+    ActionUnavailable { action: String, reason: String },
+    #[error("{path} is outside the session's project and agent log directories")]
+    PathNotAllowed { path: String },
+    #[error("backend reported listening on port {port}, but connections {failure}")]
+    ConnectionBlocked { port: u16, failure: &'static str },
+    #[error("{what} lock poisoned")]
+    StatePoisoned { what: &'static str },
+    #[error("missing {label} window")]
+    MissingWindow { label: &'static str },
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+impl DesktopError {
+    // kind is a stable identifier for the failure class, used by
+    // the frontend and logs to branch on errors without parsing
+    // message text.
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Self::SidecarSpawn { .. } => "sidecar_spawn",
+            Self::SidecarIntegrity { .. } => "sidecar_integrity",
+            Self::PortUnavailable { .. } => "port_unavailable",
+            Self::EnvProbe { .. } => "env_probe",
+            Self::Readiness { .. } => "readiness",
+            Self::SidecarExited { .. } => "sidecar_exited",
+            Self::RemoteConnect { .. } => "remote_connect",
+            Self::BackendRequest { .. } => "backend_request",
+            Self::BackendUnsupported { .. } => "backend_unsupported",
+            Self::ActionUnavailable { .. } => "action_unavailable",
+            Self::PathNotAllowed { .. } => "path_not_allowed",
+            Self::ConnectionBlocked { .. } => "connection_blocked",
+            Self::StatePoisoned { .. } => "state_poisoned",
+            Self::MissingWindow { .. } => "missing_window",
+            Self::Io(_) => "io",
+        }
+    }
+
+    // user_message is a short, non-technical description of what
+    // went wrong, suitable for the splash screen or a dialog.
+    pub(crate) fn user_message(&self) -> String {
+        self.user_message_in(i18n::localizer())
+    }
+
+    fn user_message_in(&self, l10n: &Localizer) -> String {
+        match self {
+            Self::SidecarSpawn { .. } => l10n.format("error-sidecar-spawn", &[]),
+            Self::SidecarIntegrity { .. } => l10n.format("error-sidecar-integrity", &[]),
+            Self::PortUnavailable { port: 0, .. } => l10n.format("error-port-unavailable-any", &[]),
+            Self::PortUnavailable { port, .. } => {
+                l10n.format("error-port-unavailable", &[("port", port)])
+            }
+            Self::EnvProbe { .. } => l10n.format("error-env-probe", &[]),
+            Self::Readiness { timeout } => {
+                l10n.format("error-readiness", &[("seconds", &timeout.as_secs())])
+            }
+            Self::SidecarExited { .. } => l10n.format("error-sidecar-exited", &[]),
+            Self::RemoteConnect { url, .. } => l10n.format("error-remote-connect", &[("url", url)]),
+            Self::BackendRequest { .. } => l10n.format("error-backend-request", &[]),
+            Self::BackendUnsupported { .. } => l10n.format("error-backend-unsupported", &[]),
+            Self::ActionUnavailable { reason, .. } => {
+                l10n.format("error-action-unavailable", &[("reason", reason)])
+            }
+            Self::PathNotAllowed { .. } => l10n.format("error-path-not-allowed", &[]),
+            Self::ConnectionBlocked { port, .. } => {
+                l10n.format("error-connection-blocked", &[("port", port)])
+            }
+            Self::StatePoisoned { .. } | Self::MissingWindow { .. } | Self::Io(_) => {
+                l10n.format("error-internal", &[])
+            }
+        }
+    }
+
+    // hint suggests a next step the user can take to recover.
+    pub(crate) fn hint(&self) -> String {
+        let key = match self {
+            Self::SidecarSpawn { .. } => "hint-sidecar-spawn",
+            Self::SidecarIntegrity { .. } => "hint-sidecar-integrity",
+            Self::PortUnavailable { .. } => "hint-port-unavailable",
+            Self::EnvProbe { .. } => "hint-env-probe",
+            Self::Readiness { .. } => "hint-readiness",
+            Self::SidecarExited { .. } => "hint-sidecar-exited",
+            Self::RemoteConnect { .. } => "hint-remote-connect",
+            Self::BackendRequest { .. } => "hint-backend-request",
+            Self::BackendUnsupported { .. } => "hint-backend-unsupported",
+            Self::ActionUnavailable { .. } => "hint-action-unavailable",
+            Self::PathNotAllowed { .. } => "hint-path-not-allowed",
+            // Firewall and endpoint protection steps differ by OS.
+            Self::ConnectionBlocked { .. } if cfg!(windows) => "hint-connection-blocked-windows",
+            Self::ConnectionBlocked { .. } if cfg!(target_os = "macos") => {
+                "hint-connection-blocked-macos"
+            }
+            Self::ConnectionBlocked { .. } => "hint-connection-blocked-linux",
+            Self::StatePoisoned { .. } | Self::MissingWindow { .. } | Self::Io(_) => {
+                "hint-internal"
+            }
+        };
+        i18n::tr(key)
+    }
+}
+
+// Serialize renders errors as {kind, message, hint, detail} so
+// Tauri commands can return DesktopError directly.
+impl Serialize for DesktopError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("DesktopError", 4)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.user_message())?;
+        state.serialize_field("hint", &self.hint())?;
+        state.serialize_field("detail", &self.to_string())?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn desktop_error_messages_include_context() {
+        let err = DesktopError::PortUnavailable {
+            port: 8080,
+            reason: "address in use".to_string(),
+        };
+        assert_eq!(err.kind(), "port_unavailable");
+        assert_eq!(
+            err.to_string(),
+            "backend port 8080 is not available: address in use"
+        );
+        assert!(err.user_message().contains("8080"));
+        assert!(!err.hint().is_empty());
+
+        let readiness = DesktopError::Readiness {
+            timeout: Duration::from_secs(30),
+        };
+        assert_eq!(
+            readiness.user_message_in(&Localizer::for_locale("en")),
+            "AgentsView backend did not start within 30 seconds."
+        );
+        assert_eq!(
+            readiness.user_message_in(&Localizer::for_locale("es")),
+            "El backend de AgentsView no se inició en 30 segundos."
+        );
+    }
+
+    #[test]
+    fn connection_blocked_names_the_port() {
+        let err = DesktopError::ConnectionBlocked {
+            port: 8080,
+            failure: "were refused",
+        };
+        assert_eq!(err.kind(), "connection_blocked");
+        assert_eq!(
+            err.to_string(),
+            "backend reported listening on port 8080, but connections were refused"
+        );
+        assert!(err
+            .user_message_in(&Localizer::for_locale("en"))
+            .contains("8080"));
+        assert!(!err.hint().is_empty());
+    }
+
+    #[test]
+    fn desktop_error_wraps_io_errors() {
+        let err: DesktopError = io::Error::other("disk full").into();
+        assert_eq!(err.kind(), "io");
+        assert_eq!(err.to_string(), "disk full");
+    }
+}
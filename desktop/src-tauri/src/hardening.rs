@@ -0,0 +1,74 @@
+use std::ffi::OsStr;
+use std::sync::OnceLock;
+
+// DEVTOOLS_ENV opens up the web inspector in a release build built
+// with the devtools feature, for debugging a problem that only
+// shows up there.
+const DEVTOOLS_ENV: &str = "AGENTSVIEW_DESKTOP_DEVTOOLS";
+
+static USER_AGENT: OnceLock<String> = OnceLock::new();
+
+// devtools_enabled reports whether windows get the web inspector,
+// including the context menu's Inspect Element. Debug builds always
+// do; release builds only with the devtools feature and DEVTOOLS_ENV
+// set.
+pub(crate) fn devtools_enabled() -> bool {
+    let flag = std::env::var_os(DEVTOOLS_ENV);
+    let enabled = devtools_enabled_from(
+        cfg!(debug_assertions),
+        cfg!(feature = "devtools"),
+        flag.as_deref(),
+    );
+    if !cfg!(debug_assertions) && crate::mock::is_truthy_flag(flag.as_deref()) {
+        if enabled {
+            eprintln!("[agentsview] {DEVTOOLS_ENV} is set; enabling the web inspector");
+        } else {
+            eprintln!(
+                "[agentsview] ignoring {DEVTOOLS_ENV}: this build lacks the devtools feature"
+            );
+        }
+    }
+    enabled
+}
+
+fn devtools_enabled_from(debug_build: bool, feature: bool, flag: Option<&OsStr>) -> bool {
+    debug_build || (feature && crate::mock::is_truthy_flag(flag))
+}
+
+// user_agent is the User-Agent the window and the shell's own
+// requests send to the backend. It names the app, its version and
+// the platform, and does not change with the webview engine or its
+// version, so backend logs and any proxy in front of a remote
+// server see one client.
+pub(crate) fn user_agent() -> &'static str {
+    USER_AGENT.get_or_init(|| {
+        user_agent_for(
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+            env!("CARGO_PKG_VERSION"),
+        )
+    })
+}
+
+fn user_agent_for(os: &str, arch: &str, version: &str) -> String {
+    format!("Mozilla/5.0 ({os}; {arch}) AgentsViewDesktop/{version}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn devtools_need_a_debug_build_or_the_feature_and_flag() {
+        assert!(devtools_enabled_from(true, false, None));
+        assert!(!devtools_enabled_from(false, true, None));
+        assert!(!devtools_enabled_from(false, true, Some(OsStr::new("0"))));
+        assert!(devtools_enabled_from(false, true, Some(OsStr::new("1"))));
+        assert!(!devtools_enabled_from(false, false, Some(OsStr::new("1"))));
+
+        assert_eq!(
+            user_agent_for("windows", "x86_64", "0.1.0"),
+            "Mozilla/5.0 (windows; x86_64) AgentsViewDesktop/0.1.0"
+        );
+    }
+}
@@ -0,0 +1,472 @@
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::time::Duration;
+
+use rustls::pki_types::ServerName;
+use serde::de::DeserializeOwned;
+
+use crate::hardening;
+use crate::proxy::ProxyServer;
+
+// MAX_RESPONSE_BYTES caps what a probe reads. Version and health
+// responses are a few hundred bytes.
+const MAX_RESPONSE_BYTES: usize = 1024 * 1024;
+// MAX_REDIRECTS is how many same-server redirects get follows.
+const MAX_REDIRECTS: usize = 3;
+
+// Response is a parsed HTTP/1.x response, with a chunked body
+// already decoded.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Response {
+    pub(crate) status: u16,
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) body: Vec<u8>,
+}
+
+impl Response {
+    // header returns the first value of the named header.
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub(crate) fn json<T: DeserializeOwned>(&self) -> Option<T> {
+        serde_json::from_slice(&self.body).ok()
+    }
+}
+
+// Error is why a request got no usable response. Connect keeps the
+// connect error, whose kind tells a blocked port from a server that
+// is not listening yet.
+#[derive(Debug)]
+pub(crate) enum Error {
+    Connect(io::Error),
+    Io(io::Error),
+    Malformed(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Connect(err) | Self::Io(err) => err.fmt(f),
+            Self::Malformed(reason) => f.write_str(reason),
+        }
+    }
+}
+
+// Tls is what get needs to speak HTTPS: the client configuration
+// (see tls::client_config) and the name the server's certificate
+// must match.
+pub(crate) struct Tls {
+    pub(crate) config: Arc<rustls::ClientConfig>,
+    pub(crate) server_name: String,
+}
+
+// Route is how get reaches a server: directly at one of its
+// addresses, or through an HTTP proxy, which resolves the server's
+// name itself. Loopback requests are always Direct.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Route<'a> {
+    Direct(SocketAddr),
+    Proxy(&'a ProxyServer),
+}
+
+// get fetches path from the server named host, sending cookie, if
+// any, as the Cookie header, over TLS when tls is set. Redirects
+// are followed only while they stay on the same server.
+pub(crate) fn get(
+    route: Route<'_>,
+    host: &str,
+    path: &str,
+    timeout: Duration,
+    tls: Option<&Tls>,
+    cookie: Option<&str>,
+) -> Result<Response, Error> {
+    let scheme = if tls.is_some() { "https" } else { "http" };
+    let mut path = path.to_string();
+    for _ in 0..=MAX_REDIRECTS {
+        let response = get_once(route, host, &path, timeout, tls, cookie)?;
+        if !matches!(response.status, 301 | 302 | 303 | 307 | 308) {
+            return Ok(response);
+        }
+        match response
+            .header("location")
+            .and_then(|location| same_server_path(location, scheme, host))
+        {
+            Some(next) => path = next,
+            None => return Ok(response),
+        }
+    }
+    Err(Error::Malformed("too many redirects"))
+}
+
+fn get_once(
+    route: Route<'_>,
+    host: &str,
+    path: &str,
+    timeout: Duration,
+    tls: Option<&Tls>,
+    cookie: Option<&str>,
+) -> Result<Response, Error> {
+    let (stream, proxy) = match route {
+        Route::Direct(addr) => (connect(addr, timeout).map_err(Error::Connect)?, None),
+        Route::Proxy(proxy) => {
+            let addrs = (proxy.host.as_str(), proxy.port)
+                .to_socket_addrs()
+                .map_err(Error::Connect)?;
+            let mut last_err = io::Error::from(io::ErrorKind::AddrNotAvailable);
+            let stream = addrs
+                .filter_map(|addr| connect(addr, timeout).map_err(|err| last_err = err).ok())
+                .next();
+            (stream.ok_or(Error::Connect(last_err))?, Some(proxy))
+        }
+    };
+    let Some(tls) = tls else {
+        // A proxy takes plain HTTP requests with the full URL.
+        let target = match proxy {
+            Some(proxy) => Target::Proxied(proxy, host),
+            None => Target::Origin,
+        };
+        return exchange(stream, host, path, cookie, target);
+    };
+    let mut stream = stream;
+    if let Some(proxy) = proxy {
+        open_tunnel(&mut stream, proxy, host)?;
+    }
+    let name = ServerName::try_from(tls.server_name.clone())
+        .map_err(|_| Error::Malformed("invalid TLS server name"))?;
+    let conn = rustls::ClientConnection::new(tls.config.clone(), name)
+        .map_err(|err| Error::Io(io::Error::other(err)))?;
+    exchange(
+        rustls::StreamOwned::new(conn, stream),
+        host,
+        path,
+        cookie,
+        Target::Origin,
+    )
+}
+
+fn connect(addr: SocketAddr, timeout: Duration) -> Result<TcpStream, io::Error> {
+    let stream = TcpStream::connect_timeout(&addr, timeout)?;
+    let _ = stream.set_read_timeout(Some(timeout));
+    let _ = stream.set_write_timeout(Some(timeout));
+    Ok(stream)
+}
+
+// open_tunnel asks proxy to CONNECT stream to host, so TLS runs end
+// to end with the server.
+fn open_tunnel(stream: &mut TcpStream, proxy: &ProxyServer, host: &str) -> Result<(), Error> {
+    let request = format!(
+        "CONNECT {host} HTTP/1.1\r\nHost: {host}\r\n{}\r\n",
+        proxy.authorization()
+    );
+    stream.write_all(request.as_bytes()).map_err(Error::Io)?;
+    // Read byte by byte so nothing after the proxy's reply, which
+    // belongs to the TLS handshake, is consumed.
+    let mut head = Vec::new();
+    let mut byte = [0u8; 1];
+    while !head.ends_with(b"\r\n\r\n") {
+        if head.len() > MAX_RESPONSE_BYTES {
+            return Err(Error::Malformed("proxy response too large"));
+        }
+        match stream.read(&mut byte).map_err(Error::Io)? {
+            0 => return Err(Error::Malformed("proxy closed the connection")),
+            _ => head.push(byte[0]),
+        }
+    }
+    let status = String::from_utf8_lossy(&head)
+        .split(' ')
+        .nth(1)
+        .and_then(|code| code.parse::<u16>().ok());
+    match status {
+        Some(200..=299) => Ok(()),
+        Some(407) => Err(Error::Malformed("proxy requires authentication")),
+        _ => Err(Error::Malformed("proxy refused the connection")),
+    }
+}
+
+// Target is the form of the request line: a path for the origin
+// server, or the full URL for a proxy forwarding plain HTTP.
+enum Target<'a> {
+    Origin,
+    Proxied(&'a ProxyServer, &'a str),
+}
+
+// exchange sends one GET over stream and reads the response. TLS
+// handshake and certificate failures surface here as Io errors.
+fn exchange<S: Read + Write>(
+    mut stream: S,
+    host: &str,
+    path: &str,
+    cookie: Option<&str>,
+    target: Target<'_>,
+) -> Result<Response, Error> {
+    let mut extra = cookie
+        .map(|cookie| format!("Cookie: {cookie}\r\n"))
+        .unwrap_or_default();
+    let target = match target {
+        Target::Origin => path.to_string(),
+        Target::Proxied(proxy, host) => {
+            extra.push_str(&proxy.authorization());
+            format!("http://{host}{path}")
+        }
+    };
+    let request = format!(
+        "GET {target} HTTP/1.1\r\nHost: {host}\r\nUser-Agent: {}\r\nAccept: application/json\r\n{extra}Connection: close\r\n\r\n",
+        hardening::user_agent()
+    );
+    stream.write_all(request.as_bytes()).map_err(Error::Io)?;
+
+    let mut raw = Vec::with_capacity(4096);
+    let mut chunk = [0u8; 4096];
+    loop {
+        let n = match stream.read(&mut chunk) {
+            Ok(n) => n,
+            // Servers that close without a TLS close_notify still
+            // end the response there.
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => 0,
+            Err(err) => return Err(Error::Io(err)),
+        };
+        raw.extend_from_slice(&chunk[..n]);
+        if let Some(response) = parse_response(&raw, n == 0)? {
+            return Ok(response);
+        }
+        if raw.len() > MAX_RESPONSE_BYTES {
+            return Err(Error::Malformed("response too large"));
+        }
+    }
+}
+
+// parse_response parses raw as far as it has arrived. It returns
+// None while more bytes are needed; once eof is set the response
+// is either complete or malformed.
+fn parse_response(raw: &[u8], eof: bool) -> Result<Option<Response>, Error> {
+    let incomplete = |reason| {
+        if eof {
+            Err(Error::Malformed(reason))
+        } else {
+            Ok(None)
+        }
+    };
+    let Some(head_end) = find(raw, b"\r\n\r\n") else {
+        return incomplete("incomplete response head");
+    };
+    let head = std::str::from_utf8(&raw[..head_end])
+        .map_err(|_| Error::Malformed("response head is not UTF-8"))?;
+    let mut lines = head.split("\r\n");
+    let mut status_line = lines.next().unwrap_or_default().split(' ');
+    if !status_line
+        .next()
+        .is_some_and(|version| version.starts_with("HTTP/1."))
+    {
+        return Err(Error::Malformed("not an HTTP/1.x response"));
+    }
+    let status = status_line
+        .next()
+        .and_then(|code| code.parse().ok())
+        .ok_or(Error::Malformed("invalid status line"))?;
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+    let mut response = Response {
+        status,
+        headers,
+        body: Vec::new(),
+    };
+
+    let rest = &raw[head_end + 4..];
+    let chunked = response
+        .header("transfer-encoding")
+        .is_some_and(|coding| coding.to_ascii_lowercase().contains("chunked"));
+    if chunked {
+        match decode_chunked(rest)? {
+            Some(body) => response.body = body,
+            None => return incomplete("truncated chunked body"),
+        }
+    } else if let Some(length) = response.header("content-length") {
+        let length: usize = length
+            .parse()
+            .map_err(|_| Error::Malformed("invalid content-length"))?;
+        if rest.len() < length {
+            return incomplete("truncated body");
+        }
+        response.body = rest[..length].to_vec();
+    } else if !matches!(status, 100..=199 | 204 | 304) {
+        // Without a length the body runs until the server closes
+        // the connection.
+        if !eof {
+            return Ok(None);
+        }
+        response.body = rest.to_vec();
+    }
+    Ok(Some(response))
+}
+
+// decode_chunked joins the chunks of a chunked body, or returns
+// None if the final chunk has not arrived. Extensions and trailers
+// are ignored.
+fn decode_chunked(mut data: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+    let mut body = Vec::new();
+    loop {
+        let Some(line_end) = find(data, b"\r\n") else {
+            return Ok(None);
+        };
+        let size = std::str::from_utf8(&data[..line_end])
+            .ok()
+            .and_then(|line| line.split(';').next())
+            .and_then(|size| usize::from_str_radix(size.trim(), 16).ok())
+            .ok_or(Error::Malformed("invalid chunk size"))?;
+        data = &data[line_end + 2..];
+        if size == 0 {
+            return Ok(Some(body));
+        }
+        if data.len() < size + 2 {
+            return Ok(None);
+        }
+        body.extend_from_slice(&data[..size]);
+        data = &data[size + 2..];
+    }
+}
+
+// same_server_path returns the path a redirect points to, if it
+// stays on host: a path, or a URL with the same scheme and host.
+fn same_server_path(location: &str, scheme: &str, host: &str) -> Option<String> {
+    if location.starts_with('/') && !location.starts_with("//") {
+        return Some(location.to_string());
+    }
+    let rest = location
+        .strip_prefix(scheme)?
+        .strip_prefix("://")?
+        .strip_prefix(host)?;
+    match rest {
+        "" => Some("/".to_string()),
+        path if path.starts_with('/') => Some(path.to_string()),
+        _ => None,
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{Ipv4Addr, SocketAddrV4, TcpListener};
+    use std::thread;
+
+    #[test]
+    fn parse_response_handles_lengths_and_chunking() {
+        let sized = b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n{}";
+        let response = parse_response(sized, false)
+            .expect("valid")
+            .expect("complete");
+        assert_eq!(
+            (response.status, response.body.as_slice()),
+            (200, &b"{}"[..])
+        );
+        assert_eq!(
+            parse_response(&sized[..sized.len() - 1], false).ok(),
+            Some(None)
+        );
+
+        let chunked = b"HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\n\r\n4;x=y\r\n{\"a\"\r\n3\r\n:1}\r\n0\r\n\r\n";
+        let response = parse_response(chunked, false)
+            .expect("valid")
+            .expect("complete");
+        assert_eq!(response.body, b"{\"a\":1}");
+        assert!(parse_response(&chunked[..chunked.len() - 5], true).is_err());
+
+        let until_close = b"HTTP/1.0 200 OK\r\n\r\nbody";
+        assert_eq!(parse_response(until_close, false).ok(), Some(None));
+        let response = parse_response(until_close, true)
+            .expect("valid")
+            .expect("complete");
+        assert_eq!(response.body, b"body");
+
+        assert!(parse_response(b"HTTP/2 200\r\n\r\n", true).is_err());
+        assert!(parse_response(b"SSH-2.0-OpenSSH\r\n", true).is_err());
+    }
+
+    #[test]
+    fn get_follows_redirects_on_the_same_server() {
+        let listener =
+            TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0)).expect("bind listener");
+        let addr = listener.local_addr().expect("local addr");
+        let host = addr.to_string();
+        let redirect = format!(
+            "HTTP/1.1 307 Temporary Redirect\r\nLocation: http://{host}/v2\r\nContent-Length: 0\r\n\r\n"
+        );
+        let server = thread::spawn(move || {
+            for response in [
+                redirect.as_bytes(),
+                b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n{}",
+            ] {
+                let (mut conn, _) = listener.accept().expect("accept");
+                let mut request = [0u8; 1024];
+                let _ = conn.read(&mut request);
+                let _ = conn.write_all(response);
+            }
+        });
+        let response = get(
+            Route::Direct(addr),
+            &host,
+            "/v1",
+            Duration::from_secs(2),
+            None,
+            None,
+        )
+        .expect("response");
+        assert_eq!(response.status, 200);
+        server.join().expect("server");
+
+        assert_eq!(
+            same_server_path("/api", "http", "h:1"),
+            Some("/api".to_string())
+        );
+        assert_eq!(same_server_path("//evil/api", "http", "h:1"), None);
+        assert_eq!(same_server_path("http://other:1/api", "http", "h:1"), None);
+        assert_eq!(same_server_path("https://h:1/api", "http", "h:1"), None);
+    }
+
+    #[test]
+    fn get_sends_absolute_targets_and_credentials_to_a_proxy() {
+        let listener =
+            TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0)).expect("bind listener");
+        let addr = listener.local_addr().expect("local addr");
+        let server = thread::spawn(move || {
+            let (mut conn, _) = listener.accept().expect("accept");
+            let mut request = [0u8; 1024];
+            let n = conn.read(&mut request).expect("read request");
+            let _ = conn.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n{}");
+            String::from_utf8_lossy(&request[..n]).into_owned()
+        });
+        let proxy = ProxyServer {
+            host: addr.ip().to_string(),
+            port: addr.port(),
+            auth: Some("YTpi".to_string()),
+        };
+        let response = get(
+            Route::Proxy(&proxy),
+            "agents.example.com:8080",
+            "/api/v1/version",
+            Duration::from_secs(2),
+            None,
+            None,
+        )
+        .expect("response");
+        assert_eq!(response.status, 200);
+        let request = server.join().expect("server");
+        assert!(
+            request.starts_with("GET http://agents.example.com:8080/api/v1/version HTTP/1.1\r\n")
+        );
+        assert!(request.contains("\r\nProxy-Authorization: Basic YTpi\r\n"));
+    }
+}
@@ -0,0 +1,497 @@
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+use serde::Serialize;
+use tauri::WebviewWindow;
+
+const DEFAULT_LOCALE: &str = "en";
+
+// LOCALE_EVENT is emitted to each page once it loads, carrying the
+// same LocaleInfo as the locale_info command.
+pub(crate) const LOCALE_EVENT: &str = "agentsview://locale";
+
+// RTL_LANGUAGES lists the languages written right to left,
+// including the legacy iw code for Hebrew.
+const RTL_LANGUAGES: &[&str] = &[
+    "ar", "ckb", "dv", "fa", "he", "iw", "ps", "sd", "ug", "ur", "yi",
+];
+
+// CATALOGS bundles the shell's message files. English is the
+// source catalog; other locales fall back to it key by key.
+const CATALOGS: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.ftl")),
+    ("es", include_str!("../locales/es.ftl")),
+];
+
+static REQUESTED_LOCALES: OnceLock<Vec<String>> = OnceLock::new();
+static LOCALIZER: OnceLock<Localizer> = OnceLock::new();
+
+// Localizer resolves message keys for one locale. Catalogs use a
+// subset of Fluent syntax: `key = value` messages, indented
+// continuation lines, `#` comments, and `{ $name }` placeables.
+#[derive(Debug)]
+pub(crate) struct Localizer {
+    messages: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+impl Localizer {
+    pub(crate) fn for_locale(locale: &'static str) -> Self {
+        Self {
+            messages: parse_catalog(catalog_source(locale)),
+            fallback: parse_catalog(catalog_source(DEFAULT_LOCALE)),
+        }
+    }
+
+    // format looks up key, falling back to English and then to the
+    // key itself so a missing translation never hides a message.
+    pub(crate) fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let template = self
+            .messages
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .map(String::as_str)
+            .unwrap_or(key);
+        substitute_placeables(template, args)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum TextDirection {
+    Ltr,
+    Rtl,
+}
+
+// LocaleInfo describes the user's preferred locale, which may have
+// no shell catalog, so the frontend can pick its own translations
+// and mirror its layout without repeating detection.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub(crate) struct LocaleInfo {
+    locale: String,
+    direction: TextDirection,
+}
+
+impl LocaleInfo {
+    fn from_requested(requested: &[String]) -> Self {
+        let locale = requested
+            .first()
+            .cloned()
+            .unwrap_or_else(|| DEFAULT_LOCALE.to_string());
+        let language = locale.split('-').next().unwrap_or_default();
+        let direction = if RTL_LANGUAGES.contains(&language) {
+            TextDirection::Rtl
+        } else {
+            TextDirection::Ltr
+        };
+        Self { locale, direction }
+    }
+
+    pub(crate) fn direction(&self) -> TextDirection {
+        self.direction
+    }
+}
+
+// localizer returns the shell's localizer for the detected locale.
+pub(crate) fn localizer() -> &'static Localizer {
+    LOCALIZER.get_or_init(|| Localizer::for_locale(negotiate_locale(requested_locales())))
+}
+
+// locale_info is called by pages that load before LOCALE_EVENT
+// listeners can be registered, such as the splash screen.
+#[tauri::command]
+pub(crate) fn locale_info() -> LocaleInfo {
+    LocaleInfo::from_requested(requested_locales())
+}
+
+// apply_window_direction mirrors the native window chrome (title
+// bar and menus) for RTL locales. macOS derives this from the
+// system language on its own.
+pub(crate) fn apply_window_direction(window: &WebviewWindow) {
+    if locale_info().direction() != TextDirection::Rtl {
+        return;
+    }
+    set_native_rtl_layout(window);
+}
+
+pub(crate) fn tr(key: &str) -> String {
+    localizer().format(key, &[])
+}
+
+pub(crate) fn tr_args(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    localizer().format(key, args)
+}
+
+// localize is called by the splash page to translate its static
+// text. Unknown keys are returned unchanged.
+#[tauri::command]
+pub(crate) fn localize(keys: Vec<String>) -> HashMap<String, String> {
+    keys.into_iter()
+        .map(|key| {
+            let value = tr(&key);
+            (key, value)
+        })
+        .collect()
+}
+
+fn catalog_source(locale: &str) -> &'static str {
+    CATALOGS
+        .iter()
+        .find(|(code, _)| *code == locale)
+        .map(|(_, source)| *source)
+        .unwrap_or_default()
+}
+
+fn parse_catalog(source: &str) -> HashMap<String, String> {
+    let mut messages = HashMap::new();
+    let mut current: Option<(String, String)> = None;
+    for line in source.lines() {
+        if line.starts_with([' ', '\t']) && !line.trim().is_empty() {
+            if let Some((_, value)) = current.as_mut() {
+                if !value.is_empty() {
+                    value.push('\n');
+                }
+                value.push_str(line.trim());
+            }
+            continue;
+        }
+        if let Some((key, value)) = current.take() {
+            messages.insert(key, value);
+        }
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        if key.is_empty() {
+            continue;
+        }
+        current = Some((key.to_string(), value.trim().to_string()));
+    }
+    if let Some((key, value)) = current {
+        messages.insert(key, value);
+    }
+    messages
+}
+
+fn substitute_placeables(template: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let inner = rest[start + 1..start + len].trim();
+        out.push_str(&rest[..start]);
+        match inner
+            .strip_prefix('$')
+            .and_then(|name| args.iter().find(|(arg, _)| *arg == name))
+        {
+            Some((_, value)) => out.push_str(&value.to_string()),
+            None => out.push_str(&rest[start..=start + len]),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+// requested_locales returns the user's preferred locales, most
+// preferred first. AGENTSVIEW_DESKTOP_LOCALE overrides detection.
+fn requested_locales() -> &'static [String] {
+    REQUESTED_LOCALES.get_or_init(|| {
+        let mut requested = requested_locales_from_lookup(|key| std::env::var_os(key));
+        requested.extend(system_locales());
+        requested
+    })
+}
+
+fn requested_locales_from_lookup<F>(lookup: F) -> Vec<String>
+where
+    F: FnMut(&str) -> Option<OsString>,
+{
+    ["AGENTSVIEW_DESKTOP_LOCALE", "LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(lookup)
+        .filter_map(|value| normalize_locale_tag(&value.to_string_lossy()))
+        .collect()
+}
+
+// normalize_locale_tag turns POSIX and BCP 47 forms such as
+// es_ES.UTF-8@euro into a lowercase es-es tag. The C and POSIX
+// locales carry no language preference.
+fn normalize_locale_tag(raw: &str) -> Option<String> {
+    let tag = raw.split(['.', '@']).next().unwrap_or_default().trim();
+    if tag.is_empty() || tag == "C" || tag == "POSIX" {
+        return None;
+    }
+    Some(tag.replace('_', "-").to_ascii_lowercase())
+}
+
+// negotiate_locale returns the catalog for the first requested
+// locale whose full tag or language has one.
+fn negotiate_locale(requested: &[String]) -> &'static str {
+    for tag in requested {
+        let language = tag.split('-').next().unwrap_or_default();
+        for (code, _) in CATALOGS {
+            if code.eq_ignore_ascii_case(tag) || code.eq_ignore_ascii_case(language) {
+                return code;
+            }
+        }
+    }
+    DEFAULT_LOCALE
+}
+
+// system_locales reads the OS language preferences, which GUI
+// launches on macOS and Windows do not expose through LANG. Linux
+// falls back to the system-wide /etc/locale.conf.
+fn system_locales() -> Vec<String> {
+    if cfg!(target_os = "macos") {
+        return std::process::Command::new("defaults")
+            .args(["read", "-g", "AppleLanguages"])
+            .output()
+            .ok()
+            .map(|out| parse_apple_languages(&String::from_utf8_lossy(&out.stdout)))
+            .unwrap_or_default();
+    }
+    if cfg!(target_os = "linux") {
+        return ["/etc/locale.conf", "/etc/default/locale"]
+            .into_iter()
+            .find_map(|path| parse_locale_conf(&std::fs::read_to_string(path).ok()?))
+            .into_iter()
+            .collect();
+    }
+    windows_user_locale().into_iter().collect()
+}
+
+// system_posix_locale is the first OS language preference that
+// names a region, as a POSIX LANG value such as es_ES.UTF-8.
+pub(crate) fn system_posix_locale() -> Option<String> {
+    system_locales().iter().find_map(|tag| posix_locale(tag))
+}
+
+// posix_locale turns a normalized tag such as zh-hans-cn into
+// zh_CN.UTF-8. Tags without a region give None, since a bare
+// language is not a locale the C library knows.
+fn posix_locale(tag: &str) -> Option<String> {
+    let mut parts = tag.split('-');
+    let language = parts.next()?;
+    let region =
+        parts.find(|part| part.len() == 2 && part.chars().all(|c| c.is_ascii_alphabetic()))?;
+    Some(format!("{language}_{}.UTF-8", region.to_ascii_uppercase()))
+}
+
+// parse_locale_conf reads the LANG line of /etc/locale.conf.
+fn parse_locale_conf(content: &str) -> Option<String> {
+    content
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("LANG="))
+        .map(|value| value.trim_matches(['"', '\'']))
+        .find_map(normalize_locale_tag)
+}
+
+// parse_apple_languages reads the plist array printed by
+// `defaults read -g AppleLanguages`.
+fn parse_apple_languages(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(|line| line.trim().trim_end_matches(',').trim_matches('"'))
+        .filter(|line| !line.is_empty() && *line != "(" && *line != ")")
+        .filter_map(normalize_locale_tag)
+        .collect()
+}
+
+#[cfg(windows)]
+fn windows_user_locale() -> Option<String> {
+    const LOCALE_NAME_MAX_LENGTH: usize = 85;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetUserDefaultLocaleName(name: *mut u16, len: i32) -> i32;
+    }
+
+    let mut buf = [0u16; LOCALE_NAME_MAX_LENGTH];
+    // SAFETY: buf is a valid out-buffer of the advertised length.
+    let len = unsafe { GetUserDefaultLocaleName(buf.as_mut_ptr(), buf.len() as i32) };
+    if len <= 1 {
+        return None;
+    }
+    normalize_locale_tag(&String::from_utf16_lossy(&buf[..len as usize - 1]))
+}
+
+#[cfg(not(windows))]
+fn windows_user_locale() -> Option<String> {
+    None
+}
+
+#[cfg(windows)]
+fn set_native_rtl_layout(window: &WebviewWindow) {
+    const GWL_EXSTYLE: i32 = -20;
+    const WS_EX_LAYOUTRTL: isize = 0x0040_0000;
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetWindowLongPtrW(hwnd: isize, index: i32) -> isize;
+        fn SetWindowLongPtrW(hwnd: isize, index: i32, value: isize) -> isize;
+    }
+
+    let Ok(hwnd) = window.hwnd() else {
+        return;
+    };
+    let hwnd = hwnd.0 as isize;
+    // SAFETY: hwnd is the live top-level window owned by this
+    // process; only its extended style bits are changed.
+    unsafe {
+        let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style | WS_EX_LAYOUTRTL);
+    }
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+fn set_native_rtl_layout(_window: &WebviewWindow) {
+    const GTK_TEXT_DIR_RTL: i32 = 2;
+
+    #[link(name = "gtk-3")]
+    extern "C" {
+        fn gtk_widget_set_default_direction(dir: i32);
+    }
+
+    // GTK only picks RTL up from its own translations, which miss
+    // the AGENTSVIEW_DESKTOP_LOCALE override. Setup runs on the
+    // main thread, as GTK requires.
+    // SAFETY: takes a plain enum value and touches no pointers.
+    unsafe { gtk_widget_set_default_direction(GTK_TEXT_DIR_RTL) };
+}
+
+#[cfg(not(any(
+    windows,
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+)))]
+fn set_native_rtl_layout(_window: &WebviewWindow) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalogs_parse_and_cover_english_keys() {
+        let english = parse_catalog(catalog_source("en"));
+        assert!(english.contains_key("splash-heading"));
+        for (code, source) in CATALOGS {
+            let catalog = parse_catalog(source);
+            for key in catalog.keys() {
+                assert!(english.contains_key(key), "{code} has unknown key {key}");
+            }
+        }
+    }
+
+    #[test]
+    fn parse_catalog_handles_comments_and_continuations() {
+        let catalog = parse_catalog(
+            "# comment\n## section\n\nplain = Hello\nmulti =\n    first\n    second\nbroken line\n",
+        );
+        assert_eq!(catalog.get("plain").map(String::as_str), Some("Hello"));
+        assert_eq!(
+            catalog.get("multi").map(String::as_str),
+            Some("first\nsecond")
+        );
+        assert_eq!(catalog.len(), 2);
+    }
+
+    #[test]
+    fn format_substitutes_args_and_falls_back() {
+        let localizer = Localizer::for_locale("es");
+        assert_eq!(
+            localizer.format("error-port-unavailable", &[("port", &8080)]),
+            "AgentsView no pudo usar el puerto local 8080."
+        );
+        assert_eq!(localizer.format("no-such-key", &[]), "no-such-key");
+
+        let english = Localizer::for_locale("en");
+        assert_eq!(
+            english.format("instance-running", &[]),
+            "AgentsView is already running (process { $pid })."
+        );
+    }
+
+    #[test]
+    fn negotiate_locale_matches_tag_then_language() {
+        let requested = |tags: &[&str]| tags.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        assert_eq!(negotiate_locale(&requested(&["es-mx"])), "es");
+        assert_eq!(negotiate_locale(&requested(&["fr-fr", "es"])), "es");
+        assert_eq!(negotiate_locale(&requested(&["fr-fr"])), "en");
+        assert_eq!(negotiate_locale(&[]), "en");
+    }
+
+    #[test]
+    fn requested_locales_prefer_override_and_skip_posix() {
+        let mut env = HashMap::new();
+        env.insert("LANG", OsString::from("es_ES.UTF-8"));
+        env.insert("LC_ALL", OsString::from("C"));
+        assert_eq!(
+            requested_locales_from_lookup(|key| env.get(key).cloned()),
+            vec!["es-es".to_string()]
+        );
+
+        env.insert("AGENTSVIEW_DESKTOP_LOCALE", OsString::from("en_US"));
+        assert_eq!(
+            requested_locales_from_lookup(|key| env.get(key).cloned()),
+            vec!["en-us".to_string(), "es-es".to_string()]
+        );
+    }
+
+    #[test]
+    fn locale_info_reports_preferred_locale_and_direction() {
+        let requested = |tags: &[&str]| tags.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        let arabic = LocaleInfo::from_requested(&requested(&["ar-eg", "en-us"]));
+        assert_eq!(arabic.locale, "ar-eg");
+        assert_eq!(arabic.direction(), TextDirection::Rtl);
+
+        let spanish = LocaleInfo::from_requested(&requested(&["es-mx", "he"]));
+        assert_eq!(spanish.direction(), TextDirection::Ltr);
+
+        let fallback = LocaleInfo::from_requested(&[]);
+        assert_eq!(fallback.locale, "en");
+        assert_eq!(fallback.direction(), TextDirection::Ltr);
+
+        assert_eq!(
+            serde_json::to_value(&arabic).expect("serialize"),
+            serde_json::json!({"locale": "ar-eg", "direction": "rtl"})
+        );
+    }
+
+    #[test]
+    fn posix_locale_needs_a_region() {
+        assert_eq!(posix_locale("es-es").as_deref(), Some("es_ES.UTF-8"));
+        assert_eq!(posix_locale("zh-hans-cn").as_deref(), Some("zh_CN.UTF-8"));
+        assert_eq!(posix_locale("es-419"), None);
+        assert_eq!(posix_locale("en"), None);
+        assert_eq!(
+            parse_locale_conf("# generated\nLANG=\"de_DE.UTF-8\"\nLC_TIME=C\n").as_deref(),
+            Some("de-de")
+        );
+    }
+
+    #[test]
+    fn parse_apple_languages_reads_defaults_output() {
+        assert_eq!(
+            parse_apple_languages("(\n    \"es-ES\",\n    \"en-US\"\n)\n"),
+            vec!["es-es".to_string(), "en-us".to_string()]
+        );
+    }
+}
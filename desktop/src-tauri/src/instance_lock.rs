@@ -0,0 +1,245 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+pub(crate) const LOCK_FILE: &str = "desktop.lock";
+
+// LockInfo is the content of the instance lock file: the desktop
+// shell's PID and, once known, the port its sidecar listens on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct LockInfo {
+    pub(crate) pid: u32,
+    #[serde(default)]
+    pub(crate) port: Option<u16>,
+}
+
+// InstanceLock owns the lock file for this desktop process. The
+// file is rewritten when the sidecar port changes and removed on
+// release.
+#[derive(Debug)]
+pub(crate) struct InstanceLock {
+    path: PathBuf,
+    info: Mutex<LockInfo>,
+}
+
+#[derive(Debug)]
+pub(crate) enum AcquireOutcome {
+    Acquired(InstanceLock),
+    HeldBy(LockInfo),
+}
+
+// acquire_lock creates the lock file exclusively. When a lock
+// already exists, its PID is checked with is_alive: a live owner
+// means another instance is running, while a dead or unreadable
+// lock is treated as stale, removed, and acquisition retried.
+pub(crate) fn acquire_lock<F>(path: &Path, pid: u32, is_alive: F) -> io::Result<AcquireOutcome>
+where
+    F: Fn(u32) -> bool,
+{
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let info = LockInfo { pid, port: None };
+
+    for _ in 0..2 {
+        match OpenOptions::new().write(true).create_new(true).open(path) {
+            Ok(mut file) => {
+                file.write_all(&serde_json::to_vec(&info).map_err(io::Error::other)?)?;
+                return Ok(AcquireOutcome::Acquired(InstanceLock {
+                    path: path.to_path_buf(),
+                    info: Mutex::new(info),
+                }));
+            }
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => match read_lock(path) {
+                Some(existing) if existing.pid != pid && is_alive(existing.pid) => {
+                    return Ok(AcquireOutcome::HeldBy(existing));
+                }
+                existing => {
+                    eprintln!(
+                        "[agentsview] removing stale instance lock {} (pid: {:?})",
+                        path.display(),
+                        existing.map(|info| info.pid)
+                    );
+                    match fs::remove_file(path) {
+                        Ok(()) => {}
+                        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                        Err(err) => return Err(err),
+                    }
+                }
+            },
+            Err(err) => return Err(err),
+        }
+    }
+    Err(io::Error::other(format!(
+        "could not acquire instance lock {}",
+        path.display()
+    )))
+}
+
+pub(crate) fn read_lock(path: &Path) -> Option<LockInfo> {
+    let content = fs::read(path).ok()?;
+    serde_json::from_slice(&content).ok()
+}
+
+impl InstanceLock {
+    // set_port records the sidecar port so other launches can
+    // report where the running instance is serving.
+    pub(crate) fn set_port(&self, port: Option<u16>) -> io::Result<()> {
+        let mut info = self
+            .info
+            .lock()
+            .map_err(|_| io::Error::other("instance lock poisoned"))?;
+        info.port = port;
+        let json = serde_json::to_vec(&*info).map_err(io::Error::other)?;
+        let dir = self
+            .path
+            .parent()
+            .ok_or_else(|| io::Error::other("lock path has no parent directory"))?;
+        let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+        tmp.write_all(&json)?;
+        tmp.persist(&self.path).map_err(|err| err.error)?;
+        Ok(())
+    }
+
+    // release removes the lock file if it still belongs to this
+    // process.
+    pub(crate) fn release(&self) {
+        let pid = self.info.lock().map(|info| info.pid).ok();
+        if read_lock(&self.path).map(|info| info.pid) == pid {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+// InstanceLockState holds this process's lock in managed state.
+#[derive(Default)]
+pub(crate) struct InstanceLockState(pub(crate) Mutex<Option<InstanceLock>>);
+
+// process_alive reports whether a process with the given PID
+// exists.
+#[cfg(unix)]
+pub(crate) fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: signal 0 performs error checking only and sends
+    // nothing to the target process.
+    if unsafe { libc::kill(pid, 0) } == 0 {
+        return true;
+    }
+    io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(windows)]
+pub(crate) fn process_alive(pid: u32) -> bool {
+    const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+    const STILL_ACTIVE: u32 = 259;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn OpenProcess(access: u32, inherit: i32, pid: u32) -> *mut std::ffi::c_void;
+        fn GetExitCodeProcess(process: *mut std::ffi::c_void, code: *mut u32) -> i32;
+        fn CloseHandle(handle: *mut std::ffi::c_void) -> i32;
+    }
+
+    // SAFETY: the handle is checked for null and closed before
+    // returning; exit_code is a valid out-pointer.
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return false;
+        }
+        let mut exit_code = 0u32;
+        let ok = GetExitCodeProcess(handle, &mut exit_code) != 0;
+        CloseHandle(handle);
+        ok && exit_code == STILL_ACTIVE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expect_acquired(outcome: AcquireOutcome) -> InstanceLock {
+        match outcome {
+            AcquireOutcome::Acquired(lock) => lock,
+            AcquireOutcome::HeldBy(info) => panic!("lock unexpectedly held by {info:?}"),
+        }
+    }
+
+    #[test]
+    fn acquire_lock_creates_file_and_records_port() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("data").join(LOCK_FILE);
+
+        let lock = expect_acquired(acquire_lock(&path, 4242, |_| true).expect("acquire"));
+        assert_eq!(
+            read_lock(&path),
+            Some(LockInfo {
+                pid: 4242,
+                port: None
+            })
+        );
+
+        lock.set_port(Some(18080)).expect("set port");
+        assert_eq!(read_lock(&path).and_then(|info| info.port), Some(18080));
+
+        lock.release();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn acquire_lock_reports_live_owner() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join(LOCK_FILE);
+        fs::write(&path, r#"{"pid":1111,"port":8080}"#).expect("write lock");
+
+        match acquire_lock(&path, 2222, |pid| pid == 1111).expect("acquire") {
+            AcquireOutcome::HeldBy(info) => {
+                assert_eq!(
+                    info,
+                    LockInfo {
+                        pid: 1111,
+                        port: Some(8080)
+                    }
+                );
+            }
+            AcquireOutcome::Acquired(_) => panic!("live lock should not be taken over"),
+        }
+    }
+
+    #[test]
+    fn acquire_lock_recovers_stale_and_corrupt_locks() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join(LOCK_FILE);
+
+        fs::write(&path, r#"{"pid":1111}"#).expect("write stale lock");
+        let lock = expect_acquired(acquire_lock(&path, 2222, |_| false).expect("acquire"));
+        assert_eq!(read_lock(&path).map(|info| info.pid), Some(2222));
+        lock.release();
+
+        fs::write(&path, "garbage").expect("write corrupt lock");
+        expect_acquired(acquire_lock(&path, 3333, |_| true).expect("acquire"));
+        assert_eq!(read_lock(&path).map(|info| info.pid), Some(3333));
+    }
+
+    #[test]
+    fn release_leaves_foreign_lock_in_place() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join(LOCK_FILE);
+        let lock = expect_acquired(acquire_lock(&path, 4242, |_| false).expect("acquire"));
+
+        fs::write(&path, r#"{"pid":5555}"#).expect("replace lock");
+        lock.release();
+        assert!(path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn process_alive_detects_current_process() {
+        assert!(process_alive(std::process::id()));
+    }
+}
@@ -0,0 +1,109 @@
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use crate::error::DesktopError;
+
+// MANIFEST is "<sha256>:<size>" of the sidecar prepare-sidecar
+// produced for this build, recorded by build.rs. It is empty when
+// the build had no sidecar to hash or signs the sidecar afterwards.
+const MANIFEST: &str = env!("AGENTSVIEW_SIDECAR_MANIFEST");
+// SKIP_CHECK_ENV lets developers run a bundled sidecar they
+// rebuilt without rebuilding the shell.
+const SKIP_CHECK_ENV: &str = "AGENTSVIEW_DESKTOP_SKIP_SIDECAR_CHECK";
+
+// verify_bundled_sidecar checks the sidecar next to the desktop
+// executable against the manifest before it is spawned. A missing
+// binary passes, so the spawn reports it with the locate dialog.
+pub(crate) fn verify_bundled_sidecar() -> Result<(), DesktopError> {
+    let Some((digest, size)) = parse_manifest(MANIFEST) else {
+        return Ok(());
+    };
+    if std::env::var_os(SKIP_CHECK_ENV).is_some() {
+        eprintln!("[agentsview] {SKIP_CHECK_ENV} is set; not checking the sidecar binary");
+        return Ok(());
+    }
+    let Some(path) = bundled_sidecar_path() else {
+        return Ok(());
+    };
+    check_file(&path, digest, size).map_err(|reason| DesktopError::SidecarIntegrity {
+        path: path.display().to_string(),
+        reason,
+    })
+}
+
+// bundled_sidecar_path is where tauri places externalBin: next to
+// the desktop executable, without the target triple.
+fn bundled_sidecar_path() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let name = format!("agentsview{}", std::env::consts::EXE_SUFFIX);
+    Some(exe.parent()?.join(name))
+}
+
+fn parse_manifest(manifest: &str) -> Option<(&str, u64)> {
+    let (digest, size) = manifest.trim().split_once(':')?;
+    let valid_digest = digest.len() == 64 && digest.bytes().all(|b| b.is_ascii_hexdigit());
+    if !valid_digest {
+        return None;
+    }
+    Some((digest, size.parse().ok()?))
+}
+
+// check_file compares path's size and SHA-256 with the manifest.
+// The size is checked first, so a truncated download or copy is
+// named as such rather than as a checksum mismatch.
+fn check_file(path: &Path, digest: &str, size: u64) -> Result<(), String> {
+    let len = match fs::metadata(path) {
+        Ok(meta) => meta.len(),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(format!("could not read it: {err}")),
+    };
+    if len < size {
+        return Err(format!("it is truncated ({len} of {size} bytes)"));
+    }
+    if len != size {
+        return Err(format!("it is {len} bytes, expected {size}"));
+    }
+    let mut hasher = Sha256::new();
+    File::open(path)
+        .and_then(|mut file| io::copy(&mut file, &mut hasher))
+        .map_err(|err| format!("could not read it: {err}"))?;
+    let actual = format!("{:x}", hasher.finalize());
+    if !actual.eq_ignore_ascii_case(digest) {
+        return Err(format!("its SHA-256 is {actual}, expected {digest}"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_file_rejects_truncated_and_tampered_binaries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("agentsview");
+        let digest = format!("{:x}", Sha256::digest(b"agentsview"));
+        assert_eq!(
+            parse_manifest(&format!("{digest}:10")),
+            Some((&*digest, 10))
+        );
+        assert_eq!(parse_manifest(""), None);
+        assert_eq!(parse_manifest("abc:10"), None);
+
+        assert_eq!(check_file(&path, &digest, 10), Ok(()), "missing passes");
+        fs::write(&path, b"agentsview").unwrap();
+        assert_eq!(check_file(&path, &digest, 10), Ok(()));
+
+        fs::write(&path, b"agents").unwrap();
+        assert_eq!(
+            check_file(&path, &digest, 10),
+            Err("it is truncated (6 of 10 bytes)".to_string())
+        );
+        fs::write(&path, b"agentsvieW").unwrap();
+        let err = check_file(&path, &digest, 10).unwrap_err();
+        assert!(err.contains(&format!("expected {digest}")), "{err}");
+    }
+}
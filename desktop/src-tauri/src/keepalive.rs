@@ -0,0 +1,418 @@
+use std::collections::hash_map::RandomState;
+use std::ffi::OsString;
+use std::fs;
+use std::hash::{BuildHasher, Hasher};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(30);
+const MAX_INTERVAL: Duration = Duration::from_secs(600);
+const JITTER_RATIO: f64 = 0.2;
+const BATTERY_MULTIPLIER: u32 = 4;
+const IDLE_MULTIPLIER: u32 = 3;
+const IDLE_AFTER: Duration = Duration::from_secs(300);
+// FAILURES_BEFORE_PROMPT consecutive failed probes mean the backend
+// is stuck rather than briefly busy. Until then, failed probes are
+// retried after FAILURE_RETRY_INTERVAL instead of the full delay.
+const FAILURES_BEFORE_PROMPT: u32 = 3;
+const FAILURE_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+// KeepAliveSchedule decides how long the shell waits between
+// backend keep-alive probes. The base interval is stretched on
+// battery power and while the window is idle, then jittered so
+// probes do not line up with other periodic wakeups.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct KeepAliveSchedule {
+    pub(crate) interval: Duration,
+    pub(crate) jitter_ratio: f64,
+    pub(crate) battery_multiplier: u32,
+    pub(crate) idle_multiplier: u32,
+}
+
+impl Default for KeepAliveSchedule {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_INTERVAL,
+            jitter_ratio: JITTER_RATIO,
+            battery_multiplier: BATTERY_MULTIPLIER,
+            idle_multiplier: IDLE_MULTIPLIER,
+        }
+    }
+}
+
+impl KeepAliveSchedule {
+    // from_env reads AGENTSVIEW_DESKTOP_KEEPALIVE_SECS. A value of
+    // 0 disables keep-alive probing entirely (returns None).
+    pub(crate) fn from_env() -> Option<Self> {
+        schedule_from_setting(std::env::var_os("AGENTSVIEW_DESKTOP_KEEPALIVE_SECS"))
+    }
+
+    // stretched_interval applies the battery and idle multipliers
+    // to the base interval, capped at MAX_INTERVAL.
+    pub(crate) fn stretched_interval(&self, on_battery: bool, idle: bool) -> Duration {
+        let mut factor = 1u32;
+        if on_battery {
+            factor = factor.saturating_mul(self.battery_multiplier.max(1));
+        }
+        if idle {
+            factor = factor.saturating_mul(self.idle_multiplier.max(1));
+        }
+        self.interval
+            .checked_mul(factor)
+            .unwrap_or(MAX_INTERVAL)
+            .min(MAX_INTERVAL.max(self.interval))
+    }
+
+    // next_delay returns the stretched interval shifted by a
+    // jitter sample in [-1.0, 1.0] scaled by jitter_ratio.
+    pub(crate) fn next_delay(&self, on_battery: bool, idle: bool, jitter_sample: f64) -> Duration {
+        let base = self.stretched_interval(on_battery, idle).as_secs_f64();
+        let sample = jitter_sample.clamp(-1.0, 1.0);
+        let ratio = self.jitter_ratio.clamp(0.0, 1.0);
+        Duration::from_secs_f64((base * (1.0 + sample * ratio)).max(1.0))
+    }
+}
+
+fn schedule_from_setting(value: Option<OsString>) -> Option<KeepAliveSchedule> {
+    let Some(raw) = value else {
+        return Some(KeepAliveSchedule::default());
+    };
+    match raw.to_string_lossy().trim().parse::<u64>() {
+        Ok(0) => None,
+        Ok(secs) => Some(KeepAliveSchedule {
+            interval: Duration::from_secs(secs),
+            ..KeepAliveSchedule::default()
+        }),
+        Err(_) => {
+            eprintln!(
+                "[agentsview] ignoring invalid AGENTSVIEW_DESKTOP_KEEPALIVE_SECS value: {}",
+                raw.to_string_lossy()
+            );
+            Some(KeepAliveSchedule::default())
+        }
+    }
+}
+
+// HealthEvent is what one keep-alive probe result means.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HealthEvent {
+    Healthy,
+    Failed,
+    Recovered,
+    // Unresponsive is reported once per failure streak, when it
+    // reaches FAILURES_BEFORE_PROMPT probes.
+    Unresponsive,
+}
+
+// HealthWatch turns keep-alive probe results into watchdog events.
+#[derive(Debug, Default)]
+pub(crate) struct HealthWatch {
+    failures: u32,
+}
+
+impl HealthWatch {
+    pub(crate) fn observe(&mut self, ready: bool) -> HealthEvent {
+        if ready {
+            let recovered = self.failures > 0;
+            self.failures = 0;
+            return if recovered {
+                HealthEvent::Recovered
+            } else {
+                HealthEvent::Healthy
+            };
+        }
+        self.failures = self.failures.saturating_add(1);
+        if self.failures == FAILURES_BEFORE_PROMPT {
+            HealthEvent::Unresponsive
+        } else {
+            HealthEvent::Failed
+        }
+    }
+
+    // next_delay shortens the scheduled delay while a failure
+    // streak has not yet been reported, so a stuck backend is
+    // noticed in seconds rather than minutes.
+    pub(crate) fn next_delay(&self, scheduled: Duration) -> Duration {
+        if self.failures > 0 && self.failures < FAILURES_BEFORE_PROMPT {
+            scheduled.min(FAILURE_RETRY_INTERVAL)
+        } else {
+            scheduled
+        }
+    }
+}
+
+// jitter_sample returns a pseudo-random value in [-1.0, 1.0]
+// using the std hasher seed, which is enough to spread probes
+// without pulling in an RNG dependency.
+pub(crate) fn jitter_sample() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u32(std::process::id());
+    let unit = (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64;
+    unit * 2.0 - 1.0
+}
+
+// ActivityState tracks whether the user has recently interacted
+// with the app so background work can back off while idle.
+pub(crate) struct ActivityState {
+    focused: AtomicBool,
+    last_active: Mutex<Instant>,
+}
+
+impl Default for ActivityState {
+    fn default() -> Self {
+        Self {
+            focused: AtomicBool::new(true),
+            last_active: Mutex::new(Instant::now()),
+        }
+    }
+}
+
+impl ActivityState {
+    pub(crate) fn set_focused(&self, focused: bool) {
+        self.focused.store(focused, Ordering::SeqCst);
+        if let Ok(mut guard) = self.last_active.lock() {
+            *guard = Instant::now();
+        }
+    }
+
+    pub(crate) fn is_idle(&self) -> bool {
+        if self.focused.load(Ordering::SeqCst) {
+            return false;
+        }
+        self.last_active
+            .lock()
+            .map(|guard| guard.elapsed() >= IDLE_AFTER)
+            .unwrap_or(false)
+    }
+}
+
+// on_battery_power reports whether the machine is currently
+// running from battery. Unknown states are treated as AC power.
+pub(crate) fn on_battery_power() -> bool {
+    if cfg!(target_os = "macos") {
+        return std::process::Command::new("pmset")
+            .args(["-g", "batt"])
+            .output()
+            .ok()
+            .map(|out| pmset_reports_battery(&String::from_utf8_lossy(&out.stdout)))
+            .unwrap_or(false);
+    }
+    if cfg!(target_os = "linux") {
+        return linux_on_battery(Path::new("/sys/class/power_supply"));
+    }
+    windows_on_battery()
+}
+
+fn pmset_reports_battery(output: &str) -> bool {
+    output
+        .lines()
+        .next()
+        .is_some_and(|line| line.contains("'Battery Power'"))
+}
+
+fn linux_on_battery(root: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(root) else {
+        return false;
+    };
+    let supplies: Vec<(String, String)> = entries
+        .flatten()
+        .map(|entry| {
+            let path = entry.path();
+            let read = |name: &str| {
+                fs::read_to_string(path.join(name))
+                    .map(|s| s.trim().to_string())
+                    .unwrap_or_default()
+            };
+            (read("type"), read("online"))
+        })
+        .collect();
+    power_supplies_on_battery(&supplies)
+}
+
+// power_supplies_on_battery takes (type, online) pairs from
+// /sys/class/power_supply. The machine is on battery when it has
+// at least one mains adapter and none of them are online.
+fn power_supplies_on_battery(supplies: &[(String, String)]) -> bool {
+    let mut mains = supplies
+        .iter()
+        .filter(|(kind, _)| kind == "Mains")
+        .peekable();
+    if mains.peek().is_none() {
+        return false;
+    }
+    mains.all(|(_, online)| online == "0")
+}
+
+#[cfg(windows)]
+fn windows_on_battery() -> bool {
+    #[repr(C)]
+    #[allow(dead_code)]
+    struct SystemPowerStatus {
+        ac_line_status: u8,
+        battery_flag: u8,
+        battery_life_percent: u8,
+        system_status_flag: u8,
+        battery_life_time: u32,
+        battery_full_life_time: u32,
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetSystemPowerStatus(status: *mut SystemPowerStatus) -> i32;
+    }
+
+    let mut status = SystemPowerStatus {
+        ac_line_status: 255,
+        battery_flag: 0,
+        battery_life_percent: 0,
+        system_status_flag: 0,
+        battery_life_time: 0,
+        battery_full_life_time: 0,
+    };
+    // SAFETY: status is a valid, properly aligned out-pointer.
+    let ok = unsafe { GetSystemPowerStatus(&mut status) } != 0;
+    ok && status.ac_line_status == 0
+}
+
+#[cfg(not(windows))]
+fn windows_on_battery() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stretched_interval_applies_battery_and_idle_multipliers() {
+        let schedule = KeepAliveSchedule {
+            interval: Duration::from_secs(10),
+            ..KeepAliveSchedule::default()
+        };
+        assert_eq!(
+            schedule.stretched_interval(false, false),
+            Duration::from_secs(10)
+        );
+        assert_eq!(
+            schedule.stretched_interval(true, false),
+            Duration::from_secs(40)
+        );
+        assert_eq!(
+            schedule.stretched_interval(false, true),
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            schedule.stretched_interval(true, true),
+            Duration::from_secs(120)
+        );
+
+        let slow = KeepAliveSchedule {
+            interval: Duration::from_secs(300),
+            ..KeepAliveSchedule::default()
+        };
+        assert_eq!(slow.stretched_interval(true, true), MAX_INTERVAL);
+    }
+
+    #[test]
+    fn next_delay_stays_within_jitter_bounds() {
+        let schedule = KeepAliveSchedule::default();
+        assert_eq!(schedule.next_delay(false, false, 0.0), DEFAULT_INTERVAL);
+        assert_eq!(
+            schedule.next_delay(false, false, 1.0),
+            Duration::from_secs(36)
+        );
+        assert_eq!(
+            schedule.next_delay(false, false, -5.0),
+            Duration::from_secs(24)
+        );
+        for _ in 0..64 {
+            let sample = jitter_sample();
+            assert!(
+                (-1.0..=1.0).contains(&sample),
+                "sample out of range: {sample}"
+            );
+        }
+    }
+
+    #[test]
+    fn schedule_from_setting_parses_interval_and_disable() {
+        assert_eq!(
+            schedule_from_setting(None),
+            Some(KeepAliveSchedule::default())
+        );
+        assert_eq!(schedule_from_setting(Some(OsString::from("0"))), None);
+        assert_eq!(
+            schedule_from_setting(Some(OsString::from("90"))).map(|schedule| schedule.interval),
+            Some(Duration::from_secs(90))
+        );
+        assert_eq!(
+            schedule_from_setting(Some(OsString::from("soon"))),
+            Some(KeepAliveSchedule::default())
+        );
+    }
+
+    #[test]
+    fn health_watch_reports_unresponsive_once_per_streak() {
+        let mut watch = HealthWatch::default();
+        let scheduled = Duration::from_secs(30);
+        assert_eq!(watch.observe(true), HealthEvent::Healthy);
+        assert_eq!(watch.observe(false), HealthEvent::Failed);
+        assert_eq!(watch.next_delay(scheduled), FAILURE_RETRY_INTERVAL);
+        assert_eq!(watch.observe(false), HealthEvent::Failed);
+        assert_eq!(watch.observe(false), HealthEvent::Unresponsive);
+        assert_eq!(watch.next_delay(scheduled), scheduled);
+        assert_eq!(watch.observe(false), HealthEvent::Failed);
+        assert_eq!(watch.observe(true), HealthEvent::Recovered);
+        assert_eq!(watch.next_delay(scheduled), scheduled);
+        for _ in 1..FAILURES_BEFORE_PROMPT {
+            watch.observe(false);
+        }
+        assert_eq!(watch.observe(false), HealthEvent::Unresponsive);
+    }
+
+    #[test]
+    fn power_supplies_on_battery_requires_offline_mains() {
+        let pair = |kind: &str, online: &str| (kind.to_string(), online.to_string());
+        assert!(power_supplies_on_battery(&[
+            pair("Mains", "0"),
+            pair("Battery", "")
+        ]));
+        assert!(!power_supplies_on_battery(&[
+            pair("Mains", "1"),
+            pair("Battery", "")
+        ]));
+        assert!(!power_supplies_on_battery(&[pair("Battery", "")]));
+        assert!(!power_supplies_on_battery(&[]));
+    }
+
+    #[test]
+    fn pmset_reports_battery_reads_power_source_line() {
+        assert!(pmset_reports_battery(
+            "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=1)\t80%; discharging"
+        ));
+        assert!(!pmset_reports_battery("Now drawing from 'AC Power'\n"));
+        assert!(!pmset_reports_battery(""));
+    }
+
+    #[test]
+    fn activity_state_is_idle_only_when_unfocused() {
+        let state = ActivityState::default();
+        assert!(!state.is_idle());
+        state.set_focused(false);
+        assert!(
+            !state.is_idle(),
+            "recently unfocused should not be idle yet"
+        );
+        let Some(long_ago) = Instant::now().checked_sub(IDLE_AFTER) else {
+            return;
+        };
+        if let Ok(mut guard) = state.last_active.lock() {
+            *guard = long_ago;
+        }
+        assert!(state.is_idle());
+        state.set_focused(true);
+        assert!(!state.is_idle());
+    }
+}
@@ -0,0 +1,51 @@
+// Kiosk lockdown, injected into every page when kiosk mode is on.
+// Blocks the context menu and inspector shortcuts, and returns the
+// backend UI to its dashboard after a period without input.
+(() => {
+  const IDLE_MS = __IDLE_MS__;
+
+  const inspectorShortcut = (event) => {
+    const key = event.key.toLowerCase();
+    if (key === "f12") {
+      return true;
+    }
+    const modifier = event.ctrlKey || event.metaKey;
+    return modifier && (event.shiftKey || event.altKey) && ["i", "j", "c"].includes(key);
+  };
+
+  window.addEventListener("contextmenu", (event) => event.preventDefault(), true);
+  window.addEventListener(
+    "keydown",
+    (event) => {
+      if (inspectorShortcut(event)) {
+        event.preventDefault();
+        event.stopPropagation();
+      }
+    },
+    true,
+  );
+
+  // The bundled splash page (tauri://localhost, or tauri.localhost
+  // on Windows and Android) is left alone: reloading it would hide
+  // startup errors.
+  const bundled = location.protocol === "tauri:" || location.hostname === "tauri.localhost";
+  if (!IDLE_MS || window.top !== window || bundled) {
+    return;
+  }
+  const dashboard = `${location.origin}/`;
+  let timer;
+  const restart = () => {
+    clearTimeout(timer);
+    timer = setTimeout(() => {
+      if (location.href === dashboard) {
+        restart();
+      } else {
+        location.replace(dashboard);
+      }
+    }, IDLE_MS);
+  };
+  for (const type of ["pointerdown", "pointermove", "keydown", "wheel", "touchstart"]) {
+    window.addEventListener(type, restart, { capture: true, passive: true });
+  }
+  restart();
+})();
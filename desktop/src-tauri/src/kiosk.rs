@@ -0,0 +1,175 @@
+use std::ffi::OsString;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use tauri::plugin::{Builder as PluginBuilder, TauriPlugin};
+use tauri::{Runtime, WebviewWindow};
+
+use crate::settings::{self, DesktopSettings};
+
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+const LOCKDOWN_SCRIPT: &str = include_str!("kiosk.js");
+
+static KIOSK: OnceLock<Option<KioskConfig>> = OnceLock::new();
+
+// KioskConfig describes the locked-down mode used on demo machines
+// and wall-mounted dashboards: fullscreen, no DevTools, context
+// menu, or external links, and a return to the dashboard after
+// idle_timeout without input (None disables the return).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct KioskConfig {
+    pub(crate) idle_timeout: Option<Duration>,
+}
+
+// config returns the kiosk configuration when kiosk mode is on.
+// AGENTSVIEW_DESKTOP_KIOSK and AGENTSVIEW_DESKTOP_KIOSK_IDLE_SECS
+// override the kiosk_mode and kiosk_idle_secs settings.
+pub(crate) fn config() -> Option<KioskConfig> {
+    *KIOSK
+        .get_or_init(|| kiosk_config_from(|key| std::env::var_os(key), &settings::load_settings()))
+}
+
+fn kiosk_config_from<F>(lookup: F, settings: &DesktopSettings) -> Option<KioskConfig>
+where
+    F: Fn(&str) -> Option<OsString>,
+{
+    let enabled = match lookup("AGENTSVIEW_DESKTOP_KIOSK") {
+        Some(value) => crate::mock::is_truthy_flag(Some(value.as_os_str())),
+        None => settings.kiosk_mode,
+    };
+    if !enabled {
+        return None;
+    }
+
+    let idle_secs = match lookup("AGENTSVIEW_DESKTOP_KIOSK_IDLE_SECS") {
+        Some(raw) => match raw.to_string_lossy().trim().parse::<u64>() {
+            Ok(secs) => Some(secs),
+            Err(_) => {
+                eprintln!(
+                    "[agentsview] ignoring invalid AGENTSVIEW_DESKTOP_KIOSK_IDLE_SECS value: {}",
+                    raw.to_string_lossy()
+                );
+                settings.kiosk_idle_secs
+            }
+        },
+        None => settings.kiosk_idle_secs,
+    };
+    let idle_timeout = match idle_secs {
+        Some(0) => None,
+        Some(secs) => Some(Duration::from_secs(secs)),
+        None => Some(DEFAULT_IDLE_TIMEOUT),
+    };
+    Some(KioskConfig { idle_timeout })
+}
+
+// init_plugin injects the lockdown script into every page when
+// kiosk mode is on, and is inert otherwise.
+pub(crate) fn init_plugin<R: Runtime>() -> TauriPlugin<R> {
+    let mut builder = PluginBuilder::new("kiosk");
+    if let Some(config) = config() {
+        builder = builder.js_init_script(lockdown_script(&config));
+    }
+    builder.build()
+}
+
+fn lockdown_script(config: &KioskConfig) -> String {
+    let idle_ms = config.idle_timeout.map_or(0, |timeout| timeout.as_millis());
+    LOCKDOWN_SCRIPT.replace("__IDLE_MS__", &idle_ms.to_string())
+}
+
+// enforce_fullscreen puts the window back into fullscreen when
+// kiosk mode is on; it runs at startup and after every resize so
+// the window cannot be shrunk or left.
+pub(crate) fn enforce_fullscreen(window: &WebviewWindow) {
+    if config().is_none() || window.is_fullscreen().unwrap_or(true) {
+        return;
+    }
+    if let Err(err) = window.set_fullscreen(true) {
+        eprintln!("[agentsview] failed to enter kiosk fullscreen: {err}");
+    }
+}
+
+// lock_down applies the window-level kiosk restrictions at
+// startup. Release builds ship without the inspector; debug builds
+// close it in case it was opened automatically.
+pub(crate) fn lock_down(window: &WebviewWindow) {
+    if config().is_none() {
+        return;
+    }
+    enforce_fullscreen(window);
+    #[cfg(debug_assertions)]
+    window.close_devtools();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn lookup(vars: &[(&'static str, &str)]) -> impl Fn(&str) -> Option<OsString> {
+        let vars: HashMap<&str, OsString> = vars
+            .iter()
+            .map(|(key, value)| (*key, OsString::from(value)))
+            .collect();
+        move |key| vars.get(key).cloned()
+    }
+
+    #[test]
+    fn kiosk_config_follows_settings_and_env_override() {
+        let mut settings = DesktopSettings::default();
+        assert_eq!(kiosk_config_from(lookup(&[]), &settings), None);
+
+        settings.kiosk_mode = true;
+        assert_eq!(
+            kiosk_config_from(lookup(&[]), &settings),
+            Some(KioskConfig {
+                idle_timeout: Some(DEFAULT_IDLE_TIMEOUT)
+            })
+        );
+        assert_eq!(
+            kiosk_config_from(lookup(&[("AGENTSVIEW_DESKTOP_KIOSK", "0")]), &settings),
+            None
+        );
+
+        let env_only = kiosk_config_from(
+            lookup(&[("AGENTSVIEW_DESKTOP_KIOSK", "1")]),
+            &DesktopSettings::default(),
+        );
+        assert!(env_only.is_some());
+    }
+
+    #[test]
+    fn kiosk_config_reads_idle_timeout() {
+        let settings = DesktopSettings {
+            kiosk_mode: true,
+            kiosk_idle_secs: Some(60),
+            ..Default::default()
+        };
+        let config = |vars: &[(&'static str, &str)]| {
+            kiosk_config_from(lookup(vars), &settings).and_then(|c| c.idle_timeout)
+        };
+
+        assert_eq!(config(&[]), Some(Duration::from_secs(60)));
+        assert_eq!(
+            config(&[("AGENTSVIEW_DESKTOP_KIOSK_IDLE_SECS", "90")]),
+            Some(Duration::from_secs(90))
+        );
+        assert_eq!(config(&[("AGENTSVIEW_DESKTOP_KIOSK_IDLE_SECS", "0")]), None);
+        assert_eq!(
+            config(&[("AGENTSVIEW_DESKTOP_KIOSK_IDLE_SECS", "soon")]),
+            Some(Duration::from_secs(60))
+        );
+    }
+
+    #[test]
+    fn lockdown_script_embeds_idle_timeout() {
+        let script = lockdown_script(&KioskConfig {
+            idle_timeout: Some(Duration::from_secs(2)),
+        });
+        assert!(script.contains("const IDLE_MS = 2000;"));
+        assert!(!script.contains("__IDLE_MS__"));
+
+        let disabled = lockdown_script(&KioskConfig { idle_timeout: None });
+        assert!(disabled.contains("const IDLE_MS = 0;"));
+    }
+}
@@ -1,348 +1,1149 @@
-use std::collections::BTreeMap;
-use std::error::Error;
 use std::ffi::OsString;
-use std::fs;
-use std::io;
-use std::io::{Read, Seek, SeekFrom, Write};
-use std::net::{Ipv4Addr, SocketAddrV4, TcpStream};
-use std::path::{Path, PathBuf};
-use std::process::Stdio;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 
 use tauri::async_runtime::Receiver;
 use tauri::plugin::Builder as PluginBuilder;
-use tauri::{App, AppHandle, Manager, RunEvent, Url, WebviewWindow};
-use tauri_plugin_shell::process::{CommandChild, CommandEvent};
+use tauri::webview::PageLoadEvent;
+use tauri::{
+    App, AppHandle, Emitter, Manager, RunEvent, Url, WebviewWindow, WebviewWindowBuilder,
+    WindowEvent,
+};
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+use tauri_plugin_shell::process::CommandEvent;
 use tauri_plugin_shell::ShellExt;
 
-const HOST: &str = "127.0.0.1";
-const PREFERRED_PORT: u16 = 8080;
+#[cfg(desktop)]
+mod actions;
+#[cfg(desktop)]
+mod always_on_top;
+#[cfg(desktop)]
+mod annotate;
+#[cfg(desktop)]
+mod api_url;
+#[cfg(desktop)]
+mod arch;
+#[cfg(desktop)]
+mod auth_proxy;
+mod backend_client;
+mod backend_output;
+#[cfg(desktop)]
+mod compat;
+mod connectivity;
+#[cfg(desktop)]
+mod costs;
+mod crash;
+mod csp;
+#[cfg(desktop)]
+mod deep_link;
+#[cfg(desktop)]
+mod desktop_log;
+// LAN discovery is compiled out of no-network builds, which never
+// query the network.
+#[cfg(all(desktop, not(feature = "no-network")))]
+mod discovery;
+#[cfg(desktop)]
+mod dnd;
+#[cfg(desktop)]
+mod doctor;
+mod downloads;
+mod env;
+#[cfg(desktop)]
+mod env_overrides;
+mod error;
+mod hardening;
+mod http_client;
+mod i18n;
+mod instance_lock;
+mod integrity;
+mod keepalive;
+mod kiosk;
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(desktop)]
+mod live;
+mod mock;
+mod navigation;
+#[cfg(desktop)]
+mod notify;
+#[cfg(desktop)]
+mod open_path;
+mod orphans;
+#[cfg(desktop)]
+mod palette;
+mod permissions;
+mod policy;
+mod port_conflict;
+mod ports;
+#[cfg(desktop)]
+mod presentation;
+mod process_tree;
+mod proxy;
+mod remote;
+#[cfg(desktop)]
+mod rendering;
+#[cfg(desktop)]
+mod resources;
+#[cfg(desktop)]
+mod session_window;
+mod settings;
+#[cfg(desktop)]
+mod shortcuts;
+mod shutdown;
+mod sidecar;
+mod sidecar_missing;
+#[cfg(desktop)]
+mod single_instance;
+mod socket_proxy;
+mod startup_dialog;
+#[cfg(desktop)]
+mod timestamps;
+mod tls;
+#[cfg(desktop)]
+mod tray;
+#[cfg(desktop)]
+mod ui_state;
+mod user;
+mod web_permissions;
+#[cfg(desktop)]
+mod weekly;
+#[cfg(desktop)]
+mod window_state;
+#[cfg(desktop)]
+mod zoom;
+
+use backend_output::{BackendOutput, Stream};
+use error::DesktopError;
+use instance_lock::{AcquireOutcome, InstanceLockState};
+use keepalive::{ActivityState, HealthEvent, HealthWatch, KeepAliveSchedule};
+use navigation::ExternalUrlLists;
+use process_tree::ProcessTree;
+use remote::RemoteState;
+use shutdown::{ExitGate, ExitHold, ShutdownSignal};
+use sidecar_missing::MissingSidecarState;
+
+// SIDECAR_LOG_FILE is the backend's debug log in its data dir.
+const SIDECAR_LOG_FILE: &str = "debug.log";
 const READY_TIMEOUT: Duration = Duration::from_secs(30);
 const READY_POLL_INTERVAL: Duration = Duration::from_millis(125);
-const LOGIN_SHELL_ENV_TIMEOUT: Duration = Duration::from_secs(3);
+// PROBE_TIMEOUT bounds each step of one readiness probe.
+const PROBE_TIMEOUT: Duration = Duration::from_millis(250);
+// RETEST_TIMEOUT bounds a re-test from the blocked-connection
+// dialog; the backend is already up, so it only needs a few probes.
+const RETEST_TIMEOUT: Duration = Duration::from_secs(5);
+// EXIT_FLUSH_TIMEOUT is how long the backend gets to finish its
+// writes and exit when the app quits, before it is killed.
+const EXIT_FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
 
-type DynError = Box<dyn Error>;
 type CommandRx = Receiver<CommandEvent>;
 
 #[derive(Default)]
 struct SidecarState {
-    child: Mutex<Option<CommandChild>>,
+    child: Mutex<Option<ProcessTree>>,
     backend_port: Mutex<Option<u16>>,
+    // attempt counts backend launches. Failures are only reported
+    // for the current attempt, so a retried launch does not surface
+    // errors from the one it replaced.
+    attempt: AtomicU64,
+    // clean_env launches the sidecar in env::clean_sidecar_env
+    // instead of the user's shell environment.
+    clean_env: AtomicBool,
+    // env_changed records that a desktop.env override was edited
+    // since the sidecar launched; see env_overrides.
+    env_changed: AtomicBool,
+    // keep_page leaves the webview on its page when a relaunched
+    // backend comes up on the port the page already shows, so a
+    // caller awaiting the restart is not reloaded away.
+    keep_page: AtomicBool,
+    // proxy relays the webview to a sidecar serving on a Unix
+    // socket, when sidecar_socket is set.
+    #[cfg(unix)]
+    proxy: Mutex<Option<socket_proxy::Proxy>>,
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    env::bypass_proxy_for_loopback();
+    #[cfg(windows)]
+    arch::ensure_webview2();
+    #[cfg(target_os = "linux")]
+    linux::apply();
+    #[cfg(desktop)]
+    rendering::apply();
+    let builder = tauri::Builder::default();
+    #[cfg(desktop)]
+    let builder = builder
+        .plugin(single_instance::init_plugin())
+        .plugin(deep_link::init_plugin())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(shortcuts::init_plugin())
+        .plugin(notify::init_plugin())
+        .plugin(ui_state::init_plugin())
+        .plugin(zoom::init_plugin())
+        .plugin(presentation::init_plugin())
+        .manage(palette::PaletteState::default())
+        .manage(notify::NotifyState::default())
+        .manage(deep_link::DeepLinkState::default())
+        .manage(costs::CostState::default())
+        .manage(resources::ResourceState::default())
+        .manage(tray::TrayState::default())
+        .manage(window_state::NormalGeometry::default())
+        .manage(presentation::PresentationState::default());
+    #[cfg(all(desktop, not(feature = "no-network")))]
+    let builder = builder.manage(discovery::DiscoveryState::default());
+    builder
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_dialog::init())
         .plugin(init_navigation_guard_plugin())
+        .plugin(csp::init_plugin())
+        .plugin(kiosk::init_plugin())
+        .plugin(web_permissions::init_plugin())
         .manage(SidecarState::default())
-        .setup(launch_backend)
+        .manage(BackendOutput::default())
+        .manage(MissingSidecarState::default())
+        .manage(ActivityState::default())
+        .manage(InstanceLockState::default())
+        .manage(ShutdownSignal::default())
+        .manage(ExitGate::default())
+        .manage(RemoteState::default())
+        .manage(downloads::DownloadState::default())
+        .on_window_event(|window, event| {
+            #[cfg(desktop)]
+            match event {
+                WindowEvent::Moved(_) | WindowEvent::Resized(_) => window_state::track(window),
+                WindowEvent::CloseRequested { .. } => window_state::save(window),
+                _ => {}
+            }
+            if let WindowEvent::Resized(_) = event {
+                if let Some(webview) = window.get_webview_window(window.label()) {
+                    kiosk::enforce_fullscreen(&webview);
+                }
+            }
+            if let WindowEvent::Focused(focused) = event {
+                #[cfg(desktop)]
+                if !*focused && shortcuts::is_popup(window.label()) {
+                    let _ = window.hide();
+                    return;
+                }
+                #[cfg(desktop)]
+                if *focused && window.label() == "main" {
+                    notify::open_pending(window.app_handle());
+                }
+                window.state::<ActivityState>().set_focused(*focused);
+                if !*focused && window.label() == "main" {
+                    if let Some(webview) = window.get_webview_window(window.label()) {
+                        remote::remember_route(&webview);
+                    }
+                }
+            }
+        })
+        .on_page_load(|webview, payload| {
+            if payload.event() == PageLoadEvent::Finished {
+                let _ = webview.emit(i18n::LOCALE_EVENT, i18n::locale_info());
+                #[cfg(desktop)]
+                deep_link::open_pending(webview);
+                #[cfg(desktop)]
+                presentation::on_page_load(webview);
+            }
+        })
+        .invoke_handler(tauri::generate_handler![
+            #[cfg(desktop)]
+            actions::list_desktop_actions,
+            #[cfg(desktop)]
+            actions::invoke_desktop_action,
+            #[cfg(desktop)]
+            actions::restart_backend,
+            #[cfg(desktop)]
+            actions::reload_environment,
+            #[cfg(desktop)]
+            always_on_top::set_always_on_top,
+            #[cfg(desktop)]
+            api_url::copy_api_url,
+            #[cfg(desktop)]
+            annotate::annotate_target,
+            #[cfg(desktop)]
+            annotate::annotate_submit,
+            #[cfg(desktop)]
+            annotate::annotate_close,
+            backend_output::backend_output,
+            #[cfg(desktop)]
+            costs::cost_alerts,
+            #[cfg(desktop)]
+            costs::acknowledge_cost_alerts,
+            #[cfg(desktop)]
+            costs::snooze_cost_alerts,
+            #[cfg(desktop)]
+            costs::set_cost_limits,
+            #[cfg(desktop)]
+            doctor::run_doctor,
+            #[cfg(desktop)]
+            doctor::sidecar_environment,
+            #[cfg(desktop)]
+            env_overrides::get_env_overrides,
+            #[cfg(desktop)]
+            env_overrides::set_env_override,
+            #[cfg(desktop)]
+            env_overrides::remove_env_override,
+            i18n::locale_info,
+            i18n::localize,
+            navigation::external_url_lists,
+            navigation::add_external_url_rule,
+            navigation::remove_external_url_rule,
+            #[cfg(desktop)]
+            open_path::open_path,
+            #[cfg(desktop)]
+            palette::palette_search,
+            #[cfg(desktop)]
+            palette::palette_open,
+            #[cfg(desktop)]
+            palette::palette_close,
+            permissions::capability_status,
+            permissions::request_capability,
+            permissions::reset_capability,
+            policy::managed_policy,
+            #[cfg(desktop)]
+            presentation::set_presentation_mode,
+            #[cfg(desktop)]
+            presentation::toggle_presentation_mode,
+            remote::connection_picker,
+            remote::connect_remote,
+            sidecar_missing::missing_sidecar,
+            sidecar_missing::retry_sidecar,
+            sidecar_missing::locate_sidecar,
+            #[cfg(desktop)]
+            resources::sidecar_resources,
+            #[cfg(desktop)]
+            session_window::open_session_window,
+            #[cfg(desktop)]
+            ui_state::load_ui_state,
+            #[cfg(desktop)]
+            ui_state::save_ui_state,
+            #[cfg(desktop)]
+            zoom::change_zoom
+        ])
+        .setup(|app| {
+            let window = main_window(app)?;
+            i18n::apply_window_direction(&window);
+            kiosk::lock_down(&window);
+            // Load the allowlist now so bad entries are reported at
+            // startup rather than on the first navigation.
+            navigation::allowlist();
+            // Mobile builds cannot spawn the sidecar; the splash page
+            // asks for a remote server via remote::connection_picker.
+            if cfg!(mobile) {
+                return Ok(());
+            }
+            if !acquire_instance_lock(app.handle(), &window) {
+                return Ok(());
+            }
+            if let Some(path) = orphans::pid_file() {
+                orphans::reap(&path);
+            }
+            #[cfg(desktop)]
+            {
+                shortcuts::register_all(app.handle());
+                tray::install(app.handle());
+                deep_link::install(app.handle());
+                rendering::confirm_trial(app.handle());
+                arch::warn_if_emulated(app.handle());
+                live::spawn_subscriber(app.handle().clone());
+                weekly::spawn_scheduler(app.handle().clone());
+                costs::spawn_poller(app.handle().clone());
+                resources::spawn_monitor(app.handle().clone());
+                #[cfg(not(feature = "no-network"))]
+                discovery::spawn_browser(app.handle().clone());
+            }
+            match policy::managed().remote_url.clone() {
+                Some(url) => attach_remote_backend(&window, url),
+                None => {
+                    start_backend(app.handle(), &window);
+                    #[cfg(desktop)]
+                    auth_proxy::start(app.handle());
+                    probe_login_shell_in_background(app.handle(), &window);
+                }
+            }
+            Ok(())
+        })
         .build(tauri::generate_context!())
         .expect("failed to build tauri app")
-        .run(|app_handle, event| {
-            if matches!(event, RunEvent::ExitRequested { .. } | RunEvent::Exit) {
+        .run(|app_handle, event| match event {
+            RunEvent::ExitRequested { code, api, .. } => {
+                #[cfg(desktop)]
+                window_state::save_all(app_handle);
+                app_handle.state::<ShutdownSignal>().trigger();
+                // A restart cannot be held, so the backend is
+                // stopped before it proceeds.
+                if code == Some(tauri::RESTART_EXIT_CODE) {
+                    stop_backend_gracefully(app_handle);
+                    return;
+                }
+                match app_handle.state::<ExitGate>().request() {
+                    ExitHold::Open => {}
+                    ExitHold::Start => {
+                        api.prevent_exit();
+                        exit_after_backend_stops(app_handle.clone(), code.unwrap_or(0));
+                    }
+                    ExitHold::Pending => api.prevent_exit(),
+                }
+            }
+            RunEvent::Exit => {
+                app_handle.state::<ShutdownSignal>().trigger();
                 stop_backend(app_handle);
+                release_instance_lock(app_handle);
             }
+            _ => {}
         });
 }
 
-fn launch_backend(app: &mut App) -> Result<(), DynError> {
-    let window = main_window(app)?;
-    let (rx, child) = spawn_sidecar(app)?;
+// exit_after_backend_stops lets the backend finish writing, so
+// closing the window does not cut off an import, then exits with
+// code.
+fn exit_after_backend_stops(app: AppHandle, code: i32) {
+    thread::spawn(move || {
+        stop_backend_gracefully(&app);
+        app.state::<ExitGate>().open();
+        app.exit(code);
+    });
+}
 
-    save_sidecar(app, child)?;
-    forward_sidecar_logs(rx, window);
+// acquire_instance_lock takes the per-install lock under the data
+// dir. When another live desktop instance holds it, the user is
+// told and this process exits instead of starting a second
+// sidecar against the same database. Launches of the same install
+// are normally handed to the running instance by
+// single_instance::init_plugin before this runs; the lock also
+// covers other installs sharing the data dir. Lock I/O failures
+// are logged and do not block startup.
+fn acquire_instance_lock(app: &AppHandle, window: &WebviewWindow) -> bool {
+    let Some(path) =
+        data_dir().map(|dir| dir.join(user::scoped_file_name(instance_lock::LOCK_FILE, &dir)))
+    else {
+        return true;
+    };
+    let outcome = match instance_lock::acquire_lock(
+        &path,
+        std::process::id(),
+        instance_lock::process_alive,
+    ) {
+        Ok(outcome) => outcome,
+        Err(err) => {
+            eprintln!("[agentsview] failed to acquire instance lock: {err}");
+            return true;
+        }
+    };
 
-    Ok(())
+    match outcome {
+        AcquireOutcome::Acquired(lock) => {
+            if let Ok(mut guard) = app.state::<InstanceLockState>().0.lock() {
+                *guard = Some(lock);
+            }
+            true
+        }
+        AcquireOutcome::HeldBy(info) => {
+            let mut message = i18n::tr_args("instance-running", &[("pid", &info.pid)]);
+            if let Some(port) = info.port {
+                let url = local_url(port);
+                message.push(' ');
+                message.push_str(&i18n::tr_args("instance-running-port", &[("url", &url)]));
+            }
+            set_splash_status(window, &message);
+            let app = app.clone();
+            app.clone()
+                .dialog()
+                .message(format!(
+                    "{message}\n\n{}",
+                    i18n::tr("instance-running-switch")
+                ))
+                .title(i18n::tr("instance-running-title"))
+                .kind(MessageDialogKind::Info)
+                .show(move |_| app.exit(0));
+            false
+        }
+    }
 }
 
-fn spawn_sidecar(app: &App) -> Result<(CommandRx, CommandChild), DynError> {
-    let port_arg = PREFERRED_PORT.to_string();
-    let mut command = app.shell().sidecar("agentsview")?;
-    for (key, value) in sidecar_env() {
-        command = command.env(key, value);
+fn release_instance_lock(app: &AppHandle) {
+    if let Ok(mut guard) = app.state::<InstanceLockState>().0.lock() {
+        if let Some(lock) = guard.take() {
+            lock.release();
+        }
     }
-
-    Ok(command
-        .args([
-            "serve",
-            "-no-browser",
-            "-host",
-            HOST,
-            "-port",
-            port_arg.as_str(),
-        ])
-        .spawn()?)
 }
 
-fn init_navigation_guard_plugin<R: tauri::Runtime>() -> tauri::plugin::TauriPlugin<R> {
-    PluginBuilder::new("navigation-guard")
-        .on_navigation(|webview, url| {
-            let backend_port = webview
-                .app_handle()
-                .state::<SidecarState>()
-                .backend_port
-                .lock()
-                .ok()
-                .and_then(|guard| *guard);
-            if is_allowed_navigation_url(url, backend_port) {
-                return true;
-            }
-            if is_allowed_external_open_url(url) {
-                if let Err(err) = webview
-                    .app_handle()
-                    .shell()
-                    .open(url.as_str().to_string(), None)
-                {
-                    eprintln!("[agentsview] failed to open external URL in system browser: {err}");
-                }
-            } else {
-                eprintln!(
-                    "[agentsview] blocked disallowed external URL scheme: {}",
-                    url.as_str()
-                );
+fn update_instance_lock_port(app: &AppHandle, port: Option<u16>) {
+    if let Ok(guard) = app.state::<InstanceLockState>().0.lock() {
+        if let Some(lock) = guard.as_ref() {
+            if let Err(err) = lock.set_port(port) {
+                eprintln!("[agentsview] failed to update instance lock: {err}");
             }
-            false
-        })
-        .build()
+        }
+    }
 }
 
-fn is_allowed_navigation_url(url: &Url, backend_port: Option<u16>) -> bool {
-    if url.scheme() == "tauri" && url.host_str() == Some("localhost") {
-        return true;
+// data_dir mirrors the backend's data directory resolution:
+// AGENT_VIEWER_DATA_DIR when set, otherwise ~/.agentsview. A
+// managed DataDir policy, then the sidecar_data_dir setting, take
+// precedence and are passed on to the sidecar.
+pub(crate) fn data_dir() -> Option<PathBuf> {
+    if let Some(dir) = sidecar_data_dir() {
+        return Some(dir);
     }
-    if url.scheme() != "http" || url.host_str() != Some(HOST) {
-        return false;
+    if let Some(dir) = std::env::var_os("AGENT_VIEWER_DATA_DIR").filter(|v| !v.is_empty()) {
+        return Some(PathBuf::from(dir));
     }
-    matches!(
-        (url.port(), backend_port),
-        (Some(navigated_port), Some(sidecar_port)) if navigated_port == sidecar_port
-    )
+    Some(resolve_home_dir()?.join(".agentsview"))
 }
 
-fn is_allowed_external_open_url(url: &Url) -> bool {
-    matches!(url.scheme(), "http" | "https" | "mailto")
+fn sidecar_data_dir() -> Option<PathBuf> {
+    policy::managed()
+        .data_dir
+        .clone()
+        .or_else(|| settings::load_settings().sidecar_data_dir)
 }
 
-// sidecar_env returns the environment passed to the backend
-// sidecar process. It merges the app environment with
-// login-shell variables so desktop launches inherit zshrc/bash
-// exports. An optional ~/.agentsview/desktop.env file can
-// override specific keys as an escape hatch.
-fn sidecar_env() -> Vec<(OsString, OsString)> {
-    let skip_login_shell = std::env::var_os("AGENTSVIEW_DESKTOP_SKIP_LOGIN_SHELL_ENV");
-    let should_probe =
-        should_probe_login_shell(skip_login_shell.as_ref(), cfg!(target_os = "windows"));
-
-    build_sidecar_env(
-        std::env::vars_os().collect(),
-        if should_probe {
-            read_login_shell_env().unwrap_or_default()
-        } else {
-            Vec::new()
-        },
-        read_desktop_env_file(),
-        std::env::var_os("AGENTSVIEW_DESKTOP_PATH"),
-        cfg!(target_os = "windows"),
-    )
+// start_backend launches the backend and routes failures to the
+// user: a missing sidecar gets the locate-binary dialog, anything
+// else the startup failure dialog.
+fn start_backend(app: &AppHandle, window: &WebviewWindow) {
+    let attempt = app
+        .state::<SidecarState>()
+        .attempt
+        .fetch_add(1, Ordering::SeqCst)
+        + 1;
+    let Err(err) = launch_backend(app, window, attempt) else {
+        return;
+    };
+    eprintln!("[agentsview] startup failed: {err}");
+    if let DesktopError::SidecarSpawn { reason } = &err {
+        sidecar_missing::show(app, window, reason);
+    } else {
+        show_startup_error(window, &err, attempt);
+    }
 }
 
-// read_login_shell_env invokes the user's login shell and
-// parses NUL-delimited env output (`env -0`).
-fn read_login_shell_env() -> Option<Vec<(OsString, OsString)>> {
-    let default_shell = default_login_shell();
-    let shell = std::env::var("SHELL")
-        .ok()
-        .filter(|s| !s.trim().is_empty())
-        .unwrap_or(default_shell);
-
-    let stdout = run_login_shell_env(shell.as_str(), LOGIN_SHELL_ENV_TIMEOUT)?;
-    Some(parse_nul_env(stdout.as_slice()))
+// restart_backend stops whatever is left of a failed launch and
+// starts the backend again.
+fn restart_backend(app: &AppHandle, window: &WebviewWindow) {
+    relaunch_backend(app, window, false);
 }
 
-fn default_login_shell() -> String {
-    if cfg!(target_os = "macos") {
-        return "/bin/zsh".to_string();
-    }
-    if Path::new("/bin/bash").exists() {
-        return "/bin/bash".to_string();
+// relaunch_backend restarts the backend and returns the new launch
+// attempt. With keep_page, the webview is only redirected if the
+// backend comes back on a different port.
+fn relaunch_backend(app: &AppHandle, window: &WebviewWindow, keep_page: bool) -> u64 {
+    let state = app.state::<SidecarState>();
+    // Supersede the failed attempt before stopping its sidecar, so
+    // the resulting exit is not reported as a new failure.
+    state.attempt.fetch_add(1, Ordering::SeqCst);
+    state.keep_page.store(keep_page, Ordering::SeqCst);
+    stop_backend(app);
+    // The splash status element only exists on the splash page.
+    if !keep_page {
+        set_splash_status(window, &i18n::tr("splash-status-starting"));
     }
-    "/bin/sh".to_string()
+    start_backend(app, window);
+    state.attempt.load(Ordering::SeqCst)
 }
 
-// read_desktop_env_file parses ~/.agentsview/desktop.env as
-// KEY=VALUE lines. This provides a manual override path before
-// desktop settings UI exists.
-fn read_desktop_env_file() -> Vec<(OsString, OsString)> {
-    let Some(home) = resolve_home_dir() else {
-        return Vec::new();
-    };
-    let path = home.join(".agentsview").join("desktop.env");
-    let Ok(content) = fs::read_to_string(path) else {
-        return Vec::new();
+// restart_backend_clean relaunches the sidecar in the clean
+// troubleshooting environment for the rest of the session, so
+// users can tell whether their shell customizations are what
+// breaks indexing. Remote connections have no sidecar to restart.
+#[cfg(desktop)]
+fn restart_backend_clean(app: &AppHandle) {
+    if policy::managed().remote_url.is_some() || app.state::<RemoteState>().origin().is_some() {
+        eprintln!("[agentsview] no local backend to restart");
+        return;
+    }
+    let Some(window) = app.get_webview_window("main") else {
+        return;
     };
+    eprintln!("[agentsview] restarting backend with a clean environment");
+    app.state::<SidecarState>()
+        .clean_env
+        .store(true, Ordering::SeqCst);
+    restart_backend(app, &window);
+}
 
-    parse_desktop_env_content(content.as_str())
+// probe_login_shell_in_background probes the login shell after
+// the sidecar started without it, keeping the shell profile off
+// the startup path. The sidecar is restarted only when the probe
+// changes where it looks for binaries or agent sessions.
+fn probe_login_shell_in_background(app: &AppHandle, window: &WebviewWindow) {
+    let app = app.clone();
+    let window = window.clone();
+    thread::spawn(move || {
+        if !env::refresh_login_shell_env() {
+            return;
+        }
+        let state = app.state::<SidecarState>();
+        // Attached servers and mock backends have no sidecar.
+        let spawned = state.child.lock().is_ok_and(|child| child.is_some());
+        if !spawned || state.clean_env.load(Ordering::SeqCst) {
+            return;
+        }
+        eprintln!("[agentsview] login shell env changed PATH; restarting backend");
+        relaunch_backend(&app, &window, true);
+    });
 }
 
-fn resolve_home_dir() -> Option<PathBuf> {
-    resolve_home_dir_from_lookup(|key| std::env::var_os(key), cfg!(target_os = "windows"))
+// refresh_environment reloads the environment and restarts the
+// local backend from the tray or the command palette, without
+// blocking the caller on the shell probe.
+#[cfg(desktop)]
+fn refresh_environment(app: &AppHandle) {
+    if policy::managed().remote_url.is_some() || app.state::<RemoteState>().origin().is_some() {
+        env::forget_cached_env();
+        eprintln!("[agentsview] no local backend to restart");
+        return;
+    }
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let app = app.clone();
+    thread::spawn(move || {
+        reload_environment(&app, &window, false);
+    });
 }
 
-fn should_probe_login_shell(skip: Option<&OsString>, is_windows: bool) -> bool {
-    !is_windows && skip.is_none()
+// reload_environment drops the cached login-shell env, probes the
+// shell again and relaunches the backend, which re-reads
+// desktop.env, direnv and the registry as it spawns. A clean
+// environment from restart_backend_clean is dropped too. It
+// returns the new launch attempt; see relaunch_backend.
+#[cfg(desktop)]
+fn reload_environment(app: &AppHandle, window: &WebviewWindow, keep_page: bool) -> u64 {
+    env::forget_cached_env();
+    env::refresh_login_shell_env();
+    app.state::<SidecarState>()
+        .clean_env
+        .store(false, Ordering::SeqCst);
+    eprintln!("[agentsview] restarting backend with a refreshed environment");
+    relaunch_backend(app, window, keep_page)
 }
 
-fn build_sidecar_env(
-    inherited: Vec<(OsString, OsString)>,
-    login_shell: Vec<(OsString, OsString)>,
-    desktop_file: Vec<(OsString, OsString)>,
-    forced_path: Option<OsString>,
-    case_insensitive_keys: bool,
-) -> Vec<(OsString, OsString)> {
-    let mut merged = BTreeMap::new();
-    merge_env_pairs(&mut merged, inherited, case_insensitive_keys);
-    merge_env_pairs(&mut merged, login_shell, case_insensitive_keys);
-    merge_env_pairs(&mut merged, desktop_file, case_insensitive_keys);
+// attach_remote_backend connects to the server named by the
+// RemoteURL policy or remote::backend_url instead of spawning the
+// bundled backend.
+fn attach_remote_backend(window: &WebviewWindow, url: String) {
+    let window = window.clone();
+    thread::spawn(move || {
+        if let Err(err) = remote::connect_blocking(&window, &url) {
+            eprintln!("[agentsview] remote backend connection failed: {err}");
+            let retry_window = window.clone();
+            startup_dialog::show_startup_failure(window.app_handle(), &err, None, move || {
+                attach_remote_backend(&retry_window, url)
+            });
+        }
+    });
+}
 
-    if let Some(path) = forced_path {
-        merged.insert(
-            normalize_env_key(std::ffi::OsStr::new("PATH"), case_insensitive_keys),
-            path,
-        );
+fn launch_backend(
+    app: &AppHandle,
+    window: &WebviewWindow,
+    attempt: u64,
+) -> Result<(), DesktopError> {
+    if mock::mock_enabled() {
+        let port = mock::start_mock_backend()?;
+        save_sidecar_port(app, port);
+        redirect_when_ready(window.clone(), StartupPort::Ready(port), attempt);
+        return Ok(());
     }
 
-    merged.into_iter().collect()
-}
+    if let Some(url) = remote::backend_url() {
+        eprintln!("[agentsview] using the external backend at {url}");
+        attach_remote_backend(window, url);
+        return Ok(());
+    }
 
-fn merge_env_pairs(
-    dest: &mut BTreeMap<OsString, OsString>,
-    pairs: Vec<(OsString, OsString)>,
-    case_insensitive_keys: bool,
-) {
-    for (k, v) in pairs {
-        dest.insert(normalize_env_key(k.as_os_str(), case_insensitive_keys), v);
+    if attach_running_server(app, window, attempt) {
+        return Ok(());
     }
+
+    let (rx, child, port) = spawn_sidecar(app)?;
+
+    save_sidecar(app, child)?;
+    forward_sidecar_logs(rx, window.clone(), attempt, port);
+
+    Ok(())
 }
 
-fn normalize_env_key(key: &std::ffi::OsStr, case_insensitive_keys: bool) -> OsString {
-    if case_insensitive_keys {
-        return OsString::from(key.to_string_lossy().to_ascii_uppercase());
+// attach_running_server points the webview at an agentsview server
+// the user already runs on the first port of ports::range, such as
+// `agentsview serve` in a terminal, instead of spawning a second backend
+// against the same database. Nothing is stored as the sidecar, so
+// stopping the backend leaves that server running. It is skipped
+// when the sidecar is customized in ways an outside server would
+// not honor.
+fn attach_running_server(app: &AppHandle, window: &WebviewWindow, attempt: u64) -> bool {
+    if settings::load_settings().always_spawn_backend
+        || sidecar_data_dir().is_some()
+        || app.state::<SidecarState>().clean_env.load(Ordering::SeqCst)
+    {
+        return false;
+    }
+    let port = ports::range().first;
+    if probe_backend(port) != Probe::Ready {
+        return false;
     }
-    key.to_os_string()
+    eprintln!("[agentsview] attaching to the agentsview server already running on port {port}");
+    save_sidecar_port(app, port);
+    let window = window.clone();
+    let shutdown = app.state::<ShutdownSignal>().inner().clone();
+    thread::spawn(move || finish_startup(&window, port, attempt, Probe::Ready, &shutdown));
+    true
 }
 
-fn run_login_shell_env(shell: &str, timeout: Duration) -> Option<Vec<u8>> {
-    let shell_arg = shell_login_env_flag(shell);
-    let mut stdout_capture = tempfile::tempfile().ok()?;
-    let stdout_writer = stdout_capture.try_clone().ok()?;
-    let mut child = std::process::Command::new(shell)
-        .args([shell_arg, "env -0"])
-        .stdin(Stdio::null())
-        .stderr(Stdio::null())
-        .stdout(Stdio::from(stdout_writer))
-        .spawn()
-        .ok()?;
+// spawn_sidecar starts `agentsview serve` on the first free port
+// of ports::range and returns that port with the process. The
+// sidecar holds the listener it binds from then on, and the port
+// it reports in its ready event is the one used, so a port taken
+// between the shell's check and the sidecar's bind only moves the
+// backend along. It runs as a process tree, so stop_backend also
+// stops any helpers the sidecar forks.
+fn spawn_sidecar(app: &AppHandle) -> Result<(CommandRx, ProcessTree, u16), DesktopError> {
+    let settings = settings::load_settings();
+    // A sidecar_path the user chose is theirs to vouch for.
+    if settings.sidecar_path.is_none() {
+        integrity::verify_bundled_sidecar()?;
+    }
+    // The previous sidecar's proxy goes first, freeing its port.
+    #[cfg(unix)]
+    if let Ok(mut proxy) = app.state::<SidecarState>().proxy.lock() {
+        proxy.take();
+    }
+    let range = ports::range();
+    let port = ports::allocate(range).ok_or_else(|| DesktopError::PortUnavailable {
+        port: 0,
+        reason: format!("no free port in {}-{}", range.first, range.last),
+    })?;
+    #[cfg(unix)]
+    let proxy = if settings.sidecar_socket {
+        let proxy =
+            socket_proxy::Proxy::start(port).map_err(|err| DesktopError::PortUnavailable {
+                port,
+                reason: err.to_string(),
+            })?;
+        Some(proxy)
+    } else {
+        None
+    };
+    #[cfg(unix)]
+    let socket = proxy.as_ref().map(socket_proxy::Proxy::socket);
+    #[cfg(not(unix))]
+    let socket: Option<&std::path::Path> = None;
+    #[cfg(not(unix))]
+    if settings.sidecar_socket {
+        eprintln!("[agentsview] sidecar_socket is not supported on this platform; using TCP");
+    }
+    let mut command = match &settings.sidecar_path {
+        Some(path) => app.shell().command(path),
+        None => app
+            .shell()
+            .sidecar("agentsview")
+            .map_err(|err| DesktopError::SidecarSpawn {
+                reason: err.to_string(),
+            })?,
+    };
+    app.state::<SidecarState>()
+        .env_changed
+        .store(false, Ordering::SeqCst);
+    let sidecar_env = if app.state::<SidecarState>().clean_env.load(Ordering::SeqCst) {
+        command = command.env_clear();
+        env::clean_sidecar_env()
+    } else {
+        env::sidecar_env()
+    };
+    for (key, value) in sidecar_env {
+        command = command.env(key, value);
+    }
+    command = command.env(sidecar::READY_EVENT_ENV, "1");
+    if let Some(dir) = sidecar_data_dir() {
+        command = command.env("AGENT_VIEWER_DATA_DIR", dir);
+    }
+    if let Some(path) = sidecar_log_path() {
+        if path
+            .file_name()
+            .is_some_and(|name| name != SIDECAR_LOG_FILE)
+        {
+            command = command.env("AGENT_VIEWER_LOG_FILE", path);
+        }
+    }
+
+    let lan_token = settings.lan_auth_token.as_deref();
+    let lan_host = match sidecar::lan_host(&settings.sidecar_args, settings.share_on_lan, lan_token)
+    {
+        Ok(Some(_)) if socket.is_some() => {
+            eprintln!("[agentsview] not sharing the backend on the LAN: it serves on a socket");
+            None
+        }
+        Ok(host) => host,
+        Err(reason) => {
+            eprintln!("[agentsview] keeping the backend on loopback: {reason}");
+            None
+        }
+    };
+    if let (Some(host), Some(token)) = (&lan_host, lan_token) {
+        eprintln!("[agentsview] sharing the backend on the LAN ({host})");
+        command = command.env(sidecar::AUTH_TOKEN_ENV, token.trim());
+    }
+    let host = lan_host.unwrap_or_else(|| ports::loopback().to_string());
+    let args = sidecar::serve_args(&host, port, socket, &settings.sidecar_args);
+    let (rx, child) = process_tree::spawn(command.args(args), settings.sidecar_low_priority)
+        .map_err(|err| DesktopError::SidecarSpawn {
+            reason: err.to_string(),
+        })?;
+    #[cfg(unix)]
+    if let Ok(mut guard) = app.state::<SidecarState>().proxy.lock() {
+        *guard = proxy;
+    }
+    Ok((rx, child, port))
+}
 
-    let deadline = Instant::now() + timeout;
-    let status = loop {
-        match child.try_wait() {
-            Ok(Some(status)) => {
-                break status;
-            }
-            Ok(None) => {
-                if Instant::now() >= deadline {
-                    let _ = child.kill();
-                    let _ = child.wait();
-                    return None;
+fn init_navigation_guard_plugin<R: tauri::Runtime>() -> tauri::plugin::TauriPlugin<R> {
+    PluginBuilder::new("navigation-guard")
+        .on_navigation(|webview, url| {
+            let backend_port = webview
+                .app_handle()
+                .state::<SidecarState>()
+                .backend_port
+                .lock()
+                .ok()
+                .and_then(|guard| *guard);
+            let remote_origin = webview.app_handle().state::<RemoteState>().origin();
+            let decision = decide_navigation(
+                url,
+                backend_port,
+                remote_origin.as_ref(),
+                navigation::is_allowlisted(url),
+                kiosk::config().is_some(),
+                &ExternalUrlLists::from_settings(&settings::load_settings()),
+            );
+            #[cfg(desktop)]
+            desktop_log::record(&navigation_log_line(url, decision));
+            match decision {
+                NavigationDecision::Allow(_) => return true,
+                NavigationDecision::OpenExternally => {
+                    let (external, removed) = navigation::scrub_external_url(url);
+                    if !removed.is_empty() {
+                        eprintln!(
+                            "[agentsview] removed {} before opening {}",
+                            removed.join(", "),
+                            url_for_log(&external)
+                        );
+                    }
+                    #[allow(deprecated)]
+                    if let Err(err) = webview
+                        .app_handle()
+                        .shell()
+                        .open(external.as_str().to_string(), None)
+                    {
+                        eprintln!(
+                            "[agentsview] failed to open external URL in system browser: {err}"
+                        );
+                    }
+                }
+                NavigationDecision::Block(reason) => {
+                    eprintln!(
+                        "[agentsview] blocked navigation to {}: {reason}",
+                        url_for_log(url)
+                    );
                 }
-                thread::sleep(Duration::from_millis(25));
-            }
-            Err(err) => {
-                eprintln!("[agentsview] login shell probe try_wait failed: {err}");
-                let _ = child.kill();
-                let _ = child.wait();
-                return None;
             }
+            false
+        })
+        .build()
+}
+
+// The rules under which the navigation guard keeps a URL in the
+// window, as recorded in the desktop log.
+const RULE_BUNDLED_TAURI: &str = "bundled page (tauri://localhost)";
+const RULE_BUNDLED_HTTP: &str = "bundled page (http(s)://tauri.localhost)";
+const RULE_REMOTE_ORIGIN: &str = "attached remote origin";
+const RULE_SIDECAR_PORT: &str = "sidecar port";
+const RULE_ALLOWLIST: &str = "navigation allowlist";
+// BUNDLED_ORIGIN is where this platform's webview serves the
+// bundled pages from.
+const BUNDLED_ORIGIN: &str = if cfg!(any(windows, target_os = "android")) {
+    "http://tauri.localhost"
+} else {
+    "tauri://localhost"
+};
+
+// NavigationDecision is what the navigation guard does with a URL
+// the window is about to load.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NavigationDecision {
+    // Allow loads the URL in the window, by the rule named.
+    Allow(&'static str),
+    // OpenExternally hands the URL to the OS, e.g. the browser.
+    OpenExternally,
+    // Block drops the navigation, for the reason given.
+    Block(&'static str),
+}
+
+// decide_navigation keeps the shell's own pages, the backend and
+// allowlisted origins in the window and hands web and mail links
+// to the OS. Dangerous schemes are refused before anything else,
+// so no allowlist entry can let them in.
+fn decide_navigation(
+    url: &Url,
+    backend_port: Option<u16>,
+    remote_origin: Option<&Url>,
+    allowlisted: bool,
+    kiosk: bool,
+    external: &ExternalUrlLists,
+) -> NavigationDecision {
+    if let Some(reason) = dangerous_scheme_reason(url.scheme()) {
+        return NavigationDecision::Block(reason);
+    }
+    if let Some(rule) = navigation_allow_rule(url, backend_port, remote_origin) {
+        return NavigationDecision::Allow(rule);
+    }
+    if allowlisted {
+        return NavigationDecision::Allow(RULE_ALLOWLIST);
+    }
+    if kiosk {
+        return NavigationDecision::Block("external links are disabled in kiosk mode");
+    }
+    if is_allowed_external_open_url(url, external) {
+        return NavigationDecision::OpenExternally;
+    }
+    NavigationDecision::Block(match url.scheme() {
+        scheme if EXTERNAL_SCHEMES.contains(&scheme) => {
+            "the external link lists do not allow this domain"
         }
+        "http" | "https" => "no-network builds do not open web links",
+        _ => "the scheme is not allowed",
+    })
+}
+
+// dangerous_scheme_reason explains why a scheme that can run script
+// in the window's origin or read local files is never loaded.
+fn dangerous_scheme_reason(scheme: &str) -> Option<&'static str> {
+    match scheme {
+        "javascript" | "vbscript" => Some("script URLs are never loaded"),
+        "data" => Some("data URLs are never loaded"),
+        "file" | "filesystem" => Some("local files are never loaded"),
+        _ => None,
+    }
+}
+
+// navigation_log_line describes a navigation guard decision for
+// the desktop log: the URL without credentials, the rule or reason
+// behind the decision, and the platform and the origin it serves
+// the bundled pages from, which differ between webviews.
+fn navigation_log_line(url: &Url, decision: NavigationDecision) -> String {
+    let (action, rule) = match decision {
+        NavigationDecision::Allow(rule) => ("allow", rule),
+        NavigationDecision::OpenExternally => ("open-externally", "external link lists"),
+        NavigationDecision::Block(reason) => ("block", reason),
     };
-    if !status.success() {
-        return None;
+    let (scrubbed, _) = navigation::scrub_external_url(url);
+    format!(
+        "navigation {action} {} rule=\"{rule}\" platform={} platform_origin={BUNDLED_ORIGIN}",
+        url_for_log(&scrubbed),
+        std::env::consts::OS,
+    )
+}
+
+// url_for_log shortens long URLs, such as data: URLs, for the log.
+fn url_for_log(url: &Url) -> String {
+    const MAX_CHARS: usize = 200;
+    let text = url.as_str();
+    match text.char_indices().nth(MAX_CHARS) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
     }
+}
 
-    if stdout_capture.seek(SeekFrom::Start(0)).is_err() {
+// navigation_allow_rule names the rule that keeps url in the
+// window, if any: the bundled pages on each platform,
+// tauri://localhost on macOS and Linux and
+// http(s)://tauri.localhost on Windows and Android, the attached
+// remote origin and the sidecar's own loopback port.
+fn navigation_allow_rule(
+    url: &Url,
+    backend_port: Option<u16>,
+    remote_origin: Option<&Url>,
+) -> Option<&'static str> {
+    if dangerous_scheme_reason(url.scheme()).is_some() {
         return None;
     }
-    let mut output = Vec::new();
-    if stdout_capture.read_to_end(&mut output).is_err() {
+    if let Some(rule) = bundled_page_rule(url) {
+        return Some(rule);
+    }
+    if remote_origin.is_some_and(|origin| remote::same_origin(origin, url)) {
+        return Some(RULE_REMOTE_ORIGIN);
+    }
+    if url.scheme() != "http" || !url.host_str().is_some_and(ports::is_loopback_host) {
         return None;
     }
-    Some(output)
+    match (url.port(), backend_port) {
+        (Some(navigated_port), Some(sidecar_port)) if navigated_port == sidecar_port => {
+            Some(RULE_SIDECAR_PORT)
+        }
+        _ => None,
+    }
 }
 
-fn shell_login_env_flag(shell: &str) -> &'static str {
-    let name = Path::new(shell)
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or_default();
-    match name {
-        "sh" | "dash" | "busybox" => "-c",
-        "fish" => "-lc",
-        _ => "-lic",
+// bundled_page_rule names the rule under which url is one of the
+// shell's bundled pages, if it is.
+fn bundled_page_rule(url: &Url) -> Option<&'static str> {
+    if url.scheme() == "tauri" && url.host_str() == Some("localhost") {
+        return Some(RULE_BUNDLED_TAURI);
     }
+    // The bundled pages are served without a port; with one,
+    // tauri.localhost resolves to whatever listens on loopback.
+    if matches!(url.scheme(), "http" | "https")
+        && url.host_str() == Some("tauri.localhost")
+        && url.port().is_none()
+    {
+        return Some(RULE_BUNDLED_HTTP);
+    }
+    None
 }
 
-fn parse_nul_env(content: &[u8]) -> Vec<(OsString, OsString)> {
-    let mut vars = Vec::new();
-    for entry in content.split(|b| *b == 0) {
-        if entry.is_empty() {
-            continue;
-        }
-        let Some(eq) = entry.iter().position(|b| *b == b'=') else {
-            continue;
-        };
-        if eq == 0 {
-            continue;
-        }
-        vars.push((
-            os_string_from_bytes(&entry[..eq]),
-            os_string_from_bytes(&entry[eq + 1..]),
-        ));
+// require_bundled_page refuses command unless the calling webview
+// shows one of the shell's bundled pages. Commands that change what
+// the shell trusts check it themselves, so the backend's web UI
+// cannot reach them even through a capability granted by mistake.
+pub(crate) fn require_bundled_page(
+    webview: &tauri::Webview,
+    command: &str,
+) -> Result<(), DesktopError> {
+    let bundled = webview
+        .url()
+        .is_ok_and(|url| bundled_page_rule(&url).is_some());
+    if bundled {
+        Ok(())
+    } else {
+        Err(DesktopError::ActionUnavailable {
+            action: command.to_string(),
+            reason: "only the shell's own pages may call it".to_string(),
+        })
     }
-    vars
 }
 
-#[cfg(unix)]
-fn os_string_from_bytes(bytes: &[u8]) -> OsString {
-    use std::os::unix::ffi::OsStringExt;
-    OsString::from_vec(bytes.to_vec())
+// EXTERNAL_SCHEMES are the link schemes handed to the OS.
+// no-network builds never open http(s) links, so the shell cannot
+// start traffic to anything but the local backend.
+#[cfg(not(feature = "no-network"))]
+const EXTERNAL_SCHEMES: &[&str] = &["http", "https", "mailto"];
+#[cfg(feature = "no-network")]
+const EXTERNAL_SCHEMES: &[&str] = &["mailto"];
+
+// is_allowed_external_open_url decides which links are handed to
+// the OS: links in EXTERNAL_SCHEMES that the user's external link
+// lists permit.
+fn is_allowed_external_open_url(url: &Url, external: &ExternalUrlLists) -> bool {
+    EXTERNAL_SCHEMES.contains(&url.scheme()) && external.permits(url)
 }
 
-#[cfg(not(unix))]
-fn os_string_from_bytes(bytes: &[u8]) -> OsString {
-    OsString::from(String::from_utf8_lossy(bytes).into_owned())
+// resolve_home_dir prefers the environment and falls back to the
+// OS account database, since launchd-spawned and some SSO login
+// contexts start the app without HOME/USERPROFILE.
+fn resolve_home_dir() -> Option<PathBuf> {
+    resolve_home_dir_from_lookup(|key| std::env::var_os(key), cfg!(target_os = "windows"))
+        .or_else(system_home_dir)
 }
 
-fn parse_desktop_env_content(content: &str) -> Vec<(OsString, OsString)> {
-    let mut vars = Vec::new();
-    for line in content.lines() {
-        let line = line.trim();
-        if line.is_empty() || line.starts_with('#') {
-            continue;
-        }
-        let Some((k, v)) = line.split_once('=') else {
-            continue;
+// system_home_dir reads the current user's home directory from
+// the passwd database.
+#[cfg(unix)]
+fn system_home_dir() -> Option<PathBuf> {
+    use std::ffi::{CStr, OsStr};
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut buf: Vec<libc::c_char> = vec![0; 1024];
+    loop {
+        // SAFETY: passwd is plain old data that getpwuid_r fills
+        // in; its string fields point into buf, which outlives
+        // every read below.
+        let mut pwd: libc::passwd = unsafe { std::mem::zeroed() };
+        let mut result: *mut libc::passwd = std::ptr::null_mut();
+        let rc = unsafe {
+            libc::getpwuid_r(
+                libc::getuid(),
+                &mut pwd,
+                buf.as_mut_ptr(),
+                buf.len(),
+                &mut result,
+            )
         };
-        let key = k.trim();
-        if key.is_empty() {
+        if rc == libc::ERANGE && buf.len() < 1 << 20 {
+            buf.resize(buf.len() * 2, 0);
             continue;
         }
-        vars.push((OsString::from(key), OsString::from(v.trim())));
+        if rc != 0 || result.is_null() || pwd.pw_dir.is_null() {
+            return None;
+        }
+        // SAFETY: pw_dir is a NUL-terminated string inside buf.
+        let dir = unsafe { CStr::from_ptr(pwd.pw_dir) }.to_bytes();
+        if dir.is_empty() {
+            return None;
+        }
+        return Some(PathBuf::from(OsStr::from_bytes(dir)));
+    }
+}
+
+// system_home_dir asks the shell for the FOLDERID_Profile known
+// folder.
+#[cfg(windows)]
+fn system_home_dir() -> Option<PathBuf> {
+    use std::ffi::c_void;
+    use std::os::windows::ffi::OsStringExt;
+
+    #[repr(C)]
+    struct Guid {
+        data1: u32,
+        data2: u16,
+        data3: u16,
+        data4: [u8; 8],
+    }
+
+    // {5E6C858F-0E22-4760-9AFE-EA3317B67173}
+    const FOLDERID_PROFILE: Guid = Guid {
+        data1: 0x5E6C_858F,
+        data2: 0x0E22,
+        data3: 0x4760,
+        data4: [0x9A, 0xFE, 0xEA, 0x33, 0x17, 0xB6, 0x71, 0x73],
+    };
+
+    #[link(name = "shell32")]
+    extern "system" {
+        fn SHGetKnownFolderPath(
+            rfid: *const Guid,
+            flags: u32,
+            token: *mut c_void,
+            path: *mut *mut u16,
+        ) -> i32;
+    }
+    #[link(name = "ole32")]
+    extern "system" {
+        fn CoTaskMemFree(ptr: *mut c_void);
+    }
+
+    let mut raw: *mut u16 = std::ptr::null_mut();
+    // SAFETY: raw is a valid out-pointer; the returned buffer is
+    // NUL-terminated and freed with CoTaskMemFree on every path,
+    // as the API requires even on failure.
+    unsafe {
+        let hr = SHGetKnownFolderPath(&FOLDERID_PROFILE, 0, std::ptr::null_mut(), &mut raw);
+        let path = if hr >= 0 && !raw.is_null() {
+            let len = (0..).take_while(|&i| *raw.add(i) != 0).count();
+            let wide = std::slice::from_raw_parts(raw, len);
+            (!wide.is_empty()).then(|| PathBuf::from(OsString::from_wide(wide)))
+        } else {
+            None
+        };
+        CoTaskMemFree(raw.cast());
+        path
     }
-    vars
 }
 
 fn resolve_home_dir_from_lookup<F>(mut lookup: F, prefer_userprofile: bool) -> Option<PathBuf>
@@ -369,17 +1170,24 @@ where
 
     let drive = get("HOMEDRIVE", &mut lookup)?;
     let path = get("HOMEPATH", &mut lookup)?;
-    let mut combined = OsString::from(drive);
+    let mut combined = drive;
     combined.push(path);
     Some(PathBuf::from(combined))
 }
 
-fn save_sidecar(app: &App, child: CommandChild) -> Result<(), DynError> {
+fn save_sidecar(app: &AppHandle, child: ProcessTree) -> Result<(), DesktopError> {
+    if let Some(path) = orphans::pid_file() {
+        if let Err(err) = orphans::record(&path, child.pid()) {
+            eprintln!("[agentsview] failed to record sidecar pid: {err}");
+        }
+    }
     let state = app.state::<SidecarState>();
     let mut guard = state
         .child
         .lock()
-        .map_err(|_| io::Error::other("sidecar state lock poisoned"))?;
+        .map_err(|_| DesktopError::StatePoisoned {
+            what: "sidecar state",
+        })?;
     *guard = Some(child);
     Ok(())
 }
@@ -387,11 +1195,43 @@ fn save_sidecar(app: &App, child: CommandChild) -> Result<(), DynError> {
 fn save_sidecar_port(app: &AppHandle, port: u16) {
     let state = app.state::<SidecarState>();
     set_sidecar_port(&state, Some(port));
+    update_instance_lock_port(app, Some(port));
 }
 
 fn clear_sidecar_port(app: &AppHandle) {
     let state = app.state::<SidecarState>();
     set_sidecar_port(&state, None);
+    update_instance_lock_port(app, None);
+}
+
+pub(crate) fn current_sidecar_port(app: &AppHandle) -> Option<u16> {
+    app.state::<SidecarState>()
+        .backend_port
+        .lock()
+        .ok()
+        .and_then(|guard| *guard)
+}
+
+// sidecar_socket is the Unix socket the sidecar serves on when
+// sidecar_socket is set.
+#[cfg(desktop)]
+pub(crate) fn sidecar_socket(app: &AppHandle) -> Option<PathBuf> {
+    #[cfg(unix)]
+    if let Ok(proxy) = app.state::<SidecarState>().proxy.lock() {
+        return proxy.as_ref().map(|proxy| proxy.socket().to_path_buf());
+    }
+    #[cfg(not(unix))]
+    let _ = app;
+    None
+}
+
+#[cfg(desktop)]
+fn current_sidecar_pid(app: &AppHandle) -> Option<u32> {
+    app.state::<SidecarState>()
+        .child
+        .lock()
+        .ok()
+        .and_then(|guard| guard.as_ref().map(ProcessTree::pid))
 }
 
 fn set_sidecar_port(state: &SidecarState, port: Option<u16>) {
@@ -405,40 +1245,66 @@ fn handle_sidecar_terminated(state: &SidecarState, startup_handled: &AtomicBool)
     !startup_handled.swap(true, Ordering::SeqCst)
 }
 
-fn forward_sidecar_logs(mut rx: CommandRx, window: WebviewWindow) {
+fn forward_sidecar_logs(
+    mut rx: CommandRx,
+    window: WebviewWindow,
+    attempt: u64,
+    requested_port: u16,
+) {
     let startup_handled = Arc::new(AtomicBool::new(false));
     let timeout_window = window.clone();
     let timeout_state = startup_handled.clone();
+    let shutdown = window.state::<ShutdownSignal>().inner().clone();
     thread::spawn(move || {
-        thread::sleep(READY_TIMEOUT);
+        if shutdown.wait(READY_TIMEOUT) {
+            return;
+        }
         if !timeout_state.load(Ordering::SeqCst) {
-            let _ = timeout_window.eval(
-                "document.getElementById('status').textContent = 'AgentsView backend did not become ready in time.';",
+            show_startup_error(
+                &timeout_window,
+                &DesktopError::Readiness {
+                    timeout: READY_TIMEOUT,
+                },
+                attempt,
             );
         }
     });
 
     tauri::async_runtime::spawn(async move {
         let mut stdout_buffer = String::new();
+        let mut bind_failure = None;
+        let output = window.state::<BackendOutput>().inner();
         while let Some(event) = rx.recv().await {
             match event {
                 CommandEvent::Stdout(chunk_bytes) => {
                     let chunk = String::from_utf8_lossy(&chunk_bytes);
                     eprintln!("[agentsview] {}", chunk.trim_end());
+                    output.push(attempt, Stream::Stdout, &chunk);
                     if !startup_handled.load(Ordering::SeqCst) {
-                        if let Some(port) = parse_listening_port_from_stdout_buffer(
+                        if let Some(startup) = parse_listening_port_from_stdout_buffer(
                             &mut stdout_buffer,
                             chunk.as_ref(),
                         ) {
-                            save_sidecar_port(&window.app_handle(), port);
+                            if startup.port() != requested_port {
+                                eprintln!(
+                                    "[agentsview] port {requested_port} was taken before the backend bound it; it is listening on {} instead",
+                                    startup.port()
+                                );
+                            }
+                            save_sidecar_port(window.app_handle(), startup.port());
+                            ports::remember(startup.port());
                             startup_handled.store(true, Ordering::SeqCst);
-                            redirect_when_ready(window.clone(), port);
+                            redirect_when_ready(window.clone(), startup, attempt);
                         }
                     }
                 }
                 CommandEvent::Stderr(line_bytes) => {
                     let line = String::from_utf8_lossy(&line_bytes);
                     eprintln!("[agentsview:stderr] {}", line.trim_end());
+                    output.push(attempt, Stream::Stderr, &line);
+                    if !startup_handled.load(Ordering::SeqCst) {
+                        bind_failure = bind_failure.or_else(|| port_conflict::bind_failure(&line));
+                    }
                 }
                 CommandEvent::Terminated(payload) => {
                     eprintln!(
@@ -446,10 +1312,22 @@ fn forward_sidecar_logs(mut rx: CommandRx, window: WebviewWindow) {
                         payload.code, payload.signal
                     );
                     let state = window.app_handle().state::<SidecarState>();
-                    if handle_sidecar_terminated(&state, startup_handled.as_ref()) {
-                        let _ = window.eval(
-                            "document.getElementById('status').textContent = 'AgentsView backend exited before startup completed.';",
+                    let during_startup =
+                        handle_sidecar_terminated(&state, startup_handled.as_ref());
+                    if window.state::<ShutdownSignal>().is_triggered() {
+                        break;
+                    }
+                    if let Some(port) = bind_failure.filter(|_| during_startup) {
+                        port_conflict::report(&window, port, attempt);
+                    } else {
+                        let message = crash::message(
+                            payload.code,
+                            payload.signal,
+                            during_startup,
+                            output,
+                            attempt,
                         );
+                        show_sidecar_crash(&window, message, attempt);
                     }
                     break;
                 }
@@ -462,47 +1340,299 @@ fn forward_sidecar_logs(mut rx: CommandRx, window: WebviewWindow) {
     });
 }
 
-fn main_window(app: &App) -> Result<WebviewWindow, DynError> {
-    app.get_webview_window("main")
-        .ok_or_else(|| io::Error::other("missing main window").into())
+// main_window creates the main window from its entry in
+// tauri.conf.json, which is marked create: false so the inspector
+// setting, user agent and download handler can be applied here. On
+// desktop it is shown once it is back where it was last closed,
+// rather than appearing at the default geometry first.
+fn main_window(app: &App) -> Result<WebviewWindow, DesktopError> {
+    let missing = DesktopError::MissingWindow { label: "main" };
+    let Some(config) = app.config().app.windows.iter().find(|w| w.label == "main") else {
+        return Err(missing);
+    };
+    let build = || {
+        let builder = WebviewWindowBuilder::from_config(app, config)?
+            .devtools(hardening::devtools_enabled())
+            .user_agent(hardening::user_agent())
+            .on_download(downloads::on_download);
+        #[cfg(desktop)]
+        let builder = builder.visible(false);
+        builder.build()
+    };
+    let window = build().map_err(|err| {
+        eprintln!("[agentsview] could not create the main window: {err}");
+        missing
+    })?;
+    #[cfg(desktop)]
+    {
+        zoom::apply_saved(&window);
+        window_state::restore(&window.as_ref().window());
+        if config.visible {
+            let _ = window.show();
+        }
+    }
+    Ok(window)
+}
+
+// show_startup_error reports a classified failure of backend
+// launch attempt in the startup failure dialog, whose Retry
+// restarts the backend. Failures of superseded attempts are only
+// logged.
+fn show_startup_error(window: &WebviewWindow, err: &DesktopError, attempt: u64) {
+    let app = window.app_handle();
+    if superseded(app, attempt) {
+        eprintln!("[agentsview] ignoring failure of a superseded launch: {err}");
+        return;
+    }
+    let retry_window = window.clone();
+    startup_dialog::show_startup_failure(app, err, sidecar_log_path(), move || {
+        restart_backend(retry_window.app_handle(), &retry_window)
+    });
+}
+
+// show_sidecar_crash reports a sidecar that exited on its own,
+// before or after startup. Exits of superseded launches are the
+// ones restarts cause, and are not reported.
+fn show_sidecar_crash(window: &WebviewWindow, message: String, attempt: u64) {
+    let app = window.app_handle();
+    if superseded(app, attempt) {
+        eprintln!("[agentsview] ignoring exit of a superseded launch");
+        return;
+    }
+    let restart_window = window.clone();
+    startup_dialog::show_sidecar_crash(app, message, move || {
+        let app = restart_window.app_handle();
+        if !superseded(app, attempt) {
+            restart_backend(app, &restart_window);
+        }
+    });
 }
 
-fn redirect_when_ready(window: WebviewWindow, port: u16) {
-    let target_url = format!("http://{HOST}:{port}");
+// show_connection_blocked reports a backend that is listening but
+// unreachable. Its Re-test button probes the same port again
+// instead of restarting the backend, so the user can retry right
+// after allowing the connection in their firewall.
+fn show_connection_blocked(window: &WebviewWindow, err: &DesktopError, port: u16, attempt: u64) {
+    let app = window.app_handle();
+    if superseded(app, attempt) {
+        eprintln!("[agentsview] ignoring failure of a superseded launch: {err}");
+        return;
+    }
+    let retest_window = window.clone();
+    startup_dialog::show_connection_blocked(app, err, sidecar_log_path(), move || {
+        retest_connection(retest_window, port, attempt)
+    });
+}
+
+fn superseded(app: &AppHandle, attempt: u64) -> bool {
+    app.state::<SidecarState>().attempt.load(Ordering::SeqCst) != attempt
+}
+
+// sidecar_log_path is the backend's debug log, scoped per user in
+// shared data dirs.
+fn sidecar_log_path() -> Option<PathBuf> {
+    let dir = data_dir()?;
+    let name = user::scoped_file_name(SIDECAR_LOG_FILE, &dir);
+    Some(dir.join(name))
+}
+
+fn set_splash_status(window: &WebviewWindow, text: &str) {
+    let script = format!("document.getElementById('status').textContent = {text:?};");
+    let _ = window.eval(&script);
+}
+
+// redirect_when_ready redirects once the backend on port is ready.
+// A port from the ready event already accepts connections, so one
+// probe confirms it. Otherwise it polls /api/v1/version.
+fn redirect_when_ready(window: WebviewWindow, startup: StartupPort, attempt: u64) {
+    let shutdown = window.state::<ShutdownSignal>().inner().clone();
 
     thread::spawn(move || {
-        if wait_for_server(port, READY_TIMEOUT) {
-            let script = format!("window.location.replace({target_url:?});");
-            let _ = window.eval(&script);
+        let port = startup.port();
+        if matches!(startup, StartupPort::Ready(_)) && probe_backend(port) == Probe::Ready {
+            finish_startup(&window, port, attempt, Probe::Ready, &shutdown);
             return;
         }
+        let probe = wait_for_server(port, READY_TIMEOUT, &shutdown);
+        finish_startup(&window, port, attempt, probe, &shutdown);
+    });
+}
 
-        let _ = window.eval(
-            "document.getElementById('status').textContent = 'AgentsView backend did not start within 30 seconds.';",
-        );
+// retest_connection probes the backend again after the user
+// adjusted their firewall, then redirects or reports as at startup.
+fn retest_connection(window: WebviewWindow, port: u16, attempt: u64) {
+    if superseded(window.app_handle(), attempt) {
+        return;
+    }
+    let shutdown = window.state::<ShutdownSignal>().inner().clone();
+    thread::spawn(move || {
+        let probe = wait_for_server(port, RETEST_TIMEOUT, &shutdown);
+        finish_startup(&window, port, attempt, probe, &shutdown);
     });
 }
 
+// finish_startup redirects the webview to a ready backend.
+// Otherwise it reports the failure: when no probe could even
+// connect although the sidecar reported listening, a firewall or
+// endpoint protection is the likely cause.
+fn finish_startup(
+    window: &WebviewWindow,
+    port: u16,
+    attempt: u64,
+    probe: Probe,
+    shutdown: &ShutdownSignal,
+) {
+    if shutdown.is_triggered() {
+        return;
+    }
+    let failure = match probe {
+        Probe::Ready => {
+            let keep_page = window
+                .state::<SidecarState>()
+                .keep_page
+                .swap(false, Ordering::SeqCst)
+                && window.url().is_ok_and(|url| {
+                    url.host_str().is_some_and(ports::is_loopback_host) && url.port() == Some(port)
+                });
+            if !keep_page {
+                let target_url = socket_proxy::login_url(local_url(port));
+                let script = format!("window.location.replace({target_url:?});");
+                let _ = window.eval(&script);
+            }
+            #[cfg(desktop)]
+            compat::spawn_check(window.app_handle().clone(), port);
+            keep_backend_alive(window.app_handle().clone(), port, attempt, shutdown);
+            return;
+        }
+        // A sidecar that exited also refuses connections; its exit
+        // has its own dialog.
+        _ if current_sidecar_port(window.app_handle()) != Some(port) => return,
+        Probe::Refused => "were refused",
+        Probe::TimedOut => "timed out",
+        Probe::Unhealthy => {
+            let err = DesktopError::Readiness {
+                timeout: READY_TIMEOUT,
+            };
+            show_startup_error(window, &err, attempt);
+            return;
+        }
+    };
+    eprintln!("[agentsview] backend on port {port} is unreachable: connections {failure}");
+    let err = DesktopError::ConnectionBlocked { port, failure };
+    show_connection_blocked(window, &err, port, attempt);
+}
+
+// keep_backend_alive probes the backend on a jittered schedule
+// after the webview has been redirected, logging when it stops or
+// resumes answering. The interval stretches on battery power and
+// while the app is idle so the shell does not cause frequent
+// wakeups. While the backend is away, as when something outside
+// the shell restarts it, the window shows a reconnecting banner
+// and reloads once it answers again. When it stops answering
+// altogether, the user is also offered a restart instead of a UI
+// that silently stops updating. The loop ends once the sidecar
+// port changes or the app shuts down.
+fn keep_backend_alive(app: AppHandle, port: u16, attempt: u64, shutdown: &ShutdownSignal) {
+    let Some(schedule) = KeepAliveSchedule::from_env() else {
+        return;
+    };
+    let mut watch = HealthWatch::default();
+    let mut link = connectivity::Link::default();
+    let origin = Url::parse(&local_url(port)).ok();
+    loop {
+        let idle = app.state::<ActivityState>().is_idle();
+        let delay = schedule.next_delay(
+            keepalive::on_battery_power(),
+            idle,
+            keepalive::jitter_sample(),
+        );
+        if shutdown.wait(link.next_delay(watch.next_delay(delay))) {
+            return;
+        }
+
+        if current_sidecar_port(&app) != Some(port) {
+            return;
+        }
+
+        let ready = backend_endpoint_ready(port);
+        let change = link.observe(ready);
+        if let (Some(change), Some(origin), Some(window)) =
+            (change, &origin, app.get_webview_window("main"))
+        {
+            connectivity::update(&window, change, origin);
+        }
+        match watch.observe(ready) {
+            HealthEvent::Healthy => {}
+            HealthEvent::Failed => eprintln!("[agentsview] backend keep-alive probe failed"),
+            HealthEvent::Recovered => {
+                eprintln!("[agentsview] backend keep-alive probe recovered")
+            }
+            HealthEvent::Unresponsive => {
+                eprintln!("[agentsview] backend on port {port} stopped responding");
+                let restart_app = app.clone();
+                startup_dialog::show_backend_unresponsive(&app, move || {
+                    if superseded(&restart_app, attempt) {
+                        return;
+                    }
+                    if let Some(window) = restart_app.get_webview_window("main") {
+                        restart_backend(&restart_app, &window);
+                    }
+                });
+            }
+        }
+    }
+}
+
+// parse_listening_port scrapes the port from the sidecar's
+// human-readable "listening at" line. It is the fallback for
+// backends that predate the ready event. The address may be IPv4
+// or IPv6 loopback.
 fn parse_listening_port(line: &str) -> Option<u16> {
-    let marker = format!("listening at http://{HOST}:");
-    let idx = line.find(marker.as_str())?;
-    let after = &line[(idx + marker.len())..];
-    let digits: String = after.chars().take_while(|ch| ch.is_ascii_digit()).collect();
-    if digits.is_empty() {
+    let marker = "listening at http://";
+    let idx = line.find(marker)?;
+    let authority = line[(idx + marker.len())..]
+        .split(|ch: char| ch.is_whitespace() || ch == '/')
+        .next()?;
+    let (host, port) = authority.rsplit_once(':')?;
+    if !ports::is_loopback_host(host) || !port.bytes().all(|byte| byte.is_ascii_digit()) {
         return None;
     }
-    digits.parse::<u16>().ok()
+    port.parse::<u16>().ok()
+}
+
+// StartupPort is how the sidecar reported its port. Ready comes
+// from the ready event, which is only written once the port
+// accepts connections; Listening is scraped from older sidecars'
+// log line, which may come before that.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StartupPort {
+    Ready(u16),
+    Listening(u16),
+}
+
+impl StartupPort {
+    fn port(self) -> u16 {
+        match self {
+            Self::Ready(port) | Self::Listening(port) => port,
+        }
+    }
 }
 
-fn parse_listening_port_from_stdout_buffer(buffer: &mut String, chunk: &str) -> Option<u16> {
+fn parse_listening_port_from_stdout_buffer(
+    buffer: &mut String,
+    chunk: &str,
+) -> Option<StartupPort> {
     buffer.push_str(chunk);
 
     let mut consumed = 0;
     while let Some(rel_idx) = buffer[consumed..].find('\n') {
         let end = consumed + rel_idx;
         let line = buffer[consumed..end].trim_end_matches('\r');
+        if let Some(port) = sidecar::parse_ready_event(line) {
+            return Some(StartupPort::Ready(port));
+        }
         if let Some(port) = parse_listening_port(line) {
-            return Some(port);
+            return Some(StartupPort::Listening(port));
         }
         consumed = end + 1;
     }
@@ -524,86 +1654,151 @@ fn stop_backend(app: &AppHandle) {
         if let Err(err) = child.kill() {
             eprintln!("[agentsview] failed to stop sidecar: {err}");
         }
+        if let Some(path) = orphans::pid_file() {
+            orphans::clear(&path);
+        }
     }
     clear_sidecar_port(app);
 }
 
-fn wait_for_server(port: u16, timeout: Duration) -> bool {
-    let deadline = Instant::now() + timeout;
-    while Instant::now() < deadline {
-        if backend_endpoint_ready(port) {
-            return true;
+// stop_backend_gracefully asks the sidecar to shut down and waits
+// up to EXIT_FLUSH_TIMEOUT for it before stopping whatever is left
+// of its process tree.
+fn stop_backend_gracefully(app: &AppHandle) {
+    if let Ok(guard) = app.state::<SidecarState>().child.lock() {
+        if let Some(child) = guard.as_ref() {
+            match child.terminate() {
+                Ok(()) => {
+                    if !child.wait_for_exit(EXIT_FLUSH_TIMEOUT) {
+                        eprintln!(
+                            "[agentsview] backend did not exit within {}s, stopping it",
+                            EXIT_FLUSH_TIMEOUT.as_secs()
+                        );
+                    }
+                }
+                Err(err) if err.kind() == std::io::ErrorKind::Unsupported => {}
+                Err(err) => eprintln!("[agentsview] failed to ask sidecar to exit: {err}"),
+            }
         }
-        thread::sleep(READY_POLL_INTERVAL);
     }
-    false
+    stop_backend(app);
 }
 
-fn backend_endpoint_ready(port: u16) -> bool {
-    let request =
-        format!("GET /api/v1/version HTTP/1.1\r\nHost: {HOST}:{port}\r\nConnection: close\r\n\r\n");
-    let response = match read_http_response(port, request.as_str()) {
-        Some(resp) => resp,
-        None => return false,
-    };
-    version_response_looks_valid(response.as_slice())
+// Probe is the outcome of a readiness probe. Refused and TimedOut
+// mean the TCP connection itself failed; Unhealthy means something
+// answered, just not a ready backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Probe {
+    Ready,
+    Refused,
+    TimedOut,
+    Unhealthy,
 }
 
-fn read_http_response(port: u16, request: &str) -> Option<Vec<u8>> {
-    let addr = SocketAddrV4::new(Ipv4Addr::LOCALHOST, port);
-    let mut stream = match TcpStream::connect_timeout(&addr.into(), Duration::from_millis(250)) {
-        Ok(stream) => stream,
-        Err(_) => return None,
-    };
-
-    let _ = stream.set_read_timeout(Some(Duration::from_millis(250)));
-    let _ = stream.set_write_timeout(Some(Duration::from_millis(250)));
-
-    if stream.write_all(request.as_bytes()).is_err() {
-        return None;
+impl Probe {
+    fn from_connect_error(kind: std::io::ErrorKind) -> Self {
+        use std::io::ErrorKind;
+        match kind {
+            ErrorKind::ConnectionRefused
+            | ErrorKind::ConnectionReset
+            | ErrorKind::PermissionDenied => Self::Refused,
+            ErrorKind::TimedOut | ErrorKind::WouldBlock => Self::TimedOut,
+            _ => Self::Unhealthy,
+        }
     }
 
-    let mut buf = Vec::with_capacity(4096);
-    if stream.read_to_end(&mut buf).is_err() {
-        return None;
+    // combine folds the probes of one wait into a verdict: any
+    // connection rules out a firewall block, and a timeout among
+    // refusals still points at packets being dropped.
+    fn combine(self, next: Self) -> Self {
+        match (self, next) {
+            (Self::Ready, _) | (_, Self::Ready) => Self::Ready,
+            (Self::Unhealthy, _) | (_, Self::Unhealthy) => Self::Unhealthy,
+            (Self::TimedOut, _) | (_, Self::TimedOut) => Self::TimedOut,
+            (Self::Refused, Self::Refused) => Self::Refused,
+        }
     }
-    if buf.is_empty() {
-        return None;
+}
+
+// wait_for_server probes the backend until it is ready or timeout
+// passes, always probing at least once, and returns the combined
+// verdict of every probe.
+fn wait_for_server(port: u16, timeout: Duration, shutdown: &ShutdownSignal) -> Probe {
+    let deadline = Instant::now() + timeout;
+    let mut verdict: Option<Probe> = None;
+    loop {
+        let probe = probe_backend(port);
+        let combined = verdict.map_or(probe, |verdict| verdict.combine(probe));
+        if combined == Probe::Ready || Instant::now() >= deadline {
+            return combined;
+        }
+        verdict = Some(combined);
+        if shutdown.wait(READY_POLL_INTERVAL) {
+            return combined;
+        }
     }
-    Some(buf)
 }
 
-fn version_response_looks_valid(response: &[u8]) -> bool {
-    if !(response.starts_with(b"HTTP/1.1 200") || response.starts_with(b"HTTP/1.0 200")) {
-        return false;
+// local_addr is where a local backend on port listens.
+pub(crate) fn local_addr(port: u16) -> SocketAddr {
+    SocketAddr::new(ports::loopback(), port)
+}
+
+// local_url is the address of a local backend on port as a URL.
+pub(crate) fn local_url(port: u16) -> String {
+    format!("http://{}", local_addr(port))
+}
+
+fn backend_endpoint_ready(port: u16) -> bool {
+    probe_backend(port) == Probe::Ready
+}
+
+fn probe_backend(port: u16) -> Probe {
+    let addr = local_addr(port);
+    let cookie = socket_proxy::cookie();
+    match http_client::get(
+        http_client::Route::Direct(addr),
+        &addr.to_string(),
+        "/api/v1/version",
+        PROBE_TIMEOUT,
+        None,
+        cookie.as_deref(),
+    ) {
+        Ok(response) if version_response_looks_valid(&response) => Probe::Ready,
+        Ok(_) => Probe::Unhealthy,
+        Err(http_client::Error::Connect(err)) => Probe::from_connect_error(err.kind()),
+        Err(_) => Probe::Unhealthy,
     }
-    let body = if let Some(idx) = response.windows(4).position(|w| w == b"\r\n\r\n") {
-        &response[(idx + 4)..]
-    } else if let Some(idx) = response.windows(2).position(|w| w == b"\n\n") {
-        &response[(idx + 2)..]
-    } else {
-        return false;
-    };
-    let body = String::from_utf8_lossy(body);
-    body.contains("\"version\"") && body.contains("\"commit\"") && body.contains("\"build_date\"")
+}
+
+// version_response_looks_valid checks that a /api/v1/version
+// response came from agentsview: a 200 whose JSON body has the
+// version, commit and build_date strings.
+fn version_response_looks_valid(response: &http_client::Response) -> bool {
+    response.status == 200
+        && response.json::<serde_json::Value>().is_some_and(|body| {
+            ["version", "commit", "build_date"]
+                .iter()
+                .all(|key| body.get(key).is_some_and(serde_json::Value::is_string))
+        })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashMap;
-    #[cfg(unix)]
-    use std::os::unix::ffi::OsStrExt;
-    #[cfg(unix)]
-    use std::os::unix::fs::PermissionsExt;
-    #[cfg(unix)]
-    use std::time::{SystemTime, UNIX_EPOCH};
+    use std::collections::{BTreeSet, HashMap};
+    use std::io::{Read, Write};
 
     #[test]
     fn parse_listening_port_extracts_backend_port() {
         let line = "agentsview dev listening at http://127.0.0.1:18080 (started in 1.2s)";
         assert_eq!(parse_listening_port(line), Some(18080));
         assert_eq!(parse_listening_port("unrelated line"), None);
+
+        let ipv6 = "agentsview dev listening at http://[::1]:18081 (started in 1.2s)";
+        assert_eq!(parse_listening_port(ipv6), Some(18081));
+        let lan = "agentsview dev listening at http://192.168.1.20:18082 (started in 1.2s)";
+        assert_eq!(parse_listening_port(lan), None);
     }
 
     #[test]
@@ -624,44 +1819,231 @@ mod tests {
         );
         assert_eq!(
             parse_listening_port_from_stdout_buffer(&mut buf, "080 (started in 1.2s)\n"),
-            Some(18080)
+            Some(StartupPort::Listening(18080))
+        );
+    }
+
+    #[test]
+    fn parse_listening_port_from_stdout_buffer_uses_sidecar_reported_port() {
+        let mut buf = String::new();
+        assert_eq!(
+            parse_listening_port_from_stdout_buffer(
+                &mut buf,
+                "Port 8080 in use, using 8081\nagentsview dev listening at http://127.0.0.1:8081 (started in 0.4s)\n",
+            ),
+            Some(StartupPort::Listening(8081))
+        );
+    }
+
+    #[test]
+    fn parse_listening_port_from_stdout_buffer_prefers_ready_event() {
+        let mut buf = String::new();
+        assert_eq!(
+            parse_listening_port_from_stdout_buffer(
+                &mut buf,
+                "{\"event\":\"ready\",\"host\":\"127.0.0.1\",\"port\":8082}\nagentsview 2.0 serving on 8082\n",
+            ),
+            Some(StartupPort::Ready(8082))
         );
     }
 
     #[test]
-    fn is_allowed_navigation_url_allows_local_only() {
+    fn navigation_allow_rule_allows_local_only() {
         let tauri_url = Url::parse("tauri://localhost/index.html").expect("valid tauri url");
-        assert!(is_allowed_navigation_url(&tauri_url, None));
+        assert_eq!(
+            navigation_allow_rule(&tauri_url, None, None),
+            Some(RULE_BUNDLED_TAURI)
+        );
 
         let local_backend = Url::parse("http://127.0.0.1:18080/").expect("valid localhost url");
-        assert!(is_allowed_navigation_url(&local_backend, Some(18080)));
-        assert!(!is_allowed_navigation_url(&local_backend, Some(19090)));
-        assert!(!is_allowed_navigation_url(&local_backend, None));
+        assert_eq!(
+            navigation_allow_rule(&local_backend, Some(18080), None),
+            Some(RULE_SIDECAR_PORT)
+        );
+        assert_eq!(
+            navigation_allow_rule(&local_backend, Some(19090), None),
+            None
+        );
+        assert_eq!(navigation_allow_rule(&local_backend, None, None), None);
+        let ipv6_backend = Url::parse("http://[::1]:18080/").expect("valid ipv6 url");
+        assert_eq!(
+            navigation_allow_rule(&ipv6_backend, Some(18080), None),
+            Some(RULE_SIDECAR_PORT)
+        );
 
         let remote = Url::parse("https://example.com/").expect("valid remote url");
-        assert!(!is_allowed_navigation_url(&remote, Some(18080)));
+        assert_eq!(navigation_allow_rule(&remote, Some(18080), None), None);
 
         let localhost_name =
             Url::parse("http://localhost:18080/").expect("valid localhost-name url");
-        assert!(!is_allowed_navigation_url(&localhost_name, Some(18080)));
+        assert_eq!(
+            navigation_allow_rule(&localhost_name, Some(18080), None),
+            None
+        );
+    }
+
+    #[test]
+    fn navigation_allow_rule_allows_attached_remote_origin() {
+        let origin = Url::parse("http://192.168.1.20:8080/").expect("valid remote origin");
+        let page = Url::parse("http://192.168.1.20:8080/sessions/abc").expect("valid remote url");
+        assert_eq!(
+            navigation_allow_rule(&page, None, Some(&origin)),
+            Some(RULE_REMOTE_ORIGIN)
+        );
+        assert_eq!(navigation_allow_rule(&page, None, None), None);
+
+        let other = Url::parse("http://192.168.1.21:8080/").expect("valid remote url");
+        assert_eq!(navigation_allow_rule(&other, None, Some(&origin)), None);
+
+        let android = Url::parse("http://tauri.localhost/index.html").expect("valid android url");
+        assert_eq!(
+            navigation_allow_rule(&android, None, None),
+            Some(RULE_BUNDLED_HTTP)
+        );
+
+        let tls_origin = Url::parse("https://agents.example.com/").expect("valid remote origin");
+        let tls_page =
+            Url::parse("https://agents.example.com/sessions/abc").expect("valid remote url");
+        assert_eq!(
+            navigation_allow_rule(&tls_page, None, Some(&tls_origin)),
+            Some(RULE_REMOTE_ORIGIN)
+        );
+        let downgraded =
+            Url::parse("http://agents.example.com/sessions/abc").expect("valid remote url");
+        assert_eq!(
+            navigation_allow_rule(&downgraded, None, Some(&tls_origin)),
+            None
+        );
+    }
+
+    #[test]
+    fn decide_navigation_covers_platform_origins_and_dangerous_schemes() {
+        use NavigationDecision::{Allow, Block, OpenExternally};
+
+        let decide = |url: &str, allowlisted: bool, kiosk: bool| {
+            let url = Url::parse(url).expect("valid url");
+            let external = ExternalUrlLists::default();
+            decide_navigation(&url, Some(18080), None, allowlisted, kiosk, &external)
+        };
+        let web = if cfg!(feature = "no-network") {
+            Block("no-network builds do not open web links")
+        } else {
+            OpenExternally
+        };
+        let matrix = [
+            // macOS and Linux bundled pages.
+            ("tauri://localhost/index.html", Allow(RULE_BUNDLED_TAURI)),
+            (
+                "tauri://localhost/splash.html?x=1",
+                Allow(RULE_BUNDLED_TAURI),
+            ),
+            (
+                "tauri://evil.example/index.html",
+                Block("the scheme is not allowed"),
+            ),
+            // Windows (WebView2) and Android bundled pages.
+            (
+                "http://tauri.localhost/index.html",
+                Allow(RULE_BUNDLED_HTTP),
+            ),
+            (
+                "https://tauri.localhost/index.html",
+                Allow(RULE_BUNDLED_HTTP),
+            ),
+            ("http://tauri.localhost:18081/", web),
+            ("http://tauri.localhost.evil.example/", web),
+            ("http://tauri.localhost@evil.example/", web),
+            // The sidecar.
+            ("http://127.0.0.1:18080/sessions", Allow(RULE_SIDECAR_PORT)),
+            ("http://[::1]:18080/", Allow(RULE_SIDECAR_PORT)),
+            ("https://127.0.0.1:18080/", web),
+            ("http://127.0.0.1:18081/", web),
+            ("http://localhost:18080/", web),
+            // Links for the OS.
+            ("https://example.com/docs", web),
+            ("mailto:team@example.com", OpenExternally),
+            // Dangerous and unknown schemes.
+            ("javascript:alert(1)", Block("script URLs are never loaded")),
+            ("JavaScript:alert(1)", Block("script URLs are never loaded")),
+            ("vbscript:msgbox(1)", Block("script URLs are never loaded")),
+            (
+                "data:text/html,<script>alert(1)</script>",
+                Block("data URLs are never loaded"),
+            ),
+            ("file:///etc/passwd", Block("local files are never loaded")),
+            (
+                "file://localhost/C:/Windows/win.ini",
+                Block("local files are never loaded"),
+            ),
+            (
+                "filesystem:http://127.0.0.1:18080/temporary/x",
+                Block("local files are never loaded"),
+            ),
+            (
+                "blob:http://127.0.0.1:18080/1234",
+                Block("the scheme is not allowed"),
+            ),
+            ("ftp://example.com/", Block("the scheme is not allowed")),
+        ];
+        for (url, expected) in matrix {
+            assert_eq!(decide(url, false, false), expected, "{url}");
+        }
+
+        assert_eq!(
+            decide("http://localhost:5173/", true, false),
+            Allow(RULE_ALLOWLIST)
+        );
+        assert_eq!(
+            decide("data:text/html,hi", true, false),
+            Block("data URLs are never loaded")
+        );
+        assert_eq!(
+            decide("https://example.com/", false, true),
+            Block("external links are disabled in kiosk mode")
+        );
+        assert_eq!(
+            decide("tauri://localhost/index.html", false, true),
+            Allow(RULE_BUNDLED_TAURI)
+        );
+
+        let long = Url::parse(&format!("data:text/plain,{}", "a".repeat(500))).expect("valid url");
+        assert_eq!(url_for_log(&long).len(), 203);
+
+        let line = |url: &str, decision| {
+            navigation_log_line(&Url::parse(url).expect("valid url"), decision)
+        };
+        let os = std::env::consts::OS;
+        assert_eq!(
+            line("tauri://localhost/index.html", Allow(RULE_BUNDLED_TAURI)),
+            format!("navigation allow tauri://localhost/index.html rule=\"bundled page (tauri://localhost)\" platform={os} platform_origin={BUNDLED_ORIGIN}")
+        );
+        assert!(line(
+            "http://tauri.localhost:18081/?token=s3cret",
+            Block("the external link lists do not allow this domain")
+        )
+        .starts_with(
+            "navigation block http://tauri.localhost:18081/ rule=\"the external link lists do not allow this domain\""
+        ));
     }
 
     #[test]
     fn is_allowed_external_open_url_limits_schemes() {
+        let open = ExternalUrlLists::default();
+        let network_allowed = !cfg!(feature = "no-network");
         let https = Url::parse("https://example.com").expect("valid https url");
-        assert!(is_allowed_external_open_url(&https));
+        assert_eq!(is_allowed_external_open_url(&https, &open), network_allowed);
 
         let http = Url::parse("http://example.com").expect("valid http url");
-        assert!(is_allowed_external_open_url(&http));
+        assert_eq!(is_allowed_external_open_url(&http, &open), network_allowed);
 
         let mailto = Url::parse("mailto:test@example.com").expect("valid mailto url");
-        assert!(is_allowed_external_open_url(&mailto));
+        assert!(is_allowed_external_open_url(&mailto, &open));
 
         let file = Url::parse("file:///tmp/foo").expect("valid file url");
-        assert!(!is_allowed_external_open_url(&file));
+        assert!(!is_allowed_external_open_url(&file, &open));
 
         let custom = Url::parse("custom-scheme://foo").expect("valid custom url");
-        assert!(!is_allowed_external_open_url(&custom));
+        assert!(!is_allowed_external_open_url(&custom, &open));
     }
 
     #[test]
@@ -706,92 +2088,76 @@ mod tests {
         assert!(!handle_sidecar_terminated(&state, &startup_handled));
     }
 
-    #[test]
-    fn shell_login_env_flag_matches_shell_compatibility() {
-        assert_eq!(shell_login_env_flag("/bin/sh"), "-c");
-        assert_eq!(shell_login_env_flag("/usr/bin/dash"), "-c");
-        assert_eq!(shell_login_env_flag("/opt/homebrew/bin/fish"), "-lc");
-        assert_eq!(shell_login_env_flag("/bin/bash"), "-lic");
-        assert_eq!(shell_login_env_flag("/bin/zsh"), "-lic");
-    }
-
     #[test]
     fn version_response_requires_identity_fields() {
-        let valid = b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n{\"version\":\"1.0.0\",\"commit\":\"abc\",\"build_date\":\"2026-01-01T00:00:00Z\"}";
-        assert!(version_response_looks_valid(valid));
-
-        let missing = b"HTTP/1.1 200 OK\r\n\r\n{\"version\":\"1.0.0\"}";
-        assert!(!version_response_looks_valid(missing));
-
-        let wrong_status = b"HTTP/1.1 404 Not Found\r\n\r\n{}";
-        assert!(!version_response_looks_valid(wrong_status));
+        let response = |status: u16, body: &str| http_client::Response {
+            status,
+            headers: Vec::new(),
+            body: body.as_bytes().to_vec(),
+        };
+        let valid = r#"{"version":"1.0.0","commit":"abc","build_date":"2026-01-01T00:00:00Z"}"#;
+        assert!(version_response_looks_valid(&response(200, valid)));
+        assert!(!version_response_looks_valid(&response(404, valid)));
+        assert!(!version_response_looks_valid(&response(
+            200,
+            r#"{"version":"1.0.0"}"#
+        )));
+        assert!(!version_response_looks_valid(&response(
+            200,
+            r#"{"version":1,"commit":"abc","build_date":"x"}"#
+        )));
+        assert!(!version_response_looks_valid(&response(
+            200,
+            "<html>\"version\" \"commit\" \"build_date\"</html>"
+        )));
     }
 
     #[test]
-    fn should_probe_login_shell_skips_windows_or_explicit_skip() {
-        assert!(should_probe_login_shell(None, false));
-        assert!(!should_probe_login_shell(Some(&OsString::from("1")), false));
-        assert!(!should_probe_login_shell(None, true));
+    fn probe_backend_recognizes_a_running_server() {
+        let response = b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n{\"version\":\"1.0.0\",\"commit\":\"abc\",\"build_date\":\"2026-01-01T00:00:00Z\"}";
+        let listener =
+            std::net::TcpListener::bind((ports::loopback(), 0)).expect("bind probe port");
+        let port = listener.local_addr().expect("probe port").port();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("accept probe");
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request);
+            let _ = stream.write_all(response);
+        });
+        assert_eq!(probe_backend(port), Probe::Ready);
+        server.join().expect("probe server");
     }
 
     #[test]
-    fn build_sidecar_env_applies_precedence_and_path_override() {
-        let merged = build_sidecar_env(
-            vec![
-                (OsString::from("PATH"), OsString::from("/bin")),
-                (OsString::from("HOME"), OsString::from("/base")),
-            ],
-            vec![(OsString::from("HOME"), OsString::from("/login"))],
-            vec![(OsString::from("HOME"), OsString::from("/desktop"))],
-            Some(OsString::from("/custom/path")),
-            false,
+    fn probe_verdict_only_blames_connections_that_never_opened() {
+        use std::io::ErrorKind;
+        assert_eq!(
+            Probe::from_connect_error(ErrorKind::ConnectionRefused),
+            Probe::Refused
         );
-        let map: HashMap<_, _> = merged.into_iter().collect();
         assert_eq!(
-            map.get(&OsString::from("HOME")),
-            Some(&OsString::from("/desktop"))
+            Probe::from_connect_error(ErrorKind::TimedOut),
+            Probe::TimedOut
         );
         assert_eq!(
-            map.get(&OsString::from("PATH")),
-            Some(&OsString::from("/custom/path"))
+            Probe::from_connect_error(ErrorKind::Other),
+            Probe::Unhealthy
         );
-    }
 
-    #[test]
-    fn build_sidecar_env_supports_case_insensitive_windows_keys() {
-        let merged = build_sidecar_env(
-            vec![(OsString::from("Path"), OsString::from("A"))],
-            vec![(OsString::from("PATH"), OsString::from("B"))],
-            vec![],
-            Some(OsString::from("C")),
-            true,
-        );
-        let map: HashMap<_, _> = merged.into_iter().collect();
-        assert_eq!(map.len(), 1);
-        assert_eq!(map.get(&OsString::from("PATH")), Some(&OsString::from("C")));
+        assert_eq!(Probe::Refused.combine(Probe::Refused), Probe::Refused);
+        assert_eq!(Probe::Refused.combine(Probe::TimedOut), Probe::TimedOut);
+        assert_eq!(Probe::TimedOut.combine(Probe::Unhealthy), Probe::Unhealthy);
+        assert_eq!(Probe::Unhealthy.combine(Probe::Ready), Probe::Ready);
     }
 
     #[test]
-    fn parse_desktop_env_content_ignores_comments_and_invalid_lines() {
-        let parsed = parse_desktop_env_content(
-            r#"
-            # comment
-            PATH=/custom/bin
-            BADLINE
-            =missingkey
-            FOO = bar
-            "#,
-        );
-        let map: HashMap<_, _> = parsed.into_iter().collect();
-        assert_eq!(
-            map.get(&OsString::from("PATH")),
-            Some(&OsString::from("/custom/bin"))
-        );
-        assert_eq!(
-            map.get(&OsString::from("FOO")),
-            Some(&OsString::from("bar"))
-        );
-        assert!(!map.contains_key(&OsString::from("BADLINE")));
+    fn wait_for_server_reports_refused_connections() {
+        let listener =
+            std::net::TcpListener::bind((ports::loopback(), 0)).expect("bind probe port");
+        let port = listener.local_addr().expect("probe port").port();
+        drop(listener);
+        let verdict = wait_for_server(port, Duration::ZERO, &ShutdownSignal::default());
+        assert_eq!(verdict, Probe::Refused);
     }
 
     #[test]
@@ -806,94 +2172,59 @@ mod tests {
         assert_eq!(resolved_windows, Some(PathBuf::from("C:\\Users\\a")));
     }
 
-    #[test]
-    fn parse_nul_env_tolerates_invalid_utf8_entries() {
-        let raw = b"PATH=/bin\0BROKEN=\xFF\xFE\0EMPTY=\0\0";
-        let parsed = parse_nul_env(raw);
-        let map: HashMap<_, _> = parsed.into_iter().collect();
-        assert!(map.contains_key(&OsString::from("PATH")));
-
-        #[cfg(unix)]
-        {
-            let broken = map
-                .get(&OsString::from("BROKEN"))
-                .expect("BROKEN key present");
-            assert_eq!(broken.as_os_str().as_bytes(), b"\xFF\xFE");
-        }
-    }
-
     #[cfg(unix)]
     #[test]
-    fn run_login_shell_env_handles_large_stdout() {
-        let stamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("valid clock")
-            .as_nanos();
-        let script_path = std::env::temp_dir().join(format!(
-            "agentsview-login-shell-{stamp}-{}.sh",
-            std::process::id()
-        ));
-        fs::write(&script_path, "#!/bin/sh\nhead -c 262144 /dev/zero\n")
-            .expect("write shell script");
-        let mut perms = fs::metadata(&script_path)
-            .expect("read shell script metadata")
-            .permissions();
-        perms.set_mode(0o700);
-        fs::set_permissions(&script_path, perms).expect("set executable permissions");
-
-        let output = run_login_shell_env(
-            script_path.to_str().expect("script path utf-8"),
-            Duration::from_secs(2),
-        );
-        let _ = fs::remove_file(&script_path);
-
-        let output = output.expect("expected shell output");
-        assert!(
-            output.len() >= 262_144,
-            "expected at least 262144 bytes, got {}",
-            output.len()
-        );
-    }
-
-    #[cfg(unix)]
-    #[test]
-    fn run_login_shell_env_timeout_returns_when_stdout_fd_stays_open() {
-        let stamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("valid clock")
-            .as_nanos();
-        let script_path = std::env::temp_dir().join(format!(
-            "agentsview-login-shell-timeout-{stamp}-{}.sh",
-            std::process::id()
-        ));
-        fs::write(&script_path, "#!/bin/sh\n(sleep 2) &\nsleep 10\n").expect("write shell script");
-        let mut perms = fs::metadata(&script_path)
-            .expect("read shell script metadata")
-            .permissions();
-        perms.set_mode(0o700);
-        fs::set_permissions(&script_path, perms).expect("set executable permissions");
-
-        let started = Instant::now();
-        let output = run_login_shell_env(
-            script_path.to_str().expect("script path utf-8"),
-            Duration::from_millis(120),
-        );
-        let elapsed = started.elapsed();
-        let _ = fs::remove_file(&script_path);
-
-        assert!(output.is_none(), "timeout path should return None");
-        assert!(
-            elapsed < Duration::from_secs(1),
-            "timeout path took too long: {elapsed:?}"
-        );
+    fn system_home_dir_reads_passwd_entry() {
+        let home = system_home_dir().expect("passwd entry for current user");
+        assert!(home.is_absolute(), "expected absolute path, got {home:?}");
     }
 
+    // The app manifest in build.rs must list every command in the
+    // invoke handler, or Tauri refuses it in every window, and the
+    // capabilities may only grant listed commands.
     #[test]
-    fn run_login_shell_env_returns_none_when_shell_missing() {
-        let output = run_login_shell_env(
-            "agentsview-missing-shell-binary",
-            Duration::from_millis(100),
-        );
-        assert!(output.is_none(), "missing shell should return None");
+    fn app_commands_match_the_handler_and_capabilities() {
+        let build = include_str!("../build.rs");
+        let listed: BTreeSet<&str> = build
+            .split_once("const APP_COMMANDS")
+            .and_then(|(_, rest)| rest.split_once("];"))
+            .expect("build.rs should define APP_COMMANDS")
+            .0
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix('"')?.strip_suffix("\","))
+            .collect();
+
+        let source = include_str!("lib.rs");
+        let handler = source
+            .split_once(".invoke_handler(tauri::generate_handler![")
+            .and_then(|(_, rest)| rest.split_once("])"))
+            .expect("lib.rs should register an invoke handler")
+            .0;
+        let registered: BTreeSet<&str> = handler
+            .split(',')
+            .filter_map(|entry| entry.trim().rsplit_once("::"))
+            .map(|(_, command)| command.trim())
+            .collect();
+        assert_eq!(listed, registered);
+
+        for capability in [
+            include_str!("../capabilities/default.json"),
+            include_str!("../capabilities/backend.json"),
+        ] {
+            let capability: serde_json::Value =
+                serde_json::from_str(capability).expect("capability should be valid JSON");
+            let granted = capability["permissions"]
+                .as_array()
+                .expect("capability should list permissions")
+                .iter()
+                .filter_map(|permission| permission.as_str()?.strip_prefix("allow-"));
+            for command in granted {
+                assert!(
+                    listed.contains(command.replace('-', "_").as_str()),
+                    "{} grants unknown command {command}",
+                    capability["identifier"]
+                );
+            }
+        }
     }
 }
@@ -0,0 +1,138 @@
+use std::ffi::OsString;
+
+use crate::settings::{self, Decorations, DesktopSettings};
+
+// APP_ID matches the bundle identifier, which names the installed
+// .desktop file; Wayland compositors look the icon up by it.
+const APP_ID: &str = "io.agentsview.desktop";
+const APP_NAME: &str = "AgentsView";
+
+// WindowOptions are the Linux window integration choices, read
+// before GTK starts since none can change afterwards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct WindowOptions {
+    app_id: String,
+    decorations: Option<Decorations>,
+    force_x11: bool,
+}
+
+// apply sets the program name GTK uses as the Wayland app_id and
+// X11 WM_CLASS, and the GTK environment for the decorations and
+// X11 choices. It must run before the Tauri builder initializes
+// GTK. AGENTSVIEW_DESKTOP_APP_ID, AGENTSVIEW_DESKTOP_DECORATIONS,
+// and AGENTSVIEW_DESKTOP_FORCE_X11 override the linux_app_id,
+// linux_decorations, and linux_force_x11 settings.
+pub(crate) fn apply() {
+    let lookup = |key: &str| std::env::var_os(key);
+    let options = options_from(lookup, &settings::load_settings());
+    glib::set_prgname(Some(options.app_id.as_str()));
+    glib::set_application_name(APP_NAME);
+    for (key, value) in gtk_env(&options, lookup) {
+        std::env::set_var(key, value);
+    }
+}
+
+fn options_from<F>(lookup: F, settings: &DesktopSettings) -> WindowOptions
+where
+    F: Fn(&str) -> Option<OsString>,
+{
+    let app_id = lookup("AGENTSVIEW_DESKTOP_APP_ID")
+        .map(|value| value.to_string_lossy().trim().to_string())
+        .or_else(|| settings.linux_app_id.clone())
+        .filter(|id| !id.is_empty())
+        .unwrap_or_else(|| APP_ID.to_string());
+
+    let decorations = match lookup("AGENTSVIEW_DESKTOP_DECORATIONS") {
+        Some(raw) => match raw.to_string_lossy().trim().to_ascii_lowercase().as_str() {
+            "client" => Some(Decorations::Client),
+            "server" => Some(Decorations::Server),
+            _ => {
+                eprintln!(
+                    "[agentsview] ignoring invalid AGENTSVIEW_DESKTOP_DECORATIONS value: {}",
+                    raw.to_string_lossy()
+                );
+                settings.linux_decorations
+            }
+        },
+        None => settings.linux_decorations,
+    };
+
+    let force_x11 = match lookup("AGENTSVIEW_DESKTOP_FORCE_X11") {
+        Some(value) => crate::mock::is_truthy_flag(Some(value.as_os_str())),
+        None => settings.linux_force_x11,
+    };
+
+    WindowOptions {
+        app_id,
+        decorations,
+        force_x11,
+    }
+}
+
+// gtk_env is the GTK environment for options. Forcing X11 needs
+// an X server, so it is skipped with a warning when DISPLAY is
+// unset; a GDK_BACKEND the user set is left alone.
+fn gtk_env<F>(options: &WindowOptions, lookup: F) -> Vec<(&'static str, &'static str)>
+where
+    F: Fn(&str) -> Option<OsString>,
+{
+    let mut env = Vec::new();
+    match options.decorations {
+        Some(Decorations::Client) => env.push(("GTK_CSD", "1")),
+        Some(Decorations::Server) => env.push(("GTK_CSD", "0")),
+        None => {}
+    }
+    if options.force_x11 && lookup("GDK_BACKEND").is_none() {
+        if lookup("DISPLAY").is_some_and(|display| !display.is_empty()) {
+            env.push(("GDK_BACKEND", "x11"));
+        } else {
+            eprintln!("[agentsview] not forcing X11: DISPLAY is not set");
+        }
+    }
+    env
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options_from_env_overrides_settings() {
+        let settings = DesktopSettings {
+            linux_app_id: Some("com.example.agentsview".to_string()),
+            linux_decorations: Some(Decorations::Server),
+            ..DesktopSettings::default()
+        };
+        let options = options_from(|_| None, &settings);
+        assert_eq!(options.app_id, "com.example.agentsview");
+        assert_eq!(options.decorations, Some(Decorations::Server));
+        assert!(!options.force_x11);
+
+        let env = |key: &str| match key {
+            "AGENTSVIEW_DESKTOP_DECORATIONS" => Some(OsString::from("Client")),
+            "AGENTSVIEW_DESKTOP_FORCE_X11" => Some(OsString::from("1")),
+            _ => None,
+        };
+        let options = options_from(env, &DesktopSettings::default());
+        assert_eq!(options.app_id, APP_ID);
+        assert_eq!(options.decorations, Some(Decorations::Client));
+        assert!(options.force_x11);
+    }
+
+    #[test]
+    fn gtk_env_forces_x11_only_with_a_display() {
+        let options = WindowOptions {
+            app_id: APP_ID.to_string(),
+            decorations: Some(Decorations::Client),
+            force_x11: true,
+        };
+        let with_display = |key: &str| (key == "DISPLAY").then(|| OsString::from(":0"));
+        assert_eq!(
+            gtk_env(&options, with_display),
+            [("GTK_CSD", "1"), ("GDK_BACKEND", "x11")]
+        );
+        assert_eq!(gtk_env(&options, |_| None), [("GTK_CSD", "1")]);
+        let user_backend = |key: &str| (key == "GDK_BACKEND").then(|| OsString::from("wayland"));
+        assert_eq!(gtk_env(&options, user_backend), [("GTK_CSD", "1")]);
+    }
+}
@@ -0,0 +1,422 @@
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use tauri::{AppHandle, Manager};
+
+use crate::backend_client::{encode_query_component, BackendClient};
+use crate::error::DesktopError;
+use crate::keepalive;
+use crate::notify;
+use crate::shutdown::ShutdownSignal;
+use crate::timestamps;
+use crate::tray;
+
+// LIVE_WINDOW_SECS matches the frontend's "recently active"
+// window: a session with activity this recent is treated as still
+// running.
+const LIVE_WINDOW_SECS: u64 = 10 * 60;
+const CANDIDATE_LIMIT: usize = 10;
+const RECENT_MESSAGES: usize = 20;
+// SETTLE_SECS is how long a session must stay quiet after an
+// assistant reply without tool calls before the turn counts as
+// finished; a reply and the tool call after it can be synced
+// separately.
+const SETTLE_SECS: u64 = 15;
+// TICK is how often the tray text is redrawn while a turn runs.
+// The backend is asked again every REFRESH, or BATTERY_REFRESH on
+// battery power.
+const TICK: Duration = Duration::from_secs(1);
+const REFRESH: Duration = Duration::from_secs(5);
+const BATTERY_REFRESH: Duration = Duration::from_secs(15);
+// IDLE_WAIT_MAX caps the backoff between checks while no turn
+// runs. A backend change event ends the wait early; the cap covers
+// backends without the event stream.
+const IDLE_WAIT_MAX: Duration = Duration::from_secs(120);
+const EVENTS_PATH: &str = "/api/v1/events";
+// EVENTS_IDLE is how long the event stream may stay silent before
+// it is reopened; the backend sends a heartbeat every 30 seconds.
+const EVENTS_IDLE: Duration = Duration::from_secs(75);
+const EVENTS_RETRY_MAX: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct SessionSummary {
+    pub(crate) id: String,
+    #[serde(default)]
+    pub(crate) project: String,
+    #[serde(default)]
+    pub(crate) first_message: Option<String>,
+    #[serde(default)]
+    pub(crate) parent_session_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SessionPage {
+    #[serde(default)]
+    sessions: Vec<SessionSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageSummary {
+    #[serde(default)]
+    ordinal: u64,
+    role: String,
+    #[serde(default)]
+    timestamp: String,
+    #[serde(default)]
+    has_tool_use: bool,
+}
+
+#[derive(Deserialize)]
+struct MessagePage {
+    #[serde(default)]
+    messages: Vec<MessageSummary>,
+}
+
+// LiveSession is the most recently active session. While the agent
+// is working, turn_started_at is when its current turn began; the
+// turn starts with the latest user message and ends with a final
+// assistant reply.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LiveSession {
+    pub(crate) session_id: String,
+    pub(crate) project: String,
+    pub(crate) turn_started_at: Option<u64>,
+    // latest_ordinal is the ordinal of the newest message, where
+    // the user picks up once the agent is waiting for input.
+    pub(crate) latest_ordinal: Option<u64>,
+}
+
+// spawn_subscriber follows the live session in the background:
+// it keeps the tray's turn timer current and notifies the user
+// when the agent finishes a turn and waits for input. While no
+// turn runs it parks until the backend reports a change.
+pub(crate) fn spawn_subscriber(app: AppHandle) {
+    let shutdown = app.state::<ShutdownSignal>().inner().clone();
+    let changes = Changes::default();
+    spawn_change_listener(app.clone(), changes.clone(), shutdown.clone());
+    thread::spawn(move || {
+        let mut live: Option<LiveSession> = None;
+        let mut shown = false;
+        let mut refresh_at = Instant::now();
+        let mut idle_wait = refresh_interval();
+        loop {
+            if Instant::now() >= refresh_at {
+                let current = match BackendClient::for_app(&app) {
+                    Some(client) => match current_session(&client, timestamps::unix_now()) {
+                        Ok(current) => current,
+                        Err(err) => {
+                            eprintln!("[agentsview] live session refresh failed: {err}");
+                            live.clone()
+                        }
+                    },
+                    None => None,
+                };
+                if let Some(session) = started_waiting(live.as_ref(), current.as_ref()) {
+                    notify::notify_waiting(&app, session);
+                } else if current
+                    .as_ref()
+                    .is_some_and(|c| c.turn_started_at.is_some())
+                {
+                    notify::clear_pending(&app);
+                }
+                live = current;
+                refresh_at = Instant::now() + refresh_interval();
+            }
+            // The timer text changes every tick while a turn runs;
+            // otherwise the tray only needs clearing once and the
+            // loop backs off, waking early on a backend change.
+            let running = live.as_ref().filter(|l| l.turn_started_at.is_some());
+            if running.is_some() || shown {
+                tray::show_turn(&app, running, timestamps::unix_now());
+                shown = running.is_some();
+            }
+            let idle = running.is_none();
+            let wait = if idle {
+                let wait = idle_wait;
+                idle_wait = backoff(idle_wait, IDLE_WAIT_MAX);
+                refresh_at = Instant::now() + wait;
+                wait
+            } else {
+                idle_wait = refresh_interval();
+                TICK
+            };
+            let changed = changes.wait(wait);
+            if shutdown.is_triggered() {
+                return;
+            }
+            if changed && idle {
+                refresh_at = Instant::now();
+                idle_wait = refresh_interval();
+            }
+        }
+    });
+}
+
+fn refresh_interval() -> Duration {
+    if keepalive::on_battery_power() {
+        BATTERY_REFRESH
+    } else {
+        REFRESH
+    }
+}
+
+// backoff doubles wait, up to max.
+fn backoff(wait: Duration, max: Duration) -> Duration {
+    wait.saturating_mul(2).min(max)
+}
+
+// Changes carries backend change events from the listener thread
+// to the subscriber loop. Clones share the same flag.
+#[derive(Debug, Clone, Default)]
+struct Changes(Arc<(Mutex<bool>, Condvar)>);
+
+impl Changes {
+    fn notify(&self) {
+        let (lock, cvar) = &*self.0;
+        *lock.lock().unwrap_or_else(PoisonError::into_inner) = true;
+        cvar.notify_all();
+    }
+
+    // wait blocks for up to timeout and returns true if a change
+    // arrived before or during the wait, consuming it.
+    fn wait(&self, timeout: Duration) -> bool {
+        let (lock, cvar) = &*self.0;
+        let guard = lock.lock().unwrap_or_else(PoisonError::into_inner);
+        let (mut changed, _) = cvar
+            .wait_timeout_while(guard, timeout, |changed| !*changed)
+            .unwrap_or_else(PoisonError::into_inner);
+        std::mem::take(&mut *changed)
+    }
+}
+
+// spawn_change_listener follows the backend's event stream and
+// wakes the subscriber loop each time a sync writes sessions. It
+// reconnects when the stream drops, backing off while the backend
+// is down or predates the stream. It also wakes the loop on
+// shutdown so the loop can exit.
+fn spawn_change_listener(app: AppHandle, changes: Changes, shutdown: ShutdownSignal) {
+    thread::spawn(move || {
+        let mut retry = REFRESH;
+        loop {
+            if let Some(client) = BackendClient::for_app(&app) {
+                let result = client.watch_events(EVENTS_PATH, EVENTS_IDLE, &mut |event| {
+                    retry = REFRESH;
+                    if event == "sessions_changed" {
+                        changes.notify();
+                    }
+                });
+                match result {
+                    Ok(()) | Err(DesktopError::BackendUnsupported { .. }) => {}
+                    Err(err) => eprintln!("[agentsview] backend events failed: {err}"),
+                }
+            }
+            if shutdown.wait(retry) {
+                changes.notify();
+                return;
+            }
+            retry = backoff(retry, EVENTS_RETRY_MAX);
+        }
+    });
+}
+
+// latest_live_session returns the most recently active session
+// with activity within LIVE_WINDOW_SECS of now.
+pub(crate) fn latest_live_session(
+    client: &BackendClient,
+    now: u64,
+) -> Result<Option<SessionSummary>, DesktopError> {
+    let since = timestamps::rfc3339_utc(now.saturating_sub(LIVE_WINDOW_SECS));
+    let path = format!(
+        "/api/v1/sessions?active_since={}&limit={CANDIDATE_LIMIT}",
+        encode_query_component(&since)
+    );
+    let page = client.get_json_until::<SessionPage>(&path, &|| false)?;
+    Ok(page.and_then(|page| pick_live_session(page.sessions)))
+}
+
+// pick_live_session prefers the most recent top-level session, so
+// a running subagent is attributed to the session that spawned it.
+fn pick_live_session(sessions: Vec<SessionSummary>) -> Option<SessionSummary> {
+    let index = sessions
+        .iter()
+        .position(|session| session.parent_session_id.is_none())
+        .unwrap_or(0);
+    sessions.into_iter().nth(index)
+}
+
+fn current_session(client: &BackendClient, now: u64) -> Result<Option<LiveSession>, DesktopError> {
+    let Some(session) = latest_live_session(client, now)? else {
+        return Ok(None);
+    };
+    let path = format!(
+        "/api/v1/sessions/{}/messages?direction=desc&limit={RECENT_MESSAGES}",
+        encode_query_component(&session.id)
+    );
+    let messages = client
+        .get_json_until::<MessagePage>(&path, &|| false)?
+        .map(|page| page.messages)
+        .unwrap_or_default();
+    Ok(Some(LiveSession {
+        session_id: session.id,
+        project: session.project,
+        turn_started_at: turn_started_at(&messages, now),
+        latest_ordinal: messages.first().map(|message| message.ordinal),
+    }))
+}
+
+// started_waiting returns the session whose agent has just
+// finished its turn and is now waiting for the user.
+fn started_waiting<'a>(
+    previous: Option<&LiveSession>,
+    current: Option<&'a LiveSession>,
+) -> Option<&'a LiveSession> {
+    let (previous, current) = (previous?, current?);
+    let finished = previous.session_id == current.session_id
+        && previous.turn_started_at.is_some()
+        && current.turn_started_at.is_none();
+    finished.then_some(current)
+}
+
+// turn_started_at returns when the running turn began, given the
+// session's latest messages newest first, or None when the agent
+// has finished its reply. Turns longer than the fetched messages
+// report the oldest one, a lower bound.
+fn turn_started_at(messages: &[MessageSummary], now: u64) -> Option<u64> {
+    let newest = messages.first()?;
+    let newest_at = timestamps::parse_rfc3339(&newest.timestamp)?;
+    if newest.role == "assistant"
+        && !newest.has_tool_use
+        && now.saturating_sub(newest_at) >= SETTLE_SECS
+    {
+        return None;
+    }
+    let start = messages
+        .iter()
+        .find(|message| message.role == "user")
+        .or(messages.last())?;
+    timestamps::parse_rfc3339(&start.timestamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_773_480_413; // 2026-03-14T09:26:53Z
+
+    fn message(role: &str, timestamp: &str, has_tool_use: bool) -> MessageSummary {
+        MessageSummary {
+            ordinal: 0,
+            role: role.to_string(),
+            timestamp: timestamp.to_string(),
+            has_tool_use,
+        }
+    }
+
+    fn session(id: &str, parent: Option<&str>) -> SessionSummary {
+        SessionSummary {
+            id: id.to_string(),
+            project: "agentsview".to_string(),
+            first_message: None,
+            parent_session_id: parent.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn turn_started_at_tracks_running_turns() {
+        let running = [
+            message("assistant", "2026-03-14T09:26:50Z", true),
+            message("assistant", "2026-03-14T09:25:00Z", true),
+            message("user", "2026-03-14T09:20:53Z", false),
+            message("assistant", "2026-03-14T09:10:00Z", false),
+        ];
+        assert_eq!(turn_started_at(&running, NOW), Some(NOW - 360));
+
+        let just_replied = [
+            message("assistant", "2026-03-14T09:26:50Z", false),
+            message("user", "2026-03-14T09:20:53Z", false),
+        ];
+        assert_eq!(turn_started_at(&just_replied, NOW), Some(NOW - 360));
+
+        let finished = [
+            message("assistant", "2026-03-14T09:26:00Z", false),
+            message("user", "2026-03-14T09:20:53Z", false),
+        ];
+        assert_eq!(turn_started_at(&finished, NOW), None);
+        assert_eq!(turn_started_at(&[], NOW), None);
+    }
+
+    #[test]
+    fn turn_started_at_falls_back_to_oldest_message() {
+        let long_turn = [
+            message("assistant", "2026-03-14T09:26:50Z", true),
+            message("assistant", "2026-03-14T09:00:53Z", true),
+        ];
+        assert_eq!(turn_started_at(&long_turn, NOW), Some(NOW - 1_560));
+    }
+
+    #[test]
+    fn started_waiting_detects_finished_turns() {
+        let live = |id: &str, turn_started_at: Option<u64>| LiveSession {
+            session_id: id.to_string(),
+            project: "agentsview".to_string(),
+            turn_started_at,
+            latest_ordinal: Some(7),
+        };
+        let running = live("a", Some(NOW));
+        let waiting = live("a", None);
+
+        assert_eq!(
+            started_waiting(Some(&running), Some(&waiting)),
+            Some(&waiting)
+        );
+        assert_eq!(started_waiting(Some(&waiting), Some(&waiting)), None);
+        assert_eq!(started_waiting(Some(&running), Some(&running)), None);
+        assert_eq!(
+            started_waiting(Some(&running), Some(&live("b", None))),
+            None
+        );
+        assert_eq!(started_waiting(None, Some(&waiting)), None);
+        assert_eq!(started_waiting(Some(&running), None), None);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        assert_eq!(backoff(REFRESH, IDLE_WAIT_MAX), Duration::from_secs(10));
+        assert_eq!(
+            backoff(Duration::from_secs(80), IDLE_WAIT_MAX),
+            IDLE_WAIT_MAX
+        );
+        assert_eq!(backoff(IDLE_WAIT_MAX, IDLE_WAIT_MAX), IDLE_WAIT_MAX);
+    }
+
+    #[test]
+    fn changes_wake_a_parked_wait() {
+        let changes = Changes::default();
+        assert!(!changes.wait(Duration::from_millis(20)));
+
+        let notifier = changes.clone();
+        let started = Instant::now();
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            notifier.notify();
+        });
+        assert!(changes.wait(Duration::from_secs(10)));
+        assert!(started.elapsed() < Duration::from_secs(5));
+        handle.join().expect("notifier thread");
+
+        // The change was consumed by the first wait.
+        assert!(!changes.wait(Duration::from_millis(20)));
+    }
+
+    #[test]
+    fn pick_live_session_prefers_top_level_sessions() {
+        let picked = pick_live_session(vec![session("sub", Some("root")), session("root", None)]);
+        assert_eq!(picked.expect("session").id, "root");
+
+        let only_subagents = pick_live_session(vec![session("sub", Some("root"))]);
+        assert_eq!(only_subagents.expect("fallback").id, "sub");
+        assert!(pick_live_session(Vec::new()).is_none());
+    }
+}
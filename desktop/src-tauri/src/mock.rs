@@ -0,0 +1,330 @@
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use crate::error::DesktopError;
+use crate::ports;
+
+const MAX_REQUEST_HEAD: usize = 16 * 1024;
+
+// FIXTURES maps API paths to canned JSON bodies bundled into the
+// binary. They mirror the shapes returned by the Go backend so
+// the SPA renders without real session data.
+const FIXTURES: &[(&str, &str)] = &[
+    ("/api/v1/version", include_str!("../mock/version.json")),
+    ("/api/v1/sessions", include_str!("../mock/sessions.json")),
+    (
+        "/api/v1/sessions/mock-claude-1",
+        include_str!("../mock/session-mock-claude-1.json"),
+    ),
+    (
+        "/api/v1/sessions/mock-claude-1/messages",
+        include_str!("../mock/messages-mock-claude-1.json"),
+    ),
+    ("/api/v1/sessions/mock-claude-1/children", "[]"),
+    (
+        "/api/v1/sessions/mock-codex-1",
+        include_str!("../mock/session-mock-codex-1.json"),
+    ),
+    (
+        "/api/v1/sessions/mock-codex-1/messages",
+        include_str!("../mock/messages-mock-codex-1.json"),
+    ),
+    ("/api/v1/sessions/mock-codex-1/children", "[]"),
+    ("/api/v1/stats", include_str!("../mock/stats.json")),
+    ("/api/v1/projects", include_str!("../mock/projects.json")),
+    ("/api/v1/agents", include_str!("../mock/agents.json")),
+    ("/api/v1/machines", include_str!("../mock/machines.json")),
+    (
+        "/api/v1/sync/status",
+        include_str!("../mock/sync-status.json"),
+    ),
+];
+
+const PLACEHOLDER_INDEX: &str = include_str!("../mock/index.html");
+
+#[derive(Debug, PartialEq)]
+struct MockResponse {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+// mock_enabled reports whether AGENTSVIEW_DESKTOP_MOCK asks the
+// shell to skip the sidecar and serve fixtures instead.
+pub(crate) fn mock_enabled() -> bool {
+    is_truthy_flag(std::env::var_os("AGENTSVIEW_DESKTOP_MOCK").as_deref())
+}
+
+pub(crate) fn is_truthy_flag(value: Option<&std::ffi::OsStr>) -> bool {
+    let Some(value) = value else {
+        return false;
+    };
+    !matches!(
+        value.to_string_lossy().trim().to_ascii_lowercase().as_str(),
+        "" | "0" | "false" | "no" | "off"
+    )
+}
+
+// start_mock_backend binds an ephemeral loopback port and serves
+// the fixture API on a background thread. Non-API paths are served
+// from AGENTSVIEW_DESKTOP_MOCK_UI_DIR when set (e.g. a built
+// frontend/dist), otherwise from a bundled placeholder page.
+pub(crate) fn start_mock_backend() -> Result<u16, DesktopError> {
+    let unavailable = |err: io::Error| DesktopError::PortUnavailable {
+        port: 0,
+        reason: err.to_string(),
+    };
+    let listener = TcpListener::bind(SocketAddr::new(ports::loopback(), 0)).map_err(unavailable)?;
+    let addr = listener.local_addr().map_err(unavailable)?;
+    let ui_dir = std::env::var_os("AGENTSVIEW_DESKTOP_MOCK_UI_DIR")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from);
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+            let ui_dir = ui_dir.clone();
+            thread::spawn(move || {
+                if let Err(err) = handle_connection(stream, ui_dir.as_deref()) {
+                    eprintln!("[agentsview:mock] request failed: {err}");
+                }
+            });
+        }
+    });
+
+    eprintln!("[agentsview:mock] serving fixture API at http://{addr}");
+    Ok(addr.port())
+}
+
+fn handle_connection(mut stream: TcpStream, ui_dir: Option<&Path>) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let head = read_request_head(&mut stream)?;
+    let response = match parse_request_line(&head) {
+        Some((method, target)) => route(method, target, ui_dir),
+        None => json_error(400, "malformed request"),
+    };
+
+    let header = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        response.status,
+        status_text(response.status),
+        response.content_type,
+        response.body.len()
+    );
+    stream.write_all(header.as_bytes())?;
+    stream.write_all(&response.body)?;
+    stream.flush()
+}
+
+fn read_request_head(stream: &mut TcpStream) -> io::Result<String> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") && head.len() < MAX_REQUEST_HEAD {
+        let n = stream.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+    Ok(String::from_utf8_lossy(&head).into_owned())
+}
+
+fn parse_request_line(head: &str) -> Option<(&str, &str)> {
+    let line = head.lines().next()?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?;
+    let target = parts.next()?;
+    parts.next()?;
+    Some((method, target))
+}
+
+fn route(method: &str, target: &str, ui_dir: Option<&Path>) -> MockResponse {
+    let path = target.split(['?', '#']).next().unwrap_or_default();
+
+    if path.starts_with("/api/") {
+        if method != "GET" {
+            return json_error(405, "mock backend is read-only");
+        }
+        return match FIXTURES.iter().find(|(route, _)| *route == path) {
+            Some((_, body)) => MockResponse {
+                status: 200,
+                content_type: "application/json",
+                body: body.as_bytes().to_vec(),
+            },
+            None => json_error(404, "no mock fixture for this endpoint"),
+        };
+    }
+
+    if method != "GET" && method != "HEAD" {
+        return json_error(405, "method not allowed");
+    }
+
+    match ui_dir {
+        Some(dir) => serve_static(dir, path),
+        None => MockResponse {
+            status: 200,
+            content_type: "text/html; charset=utf-8",
+            body: PLACEHOLDER_INDEX.as_bytes().to_vec(),
+        },
+    }
+}
+
+// serve_static serves a file from the UI directory, falling back
+// to index.html for unknown paths so SPA routes resolve.
+fn serve_static(dir: &Path, path: &str) -> MockResponse {
+    let target = static_file_path(dir, path)
+        .filter(|candidate| candidate.is_file())
+        .unwrap_or_else(|| dir.join("index.html"));
+
+    match fs::read(&target) {
+        Ok(body) => MockResponse {
+            status: 200,
+            content_type: content_type_for(&target),
+            body,
+        },
+        Err(_) => json_error(404, "file not found"),
+    }
+}
+
+// static_file_path joins a request path onto the UI directory,
+// rejecting any component that could escape it.
+fn static_file_path(dir: &Path, path: &str) -> Option<PathBuf> {
+    let relative = Path::new(path.trim_start_matches('/'));
+    if relative.as_os_str().is_empty() {
+        return Some(dir.join("index.html"));
+    }
+    if relative
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_)))
+    {
+        return None;
+    }
+    Some(dir.join(relative))
+}
+
+fn content_type_for(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("js") | Some("mjs") => "text/javascript; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("json") => "application/json",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("ico") => "image/x-icon",
+        Some("woff2") => "font/woff2",
+        _ => "application/octet-stream",
+    }
+}
+
+fn json_error(status: u16, message: &str) -> MockResponse {
+    MockResponse {
+        status,
+        content_type: "application/json",
+        body: format!("{{\"error\":{message:?}}}").into_bytes(),
+    }
+}
+
+fn status_text(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+
+    #[test]
+    fn is_truthy_flag_treats_empty_and_false_values_as_off() {
+        assert!(!is_truthy_flag(None));
+        assert!(!is_truthy_flag(Some(OsStr::new(""))));
+        assert!(!is_truthy_flag(Some(OsStr::new("0"))));
+        assert!(!is_truthy_flag(Some(OsStr::new("false"))));
+        assert!(is_truthy_flag(Some(OsStr::new("1"))));
+        assert!(is_truthy_flag(Some(OsStr::new("yes"))));
+    }
+
+    #[test]
+    fn route_serves_fixtures_and_rejects_unknown_endpoints() {
+        let version = route("GET", "/api/v1/version", None);
+        assert_eq!(version.status, 200);
+        let body = String::from_utf8(version.body).expect("utf-8 body");
+        assert!(body.contains("\"version\"") && body.contains("\"build_date\""));
+
+        let sessions = route("GET", "/api/v1/sessions?limit=50", None);
+        assert_eq!(sessions.status, 200);
+        assert_eq!(sessions.content_type, "application/json");
+
+        assert_eq!(route("GET", "/api/v1/insights", None).status, 404);
+        assert_eq!(route("POST", "/api/v1/sync", None).status, 405);
+    }
+
+    #[test]
+    fn route_falls_back_to_placeholder_without_ui_dir() {
+        let index = route("GET", "/sessions/mock-claude-1", None);
+        assert_eq!(index.status, 200);
+        assert!(index.content_type.starts_with("text/html"));
+    }
+
+    #[test]
+    fn fixtures_are_valid_json() {
+        for (path, body) in FIXTURES {
+            let parsed = serde_json::from_str::<serde_json::Value>(body);
+            assert!(
+                parsed.is_ok(),
+                "fixture for {path} is not valid JSON: {}",
+                parsed.unwrap_err()
+            );
+        }
+    }
+
+    #[test]
+    fn static_file_path_rejects_traversal() {
+        let dir = Path::new("/srv/ui");
+        assert_eq!(
+            static_file_path(dir, "/"),
+            Some(PathBuf::from("/srv/ui/index.html"))
+        );
+        assert_eq!(
+            static_file_path(dir, "/assets/app.js"),
+            Some(PathBuf::from("/srv/ui/assets/app.js"))
+        );
+        assert_eq!(static_file_path(dir, "/../etc/passwd"), None);
+        assert_eq!(static_file_path(dir, "/assets/../../secret"), None);
+    }
+
+    #[test]
+    fn serve_static_falls_back_to_index_for_spa_routes() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        fs::write(dir.path().join("index.html"), "<html>spa</html>").expect("write index");
+        fs::write(dir.path().join("app.css"), "body{}").expect("write css");
+
+        let css = serve_static(dir.path(), "/app.css");
+        assert_eq!(css.content_type, "text/css; charset=utf-8");
+        assert_eq!(css.body, b"body{}");
+
+        let spa = serve_static(dir.path(), "/sessions/abc");
+        assert_eq!(spa.body, b"<html>spa</html>");
+    }
+
+    #[test]
+    fn parse_request_line_requires_method_target_and_version() {
+        assert_eq!(
+            parse_request_line("GET /api/v1/stats HTTP/1.1\r\nHost: x\r\n\r\n"),
+            Some(("GET", "/api/v1/stats"))
+        );
+        assert_eq!(parse_request_line("GET\r\n\r\n"), None);
+        assert_eq!(parse_request_line(""), None);
+    }
+}
@@ -0,0 +1,363 @@
+use std::ffi::OsString;
+use std::io;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use tauri::{Url, Webview};
+
+use crate::env;
+use crate::error::DesktopError;
+use crate::ports;
+use crate::settings::{self, DesktopSettings};
+
+static ALLOWLIST: OnceLock<Vec<Url>> = OnceLock::new();
+
+// SENSITIVE_PARAMS are query parameters that commonly carry
+// credentials: tokens, signatures of presigned URLs and session
+// ids. Names ending in SENSITIVE_SUFFIXES count too.
+const SENSITIVE_PARAMS: &[&str] = &[
+    "access_key",
+    "access_token",
+    "agentsview_proxy",
+    "api_key",
+    "apikey",
+    "auth",
+    "code",
+    "id_token",
+    "jsessionid",
+    "password",
+    "phpsessid",
+    "refresh_token",
+    "secret",
+    "session",
+    "session_id",
+    "sessionid",
+    "sid",
+    "sig",
+    "signature",
+    "token",
+    "x-amz-credential",
+    "x-amz-security-token",
+    "x-amz-signature",
+    "x-goog-credential",
+    "x-goog-signature",
+];
+const SENSITIVE_SUFFIXES: &[&str] = &["_token", "-token", "_secret", "_signature"];
+
+// allowlist returns the extra origins the navigation guard accepts
+// besides the backend, such as a frontend dev server or a second
+// agentsview server: the allowed_origins setting plus the comma or
+// space separated AGENTSVIEW_DESKTOP_ALLOWED_ORIGINS, from
+// desktop.env or the app's environment. Invalid entries are logged
+// and skipped.
+pub(crate) fn allowlist() -> &'static [Url] {
+    ALLOWLIST.get_or_init(|| {
+        let mut entries = settings::load_settings().allowed_origins;
+        if let Some(value) = allowed_origins_var() {
+            entries.extend(
+                value
+                    .to_string_lossy()
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|entry| !entry.is_empty())
+                    .map(str::to_string),
+            );
+        }
+        let mut origins = Vec::new();
+        for entry in entries {
+            match parse_origin(&entry) {
+                Ok(origin) if !origins.contains(&origin) => origins.push(origin),
+                Ok(_) => {}
+                Err(reason) => {
+                    eprintln!("[agentsview] ignoring allowed origin {entry:?}: {reason}")
+                }
+            }
+        }
+        origins
+    })
+}
+
+// is_allowlisted reports whether url is on an allowlisted origin.
+pub(crate) fn is_allowlisted(url: &Url) -> bool {
+    allowlist()
+        .iter()
+        .any(|origin| origin.origin() == url.origin())
+}
+
+// scrub_external_url removes credentials from a link before it is
+// handed to the OS: a user name and password, and the query
+// parameters SENSITIVE_PARAMS names. It returns the cleaned link
+// and what was removed, without the values, for the log.
+pub(crate) fn scrub_external_url(url: &Url) -> (Url, Vec<String>) {
+    let mut scrubbed = url.clone();
+    let mut removed = Vec::new();
+    if !url.username().is_empty() || url.password().is_some() {
+        let _ = scrubbed.set_username("");
+        let _ = scrubbed.set_password(None);
+        removed.push("user info".to_string());
+    }
+    if url.query().is_some() {
+        let (kept, dropped): (Vec<_>, Vec<_>) = url
+            .query_pairs()
+            .into_owned()
+            .partition(|(name, _)| !is_sensitive_param(name));
+        if !dropped.is_empty() {
+            removed.extend(dropped.into_iter().map(|(name, _)| name));
+            if kept.is_empty() {
+                scrubbed.set_query(None);
+            } else {
+                scrubbed.query_pairs_mut().clear().extend_pairs(kept);
+            }
+        }
+    }
+    (scrubbed, removed)
+}
+
+fn is_sensitive_param(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SENSITIVE_PARAMS.contains(&name.as_str())
+        || SENSITIVE_SUFFIXES
+            .iter()
+            .any(|suffix| name.ends_with(suffix))
+}
+
+// ExternalUrlLists are the user's domain rules for links opened
+// in the system browser. A domain matches itself and its
+// subdomains, and "*" matches every link. deny wins over allow; a
+// non-empty allow list admits only the domains on it. mailto links
+// are matched on the address's domain.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct ExternalUrlLists {
+    allow: Vec<String>,
+    deny: Vec<String>,
+}
+
+// ExternalList names one of the two lists in commands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ExternalList {
+    Allow,
+    Deny,
+}
+
+impl ExternalUrlLists {
+    pub(crate) fn from_settings(settings: &DesktopSettings) -> Self {
+        ExternalUrlLists {
+            allow: settings.external_allow_domains.clone(),
+            deny: settings.external_deny_domains.clone(),
+        }
+    }
+
+    // permits reports whether the lists let url open externally.
+    pub(crate) fn permits(&self, url: &Url) -> bool {
+        let domain = match url.scheme() {
+            "mailto" => url.path().rsplit_once('@').map(|(_, domain)| domain),
+            _ => url.host_str(),
+        }
+        .unwrap_or_default()
+        .trim_end_matches('.')
+        .to_ascii_lowercase();
+        let matches = |rules: &[String]| rules.iter().any(|rule| domain_matches(rule, &domain));
+        !matches(&self.deny) && (self.allow.is_empty() || matches(&self.allow))
+    }
+}
+
+fn domain_matches(rule: &str, domain: &str) -> bool {
+    rule == "*"
+        || domain == rule
+        || domain
+            .strip_suffix(rule)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+// normalize_domain turns a rule as typed, such as "*.Example.com.",
+// into the form stored in settings.
+fn normalize_domain(entry: &str) -> Result<String, &'static str> {
+    let entry = entry.trim().to_ascii_lowercase();
+    if entry == "*" {
+        return Ok(entry);
+    }
+    let domain = entry
+        .strip_prefix("*.")
+        .unwrap_or(&entry)
+        .trim_end_matches('.');
+    if domain.contains("://") || domain.contains('/') {
+        return Err("give a domain such as example.com, not a URL");
+    }
+    let valid = !domain.is_empty()
+        && domain.split('.').all(|label| {
+            !label.is_empty()
+                && label
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        });
+    if !valid {
+        return Err("not a domain name");
+    }
+    Ok(domain.to_string())
+}
+
+#[tauri::command]
+pub(crate) fn external_url_lists() -> ExternalUrlLists {
+    ExternalUrlLists::from_settings(&settings::load_settings())
+}
+
+// add_external_url_rule adds domain to list, taking it off the
+// other list so the two never disagree. The lists decide which
+// links leave the app, so only the shell's own pages may edit them.
+#[tauri::command]
+pub(crate) fn add_external_url_rule(
+    webview: Webview,
+    list: ExternalList,
+    domain: String,
+) -> Result<ExternalUrlLists, DesktopError> {
+    crate::require_bundled_page(&webview, "add_external_url_rule")?;
+    let domain = normalize_domain(&domain).map_err(|reason| {
+        io::Error::new(io::ErrorKind::InvalidInput, format!("{domain:?}: {reason}"))
+    })?;
+    let saved = settings::update_settings(|s| {
+        let (target, other) = match list {
+            ExternalList::Allow => (&mut s.external_allow_domains, &mut s.external_deny_domains),
+            ExternalList::Deny => (&mut s.external_deny_domains, &mut s.external_allow_domains),
+        };
+        other.retain(|rule| *rule != domain);
+        if !target.contains(&domain) {
+            target.push(domain);
+        }
+    })?;
+    Ok(ExternalUrlLists::from_settings(&saved))
+}
+
+#[tauri::command]
+pub(crate) fn remove_external_url_rule(
+    webview: Webview,
+    list: ExternalList,
+    domain: String,
+) -> Result<ExternalUrlLists, DesktopError> {
+    crate::require_bundled_page(&webview, "remove_external_url_rule")?;
+    let domain = normalize_domain(&domain).unwrap_or(domain);
+    let saved = settings::update_settings(|s| {
+        let target = match list {
+            ExternalList::Allow => &mut s.external_allow_domains,
+            ExternalList::Deny => &mut s.external_deny_domains,
+        };
+        target.retain(|rule| *rule != domain);
+    })?;
+    Ok(ExternalUrlLists::from_settings(&saved))
+}
+
+fn allowed_origins_var() -> Option<OsString> {
+    const KEY: &str = "AGENTSVIEW_DESKTOP_ALLOWED_ORIGINS";
+    env::read_desktop_env_file()
+        .into_iter()
+        .rev()
+        .find(|(key, _)| key == KEY)
+        .and_then(|(_, edit)| edit.set_value(|name| std::env::var_os(name)))
+        .or_else(|| std::env::var_os(KEY))
+}
+
+// parse_origin accepts an http(s) origin such as
+// http://localhost:5173, without a path, query or credentials.
+// no-network builds only accept loopback origins.
+fn parse_origin(entry: &str) -> Result<Url, &'static str> {
+    let url = Url::parse(entry.trim()).map_err(|_| "not a URL")?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err("only http and https origins are allowed");
+    }
+    let Some(host) = url.host_str() else {
+        return Err("no host");
+    };
+    if !url.username().is_empty() || url.password().is_some() {
+        return Err("origins cannot carry credentials");
+    }
+    if url.path() != "/" || url.query().is_some() || url.fragment().is_some() {
+        return Err("give only the scheme, host and port");
+    }
+    if cfg!(feature = "no-network") && host != "localhost" && !ports::is_loopback_host(host) {
+        return Err("no-network builds only allow loopback origins");
+    }
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_origin_accepts_bare_http_origins_only() {
+        let origin = parse_origin(" http://localhost:5173 ").expect("dev server origin");
+        let page = Url::parse("http://localhost:5173/sessions/abc").expect("valid url");
+        assert_eq!(origin.origin(), page.origin());
+        assert_eq!(
+            parse_origin("https://agents.example.com/").is_ok(),
+            !cfg!(feature = "no-network")
+        );
+
+        assert!(parse_origin("localhost:5173").is_err());
+        assert!(parse_origin("file:///etc/passwd").is_err());
+        assert!(parse_origin("https://agents.example.com/app").is_err());
+        assert!(parse_origin("https://user:pw@agents.example.com").is_err());
+        assert!(parse_origin("https://agents.example.com/?x=1").is_err());
+    }
+    #[test]
+    fn scrub_external_url_drops_credentials_only() {
+        let scrub = |link: &str| {
+            let (url, removed) = scrub_external_url(&Url::parse(link).expect("valid url"));
+            (url.to_string(), removed)
+        };
+        assert_eq!(
+            scrub("https://github.com/wesm/agentsview/issues?q=is%3Aopen"),
+            (
+                "https://github.com/wesm/agentsview/issues?q=is%3Aopen".to_string(),
+                vec![]
+            )
+        );
+        assert_eq!(
+            scrub("https://bucket.example/log.txt?X-Amz-Signature=abc&part=2&auth_token=x#l1"),
+            (
+                "https://bucket.example/log.txt?part=2#l1".to_string(),
+                vec!["X-Amz-Signature".to_string(), "auth_token".to_string()]
+            )
+        );
+        assert_eq!(
+            scrub("https://user:pw@example.com/?token=abc"),
+            (
+                "https://example.com/".to_string(),
+                vec!["user info".to_string(), "token".to_string()]
+            )
+        );
+        assert_eq!(
+            scrub("mailto:dev@example.com?subject=hi").1,
+            Vec::<String>::new()
+        );
+    }
+    #[test]
+    fn external_url_lists_deny_wins_and_allow_restricts() {
+        let url = |link: &str| Url::parse(link).expect("valid url");
+        let lists = |allow: &[&str], deny: &[&str]| ExternalUrlLists {
+            allow: allow.iter().map(|d| d.to_string()).collect(),
+            deny: deny.iter().map(|d| d.to_string()).collect(),
+        };
+
+        let open = ExternalUrlLists::default();
+        assert!(open.permits(&url("https://example.com/")));
+
+        let corporate = lists(&["github.com", "corp.example"], &["gist.github.com"]);
+        assert!(corporate.permits(&url("https://github.com/wesm/agentsview")));
+        assert!(corporate.permits(&url("https://wiki.corp.example/")));
+        assert!(corporate.permits(&url("mailto:help@corp.example")));
+        assert!(!corporate.permits(&url("https://gist.github.com/abc")));
+        assert!(!corporate.permits(&url("https://notgithub.com/")));
+        assert!(!corporate.permits(&url("mailto:someone@example.com")));
+
+        let locked = lists(&[], &["*"]);
+        assert!(!locked.permits(&url("https://github.com/")));
+
+        assert_eq!(
+            normalize_domain(" *.Corp.Example. "),
+            Ok("corp.example".to_string())
+        );
+        assert_eq!(normalize_domain("*"), Ok("*".to_string()));
+        assert!(normalize_domain("https://corp.example").is_err());
+        assert!(normalize_domain("corp..example").is_err());
+    }
+}
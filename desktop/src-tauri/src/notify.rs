@@ -0,0 +1,152 @@
+use std::sync::Mutex;
+
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Manager, Wry};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::backend_client::encode_query_component;
+use crate::i18n;
+use crate::live::LiveSession;
+use crate::palette;
+use crate::permissions::{self, Capability};
+
+// WAITING_SOUND sets agent-waiting notifications apart from the
+// system's default notification sound.
+#[cfg(target_os = "macos")]
+const WAITING_SOUND: &str = "Glass";
+#[cfg(windows)]
+const WAITING_SOUND: &str = "IM";
+#[cfg(not(any(target_os = "macos", windows)))]
+const WAITING_SOUND: &str = "message-new-instant";
+
+// NotifyState remembers the route of the last notification.
+// Desktop notifications report no clicks, so the route is opened
+// the next time the main window is focused, which is what clicking
+// the notification does.
+#[derive(Default)]
+pub(crate) struct NotifyState {
+    pending: Mutex<Option<String>>,
+}
+
+pub(crate) fn init_plugin() -> TauriPlugin<Wry> {
+    tauri_plugin_notification::init()
+}
+
+// notify_waiting tells the user that session's agent is waiting
+// for input, unless the main window is already in front.
+pub(crate) fn notify_waiting(app: &AppHandle, session: &LiveSession) {
+    let focused = app
+        .get_webview_window("main")
+        .is_some_and(|window| window.is_focused().unwrap_or(false));
+    if focused {
+        return;
+    }
+    post(
+        app,
+        Notice {
+            title: i18n::tr("notify-waiting-title"),
+            body: i18n::tr_args("notify-waiting-body", &[("project", &session.project)]),
+            sound: Some(WAITING_SOUND),
+            route: Some(waiting_route(session)),
+        },
+    );
+}
+
+// Notice is a notification that opens route, if any, in the main
+// window when the user follows it.
+pub(crate) struct Notice {
+    pub(crate) title: String,
+    pub(crate) body: String,
+    pub(crate) sound: Option<&'static str>,
+    pub(crate) route: Option<String>,
+}
+
+// post shows notice once the notification capability is granted.
+pub(crate) fn post(app: &AppHandle, notice: Notice) {
+    if notice.route.is_some() {
+        set_pending(app, notice.route);
+    }
+    let handle = app.clone();
+    permissions::ensure_capability(app, Capability::Notifications, move |granted| {
+        if !granted {
+            return;
+        }
+        let mut builder = handle
+            .notification()
+            .builder()
+            .title(notice.title)
+            .body(notice.body);
+        if let Some(sound) = notice.sound {
+            builder = builder.sound(sound);
+        }
+        if let Err(err) = builder.show() {
+            eprintln!("[agentsview] failed to show notification: {err}");
+        }
+    });
+}
+
+// open_pending navigates the main window to the route of the last
+// notification, reporting whether there was one.
+pub(crate) fn open_pending(app: &AppHandle) -> bool {
+    let Some(route) = take_pending(app) else {
+        return false;
+    };
+    if let Err(err) = palette::open_route(app, &route) {
+        eprintln!("[agentsview] failed to open waiting session: {err}");
+    }
+    true
+}
+
+// clear_pending forgets the last notification's route, e.g. once
+// a waiting agent is working again.
+pub(crate) fn clear_pending(app: &AppHandle) {
+    set_pending(app, None);
+}
+
+fn set_pending(app: &AppHandle, route: Option<String>) {
+    if let Ok(mut pending) = app.state::<NotifyState>().pending.lock() {
+        *pending = route;
+    }
+}
+
+fn take_pending(app: &AppHandle) -> Option<String> {
+    app.state::<NotifyState>()
+        .pending
+        .lock()
+        .ok()
+        .and_then(|mut pending| pending.take())
+}
+
+// waiting_route opens session scrolled to its newest message, the
+// agent's question or final reply.
+fn waiting_route(session: &LiveSession) -> String {
+    let mut route = format!(
+        "#/sessions?session={}",
+        encode_query_component(&session.session_id)
+    );
+    if let Some(ordinal) = session.latest_ordinal {
+        route.push_str(&format!("&msg={ordinal}"));
+    }
+    route
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn waiting_route_points_at_the_latest_message() {
+        let mut session = LiveSession {
+            session_id: "codex:a/b".to_string(),
+            project: "agentsview".to_string(),
+            turn_started_at: None,
+            latest_ordinal: Some(42),
+        };
+        assert_eq!(
+            waiting_route(&session),
+            "#/sessions?session=codex%3Aa%2Fb&msg=42"
+        );
+        session.latest_ordinal = None;
+        assert_eq!(waiting_route(&session), "#/sessions?session=codex%3Aa%2Fb");
+    }
+}
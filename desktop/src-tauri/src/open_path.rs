@@ -0,0 +1,277 @@
+use std::collections::BTreeSet;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::actions;
+use crate::error::DesktopError;
+
+// AGENT_LOG_ROOTS mirrors the backend's agent registry: the env
+// var overriding each agent's log directory, its config.json key
+// (empty for agents without one), and its default directories
+// relative to the home directory.
+const AGENT_LOG_ROOTS: &[(&str, &str, &[&str])] = &[
+    (
+        "CLAUDE_PROJECTS_DIR",
+        "claude_project_dirs",
+        &[".claude/projects"],
+    ),
+    (
+        "CODEX_SESSIONS_DIR",
+        "codex_sessions_dirs",
+        &[".codex/sessions"],
+    ),
+    ("COPILOT_DIR", "copilot_dirs", &[".copilot"]),
+    ("GEMINI_DIR", "gemini_dirs", &[".gemini"]),
+    ("OPENCODE_DIR", "opencode_dirs", &[".local/share/opencode"]),
+    ("CURSOR_PROJECTS_DIR", "", &[".cursor/projects"]),
+    ("AMP_DIR", "", &[".local/share/amp/threads"]),
+    (
+        "VSCODE_COPILOT_DIR",
+        "vscode_copilot_dirs",
+        &[
+            "AppData/Roaming/Code/User",
+            "AppData/Roaming/Code - Insiders/User",
+            "AppData/Roaming/VSCodium/User",
+            "Library/Application Support/Code/User",
+            "Library/Application Support/Code - Insiders/User",
+            "Library/Application Support/VSCodium/User",
+            ".config/Code/User",
+            ".config/Code - Insiders/User",
+            ".config/VSCodium/User",
+        ],
+    ),
+    ("OPENCLAW_DIR", "openclaw_dirs", &[".openclaw/agents"]),
+];
+const BACKEND_CONFIG_FILE: &str = "config.json";
+
+// open_path reveals a file referenced in a session's transcript
+// in the system file manager. It never launches the file, since a
+// transcript can name a script or an executable. Only files under
+// the session's recorded working directories or an agent log root
+// are revealed; both sides are canonicalized first, so neither
+// `..` nor a symlink can reach outside them. Relative paths
+// resolve against the session's first working directory.
+#[tauri::command]
+pub(crate) async fn open_path(
+    app: AppHandle,
+    session_id: String,
+    path: String,
+) -> Result<(), DesktopError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let session = actions::session_file(&app, Some(&session_id))?;
+        let recorded = session
+            .file_path
+            .as_deref()
+            .map(|transcript| recorded_dirs(Path::new(transcript)))
+            .unwrap_or_default();
+        let requested = match recorded.first() {
+            Some(cwd) => cwd.join(&path),
+            None => PathBuf::from(&path),
+        };
+        let mut roots = recorded;
+        roots.extend(agent_log_roots(
+            crate::resolve_home_dir().as_deref(),
+            &|key| std::env::var_os(key),
+            &read_backend_config(),
+        ));
+        let target = allowed_target(&requested, &roots)?;
+        reveal(&target)?;
+        Ok(())
+    })
+    .await
+    .map_err(|_| DesktopError::StatePoisoned { what: "open path" })?
+}
+
+// allowed_target canonicalizes path and returns it if it is a file
+// inside one of roots.
+fn allowed_target(path: &Path, roots: &[PathBuf]) -> Result<PathBuf, DesktopError> {
+    let not_allowed = || DesktopError::PathNotAllowed {
+        path: path.display().to_string(),
+    };
+    if !path.is_absolute() {
+        return Err(not_allowed());
+    }
+    let target = fs::canonicalize(path)?;
+    if !target.is_file() {
+        return Err(not_allowed());
+    }
+    let inside = roots
+        .iter()
+        .filter_map(|root| fs::canonicalize(root).ok())
+        .any(|root| target.starts_with(root));
+    if inside {
+        Ok(target)
+    } else {
+        Err(not_allowed())
+    }
+}
+
+// recorded_dirs lists the distinct working directories a
+// transcript records, in order of first appearance.
+fn recorded_dirs(transcript: &Path) -> Vec<PathBuf> {
+    let Ok(file) = fs::File::open(transcript) else {
+        return Vec::new();
+    };
+    let mut seen = BTreeSet::new();
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| actions::line_cwd(&line))
+        .filter(|dir| dir.is_absolute() && seen.insert(dir.clone()))
+        .collect()
+}
+
+fn read_backend_config() -> Value {
+    crate::data_dir()
+        .and_then(|dir| fs::read(dir.join(BACKEND_CONFIG_FILE)).ok())
+        .and_then(|content| serde_json::from_slice(&content).ok())
+        .unwrap_or(Value::Null)
+}
+
+// agent_log_roots lists every directory the backend may read agent
+// logs from: the defaults, env var overrides, and directories
+// configured in the backend's config.json.
+fn agent_log_roots(
+    home: Option<&Path>,
+    env: &dyn Fn(&str) -> Option<OsString>,
+    config: &Value,
+) -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    for (env_var, config_key, defaults) in AGENT_LOG_ROOTS {
+        if let Some(home) = home {
+            roots.extend(defaults.iter().map(|dir| home.join(dir)));
+        }
+        if let Some(dir) = env(env_var).filter(|dir| !dir.is_empty()) {
+            roots.push(PathBuf::from(dir));
+        }
+        let configured = config.get(config_key).filter(|_| !config_key.is_empty());
+        if let Some(dirs) = configured.and_then(Value::as_array) {
+            roots.extend(dirs.iter().filter_map(Value::as_str).map(PathBuf::from));
+        }
+    }
+    roots
+}
+
+// reveal shows path selected in the file manager: Finder on macOS,
+// Explorer on Windows, and on other platforms the file manager
+// opened on its folder, since xdg-open has no way to select a file.
+fn reveal(path: &Path) -> std::io::Result<()> {
+    reveal_command(path).spawn().map(drop)
+}
+
+#[cfg(target_os = "macos")]
+fn reveal_command(path: &Path) -> Command {
+    let mut command = Command::new("open");
+    command.arg("-R").arg(path);
+    command
+}
+
+#[cfg(windows)]
+fn reveal_command(path: &Path) -> Command {
+    use std::os::windows::process::CommandExt;
+
+    // Explorer parses its own command line and only accepts the
+    // path quoted after the comma.
+    let mut command = Command::new("explorer");
+    command.raw_arg(format!("/select,\"{}\"", path.display()));
+    command
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn reveal_command(path: &Path) -> Command {
+    let mut command = Command::new("xdg-open");
+    command.arg(path.parent().unwrap_or(path));
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn agent_log_roots_include_defaults_overrides_and_config() {
+        let env = |key: &str| (key == "CODEX_SESSIONS_DIR").then(|| OsString::from("/logs/codex"));
+        let config = serde_json::json!({"claude_project_dirs": ["/mnt/claude"]});
+        let roots = agent_log_roots(Some(Path::new("/home/me")), &env, &config);
+        assert!(roots.contains(&PathBuf::from("/home/me/.claude/projects")));
+        assert!(roots.contains(&PathBuf::from("/logs/codex")));
+        assert!(roots.contains(&PathBuf::from("/mnt/claude")));
+        assert!(agent_log_roots(None, &|_| None, &Value::Null).is_empty());
+    }
+
+    #[test]
+    fn allowed_target_rejects_paths_outside_roots() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let project = dir.path().join("project");
+        let outside = dir.path().join("outside");
+        fs::create_dir_all(project.join("src")).expect("create project dir");
+        fs::create_dir_all(&outside).expect("create outside dir");
+        fs::write(project.join("src/main.rs"), "").expect("write project file");
+        fs::write(outside.join("secret"), "").expect("write outside file");
+        let roots = vec![project.clone()];
+
+        let target =
+            allowed_target(&project.join("src/main.rs"), &roots).expect("file is inside root");
+        assert!(target.ends_with("project/src/main.rs"));
+        assert!(matches!(
+            allowed_target(&project.join("../outside/secret"), &roots),
+            Err(DesktopError::PathNotAllowed { .. })
+        ));
+        assert!(matches!(
+            allowed_target(&project.join("src"), &roots),
+            Err(DesktopError::PathNotAllowed { .. })
+        ));
+        assert!(matches!(
+            allowed_target(Path::new("src/main.rs"), &roots),
+            Err(DesktopError::PathNotAllowed { .. })
+        ));
+        assert!(matches!(
+            allowed_target(&project.join("missing"), &roots),
+            Err(DesktopError::Io(_))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn allowed_target_follows_symlinks_before_checking() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let project = dir.path().join("project");
+        fs::create_dir_all(&project).expect("create project dir");
+        fs::write(dir.path().join("secret"), "").expect("write secret file");
+        std::os::unix::fs::symlink(dir.path().join("secret"), project.join("link"))
+            .expect("create symlink");
+
+        assert!(matches!(
+            allowed_target(&project.join("link"), &[project]),
+            Err(DesktopError::PathNotAllowed { .. })
+        ));
+    }
+
+    #[test]
+    fn recorded_dirs_lists_each_working_directory_once() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let transcript = dir.path().join("session.jsonl");
+        fs::write(
+            &transcript,
+            concat!(
+                "{\"type\":\"summary\"}\n",
+                "{\"cwd\":\"/work/app\"}\n",
+                "{\"cwd\":\"/work/app\"}\n",
+                "{\"cwd\":\"/work/app/web\"}\n",
+                "{\"cwd\":\"relative\"}\n",
+            ),
+        )
+        .expect("write transcript");
+        assert_eq!(
+            recorded_dirs(&transcript),
+            vec![PathBuf::from("/work/app"), PathBuf::from("/work/app/web")]
+        );
+        assert!(recorded_dirs(&dir.path().join("missing")).is_empty());
+    }
+}
@@ -0,0 +1,284 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::instance_lock;
+
+pub(crate) const PID_FILE: &str = "sidecar.pid";
+// ORPHAN_EXIT_TIMEOUT is how long an orphaned sidecar gets to shut
+// down cleanly, releasing the database, before it is killed.
+const ORPHAN_EXIT_TIMEOUT: Duration = Duration::from_secs(3);
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// SidecarRecord identifies a running sidecar: its pid and the
+// executable that pid ran when it was recorded, so a reused pid is
+// never mistaken for it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct SidecarRecord {
+    pid: u32,
+    exe: PathBuf,
+}
+
+// pid_file is where the running sidecar is recorded, next to the
+// instance lock in the data dir.
+pub(crate) fn pid_file() -> Option<PathBuf> {
+    let dir = crate::data_dir()?;
+    let name = crate::user::scoped_file_name(PID_FILE, &dir);
+    Some(dir.join(name))
+}
+
+// record notes pid as the running sidecar. Without its executable
+// path there is nothing safe to compare against later, so nothing
+// is recorded.
+pub(crate) fn record(path: &Path, pid: u32) -> io::Result<()> {
+    let Some(exe) = process_exe(pid) else {
+        return Ok(());
+    };
+    let json = serde_json::to_vec(&SidecarRecord { pid, exe }).map_err(io::Error::other)?;
+    let dir = path
+        .parent()
+        .ok_or_else(|| io::Error::other("pid file has no parent directory"))?;
+    fs::create_dir_all(dir)?;
+    let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+    tmp.write_all(&json)?;
+    tmp.persist(path).map_err(|err| err.error)?;
+    Ok(())
+}
+
+pub(crate) fn clear(path: &Path) {
+    match fs::remove_file(path) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(err) => eprintln!("[agentsview] failed to remove {}: {err}", path.display()),
+    }
+}
+
+// reap stops a sidecar left running by a desktop session that
+// crashed, which would otherwise keep the port and database. It
+// must only run while holding the instance lock, so the recorded
+// sidecar cannot belong to a live shell. The orphan is asked to
+// exit, then killed after ORPHAN_EXIT_TIMEOUT.
+pub(crate) fn reap(path: &Path) {
+    let Some(record) = read_record(path) else {
+        clear(path);
+        return;
+    };
+    if is_orphan(&record, instance_lock::process_alive, process_exe) {
+        eprintln!(
+            "[agentsview] stopping orphaned sidecar from a previous session (pid: {})",
+            record.pid
+        );
+        if let Err(err) = terminate(record.pid) {
+            eprintln!("[agentsview] failed to stop orphaned sidecar: {err}");
+        }
+    }
+    clear(path);
+}
+
+fn read_record(path: &Path) -> Option<SidecarRecord> {
+    serde_json::from_slice(&fs::read(path).ok()?).ok()
+}
+
+fn is_orphan<A, E>(record: &SidecarRecord, is_alive: A, exe_of: E) -> bool
+where
+    A: Fn(u32) -> bool,
+    E: Fn(u32) -> Option<PathBuf>,
+{
+    record.pid != std::process::id()
+        && is_alive(record.pid)
+        && exe_of(record.pid).is_some_and(|exe| exe == record.exe)
+}
+
+fn wait_for_exit(pid: u32, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while instance_lock::process_alive(pid) {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        thread::sleep(EXIT_POLL_INTERVAL);
+    }
+    true
+}
+
+// terminate signals the orphan's process group, which includes any
+// helpers it forked, falling back to the process alone for
+// sidecars that were not spawned as group leaders.
+#[cfg(unix)]
+pub(crate) fn terminate(pid: u32) -> io::Result<()> {
+    let pid = libc::pid_t::try_from(pid)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+    let signal = |sig| {
+        // SAFETY: kill has no memory-safety preconditions.
+        if unsafe { libc::kill(-pid, sig) } == 0 || unsafe { libc::kill(pid, sig) } == 0 {
+            return Ok(());
+        }
+        match io::Error::last_os_error() {
+            err if err.raw_os_error() == Some(libc::ESRCH) => Ok(()),
+            err => Err(err),
+        }
+    };
+    signal(libc::SIGTERM)?;
+    if wait_for_exit(pid as u32, ORPHAN_EXIT_TIMEOUT) {
+        return Ok(());
+    }
+    signal(libc::SIGKILL)?;
+    wait_for_exit(pid as u32, ORPHAN_EXIT_TIMEOUT);
+    Ok(())
+}
+
+// terminate kills the orphan. It has no console to receive a close
+// event, so there is no clean shutdown to ask for.
+#[cfg(windows)]
+pub(crate) fn terminate(pid: u32) -> io::Result<()> {
+    const PROCESS_TERMINATE: u32 = 0x0001;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn OpenProcess(access: u32, inherit: i32, pid: u32) -> *mut std::ffi::c_void;
+        fn TerminateProcess(process: *mut std::ffi::c_void, code: u32) -> i32;
+        fn CloseHandle(handle: *mut std::ffi::c_void) -> i32;
+    }
+
+    // SAFETY: the handle is checked for null and closed before
+    // returning.
+    unsafe {
+        let handle = OpenProcess(PROCESS_TERMINATE, 0, pid);
+        if handle.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+        let ok = TerminateProcess(handle, 1) != 0;
+        let err = io::Error::last_os_error();
+        CloseHandle(handle);
+        if !ok {
+            return Err(err);
+        }
+    }
+    wait_for_exit(pid, ORPHAN_EXIT_TIMEOUT);
+    Ok(())
+}
+
+#[cfg(not(any(unix, windows)))]
+pub(crate) fn terminate(_pid: u32) -> io::Result<()> {
+    Err(io::Error::from(io::ErrorKind::Unsupported))
+}
+
+// process_exe returns the executable a running process was started
+// from. Linux marks a binary replaced by an update as deleted; the
+// original path is what was recorded.
+#[cfg(target_os = "linux")]
+pub(crate) fn process_exe(pid: u32) -> Option<PathBuf> {
+    let exe = fs::read_link(format!("/proc/{pid}/exe")).ok()?;
+    let text = exe.to_string_lossy();
+    match text.strip_suffix(" (deleted)") {
+        Some(original) => Some(PathBuf::from(original)),
+        None => Some(exe),
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) fn process_exe(pid: u32) -> Option<PathBuf> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let pid = libc::c_int::try_from(pid).ok()?;
+    let mut buf = vec![0u8; libc::PROC_PIDPATHINFO_MAXSIZE as usize];
+    // SAFETY: buf is valid for writes of its full length.
+    let len = unsafe { libc::proc_pidpath(pid, buf.as_mut_ptr().cast(), buf.len() as u32) };
+    let len = usize::try_from(len).ok().filter(|len| *len > 0)?;
+    Some(PathBuf::from(std::ffi::OsStr::from_bytes(&buf[..len])))
+}
+
+#[cfg(windows)]
+pub(crate) fn process_exe(pid: u32) -> Option<PathBuf> {
+    use std::os::windows::ffi::OsStringExt;
+
+    const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn OpenProcess(access: u32, inherit: i32, pid: u32) -> *mut std::ffi::c_void;
+        fn QueryFullProcessImageNameW(
+            process: *mut std::ffi::c_void,
+            flags: u32,
+            name: *mut u16,
+            size: *mut u32,
+        ) -> i32;
+        fn CloseHandle(handle: *mut std::ffi::c_void) -> i32;
+    }
+
+    let mut buf = vec![0u16; 32_768];
+    let mut len = buf.len() as u32;
+    // SAFETY: the handle is checked for null and closed before
+    // returning; buf and len describe a valid buffer.
+    let ok = unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return None;
+        }
+        let ok = QueryFullProcessImageNameW(handle, 0, buf.as_mut_ptr(), &mut len) != 0;
+        CloseHandle(handle);
+        ok
+    };
+    ok.then(|| PathBuf::from(std::ffi::OsString::from_wide(&buf[..len as usize])))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+pub(crate) fn process_exe(_pid: u32) -> Option<PathBuf> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_orphan_requires_a_live_matching_process() {
+        let record = SidecarRecord {
+            pid: 4242,
+            exe: PathBuf::from("/opt/agentsview/agentsview"),
+        };
+        let same_exe = |_| Some(PathBuf::from("/opt/agentsview/agentsview"));
+        assert!(is_orphan(&record, |_| true, same_exe));
+        assert!(!is_orphan(&record, |_| false, same_exe));
+        assert!(!is_orphan(
+            &record,
+            |_| true,
+            |_| Some(PathBuf::from("/bin/sh"))
+        ));
+        assert!(!is_orphan(&record, |_| true, |_| None));
+
+        let own = SidecarRecord {
+            pid: std::process::id(),
+            ..record
+        };
+        assert!(!is_orphan(&own, |_| true, same_exe));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn reap_stops_recorded_sidecar_and_clears_file() {
+        use std::os::unix::process::CommandExt;
+
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join(PID_FILE);
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .process_group(0)
+            .spawn()
+            .expect("spawn sleep");
+        let pid = child.id();
+        record(&path, pid).expect("record sidecar");
+        assert!(read_record(&path).is_some());
+
+        // The test process is the orphan's parent here, so it must
+        // reap it for the exit to be observed.
+        let reaper = thread::spawn(move || child.wait());
+        reap(&path);
+        assert!(reaper.join().expect("reaper").is_ok());
+        assert!(!instance_lock::process_alive(pid));
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,415 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Url};
+
+use crate::backend_client::{encode_query_component, BackendClient};
+use crate::error::DesktopError;
+use crate::i18n;
+use crate::shortcuts::{self, Popup};
+
+// DEBOUNCE is how long a query must stay unchanged before it is
+// sent to the backend; each keystroke supersedes the last query.
+const DEBOUNCE: Duration = Duration::from_millis(150);
+const MAX_SESSIONS: usize = 8;
+const MAX_PROJECTS: usize = 5;
+// SEARCH_LIMIT over-fetches message hits since several usually
+// belong to the same session.
+const SEARCH_LIMIT: usize = 40;
+const MIN_SEARCH_CHARS: usize = 2;
+
+// PaletteState numbers palette queries so a newer keystroke
+// cancels the debounce and backend requests of older ones.
+#[derive(Default)]
+pub(crate) struct PaletteState {
+    generation: AtomicU64,
+}
+
+impl PaletteState {
+    fn begin(&self) -> u64 {
+        self.generation.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    fn is_current(&self, generation: u64) -> bool {
+        self.generation.load(Ordering::SeqCst) == generation
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum PaletteKind {
+    Session,
+    Project,
+    Date,
+}
+
+// PaletteEntry is one selectable result. route is the SPA hash
+// the main window navigates to when the entry is chosen.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct PaletteEntry {
+    kind: PaletteKind,
+    title: String,
+    detail: String,
+    route: String,
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    results: Vec<SearchHit>,
+}
+
+#[derive(Deserialize)]
+struct SearchHit {
+    session_id: String,
+    #[serde(default)]
+    project: String,
+    #[serde(default)]
+    timestamp: String,
+    #[serde(default)]
+    snippet: String,
+}
+
+#[derive(Deserialize)]
+struct ProjectsResponse {
+    #[serde(default)]
+    projects: Vec<ProjectInfo>,
+}
+
+#[derive(Deserialize)]
+struct ProjectInfo {
+    name: String,
+    #[serde(default)]
+    session_count: u64,
+}
+
+// palette_search answers a palette query with matching sessions,
+// projects, and dates. It resolves to None when a newer query
+// superseded this one, so the palette can drop stale results.
+#[tauri::command]
+pub(crate) async fn palette_search(
+    app: AppHandle,
+    query: String,
+) -> Result<Option<Vec<PaletteEntry>>, DesktopError> {
+    let generation = app.state::<PaletteState>().begin();
+    tauri::async_runtime::spawn_blocking(move || {
+        thread::sleep(DEBOUNCE);
+        let state = app.state::<PaletteState>();
+        let cancelled = || !state.is_current(generation);
+        if cancelled() {
+            return Ok(None);
+        }
+        let Some(client) = BackendClient::for_app(&app) else {
+            return Ok(Some(date_entries(query.trim())));
+        };
+        search(&client, query.trim(), &cancelled)
+    })
+    .await
+    .map_err(|_| DesktopError::StatePoisoned {
+        what: "palette search",
+    })?
+}
+
+fn search(
+    client: &BackendClient,
+    query: &str,
+    cancelled: &dyn Fn() -> bool,
+) -> Result<Option<Vec<PaletteEntry>>, DesktopError> {
+    if query.is_empty() {
+        return Ok(Some(Vec::new()));
+    }
+    let hits = if query.chars().count() >= MIN_SEARCH_CHARS {
+        let path = format!(
+            "/api/v1/search?q={}&limit={SEARCH_LIMIT}",
+            encode_query_component(query)
+        );
+        match client.get_json_until::<SearchResponse>(&path, cancelled) {
+            Ok(Some(response)) => response.results,
+            Ok(None) => return Ok(None),
+            // Backends built without full-text search answer 501;
+            // projects and dates still work.
+            Err(err) => {
+                eprintln!("[agentsview] palette search failed: {err}");
+                Vec::new()
+            }
+        }
+    } else {
+        Vec::new()
+    };
+    let Some(projects) =
+        client.get_json_until::<ProjectsResponse>("/api/v1/projects", cancelled)?
+    else {
+        return Ok(None);
+    };
+    Ok(Some(build_entries(query, hits, projects.projects)))
+}
+
+fn build_entries(
+    query: &str,
+    hits: Vec<SearchHit>,
+    projects: Vec<ProjectInfo>,
+) -> Vec<PaletteEntry> {
+    let mut entries = session_entries(hits);
+    entries.extend(project_entries(query, projects));
+    entries.extend(date_entries(query));
+    entries
+}
+
+// session_entries keeps the best-ranked hit per session; the
+// backend returns hits in rank order.
+fn session_entries(hits: Vec<SearchHit>) -> Vec<PaletteEntry> {
+    let mut seen = Vec::new();
+    let mut entries = Vec::new();
+    for hit in hits {
+        if entries.len() == MAX_SESSIONS {
+            break;
+        }
+        if seen.contains(&hit.session_id) {
+            continue;
+        }
+        let date = hit.timestamp.get(..10).unwrap_or_default();
+        entries.push(PaletteEntry {
+            kind: PaletteKind::Session,
+            title: strip_highlight(&hit.snippet),
+            detail: [hit.project.as_str(), date]
+                .into_iter()
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>()
+                .join(" · "),
+            route: format!(
+                "#/sessions?session={}",
+                encode_query_component(&hit.session_id)
+            ),
+        });
+        seen.push(hit.session_id);
+    }
+    entries
+}
+
+fn project_entries(query: &str, projects: Vec<ProjectInfo>) -> Vec<PaletteEntry> {
+    let needle = query.to_lowercase();
+    projects
+        .into_iter()
+        .filter(|project| project.name.to_lowercase().contains(&needle))
+        .take(MAX_PROJECTS)
+        .map(|project| PaletteEntry {
+            kind: PaletteKind::Project,
+            route: format!(
+                "#/sessions?project={}",
+                encode_query_component(&project.name)
+            ),
+            detail: i18n::tr_args(
+                "palette-project-sessions",
+                &[("count", &project.session_count)],
+            ),
+            title: project.name,
+        })
+        .collect()
+}
+
+// date_entries offers a day for YYYY-MM-DD queries and a month
+// range for YYYY-MM queries.
+fn date_entries(query: &str) -> Vec<PaletteEntry> {
+    let parts: Vec<&str> = query.split('-').collect();
+    let number = |part: &str, len: usize| {
+        (part.len() == len && part.bytes().all(|b| b.is_ascii_digit()))
+            .then(|| part.parse::<u32>().ok())
+            .flatten()
+    };
+    let (year, month) = match parts.as_slice() {
+        [year, month, ..] => match (number(year, 4), number(month, 2)) {
+            (Some(year), Some(month @ 1..=12)) => (year, month),
+            _ => return Vec::new(),
+        },
+        _ => return Vec::new(),
+    };
+    let last_day = days_in_month(year, month);
+    match parts.as_slice() {
+        [_, _] => vec![PaletteEntry {
+            kind: PaletteKind::Date,
+            title: query.to_string(),
+            detail: String::new(),
+            route: format!(
+                "#/sessions?date_from={year:04}-{month:02}-01&date_to={year:04}-{month:02}-{last_day:02}"
+            ),
+        }],
+        [_, _, day] => match number(day, 2) {
+            Some(day) if (1..=last_day).contains(&day) => vec![PaletteEntry {
+                kind: PaletteKind::Date,
+                title: query.to_string(),
+                detail: String::new(),
+                route: format!("#/sessions?date={query}"),
+            }],
+            _ => Vec::new(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// strip_highlight removes the <mark> tags the backend wraps around
+// matched terms; the palette renders titles as plain text.
+fn strip_highlight(snippet: &str) -> String {
+    snippet
+        .replace("<mark>", "")
+        .replace("</mark>", "")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// POPUP is the quick-switcher window: small, kept above other
+// apps, and hidden again when it loses focus.
+pub(crate) const POPUP: Popup = Popup {
+    label: "palette",
+    page: "palette.html",
+    width: 640.0,
+    height: 420.0,
+};
+
+// open_palette shows the quick-switcher over the focused app.
+pub(crate) fn open_palette(app: &AppHandle) {
+    shortcuts::show_popup(app, &POPUP);
+}
+
+// palette_open navigates the main window to the chosen entry and
+// brings it forward.
+#[tauri::command]
+pub(crate) fn palette_open(app: AppHandle, route: String) -> Result<(), DesktopError> {
+    shortcuts::hide_popup(&app, &POPUP);
+    open_route(&app, &route)
+}
+
+// open_route navigates the main window to an SPA hash route and
+// brings it forward. Routes only apply once the main window shows
+// the backend; over the splash screen it is just focused.
+pub(crate) fn open_route(app: &AppHandle, route: &str) -> Result<(), DesktopError> {
+    let main = app
+        .get_webview_window("main")
+        .ok_or(DesktopError::MissingWindow { label: "main" })?;
+    let on_backend = main.url().is_ok_and(|url| is_backend_page(&url));
+    if on_backend && route.starts_with("#/") {
+        let _ = main.eval(&format!("window.location.hash = {route:?};"));
+    }
+    let _ = main.unminimize();
+    let _ = main.show();
+    let _ = main.set_focus();
+    Ok(())
+}
+
+// is_backend_page reports whether url is served by a backend
+// rather than the bundled splash screen, which Windows and Android
+// serve from http://tauri.localhost.
+pub(crate) fn is_backend_page(url: &Url) -> bool {
+    matches!(url.scheme(), "http" | "https") && url.host_str() != Some("tauri.localhost")
+}
+
+#[tauri::command]
+pub(crate) fn palette_close(app: AppHandle) {
+    shortcuts::hide_popup(&app, &POPUP);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(session_id: &str, snippet: &str) -> SearchHit {
+        SearchHit {
+            session_id: session_id.to_string(),
+            project: "agentsview".to_string(),
+            timestamp: "2026-03-14T09:26:53Z".to_string(),
+            snippet: snippet.to_string(),
+        }
+    }
+
+    #[test]
+    fn session_entries_dedupes_hits_and_strips_highlights() {
+        let entries = session_entries(vec![
+            hit("a", "fix the <mark>parser</mark>\n bug"),
+            hit("a", "second <mark>parser</mark> hit"),
+            hit("b/c", "<mark>parser</mark> tests"),
+        ]);
+        assert_eq!(
+            entries,
+            vec![
+                PaletteEntry {
+                    kind: PaletteKind::Session,
+                    title: "fix the parser bug".to_string(),
+                    detail: "agentsview · 2026-03-14".to_string(),
+                    route: "#/sessions?session=a".to_string(),
+                },
+                PaletteEntry {
+                    kind: PaletteKind::Session,
+                    title: "parser tests".to_string(),
+                    detail: "agentsview · 2026-03-14".to_string(),
+                    route: "#/sessions?session=b%2Fc".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn project_entries_match_case_insensitively() {
+        let projects = vec![
+            ProjectInfo {
+                name: "AgentsView".to_string(),
+                session_count: 12,
+            },
+            ProjectInfo {
+                name: "dotfiles".to_string(),
+                session_count: 3,
+            },
+        ];
+        let entries = project_entries("view", projects);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].title, "AgentsView");
+        assert_eq!(entries[0].route, "#/sessions?project=AgentsView");
+    }
+
+    #[test]
+    fn date_entries_accept_days_and_months() {
+        assert_eq!(
+            date_entries("2024-02-29")[0].route,
+            "#/sessions?date=2024-02-29"
+        );
+        assert_eq!(
+            date_entries("2023-02")[0].route,
+            "#/sessions?date_from=2023-02-01&date_to=2023-02-28"
+        );
+        assert!(date_entries("2023-02-29").is_empty());
+        assert!(date_entries("2023-13").is_empty());
+        assert!(date_entries("parser").is_empty());
+        assert!(date_entries("2023").is_empty());
+    }
+
+    #[test]
+    fn is_backend_page_excludes_bundled_pages() {
+        let page = |url: &str| is_backend_page(&Url::parse(url).expect("valid url"));
+        assert!(page("http://127.0.0.1:8080/#/sessions"));
+        assert!(page("https://agents.corp.example/"));
+        assert!(!page("tauri://localhost/index.html"));
+        assert!(!page("http://tauri.localhost/index.html"));
+    }
+
+    #[test]
+    fn palette_state_supersedes_older_queries() {
+        let state = PaletteState::default();
+        let first = state.begin();
+        assert!(state.is_current(first));
+        let second = state.begin();
+        assert!(!state.is_current(first));
+        assert!(state.is_current(second));
+    }
+}
@@ -0,0 +1,190 @@
+use std::sync::mpsc;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_dialog::{
+    DialogExt, MessageDialogButtons, MessageDialogKind, MessageDialogResult,
+};
+
+use crate::error::DesktopError;
+use crate::i18n;
+use crate::settings::{self, DesktopSettings};
+
+// Capability is an OS integration the shell only enables after the
+// user has read why it is needed. Each one gets its own opt-in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Capability {
+    Notifications,
+    Autostart,
+    GlobalShortcuts,
+}
+
+impl Capability {
+    pub(crate) const ALL: [Capability; 3] = [
+        Capability::Notifications,
+        Capability::Autostart,
+        Capability::GlobalShortcuts,
+    ];
+
+    fn message_key(self) -> &'static str {
+        match self {
+            Capability::Notifications => "capability-notifications",
+            Capability::Autostart => "capability-autostart",
+            Capability::GlobalShortcuts => "capability-global-shortcuts",
+        }
+    }
+}
+
+// Grant records the user's answer to a capability explanation, or
+// a later OS-level denial reported by the feature itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Grant {
+    Granted,
+    Denied,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub(crate) struct CapabilityStatus {
+    capability: Capability,
+    // grant is None until the user has answered the explanation.
+    grant: Option<Grant>,
+}
+
+// record_grant persists a decision. Features call it with Denied
+// when the OS refuses a capability the user opted into, so they
+// stay disabled instead of re-prompting on every launch.
+pub(crate) fn record_grant(capability: Capability, grant: Grant) {
+    if let Err(err) = settings::update_settings(|s| {
+        s.capability_grants.insert(capability, grant);
+    }) {
+        eprintln!("[agentsview] failed to save capability grant: {err}");
+    }
+}
+
+// ensure_capability calls on_decided with whether capability may
+// be used. The first time, it shows a native explanation dialog
+// and records the answer; afterwards the recorded answer is used
+// without prompting. Features must only request OS permission,
+// register login items, or grab hotkeys once this reports true.
+pub(crate) fn ensure_capability<F>(app: &AppHandle, capability: Capability, on_decided: F)
+where
+    F: FnOnce(bool) + Send + 'static,
+{
+    if let Some(grant) = settings::load_settings()
+        .capability_grants
+        .get(&capability)
+        .copied()
+    {
+        on_decided(grant == Grant::Granted);
+        return;
+    }
+
+    let key = capability.message_key();
+    let allow_label = i18n::tr("capability-allow");
+    app.dialog()
+        .message(i18n::tr(&format!("{key}-explanation")))
+        .title(i18n::tr(&format!("{key}-title")))
+        .kind(MessageDialogKind::Info)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            allow_label.clone(),
+            i18n::tr("capability-deny"),
+        ))
+        .show_with_result(move |result| {
+            let granted = match result {
+                MessageDialogResult::Ok => true,
+                MessageDialogResult::Custom(label) => label == allow_label,
+                _ => false,
+            };
+            let grant = if granted {
+                Grant::Granted
+            } else {
+                Grant::Denied
+            };
+            record_grant(capability, grant);
+            on_decided(granted);
+        });
+}
+
+fn capability_statuses(settings: &DesktopSettings) -> Vec<CapabilityStatus> {
+    Capability::ALL
+        .into_iter()
+        .map(|capability| CapabilityStatus {
+            capability,
+            grant: settings.capability_grants.get(&capability).copied(),
+        })
+        .collect()
+}
+
+// capability_status lets the frontend hide or disable features
+// whose capability was denied.
+#[tauri::command]
+pub(crate) fn capability_status() -> Vec<CapabilityStatus> {
+    capability_statuses(&settings::load_settings())
+}
+
+// request_capability runs ensure_capability for the frontend and
+// resolves once the user has answered.
+#[tauri::command]
+pub(crate) async fn request_capability(
+    app: AppHandle,
+    capability: Capability,
+) -> Result<bool, DesktopError> {
+    let (tx, rx) = mpsc::channel();
+    ensure_capability(&app, capability, move |granted| {
+        let _ = tx.send(granted);
+    });
+    tauri::async_runtime::spawn_blocking(move || rx.recv().unwrap_or(false))
+        .await
+        .map_err(|_| DesktopError::StatePoisoned {
+            what: "capability request",
+        })
+}
+
+// reset_capability forgets a decision so the explanation is shown
+// again the next time the feature is used, e.g. after the user
+// re-enables it in settings.
+#[tauri::command]
+pub(crate) fn reset_capability(capability: Capability) -> Result<(), DesktopError> {
+    settings::update_settings(|s| {
+        s.capability_grants.remove(&capability);
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capability_statuses_lists_every_capability() {
+        let mut settings = DesktopSettings::default();
+        settings
+            .capability_grants
+            .insert(Capability::Autostart, Grant::Denied);
+        settings
+            .capability_grants
+            .insert(Capability::Notifications, Grant::Granted);
+
+        assert_eq!(
+            serde_json::to_value(capability_statuses(&settings)).expect("serialize"),
+            serde_json::json!([
+                {"capability": "notifications", "grant": "granted"},
+                {"capability": "autostart", "grant": "denied"},
+                {"capability": "global_shortcuts", "grant": null},
+            ])
+        );
+    }
+
+    #[test]
+    fn every_capability_has_explanation_strings() {
+        let english = i18n::Localizer::for_locale("en");
+        for capability in Capability::ALL {
+            for suffix in ["title", "explanation"] {
+                let key = format!("{}-{suffix}", capability.message_key());
+                assert_ne!(english.format(&key, &[]), key, "missing {key}");
+            }
+        }
+    }
+}
@@ -0,0 +1,321 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+// MANAGED_DOMAIN is the preference domain (bundle identifier) that
+// macOS configuration profiles target.
+#[cfg(target_os = "macos")]
+const MANAGED_DOMAIN: &str = "io.agentsview.desktop";
+// POLICY_KEY is the Group Policy registry key, read from both
+// HKEY_CURRENT_USER and HKEY_LOCAL_MACHINE.
+#[cfg(windows)]
+const POLICY_KEY: &str = r"SOFTWARE\Policies\AgentsView\Desktop";
+
+static MANAGED: OnceLock<ManagedPolicy> = OnceLock::new();
+
+// ManagedPolicy holds the settings IT can set centrally. Each
+// field left unset falls through to the user's own configuration.
+// Key names follow plist and registry conventions so one set of
+// names works for the policy file, configuration profiles, and
+// Group Policy.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct ManagedPolicy {
+    // remote_url attaches the shell to a central agentsview
+    // server instead of spawning the bundled backend.
+    #[serde(rename = "RemoteURL", skip_serializing_if = "Option::is_none")]
+    pub(crate) remote_url: Option<String>,
+    // data_dir replaces AGENT_VIEWER_DATA_DIR for the shell and the
+    // backend.
+    #[serde(rename = "DataDir", skip_serializing_if = "Option::is_none")]
+    pub(crate) data_dir: Option<PathBuf>,
+    // disable_updates tells the frontend to hide update prompts;
+    // installs are then managed by IT.
+    #[serde(rename = "DisableUpdates", skip_serializing_if = "Option::is_none")]
+    pub(crate) disable_updates: Option<bool>,
+}
+
+impl ManagedPolicy {
+    // overlay returns self with every field set in higher replaced.
+    fn overlay(self, higher: ManagedPolicy) -> ManagedPolicy {
+        ManagedPolicy {
+            remote_url: higher.remote_url.or(self.remote_url),
+            data_dir: higher.data_dir.or(self.data_dir),
+            disable_updates: higher.disable_updates.or(self.disable_updates),
+        }
+    }
+
+    // normalized drops empty values, which MDM tools commonly
+    // write for "not configured".
+    fn normalized(mut self) -> ManagedPolicy {
+        self.remote_url = self
+            .remote_url
+            .map(|url| url.trim().to_string())
+            .filter(|url| !url.is_empty());
+        self.data_dir = self.data_dir.filter(|dir| !dir.as_os_str().is_empty());
+        self
+    }
+}
+
+// managed returns the effective policy, read once per launch. In
+// increasing precedence: the system policy file, then per-user and
+// then machine-wide managed preferences, so a machine policy
+// cannot be overridden from the user's account.
+pub(crate) fn managed() -> &'static ManagedPolicy {
+    MANAGED.get_or_init(|| {
+        let file = policy_file_path()
+            .and_then(|path| read_policy_file(&path))
+            .unwrap_or_default();
+        platform_policies()
+            .into_iter()
+            .fold(file, ManagedPolicy::overlay)
+            .normalized()
+    })
+}
+
+// managed_policy lets the frontend lock the settings IT manages
+// and hide update prompts.
+#[tauri::command]
+pub(crate) fn managed_policy() -> ManagedPolicy {
+    managed().clone()
+}
+
+// policy_file_path is a machine-wide JSON file with the same keys,
+// for Linux and for deployments without MDM.
+fn policy_file_path() -> Option<PathBuf> {
+    if cfg!(target_os = "macos") {
+        return Some(PathBuf::from(
+            "/Library/Application Support/AgentsView/policy.json",
+        ));
+    }
+    if cfg!(target_os = "windows") {
+        let program_data = std::env::var_os("ProgramData").filter(|v| !v.is_empty())?;
+        return Some(
+            PathBuf::from(program_data)
+                .join("AgentsView")
+                .join("policy.json"),
+        );
+    }
+    Some(PathBuf::from("/etc/agentsview/policy.json"))
+}
+
+fn read_policy_file(path: &Path) -> Option<ManagedPolicy> {
+    let content = fs::read_to_string(path).ok()?;
+    parse_policy_json(&content).or_else(|| {
+        eprintln!(
+            "[agentsview] ignoring invalid policy file {}",
+            path.display()
+        );
+        None
+    })
+}
+
+fn parse_policy_json(content: &str) -> Option<ManagedPolicy> {
+    serde_json::from_str(content).ok()
+}
+
+// platform_policies returns managed preferences in increasing
+// precedence: per-user, then machine-wide.
+#[cfg(target_os = "macos")]
+fn platform_policies() -> Vec<ManagedPolicy> {
+    let root = Path::new("/Library/Managed Preferences");
+    let mut paths = Vec::new();
+    if let Some(user) = std::env::var_os("USER").filter(|v| !v.is_empty()) {
+        paths.push(root.join(user).join(format!("{MANAGED_DOMAIN}.plist")));
+    }
+    paths.push(root.join(format!("{MANAGED_DOMAIN}.plist")));
+    paths
+        .iter()
+        .filter(|path| path.exists())
+        .filter_map(|path| read_managed_plist(path))
+        .collect()
+}
+
+// read_managed_plist converts a (usually binary) managed
+// preferences plist with plutil, avoiding a plist parser.
+#[cfg(target_os = "macos")]
+fn read_managed_plist(path: &Path) -> Option<ManagedPolicy> {
+    let output = std::process::Command::new("/usr/bin/plutil")
+        .args(["-convert", "json", "-o", "-"])
+        .arg(path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        eprintln!(
+            "[agentsview] could not read managed preferences {}",
+            path.display()
+        );
+        return None;
+    }
+    parse_policy_json(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(windows)]
+fn platform_policies() -> Vec<ManagedPolicy> {
+    const HKEY_CURRENT_USER: isize = 0x8000_0001_u32 as i32 as isize;
+    const HKEY_LOCAL_MACHINE: isize = 0x8000_0002_u32 as i32 as isize;
+
+    [HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE]
+        .into_iter()
+        .map(|root| ManagedPolicy {
+            remote_url: registry_string(root, "RemoteURL"),
+            data_dir: registry_string(root, "DataDir").map(PathBuf::from),
+            disable_updates: registry_dword(root, "DisableUpdates").map(|v| v != 0),
+        })
+        .collect()
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn platform_policies() -> Vec<ManagedPolicy> {
+    Vec::new()
+}
+
+#[cfg(windows)]
+mod registry {
+    use std::ffi::c_void;
+
+    pub(super) const RRF_RT_REG_SZ: u32 = 0x0000_0002;
+    pub(super) const RRF_RT_REG_DWORD: u32 = 0x0000_0010;
+    pub(super) const ERROR_SUCCESS: i32 = 0;
+    pub(super) const ERROR_MORE_DATA: i32 = 234;
+
+    #[link(name = "advapi32")]
+    extern "system" {
+        pub(super) fn RegGetValueW(
+            hkey: isize,
+            sub_key: *const u16,
+            value: *const u16,
+            flags: u32,
+            kind: *mut u32,
+            data: *mut c_void,
+            len: *mut u32,
+        ) -> i32;
+    }
+
+    pub(super) fn wide(value: &str) -> Vec<u16> {
+        value.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+}
+
+// registry_string reads a REG_SZ or REG_EXPAND_SZ value under
+// POLICY_KEY; RegGetValueW expands environment references.
+#[cfg(windows)]
+fn registry_string(root: isize, name: &str) -> Option<String> {
+    use registry::*;
+
+    let key = wide(POLICY_KEY);
+    let value = wide(name);
+    let mut buf = vec![0u16; 260];
+    loop {
+        let mut len = (buf.len() * 2) as u32;
+        // SAFETY: key and value are NUL-terminated, and len is the
+        // byte size of buf, which RegGetValueW never writes past.
+        let status = unsafe {
+            RegGetValueW(
+                root,
+                key.as_ptr(),
+                value.as_ptr(),
+                RRF_RT_REG_SZ,
+                std::ptr::null_mut(),
+                buf.as_mut_ptr().cast(),
+                &mut len,
+            )
+        };
+        match status {
+            ERROR_SUCCESS => {
+                let chars = (len as usize / 2).min(buf.len());
+                let text = String::from_utf16_lossy(&buf[..chars]);
+                return Some(text.trim_end_matches('\0').to_string());
+            }
+            ERROR_MORE_DATA => buf.resize((len as usize).div_ceil(2) + 1, 0),
+            _ => return None,
+        }
+    }
+}
+
+#[cfg(windows)]
+fn registry_dword(root: isize, name: &str) -> Option<u32> {
+    use registry::*;
+
+    let key = wide(POLICY_KEY);
+    let value = wide(name);
+    let mut data = 0u32;
+    let mut len = std::mem::size_of::<u32>() as u32;
+    // SAFETY: data is a u32 out-buffer and len its size.
+    let status = unsafe {
+        RegGetValueW(
+            root,
+            key.as_ptr(),
+            value.as_ptr(),
+            RRF_RT_REG_DWORD,
+            std::ptr::null_mut(),
+            (&mut data as *mut u32).cast(),
+            &mut len,
+        )
+    };
+    (status == ERROR_SUCCESS).then_some(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_policy_json_reads_managed_keys() {
+        let policy = parse_policy_json(
+            r#"{"RemoteURL": "https://agents.corp.example/", "DataDir": "/srv/agentsview", "DisableUpdates": true, "Unrelated": 1}"#,
+        )
+        .expect("valid policy");
+        assert_eq!(
+            policy,
+            ManagedPolicy {
+                remote_url: Some("https://agents.corp.example/".to_string()),
+                data_dir: Some(PathBuf::from("/srv/agentsview")),
+                disable_updates: Some(true),
+            }
+        );
+        assert_eq!(parse_policy_json("{}"), Some(ManagedPolicy::default()));
+        assert_eq!(parse_policy_json("not json"), None);
+    }
+
+    #[test]
+    fn overlay_prefers_higher_precedence_fields() {
+        let file = ManagedPolicy {
+            remote_url: Some("http://file.example/".to_string()),
+            data_dir: Some(PathBuf::from("/file")),
+            disable_updates: None,
+        };
+        let machine = ManagedPolicy {
+            remote_url: Some("http://machine.example/".to_string()),
+            disable_updates: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(
+            file.overlay(machine),
+            ManagedPolicy {
+                remote_url: Some("http://machine.example/".to_string()),
+                data_dir: Some(PathBuf::from("/file")),
+                disable_updates: Some(true),
+            }
+        );
+    }
+
+    #[test]
+    fn normalized_drops_empty_values() {
+        let policy = ManagedPolicy {
+            remote_url: Some("  ".to_string()),
+            data_dir: Some(PathBuf::new()),
+            disable_updates: Some(false),
+        }
+        .normalized();
+        assert_eq!(
+            policy,
+            ManagedPolicy {
+                disable_updates: Some(false),
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,282 @@
+use std::path::Path;
+use std::thread;
+
+use tauri::{Manager, WebviewWindow};
+
+use crate::i18n;
+use crate::orphans;
+use crate::ports;
+use crate::startup_dialog::{self, PortConflictChoice};
+
+// BIND_ERRORS are how the sidecar's "fatal: listening on ..." line
+// ends when the port is taken: the Unix wording, then Windows'
+// WSAEADDRINUSE text.
+const BIND_ERRORS: &[&str] = &[
+    "address already in use",
+    "only one usage of each socket address",
+];
+
+// PortOwner is the process listening on a port the sidecar could
+// not bind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PortOwner {
+    pub(crate) pid: u32,
+    pub(crate) name: String,
+}
+
+impl PortOwner {
+    // is_agentsview reports whether the owner looks like a backend
+    // left over from an earlier session, which is safe to offer to
+    // stop.
+    fn is_agentsview(&self) -> bool {
+        self.pid != std::process::id()
+            && Path::new(&self.name)
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .is_some_and(|stem| stem.eq_ignore_ascii_case("agentsview"))
+    }
+}
+
+// bind_failure returns the port a sidecar stderr line says it
+// could not listen on because another process holds it.
+pub(crate) fn bind_failure(line: &str) -> Option<u16> {
+    let lower = line.to_ascii_lowercase();
+    if !BIND_ERRORS.iter().any(|error| lower.contains(error)) {
+        return None;
+    }
+    let (_, rest) = lower.split_once("listen tcp ")?;
+    let (addr, _) = rest.split_once(": ")?;
+    addr.rsplit_once(':')?.1.parse().ok()
+}
+
+// report explains a sidecar that exited because port was taken,
+// naming the process that holds it. The dialog offers to start the
+// backend on another port, and to stop the owner when it is a stale
+// agentsview.
+pub(crate) fn report(window: &WebviewWindow, port: u16, attempt: u64) {
+    let window = window.clone();
+    thread::spawn(move || {
+        let owner = owner(port);
+        let app = window.app_handle();
+        if crate::superseded(app, attempt) {
+            return;
+        }
+        match &owner {
+            Some(owner) => eprintln!(
+                "[agentsview] port {port} is in use by {} (pid: {})",
+                owner.name, owner.pid
+            ),
+            None => eprintln!("[agentsview] port {port} is in use by an unknown process"),
+        }
+        let stale = owner
+            .as_ref()
+            .filter(|owner| owner.is_agentsview())
+            .cloned();
+        let choice_window = window.clone();
+        startup_dialog::show_port_conflict(
+            app,
+            message(port, owner.as_ref()),
+            stale.is_some(),
+            move |choice| {
+                let app = choice_window.app_handle();
+                if crate::superseded(app, attempt) {
+                    return;
+                }
+                match (choice, stale) {
+                    (PortConflictChoice::StopOwner, Some(stale)) => {
+                        eprintln!("[agentsview] stopping agentsview (pid: {})", stale.pid);
+                        if let Err(err) = orphans::terminate(stale.pid) {
+                            eprintln!("[agentsview] failed to stop agentsview: {err}");
+                            ports::avoid(port);
+                        }
+                    }
+                    (PortConflictChoice::Dismiss, _) => return,
+                    _ => ports::avoid(port),
+                }
+                crate::restart_backend(app, &choice_window);
+            },
+        );
+    });
+}
+
+fn message(port: u16, owner: Option<&PortOwner>) -> String {
+    let Some(owner) = owner else {
+        return format!(
+            "{}\n\n{}",
+            i18n::tr_args("port-conflict-unknown", &[("port", &port)]),
+            i18n::tr("port-conflict-advice")
+        );
+    };
+    let summary = i18n::tr_args(
+        "port-conflict-owner",
+        &[("port", &port), ("name", &owner.name), ("pid", &owner.pid)],
+    );
+    let advice_key = if owner.is_agentsview() {
+        "port-conflict-stale"
+    } else {
+        "port-conflict-advice"
+    };
+    format!("{summary}\n\n{}", i18n::tr(advice_key))
+}
+
+// owner finds the process listening on port, naming it after its
+// executable.
+pub(crate) fn owner(port: u16) -> Option<PortOwner> {
+    let pid = listening_pid(port)?;
+    let name = orphans::process_exe(pid)
+        .and_then(|exe| {
+            exe.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| format!("pid {pid}"));
+    Some(PortOwner { pid, name })
+}
+
+// listening_pid finds the socket's inode in the kernel's TCP tables,
+// then the process holding a descriptor for it. Processes of other
+// users cannot be inspected, so their sockets are not attributed.
+#[cfg(target_os = "linux")]
+fn listening_pid(port: u16) -> Option<u32> {
+    use std::fs;
+
+    let sockets: Vec<String> = ["/proc/net/tcp", "/proc/net/tcp6"]
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .flat_map(|table| listening_inodes(&table, port))
+        .map(|inode| format!("socket:[{inode}]"))
+        .collect();
+    if sockets.is_empty() {
+        return None;
+    }
+    fs::read_dir("/proc").ok()?.flatten().find_map(|entry| {
+        let pid = entry.file_name().to_str()?.parse::<u32>().ok()?;
+        let fds = fs::read_dir(entry.path().join("fd")).ok()?;
+        fds.flatten()
+            .filter_map(|fd| fs::read_link(fd.path()).ok())
+            .any(|target| {
+                sockets
+                    .iter()
+                    .any(|socket| target.as_os_str() == socket.as_str())
+            })
+            .then_some(pid)
+    })
+}
+
+// listening_inodes returns the inodes of sockets listening on port
+// in a /proc/net/tcp table, whose addresses are hex and whose state
+// 0A is LISTEN.
+#[cfg(any(target_os = "linux", test))]
+fn listening_inodes(table: &str, port: u16) -> Vec<u64> {
+    let port = format!("{port:04X}");
+    table
+        .lines()
+        .skip(1)
+        .filter_map(|row| {
+            let fields: Vec<&str> = row.split_whitespace().collect();
+            let (_, local_port) = fields.get(1)?.rsplit_once(':')?;
+            if local_port != port || *fields.get(3)? != "0A" {
+                return None;
+            }
+            fields.get(9)?.parse().ok()
+        })
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn listening_pid(port: u16) -> Option<u32> {
+    let output = std::process::Command::new("/usr/sbin/lsof")
+        .args(["-nP", &format!("-iTCP:{port}"), "-sTCP:LISTEN", "-t"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.trim().parse().ok())
+}
+
+#[cfg(windows)]
+fn listening_pid(port: u16) -> Option<u32> {
+    use std::os::windows::process::CommandExt;
+
+    let output = std::process::Command::new("netstat")
+        .arg("-ano")
+        .creation_flags(crate::process_tree::CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+    netstat_listener(&String::from_utf8_lossy(&output.stdout), port)
+}
+
+// netstat_listener finds the PID listening on port in `netstat
+// -ano` output. The state column is translated on localized
+// Windows, so a listener is recognized by its unset foreign
+// address instead.
+#[cfg(any(windows, test))]
+fn netstat_listener(output: &str, port: u16) -> Option<u32> {
+    let suffix = format!(":{port}");
+    output.lines().find_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [proto, local, foreign, _state, pid] = fields[..] else {
+            return None;
+        };
+        let listening = matches!(foreign, "0.0.0.0:0" | "[::]:0" | "*:*");
+        (proto.eq_ignore_ascii_case("tcp") && local.ends_with(&suffix) && listening)
+            .then(|| pid.parse().ok())
+            .flatten()
+    })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn listening_pid(_port: u16) -> Option<u32> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bind_failure_reads_the_port_from_the_fatal_line() {
+        assert_eq!(
+            bind_failure(
+                "fatal: listening on 127.0.0.1:8080: listen tcp 127.0.0.1:8080: bind: address already in use"
+            ),
+            Some(8080)
+        );
+        assert_eq!(
+            bind_failure(
+                "fatal: listening on 127.0.0.1:8081: listen tcp 127.0.0.1:8081: bind: Only one usage of each socket address (protocol/network address/port) is normally permitted."
+            ),
+            Some(8081)
+        );
+        assert_eq!(
+            bind_failure("fatal: listening on 127.0.0.1:8080: listen tcp 127.0.0.1:8080: bind: permission denied"),
+            None
+        );
+        assert_eq!(bind_failure("sync: address already in use"), None);
+    }
+
+    #[test]
+    fn listening_inodes_matches_listeners_on_the_port() {
+        let table = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 41234 1 0000000000000000 100 0 0 10 0
+   1: 0100007F:1F90 0100007F:D431 01 00000000:00000000 00:00000000 00000000  1000        0 41299 1 0000000000000000 20 4 30 10 -1
+   2: 0100007F:1F91 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 41300 1 0000000000000000 100 0 0 10 0
+";
+        assert_eq!(listening_inodes(table, 8080), [41234]);
+        assert_eq!(listening_inodes(table, 8082), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn netstat_listener_ignores_established_connections() {
+        let output = "
+Active Connections
+
+  Proto  Local Address          Foreign Address        State           PID
+  TCP    127.0.0.1:8080         127.0.0.1:52311        ESTABLISHED     4100
+  TCP    127.0.0.1:8080         0.0.0.0:0              ABHÖREN         4242
+  TCP    [::1]:8081             [::]:0                 LISTENING       5000
+";
+        assert_eq!(netstat_listener(output, 8080), Some(4242));
+        assert_eq!(netstat_listener(output, 8081), Some(5000));
+        assert_eq!(netstat_listener(output, 808), None);
+    }
+}
@@ -0,0 +1,227 @@
+use std::collections::BTreeSet;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener};
+use std::sync::{Mutex, OnceLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::settings;
+use crate::user;
+
+// AVOIDED holds ports another process took from the sidecar this
+// session, which allocate skips until the app restarts.
+static AVOIDED: Mutex<BTreeSet<u16>> = Mutex::new(BTreeSet::new());
+static LOOPBACK: OnceLock<IpAddr> = OnceLock::new();
+
+// PortRange is an inclusive range of loopback ports the sidecar
+// may listen on, tried in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct PortRange {
+    pub(crate) first: u16,
+    pub(crate) last: u16,
+}
+
+impl PortRange {
+    fn is_valid(self) -> bool {
+        self.first != 0 && self.first <= self.last
+    }
+
+    fn contains(self, port: u16) -> bool {
+        (self.first..=self.last).contains(&port)
+    }
+}
+
+// range is the sidecar_ports setting, or by default the block of
+// PORT_STRIDE ports starting at the account's preferred port
+// (8080 for the first user), so users sharing a workstation scan
+// separate blocks.
+pub(crate) fn range() -> PortRange {
+    let preferred = user::preferred_port();
+    let default = PortRange {
+        first: preferred,
+        last: preferred.saturating_add(user::PORT_STRIDE - 1),
+    };
+    match settings::load_settings().sidecar_ports {
+        Some(range) if range.is_valid() => range,
+        Some(range) => {
+            eprintln!(
+                "[agentsview] ignoring sidecar_ports {}-{}: not a valid range",
+                range.first, range.last
+            );
+            default
+        }
+        None => default,
+    }
+}
+
+// allocate returns a free port in range for the sidecar's -port:
+// the one the sidecar last served on, so bookmarks and firewall
+// rules keep working, or else the first free one. The check
+// releases the port at once; the sidecar binds it itself and keeps
+// it, moving on to the next free port if another process took it
+// in between.
+pub(crate) fn allocate(range: PortRange) -> Option<u16> {
+    let last = settings::load_settings().last_sidecar_port;
+    pick_port(range, last, |port| !avoided(port) && port_free(port))
+}
+
+// remember saves port as the one allocate tries first next launch.
+pub(crate) fn remember(port: u16) {
+    if settings::load_settings().last_sidecar_port == Some(port) {
+        return;
+    }
+    if let Err(err) = settings::update_settings(|s| s.last_sidecar_port = Some(port)) {
+        eprintln!("[agentsview] failed to save the backend port: {err}");
+    }
+}
+
+fn pick_port<F>(range: PortRange, last: Option<u16>, is_free: F) -> Option<u16>
+where
+    F: Fn(u16) -> bool,
+{
+    last.filter(|port| range.contains(*port) && is_free(*port))
+        .or_else(|| first_free(range, &is_free))
+}
+
+// avoid keeps allocate away from port for the rest of the session.
+pub(crate) fn avoid(port: u16) {
+    if let Ok(mut avoided) = AVOIDED.lock() {
+        avoided.insert(port);
+    }
+}
+
+fn avoided(port: u16) -> bool {
+    AVOIDED.lock().is_ok_and(|avoided| avoided.contains(&port))
+}
+
+fn first_free<F>(range: PortRange, is_free: F) -> Option<u16>
+where
+    F: Fn(u16) -> bool,
+{
+    (range.first..=range.last).find(|port| is_free(*port))
+}
+
+pub(crate) fn port_free(port: u16) -> bool {
+    TcpListener::bind(SocketAddr::new(loopback(), port)).is_ok()
+}
+
+// loopback is the address the shell and the sidecar talk over:
+// 127.0.0.1, or ::1 on machines whose loopback interface has no
+// IPv4 address. It is chosen once per run.
+pub(crate) fn loopback() -> IpAddr {
+    *LOOPBACK.get_or_init(|| pick_loopback(|ip| TcpListener::bind(SocketAddr::new(ip, 0)).is_ok()))
+}
+
+fn pick_loopback<F>(can_bind: F) -> IpAddr
+where
+    F: Fn(IpAddr) -> bool,
+{
+    let v4 = IpAddr::V4(Ipv4Addr::LOCALHOST);
+    let v6 = IpAddr::V6(Ipv6Addr::LOCALHOST);
+    if !can_bind(v4) && can_bind(v6) {
+        eprintln!("[agentsview] IPv4 loopback is unavailable; using {v6}");
+        return v6;
+    }
+    v4
+}
+
+// loopback_host is loopback as it appears in a URL or Host header,
+// with an IPv6 address in brackets.
+pub(crate) fn loopback_host() -> String {
+    url_host(loopback())
+}
+
+fn url_host(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V6(ip) => format!("[{ip}]"),
+        ip => ip.to_string(),
+    }
+}
+
+// is_loopback_host reports whether a URL host is a loopback IP
+// literal, bracketed or not. Names like localhost are not
+// accepted, since they may resolve elsewhere.
+pub(crate) fn is_loopback_host(host: &str) -> bool {
+    host.strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+        .unwrap_or(host)
+        .parse::<IpAddr>()
+        .is_ok_and(|ip| ip.is_loopback())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ports(first: u16, last: u16) -> PortRange {
+        PortRange { first, last }
+    }
+
+    #[test]
+    fn first_free_scans_the_range_in_order() {
+        assert_eq!(first_free(ports(8080, 8083), |_| true), Some(8080));
+        assert_eq!(
+            first_free(ports(8080, 8083), |port| port > 8081),
+            Some(8082)
+        );
+        assert_eq!(first_free(ports(8080, 8083), |_| false), None);
+        assert_eq!(first_free(ports(65535, 65535), |_| true), Some(65535));
+    }
+
+    #[test]
+    fn pick_port_prefers_the_last_port_while_it_is_usable() {
+        let range = ports(8080, 8089);
+        assert_eq!(pick_port(range, Some(8083), |_| true), Some(8083));
+        assert_eq!(
+            pick_port(range, Some(8083), |port| port != 8083),
+            Some(8080)
+        );
+        assert_eq!(pick_port(range, Some(9000), |_| true), Some(8080));
+        assert_eq!(pick_port(range, None, |port| port > 8080), Some(8081));
+    }
+
+    #[test]
+    fn port_range_rejects_empty_ranges() {
+        assert!(ports(8080, 8080).is_valid());
+        assert!(!ports(8090, 8080).is_valid());
+        assert!(!ports(0, 10).is_valid());
+    }
+
+    #[test]
+    fn pick_loopback_falls_back_to_ipv6() {
+        let v6 = IpAddr::V6(Ipv6Addr::LOCALHOST);
+        assert_eq!(pick_loopback(|_| true), IpAddr::V4(Ipv4Addr::LOCALHOST));
+        assert_eq!(pick_loopback(|ip| ip.is_ipv6()), v6);
+        assert_eq!(pick_loopback(|_| false), IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+        assert!(is_loopback_host("127.0.0.1"));
+        assert!(is_loopback_host("[::1]"));
+        assert!(!is_loopback_host("localhost"));
+        assert!(!is_loopback_host("[192.168.1.20]"));
+    }
+
+    #[test]
+    fn backend_capability_covers_every_loopback() {
+        let capability: serde_json::Value =
+            serde_json::from_str(include_str!("../capabilities/backend.json"))
+                .expect("backend.json should be valid JSON");
+        let patterns: Vec<&str> = capability["remote"]["urls"]
+            .as_array()
+            .expect("backend.json should list remote URLs")
+            .iter()
+            .filter_map(|url| url.as_str())
+            .collect();
+        let covered = |origin: &str| {
+            patterns.iter().any(|pattern| {
+                pattern
+                    .strip_suffix('*')
+                    .and_then(|prefix| origin.strip_prefix(prefix))
+                    .is_some_and(|port| port.parse::<u16>().is_ok())
+            })
+        };
+
+        for ip in [pick_loopback(|_| true), pick_loopback(|ip| ip.is_ipv6())] {
+            let origin = format!("http://{}:8080", url_host(ip));
+            assert!(covered(&origin), "{origin} is not in {patterns:?}");
+        }
+    }
+}
@@ -0,0 +1,31 @@
+// Presentation mode shortcuts, injected into every page.
+// Cmd/Ctrl+Shift+P switches the window into or out of presentation
+// mode, and Escape leaves it. The shell marks the page's root
+// element with data-agentsview-presentation while it presents, so
+// Escape only leaves presentation mode then, and the page can hide
+// its own chrome too.
+(() => {
+  const invoke = window.__TAURI_INTERNALS__?.invoke;
+  if (!invoke || window !== window.top) {
+    return;
+  }
+
+  const MARKER = "data-agentsview-presentation";
+
+  window.addEventListener(
+    "keydown",
+    (event) => {
+      const modifier = event.ctrlKey || event.metaKey;
+      if (modifier && event.shiftKey && !event.altKey && event.code === "KeyP") {
+        event.preventDefault();
+        event.stopPropagation();
+        invoke("toggle_presentation_mode").catch(() => {});
+        return;
+      }
+      if (event.key === "Escape" && document.documentElement.hasAttribute(MARKER)) {
+        invoke("set_presentation_mode", { enabled: false }).catch(() => {});
+      }
+    },
+    true,
+  );
+})();
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use tauri::plugin::{Builder as PluginBuilder, TauriPlugin};
+use tauri::{Manager, Runtime, Webview, WebviewWindow, Window};
+
+use crate::error::DesktopError;
+use crate::kiosk;
+
+const SHORTCUT_SCRIPT: &str = include_str!("presentation.js");
+// MARKER is the attribute set on the page's root element while its
+// window presents; presentation.js and the page's styles read it.
+const MARKER: &str = "data-agentsview-presentation";
+
+// PresentationState remembers, for each presenting window, how it
+// looked before, so leaving presentation mode puts it back.
+#[derive(Default)]
+pub(crate) struct PresentationState(Mutex<HashMap<String, Frame>>);
+
+// Frame is the part of a window's look that presentation mode
+// changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Frame {
+    decorated: bool,
+    maximized: bool,
+    fullscreen: bool,
+}
+
+// init_plugin injects the script that binds Cmd/Ctrl+Shift+P and
+// Escape to presentation mode.
+pub(crate) fn init_plugin<R: Runtime>() -> TauriPlugin<R> {
+    PluginBuilder::new("presentation")
+        .js_init_script(SHORTCUT_SCRIPT.to_string())
+        .build()
+}
+
+// set_presentation_mode switches the calling window to borderless
+// fullscreen for showing transcripts on a projector, or back to
+// how it was. hide_chrome, on by default, also covers the menu bar,
+// dock or taskbar; without it the borderless window fills the
+// screen's work area and leaves them in place. It returns whether
+// the window now presents.
+#[tauri::command]
+pub(crate) fn set_presentation_mode(
+    window: WebviewWindow,
+    enabled: bool,
+    hide_chrome: Option<bool>,
+) -> Result<bool, DesktopError> {
+    if enabled {
+        enter(&window, hide_chrome.unwrap_or(true))?;
+    } else {
+        leave(&window)?;
+    }
+    Ok(enabled)
+}
+
+// toggle_presentation_mode enters presentation mode with the OS
+// chrome hidden, or leaves it, for the keyboard shortcut.
+#[tauri::command]
+pub(crate) fn toggle_presentation_mode(window: WebviewWindow) -> Result<bool, DesktopError> {
+    let presenting = is_presenting(&window);
+    set_presentation_mode(window, !presenting, None)
+}
+
+// on_page_load marks a page loaded into a presenting window, which
+// a navigation or reload left unmarked.
+pub(crate) fn on_page_load(webview: &Webview) {
+    let presenting = webview
+        .state::<PresentationState>()
+        .0
+        .lock()
+        .is_ok_and(|frames| frames.contains_key(webview.label()));
+    if presenting {
+        let _ = webview.eval(&marker_script(true));
+    }
+}
+
+// maximized_before reports whether a presenting window was
+// maximized before it started, for saving its geometry as it
+// closes. None means the window is not presenting.
+pub(crate) fn maximized_before(window: &Window) -> Option<bool> {
+    window
+        .state::<PresentationState>()
+        .0
+        .lock()
+        .ok()?
+        .get(window.label())
+        .map(|frame| frame.maximized)
+}
+
+fn is_presenting(window: &WebviewWindow) -> bool {
+    window
+        .state::<PresentationState>()
+        .0
+        .lock()
+        .is_ok_and(|frames| frames.contains_key(window.label()))
+}
+
+fn enter(window: &WebviewWindow, hide_chrome: bool) -> Result<(), DesktopError> {
+    if kiosk::config().is_some() {
+        return Err(unavailable(
+            "kiosk mode keeps the window fullscreen".to_string(),
+        ));
+    }
+    let state = window.state::<PresentationState>();
+    let mut frames = state.0.lock().map_err(|_| DesktopError::StatePoisoned {
+        what: "presentation state",
+    })?;
+    if !frames.contains_key(window.label()) {
+        let frame = Frame {
+            decorated: window.is_decorated().unwrap_or(true),
+            maximized: window.is_maximized().unwrap_or(false),
+            fullscreen: window.is_fullscreen().unwrap_or(false),
+        };
+        frames.insert(window.label().to_string(), frame);
+    }
+    drop(frames);
+
+    let result = window.set_decorations(false).and_then(|()| {
+        if hide_chrome {
+            window.set_fullscreen(true)
+        } else {
+            window.set_fullscreen(false)?;
+            window.maximize()
+        }
+    });
+    if let Err(err) = result {
+        let _ = leave(window);
+        return Err(unavailable(err.to_string()));
+    }
+    let _ = window.set_focus();
+    let _ = window.eval(&marker_script(true));
+    Ok(())
+}
+
+fn leave(window: &WebviewWindow) -> Result<(), DesktopError> {
+    let frame = window
+        .state::<PresentationState>()
+        .0
+        .lock()
+        .map_err(|_| DesktopError::StatePoisoned {
+            what: "presentation state",
+        })?
+        .remove(window.label());
+    let Some(frame) = frame else {
+        return Ok(());
+    };
+    let _ = window.eval(&marker_script(false));
+    let result = window
+        .set_fullscreen(frame.fullscreen)
+        .and_then(|()| {
+            if frame.maximized {
+                window.maximize()
+            } else {
+                window.unmaximize()
+            }
+        })
+        .and_then(|()| window.set_decorations(frame.decorated));
+    result.map_err(|err| unavailable(err.to_string()))
+}
+
+fn unavailable(reason: String) -> DesktopError {
+    DesktopError::ActionUnavailable {
+        action: "set_presentation_mode".to_string(),
+        reason,
+    }
+}
+
+// marker_script adds or removes MARKER on the page's root element.
+fn marker_script(presenting: bool) -> String {
+    format!("document.documentElement.toggleAttribute({MARKER:?}, {presenting});")
+}
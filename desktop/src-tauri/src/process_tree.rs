@@ -0,0 +1,430 @@
+use std::io::{self, BufRead, BufReader, Read};
+use std::process::{ChildStdin, Command, ExitStatus, Stdio};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+use shared_child::SharedChild;
+use tauri::async_runtime::{channel, Receiver, Sender};
+use tauri_plugin_shell::process::{CommandEvent, TerminatedPayload};
+
+// DRAIN_TIMEOUT bounds how long the sidecar's exit waits for its
+// remaining output. Helpers that outlive it hold the pipes open,
+// and must not hide the exit.
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+// LOW_PRIORITY_NICE is the niceness of a low-priority sidecar:
+// enough to yield the CPU to interactive apps without starving
+// indexing.
+#[cfg(unix)]
+const LOW_PRIORITY_NICE: libc::c_int = 10;
+#[cfg(windows)]
+pub(crate) const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+#[cfg(windows)]
+const BELOW_NORMAL_PRIORITY_CLASS: u32 = 0x0000_4000;
+
+// ProcessTree is the spawned sidecar together with every process
+// it starts. On Unix the sidecar leads its own process group; on
+// Windows it runs in a job object that kills its members when the
+// last handle closes, so they also die if the shell crashes.
+#[derive(Debug)]
+pub(crate) struct ProcessTree {
+    child: Arc<SharedChild>,
+    // stdin stays open for the sidecar's lifetime, as it did when
+    // the shell plugin spawned it.
+    _stdin: Option<ChildStdin>,
+    #[cfg(windows)]
+    job: Option<job::Job>,
+}
+
+impl ProcessTree {
+    pub(crate) fn pid(&self) -> u32 {
+        self.child.id()
+    }
+
+    // exit_status is the sidecar's exit status once it has exited.
+    pub(crate) fn exit_status(&self) -> Option<ExitStatus> {
+        self.child.try_wait().ok().flatten()
+    }
+
+    // terminate asks the sidecar and its helpers to shut down,
+    // leaving them time to finish writing before kill. Windows has
+    // no such request for a process without a console.
+    #[cfg(unix)]
+    pub(crate) fn terminate(&self) -> io::Result<()> {
+        signal_group(self.child.id(), libc::SIGTERM)
+    }
+
+    #[cfg(not(unix))]
+    pub(crate) fn terminate(&self) -> io::Result<()> {
+        Err(io::Error::from(io::ErrorKind::Unsupported))
+    }
+
+    // wait_for_exit waits up to timeout for the sidecar to exit and
+    // returns whether it did.
+    pub(crate) fn wait_for_exit(&self, timeout: Duration) -> bool {
+        matches!(self.child.wait_timeout(timeout), Ok(Some(_)))
+    }
+
+    // kill terminates the sidecar and its descendants, including
+    // helpers still running after the sidecar itself exited.
+    #[cfg(unix)]
+    pub(crate) fn kill(self) -> io::Result<()> {
+        signal_group(self.child.id(), libc::SIGKILL)
+    }
+
+    #[cfg(windows)]
+    pub(crate) fn kill(self) -> io::Result<()> {
+        match &self.job {
+            Some(job) => job.terminate(),
+            None => self.child.kill(),
+        }
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    pub(crate) fn kill(self) -> io::Result<()> {
+        self.child.kill()
+    }
+}
+
+// signal_group sends signal to the sidecar's process group. A
+// group that is already gone is not an error.
+#[cfg(unix)]
+fn signal_group(pid: u32, signal: libc::c_int) -> io::Result<()> {
+    let pgid = libc::pid_t::try_from(pid)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+    // The group id is the sidecar's pid; a negative pid signals
+    // the whole group.
+    if unsafe { libc::kill(-pgid, signal) } == 0 {
+        return Ok(());
+    }
+    let err = io::Error::last_os_error();
+    if err.raw_os_error() == Some(libc::ESRCH) {
+        return Ok(());
+    }
+    Err(err)
+}
+
+// spawn starts command as the root of a process tree, reporting
+// its output and exit as the shell plugin's command events. With
+// low_priority the tree runs below normal scheduling priority.
+pub(crate) fn spawn(
+    command: tauri_plugin_shell::process::Command,
+    low_priority: bool,
+) -> io::Result<(Receiver<CommandEvent>, ProcessTree)> {
+    spawn_command(command.into(), low_priority)
+}
+
+fn spawn_command(
+    mut command: Command,
+    low_priority: bool,
+) -> io::Result<(Receiver<CommandEvent>, ProcessTree)> {
+    command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+    if low_priority {
+        lower_priority(&mut command);
+    }
+
+    let child = command.spawn()?;
+    #[cfg(windows)]
+    let job = job::Job::assign(&child)
+        .map_err(|err| {
+            eprintln!("[agentsview] sidecar job object unavailable, helpers may outlive it: {err}")
+        })
+        .ok();
+    let child = SharedChild::new(child)?;
+    let stdin = child.take_stdin();
+    let stdout = child.take_stdout();
+    let stderr = child.take_stderr();
+    let child = Arc::new(child);
+
+    let (tx, rx) = channel(1);
+    // Each reader holds a clone of drained until its pipe closes,
+    // so the waiter sees a disconnect once all output is read.
+    let (drained, drain_rx) = mpsc::channel::<()>();
+    if let Some(stdout) = stdout {
+        forward_lines(stdout, tx.clone(), drained.clone(), CommandEvent::Stdout);
+    }
+    if let Some(stderr) = stderr {
+        forward_lines(stderr, tx.clone(), drained.clone(), CommandEvent::Stderr);
+    }
+    drop(drained);
+
+    let waiter = child.clone();
+    thread::spawn(move || {
+        let event = match waiter.wait() {
+            Ok(status) => CommandEvent::Terminated(TerminatedPayload {
+                code: status.code(),
+                #[cfg(unix)]
+                signal: std::os::unix::process::ExitStatusExt::signal(&status),
+                #[cfg(not(unix))]
+                signal: None,
+            }),
+            Err(err) => CommandEvent::Error(err.to_string()),
+        };
+        let _ = drain_rx.recv_timeout(DRAIN_TIMEOUT);
+        let _ = tx.blocking_send(event);
+    });
+
+    Ok((
+        rx,
+        ProcessTree {
+            child,
+            _stdin: stdin,
+            #[cfg(windows)]
+            job,
+        },
+    ))
+}
+
+// lower_priority makes command start below normal priority. The
+// sidecar's helpers inherit it.
+#[cfg(unix)]
+fn lower_priority(command: &mut Command) {
+    use std::os::unix::process::CommandExt;
+
+    // SAFETY: setpriority is async-signal-safe. Raising the
+    // niceness needs no privileges; if it fails anyway the sidecar
+    // just runs at normal priority.
+    unsafe {
+        command.pre_exec(|| {
+            libc::setpriority(libc::PRIO_PROCESS, 0, LOW_PRIORITY_NICE);
+            Ok(())
+        });
+    }
+}
+
+// lower_priority keeps CREATE_NO_WINDOW, which the shell plugin
+// set, since creation flags are replaced as a whole.
+#[cfg(windows)]
+fn lower_priority(command: &mut Command) {
+    use std::os::windows::process::CommandExt;
+
+    command.creation_flags(CREATE_NO_WINDOW | BELOW_NORMAL_PRIORITY_CLASS);
+}
+
+#[cfg(not(any(unix, windows)))]
+fn lower_priority(_command: &mut Command) {}
+
+fn forward_lines<R: Read + Send + 'static>(
+    pipe: R,
+    tx: Sender<CommandEvent>,
+    drained: mpsc::Sender<()>,
+    event: fn(Vec<u8>) -> CommandEvent,
+) {
+    thread::spawn(move || {
+        let _drained = drained;
+        let mut reader = BufReader::new(pipe);
+        loop {
+            let mut line = Vec::new();
+            match reader.read_until(b'\n', &mut line) {
+                Ok(0) => break,
+                Ok(_) => {
+                    if tx.blocking_send(event(line)).is_err() {
+                        break;
+                    }
+                }
+                Err(err) => {
+                    let _ = tx.blocking_send(CommandEvent::Error(err.to_string()));
+                    break;
+                }
+            }
+        }
+    });
+}
+
+#[cfg(windows)]
+mod job {
+    use std::ffi::c_void;
+    use std::io;
+    use std::os::windows::io::AsRawHandle;
+
+    const JOB_OBJECT_EXTENDED_LIMIT_INFORMATION: i32 = 9;
+    const JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE: u32 = 0x2000;
+
+    // The limit structs mirror the Win32 layouts; the kernel reads
+    // the fields.
+    #[repr(C)]
+    #[derive(Default)]
+    #[allow(dead_code)]
+    struct BasicLimitInformation {
+        per_process_user_time_limit: i64,
+        per_job_user_time_limit: i64,
+        limit_flags: u32,
+        minimum_working_set_size: usize,
+        maximum_working_set_size: usize,
+        active_process_limit: u32,
+        affinity: usize,
+        priority_class: u32,
+        scheduling_class: u32,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    #[allow(dead_code)]
+    struct ExtendedLimitInformation {
+        basic: BasicLimitInformation,
+        io_counters: [u64; 6],
+        process_memory_limit: usize,
+        job_memory_limit: usize,
+        peak_process_memory_used: usize,
+        peak_job_memory_used: usize,
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn CreateJobObjectW(attributes: *const c_void, name: *const u16) -> *mut c_void;
+        fn SetInformationJobObject(
+            job: *mut c_void,
+            class: i32,
+            info: *const c_void,
+            length: u32,
+        ) -> i32;
+        fn AssignProcessToJobObject(job: *mut c_void, process: *mut c_void) -> i32;
+        fn TerminateJobObject(job: *mut c_void, exit_code: u32) -> i32;
+        fn CloseHandle(handle: *mut c_void) -> i32;
+    }
+
+    // Job owns a job object handle that kills its processes when
+    // closed.
+    #[derive(Debug)]
+    pub(super) struct Job(*mut c_void);
+
+    // SAFETY: job object handles may be used from any thread.
+    unsafe impl Send for Job {}
+    unsafe impl Sync for Job {}
+
+    impl Job {
+        // assign creates a kill-on-close job holding process. Its
+        // children join the job as they start.
+        pub(super) fn assign(process: &impl AsRawHandle) -> io::Result<Self> {
+            let handle = unsafe { CreateJobObjectW(std::ptr::null(), std::ptr::null()) };
+            if handle.is_null() {
+                return Err(io::Error::last_os_error());
+            }
+            let job = Self(handle);
+            let info = ExtendedLimitInformation {
+                basic: BasicLimitInformation {
+                    limit_flags: JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let ok = unsafe {
+                SetInformationJobObject(
+                    job.0,
+                    JOB_OBJECT_EXTENDED_LIMIT_INFORMATION,
+                    (&info as *const ExtendedLimitInformation).cast(),
+                    std::mem::size_of::<ExtendedLimitInformation>() as u32,
+                )
+            };
+            if ok == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            if unsafe { AssignProcessToJobObject(job.0, process.as_raw_handle().cast()) } == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(job)
+        }
+
+        pub(super) fn terminate(&self) -> io::Result<()> {
+            if unsafe { TerminateJobObject(self.0, 1) } == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        }
+    }
+
+    impl Drop for Job {
+        fn drop(&mut self) {
+            unsafe { CloseHandle(self.0) };
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    // alive treats a zombie as dead: the killed helper is reaped by
+    // whichever process adopts it, which may take a while.
+    fn alive(pid: libc::pid_t) -> bool {
+        if unsafe { libc::kill(pid, 0) } != 0 {
+            return false;
+        }
+        std::fs::read_to_string(format!("/proc/{pid}/stat"))
+            .map(|stat| {
+                !stat
+                    .rsplit(')')
+                    .next()
+                    .unwrap_or("")
+                    .trim_start()
+                    .starts_with('Z')
+            })
+            .unwrap_or(true)
+    }
+
+    #[test]
+    fn terminate_lets_the_sidecar_exit() {
+        let mut command = Command::new("/bin/sh");
+        command.args([
+            "-c",
+            "trap 'exit 3' TERM; echo ready; while :; do sleep 0.05; done",
+        ]);
+        let (mut rx, tree) = spawn_command(command, false).expect("spawn sh");
+        assert!(matches!(rx.blocking_recv(), Some(CommandEvent::Stdout(_))));
+
+        tree.terminate().expect("terminate tree");
+        assert!(tree.wait_for_exit(Duration::from_secs(5)));
+        assert_eq!(tree.exit_status().and_then(|status| status.code()), Some(3));
+        tree.kill().expect("kill exited tree");
+    }
+
+    #[test]
+    fn low_priority_raises_niceness() {
+        let niceness = |pid| unsafe { libc::getpriority(libc::PRIO_PROCESS, pid) };
+        let base = niceness(0);
+        let mut command = Command::new("sleep");
+        command.arg("30");
+        let (_rx, tree) = spawn_command(command, true).expect("spawn sleep");
+        assert_eq!(niceness(tree.pid()), (base + LOW_PRIORITY_NICE).min(19));
+        tree.kill().expect("kill sleep");
+    }
+
+    #[test]
+    fn kill_stops_forked_helpers() {
+        let mut command = Command::new("/bin/sh");
+        command.args(["-c", "sleep 30 & echo $!; wait"]);
+        let (mut rx, tree) = spawn_command(command, false).expect("spawn sh");
+
+        let helper = match rx.blocking_recv() {
+            Some(CommandEvent::Stdout(line)) => String::from_utf8_lossy(&line)
+                .trim()
+                .parse::<libc::pid_t>()
+                .expect("helper pid"),
+            other => panic!("unexpected event: {other:?}"),
+        };
+        assert!(alive(helper));
+        tree.kill().expect("kill tree");
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while alive(helper) && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(20));
+        }
+        assert!(!alive(helper), "helper {helper} survived");
+        loop {
+            match rx.blocking_recv() {
+                Some(CommandEvent::Terminated(payload)) => {
+                    assert_eq!(payload.signal, Some(libc::SIGKILL));
+                    break;
+                }
+                Some(_) => {}
+                None => panic!("no terminated event"),
+            }
+        }
+    }
+}
@@ -0,0 +1,484 @@
+use std::ffi::OsString;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{Manager, Url, WebviewWindow};
+
+use crate::connectivity;
+use crate::error::DesktopError;
+use crate::http_client;
+use crate::proxy;
+use crate::settings;
+use crate::tls;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+const BACKEND_URL_ENV: &str = "AGENTSVIEW_DESKTOP_BACKEND_URL";
+const BACKEND_URL_ARG: &str = "--backend-url";
+
+// RemoteState holds the origin of the remote agentsview server the
+// shell is attached to. The navigation guard allows it alongside
+// the local sidecar.
+#[derive(Default)]
+pub(crate) struct RemoteState(pub(crate) Mutex<Option<Url>>);
+
+impl RemoteState {
+    pub(crate) fn origin(&self) -> Option<Url> {
+        self.0.lock().ok().and_then(|guard| guard.clone())
+    }
+}
+
+// ConnectionPicker tells the splash page whether to show the
+// remote connection form, which servers to offer, and the page to
+// resume on the most recent one.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub(crate) struct ConnectionPicker {
+    enabled: bool,
+    connections: Vec<String>,
+    resume: Option<String>,
+}
+
+// connection_picker is called by the splash page on load. Mobile
+// builds cannot spawn a sidecar, so they attach to a remote or LAN
+// server instead.
+#[tauri::command]
+pub(crate) fn connection_picker() -> ConnectionPicker {
+    if !cfg!(mobile) {
+        return ConnectionPicker {
+            enabled: false,
+            connections: Vec::new(),
+            resume: None,
+        };
+    }
+    let settings = settings::load_settings();
+    ConnectionPicker {
+        enabled: true,
+        resume: resume_route(&settings).map(String::from),
+        connections: settings.remote_connections,
+    }
+}
+
+// resume_route returns the saved route when it still belongs to
+// the most recent connection.
+fn resume_route(settings: &settings::DesktopSettings) -> Option<Url> {
+    let last = Url::parse(settings.remote_connections.first()?).ok()?;
+    let route = Url::parse(settings.last_remote_route.as_deref()?).ok()?;
+    same_origin(&last, &route).then_some(route)
+}
+
+// remember_route saves the page the webview shows on the attached
+// remote server. The SPA routes by URL hash, which never reaches
+// the navigation guard, so this runs when the window loses focus,
+// as it does before iOS suspends the app.
+pub(crate) fn remember_route(window: &WebviewWindow) {
+    let Some(origin) = window.state::<RemoteState>().origin() else {
+        return;
+    };
+    let Ok(url) = window.url() else {
+        return;
+    };
+    if !same_origin(&origin, &url) {
+        return;
+    }
+    let route = url.to_string();
+    if settings::load_settings().last_remote_route.as_deref() == Some(route.as_str()) {
+        return;
+    }
+    if let Err(err) = settings::update_settings(|s| s.last_remote_route = Some(route)) {
+        eprintln!("[agentsview] failed to save remote route: {err}");
+    }
+}
+
+// connect_remote validates and probes the server the user picked,
+// remembers it, and points the webview at it. A route on the same
+// server, such as the resume route, is opened instead of the root.
+#[tauri::command]
+pub(crate) async fn connect_remote(
+    window: WebviewWindow,
+    url: String,
+    route: Option<String>,
+) -> Result<String, DesktopError> {
+    let url = parse_remote_url(&url)?;
+    ensure_remote_allowed(&url)?;
+    let probe_url = url.clone();
+    let url = tauri::async_runtime::spawn_blocking(move || probe_remote(&probe_url))
+        .await
+        .map_err(|err| DesktopError::RemoteConnect {
+            url: url.to_string(),
+            reason: err.to_string(),
+        })??;
+
+    let target = route
+        .and_then(|route| Url::parse(&route).ok())
+        .filter(|route| same_origin(&url, route))
+        .unwrap_or_else(|| url.clone());
+    attach_remote(&window, &url, &target);
+    Ok(target.to_string())
+}
+
+// connect_blocking attaches to url from a background thread, for
+// connections the shell makes on its own, such as a managed
+// RemoteURL policy.
+pub(crate) fn connect_blocking(window: &WebviewWindow, url: &str) -> Result<(), DesktopError> {
+    let url = parse_remote_url(url)?;
+    ensure_remote_allowed(&url)?;
+    let url = probe_remote(&url)?;
+    attach_remote(window, &url, &url);
+    Ok(())
+}
+
+// backend_url returns the server named by AGENTSVIEW_DESKTOP_BACKEND_URL
+// or --backend-url, such as a dev server or a backend in Docker,
+// which the shell uses instead of spawning its own. The env var
+// takes precedence over the argument.
+pub(crate) fn backend_url() -> Option<String> {
+    backend_url_from(std::env::args_os(), |key| std::env::var_os(key))
+}
+
+fn backend_url_from<A, F>(args: A, lookup: F) -> Option<String>
+where
+    A: Iterator<Item = OsString>,
+    F: Fn(&str) -> Option<OsString>,
+{
+    let from_args = || {
+        let args: Vec<String> = args.map(|arg| arg.to_string_lossy().into_owned()).collect();
+        args.iter().enumerate().find_map(|(index, arg)| {
+            match arg.strip_prefix(BACKEND_URL_ARG)?.strip_prefix('=') {
+                Some(value) => Some(value.to_string()),
+                None if arg == BACKEND_URL_ARG => args.get(index + 1).cloned(),
+                None => None,
+            }
+        })
+    };
+    lookup(BACKEND_URL_ENV)
+        .map(|value| value.to_string_lossy().into_owned())
+        .or_else(from_args)
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+}
+
+// ensure_remote_allowed limits no-network builds to servers on
+// this machine, such as a backend in Docker or a dev server.
+#[cfg(feature = "no-network")]
+fn ensure_remote_allowed(url: &Url) -> Result<(), DesktopError> {
+    let host = url.host_str().unwrap_or_default();
+    if host == "localhost" || crate::ports::is_loopback_host(host) {
+        return Ok(());
+    }
+    Err(DesktopError::RemoteConnect {
+        url: url.to_string(),
+        reason: "this build only connects to servers on this machine".to_string(),
+    })
+}
+
+#[cfg(not(feature = "no-network"))]
+fn ensure_remote_allowed(_url: &Url) -> Result<(), DesktopError> {
+    Ok(())
+}
+
+fn attach_remote(window: &WebviewWindow, origin: &Url, target: &Url) {
+    if let Ok(mut guard) = window.state::<RemoteState>().0.lock() {
+        *guard = Some(origin.clone());
+    }
+    if let Err(err) = settings::update_settings(|s| {
+        s.remember_connection(origin.as_str());
+        s.last_remote_route = Some(target.to_string());
+    }) {
+        eprintln!("[agentsview] failed to save remote connection: {err}");
+    }
+    let script = format!("window.location.replace({:?});", target.as_str());
+    let _ = window.eval(&script);
+    connectivity::watch_remote(window, origin.clone());
+}
+
+// parse_remote_url normalizes user input into a server origin.
+// Input without a scheme defaults to http, matching what
+// agentsview serve listens on.
+pub(crate) fn parse_remote_url(input: &str) -> Result<Url, DesktopError> {
+    let trimmed = input.trim();
+    let invalid = |reason: &str| DesktopError::RemoteConnect {
+        url: trimmed.to_string(),
+        reason: reason.to_string(),
+    };
+    if trimmed.is_empty() {
+        return Err(invalid("no server address entered"));
+    }
+
+    let candidate = if trimmed.contains("://") {
+        trimmed.to_string()
+    } else {
+        format!("http://{trimmed}")
+    };
+    let mut url = Url::parse(&candidate).map_err(|err| invalid(&err.to_string()))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(invalid("only http and https servers are supported"));
+    }
+    if url.host().is_none() {
+        return Err(invalid("server address has no host"));
+    }
+    let _ = url.set_username("");
+    let _ = url.set_password(None);
+    url.set_path("/");
+    url.set_query(None);
+    url.set_fragment(None);
+    Ok(url)
+}
+
+pub(crate) fn same_origin(a: &Url, b: &Url) -> bool {
+    a.origin() == b.origin()
+}
+
+// probe_remote checks that the server answers /api/v1/version like
+// agentsview does and returns the origin to attach to. An http
+// server that redirects to https on the same host is probed again
+// over TLS, and its https origin is returned.
+fn probe_remote(url: &Url) -> Result<Url, DesktopError> {
+    let response = fetch_version(url)?;
+    let (origin, response) = match https_upgrade(url, &response) {
+        Some(upgraded) => {
+            let response = fetch_version(&upgraded)?;
+            (upgraded, response)
+        }
+        None => (url.clone(), response),
+    };
+    if !crate::version_response_looks_valid(&response) {
+        return Err(DesktopError::RemoteConnect {
+            url: origin.to_string(),
+            reason: "server did not respond like agentsview".to_string(),
+        });
+    }
+    Ok(origin)
+}
+
+// reachable reports whether the attached server at origin still
+// answers like agentsview.
+pub(crate) fn reachable(origin: &Url) -> bool {
+    fetch_version(origin).is_ok_and(|response| crate::version_response_looks_valid(&response))
+}
+
+fn fetch_version(url: &Url) -> Result<http_client::Response, DesktopError> {
+    let fail = |reason: String| DesktopError::RemoteConnect {
+        url: url.to_string(),
+        reason,
+    };
+    let host = url.host_str().unwrap_or_default();
+    let port = url.port_or_known_default().unwrap_or(80);
+    let tls = match url.scheme() {
+        "https" => Some(http_client::Tls {
+            config: tls::client_config(&settings::load_settings()).map_err(fail)?,
+            server_name: host.trim_matches(['[', ']']).to_string(),
+        }),
+        _ => None,
+    };
+    let host = format!("{host}:{port}");
+
+    // A proxy resolves the host itself, which may be the only way
+    // to reach it.
+    if let Some(proxy) = proxy::for_url(url) {
+        return http_client::get(
+            http_client::Route::Proxy(&proxy),
+            &host,
+            "/api/v1/version",
+            PROBE_TIMEOUT,
+            tls.as_ref(),
+            None,
+        )
+        .map_err(|err| fail(format!("via proxy {}:{}: {err}", proxy.host, proxy.port)));
+    }
+
+    let addrs = url
+        .socket_addrs(|| Some(port))
+        .map_err(|err| fail(err.to_string()))?;
+    let mut last_err = "host did not resolve to any address".to_string();
+    for addr in addrs {
+        match http_client::get(
+            http_client::Route::Direct(addr),
+            &host,
+            "/api/v1/version",
+            PROBE_TIMEOUT,
+            tls.as_ref(),
+            None,
+        ) {
+            Ok(response) => return Ok(response),
+            Err(http_client::Error::Connect(err)) => last_err = err.to_string(),
+            Err(err) => return Err(fail(err.to_string())),
+        }
+    }
+    Err(fail(last_err))
+}
+
+// https_upgrade returns the https origin an http server redirected
+// to, when it is on the same host. Other redirects are not
+// followed, so a probe never leaves the server the user named.
+fn https_upgrade(url: &Url, response: &http_client::Response) -> Option<Url> {
+    if url.scheme() != "http" || !matches!(response.status, 301 | 302 | 307 | 308) {
+        return None;
+    }
+    let location = url.join(response.header("location")?).ok()?;
+    if location.scheme() != "https" || location.host() != url.host() {
+        return None;
+    }
+    let mut origin = location;
+    origin.set_path("/");
+    origin.set_query(None);
+    origin.set_fragment(None);
+    Some(origin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::{Ipv4Addr, SocketAddrV4, TcpListener};
+    use std::thread;
+
+    #[test]
+    fn parse_remote_url_normalizes_to_origin() {
+        let url = parse_remote_url("  192.168.1.20:8080/sessions?x=1#top ").expect("valid url");
+        assert_eq!(url.as_str(), "http://192.168.1.20:8080/");
+
+        let https = parse_remote_url("https://user:pw@agents.example.com/app").expect("valid url");
+        assert_eq!(https.as_str(), "https://agents.example.com/");
+    }
+
+    #[test]
+    fn parse_remote_url_rejects_unsupported_input() {
+        for input in ["", "   ", "ftp://host/", "file:///tmp/x", "http://"] {
+            let err = parse_remote_url(input).expect_err("should reject");
+            assert_eq!(err.kind(), "remote_connect", "input {input:?}");
+        }
+    }
+
+    #[test]
+    fn backend_url_reads_env_then_argument() {
+        let args = |list: &[&str]| {
+            list.iter()
+                .map(OsString::from)
+                .collect::<Vec<_>>()
+                .into_iter()
+        };
+        let no_env = |_: &str| None;
+        assert_eq!(
+            backend_url_from(args(&["app", "--backend-url", "localhost:5173"]), no_env),
+            Some("localhost:5173".to_string())
+        );
+        assert_eq!(
+            backend_url_from(args(&["app", "--backend-url=http://nas:8080"]), no_env),
+            Some("http://nas:8080".to_string())
+        );
+        assert_eq!(
+            backend_url_from(args(&["app", "--backend-url"]), no_env),
+            None
+        );
+        assert_eq!(
+            backend_url_from(args(&["app", "--backend-urls=x"]), no_env),
+            None
+        );
+
+        let env =
+            |key: &str| (key == BACKEND_URL_ENV).then(|| OsString::from(" http://docker:8080 "));
+        assert_eq!(
+            backend_url_from(args(&["app", "--backend-url", "localhost:5173"]), env),
+            Some("http://docker:8080".to_string())
+        );
+        let empty = |_: &str| Some(OsString::new());
+        assert_eq!(backend_url_from(args(&["app"]), empty), None);
+    }
+
+    #[test]
+    fn resume_route_requires_route_on_most_recent_connection() {
+        let mut settings = settings::DesktopSettings {
+            remote_connections: vec![
+                "http://10.0.0.5:8080/".to_string(),
+                "http://10.0.0.6:8080/".to_string(),
+            ],
+            last_remote_route: Some("http://10.0.0.5:8080/#/sessions/abc".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            resume_route(&settings).map(String::from),
+            Some("http://10.0.0.5:8080/#/sessions/abc".to_string())
+        );
+
+        settings.last_remote_route = Some("http://10.0.0.6:8080/#/sessions/abc".to_string());
+        assert_eq!(resume_route(&settings), None);
+
+        settings.last_remote_route = None;
+        assert_eq!(resume_route(&settings), None);
+    }
+
+    #[test]
+    fn ensure_remote_allowed_keeps_no_network_builds_on_loopback() {
+        for local in [
+            "http://localhost:5173/",
+            "http://127.0.0.1:8080/",
+            "http://[::1]:8080/",
+        ] {
+            let url = Url::parse(local).expect("valid url");
+            assert!(ensure_remote_allowed(&url).is_ok(), "{local}");
+        }
+        let lan = Url::parse("http://192.168.1.20:8080/").expect("valid url");
+        assert_eq!(
+            ensure_remote_allowed(&lan).is_ok(),
+            !cfg!(feature = "no-network")
+        );
+    }
+
+    #[test]
+    fn same_origin_compares_scheme_host_and_port() {
+        let base = Url::parse("http://10.0.0.5:8080/").expect("valid url");
+        let page = Url::parse("http://10.0.0.5:8080/sessions/abc").expect("valid url");
+        let other_port = Url::parse("http://10.0.0.5:9090/").expect("valid url");
+        let other_scheme = Url::parse("https://10.0.0.5:8080/").expect("valid url");
+        assert!(same_origin(&base, &page));
+        assert!(!same_origin(&base, &other_port));
+        assert!(!same_origin(&base, &other_scheme));
+    }
+
+    #[test]
+    fn probe_remote_accepts_agentsview_and_rejects_other_servers() {
+        fn serve_once(response: &'static [u8]) -> Url {
+            let listener = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0))
+                .expect("bind listener");
+            let port = listener.local_addr().expect("local addr").port();
+            thread::spawn(move || {
+                let (mut conn, _) = listener.accept().expect("accept");
+                let mut request = [0u8; 1024];
+                let _ = conn.read(&mut request);
+                let _ = conn.write_all(response);
+            });
+            Url::parse(&format!("http://127.0.0.1:{port}/")).expect("valid url")
+        }
+
+        let agentsview = serve_once(
+            b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n{\"version\":\"1\",\"commit\":\"a\",\"build_date\":\"b\"}",
+        );
+        assert!(probe_remote(&agentsview).is_ok());
+
+        let other = serve_once(b"HTTP/1.1 200 OK\r\nContent-Type: text/html\r\n\r\n<html>");
+        assert!(probe_remote(&other).is_err());
+    }
+
+    #[test]
+    fn https_upgrade_follows_redirects_to_https_on_the_same_host() {
+        let redirect = |location: &str| http_client::Response {
+            status: 308,
+            headers: vec![("Location".to_string(), location.to_string())],
+            body: Vec::new(),
+        };
+        let url = Url::parse("http://agents.example.com/").expect("valid url");
+        assert_eq!(
+            https_upgrade(
+                &url,
+                &redirect("https://agents.example.com:8443/api/v1/version")
+            )
+            .map(String::from),
+            Some("https://agents.example.com:8443/".to_string())
+        );
+        assert_eq!(
+            https_upgrade(&url, &redirect("https://evil.example.com/")),
+            None
+        );
+        assert_eq!(https_upgrade(&url, &redirect("/api/v2/version")), None);
+    }
+}
@@ -0,0 +1,239 @@
+use std::ffi::OsString;
+use std::sync::OnceLock;
+
+use tauri::AppHandle;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::i18n;
+use crate::settings::{self, DesktopSettings};
+
+const SAFE_MODE_ARG: &str = "--safe-mode";
+const SAFE_MODE_ENV: &str = "AGENTSVIEW_DESKTOP_SAFE_MODE";
+const WEBVIEW2_ARGS_ENV: &str = "WEBVIEW2_ADDITIONAL_BROWSER_ARGUMENTS";
+// WEBVIEW2_DEFAULT_ARGS are the arguments Tauri passes to WebView2
+// itself; setting WEBVIEW2_ADDITIONAL_BROWSER_ARGUMENTS replaces
+// them, so they are kept alongside the GPU switches.
+const WEBVIEW2_DEFAULT_ARGS: &str =
+    "--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection";
+const WEBVIEW2_SOFTWARE_ARGS: &str = "--disable-gpu --disable-gpu-compositing";
+// WEBKIT_SOFTWARE_ENV turns off WebKitGTK's DMA-BUF renderer and
+// accelerated compositing and forces Mesa onto its software
+// rasterizer.
+const WEBKIT_SOFTWARE_ENV: &[(&str, &str)] = &[
+    ("WEBKIT_DISABLE_DMABUF_RENDERER", "1"),
+    ("WEBKIT_DISABLE_COMPOSITING_MODE", "1"),
+    ("LIBGL_ALWAYS_SOFTWARE", "1"),
+];
+
+static RENDERING: OnceLock<Rendering> = OnceLock::new();
+static APPLIED: OnceLock<Vec<&'static str>> = OnceLock::new();
+
+// Rendering is how the webview draws this launch. Trial is
+// software rendering from a safe-mode launch that the user has not
+// yet chosen to keep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Rendering {
+    Hardware,
+    Software,
+    Trial,
+}
+
+// supported reports whether the webview can be switched to
+// software rendering here. WKWebView has no such switch.
+pub(crate) fn supported() -> bool {
+    !cfg!(target_os = "macos")
+}
+
+// rendering returns this launch's rendering mode: software when
+// the software_rendering setting is on, a trial when launched
+// with --safe-mode or AGENTSVIEW_DESKTOP_SAFE_MODE=1, and hardware
+// otherwise. The env var takes precedence over the argument.
+pub(crate) fn rendering() -> Rendering {
+    *RENDERING.get_or_init(|| {
+        rendering_from(
+            std::env::args_os(),
+            |key| std::env::var_os(key),
+            &settings::load_settings(),
+        )
+    })
+}
+
+fn rendering_from<A, F>(mut args: A, lookup: F, settings: &DesktopSettings) -> Rendering
+where
+    A: Iterator<Item = OsString>,
+    F: Fn(&str) -> Option<OsString>,
+{
+    if !supported() {
+        return Rendering::Hardware;
+    }
+    if settings.software_rendering {
+        return Rendering::Software;
+    }
+    let safe_mode = match lookup(SAFE_MODE_ENV) {
+        Some(value) => crate::mock::is_truthy_flag(Some(value.as_os_str())),
+        None => args.any(|arg| arg == SAFE_MODE_ARG),
+    };
+    if safe_mode {
+        Rendering::Trial
+    } else {
+        Rendering::Hardware
+    }
+}
+
+// apply sets the webview's software rendering environment when
+// this launch uses it. It must run before the webview starts,
+// while the process is still single-threaded. Variables the user
+// already set are left alone.
+pub(crate) fn apply() {
+    if rendering() == Rendering::Hardware {
+        return;
+    }
+    eprintln!("[agentsview] using software rendering");
+    let env = software_env(cfg!(windows), |key| std::env::var_os(key));
+    for (key, value) in &env {
+        std::env::set_var(key, value);
+    }
+    let _ = APPLIED.set(env.into_iter().map(|(key, _)| key).collect());
+}
+
+fn software_env<F>(windows: bool, lookup: F) -> Vec<(&'static str, OsString)>
+where
+    F: Fn(&str) -> Option<OsString>,
+{
+    if windows {
+        let args = match lookup(WEBVIEW2_ARGS_ENV).filter(|value| !value.is_empty()) {
+            Some(existing) => {
+                let mut args = existing;
+                args.push(" ");
+                args.push(WEBVIEW2_SOFTWARE_ARGS);
+                args
+            }
+            None => OsString::from(format!("{WEBVIEW2_DEFAULT_ARGS} {WEBVIEW2_SOFTWARE_ARGS}")),
+        };
+        return vec![(WEBVIEW2_ARGS_ENV, args)];
+    }
+    WEBKIT_SOFTWARE_ENV
+        .iter()
+        .filter(|(key, _)| lookup(key).is_none())
+        .map(|(key, value)| (*key, OsString::from(value)))
+        .collect()
+}
+
+// confirm_trial asks, in a native dialog that shows even when the
+// webview does not, whether to keep software rendering after a
+// safe-mode launch. Keeping it persists the software_rendering
+// setting.
+pub(crate) fn confirm_trial(app: &AppHandle) {
+    if rendering() != Rendering::Trial {
+        return;
+    }
+    app.dialog()
+        .message(i18n::tr("rendering-trial-message"))
+        .title(i18n::tr("rendering-trial-title"))
+        .kind(MessageDialogKind::Info)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            i18n::tr("rendering-trial-keep"),
+            i18n::tr("rendering-trial-discard"),
+        ))
+        .show(|keep| {
+            if !keep {
+                return;
+            }
+            if let Err(err) = settings::update_settings(|s| s.software_rendering = true) {
+                eprintln!("[agentsview] failed to save software rendering setting: {err}");
+            }
+        });
+}
+
+// relaunch restarts the app with software rendering switched on
+// or off. Switching it on relaunches in safe mode, which asks
+// before keeping it; switching it off clears the setting and the
+// environment this process set, so the new process inherits
+// neither.
+pub(crate) fn relaunch(app: &AppHandle, software: bool) {
+    if !software {
+        if let Err(err) = settings::update_settings(|s| s.software_rendering = false) {
+            eprintln!("[agentsview] failed to save software rendering setting: {err}");
+            return;
+        }
+        for key in APPLIED.get().into_iter().flatten() {
+            std::env::remove_var(key);
+        }
+    }
+    std::env::set_var(SAFE_MODE_ENV, if software { "1" } else { "0" });
+    app.request_restart();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> std::vec::IntoIter<OsString> {
+        list.iter()
+            .map(OsString::from)
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn rendering_from_prefers_setting_then_safe_mode() {
+        if !supported() {
+            return;
+        }
+        let none = |_: &str| None;
+        let defaults = DesktopSettings::default();
+        assert_eq!(
+            rendering_from(args(&["agentsview"]), none, &defaults),
+            Rendering::Hardware
+        );
+        assert_eq!(
+            rendering_from(args(&["agentsview", "--safe-mode"]), none, &defaults),
+            Rendering::Trial
+        );
+        let env_off = |key: &str| (key == SAFE_MODE_ENV).then(|| OsString::from("0"));
+        assert_eq!(
+            rendering_from(args(&["agentsview", "--safe-mode"]), env_off, &defaults),
+            Rendering::Hardware
+        );
+        let env_on = |key: &str| (key == SAFE_MODE_ENV).then(|| OsString::from("1"));
+        assert_eq!(
+            rendering_from(args(&["agentsview"]), env_on, &defaults),
+            Rendering::Trial
+        );
+        let software = DesktopSettings {
+            software_rendering: true,
+            ..DesktopSettings::default()
+        };
+        assert_eq!(
+            rendering_from(args(&["agentsview"]), env_off, &software),
+            Rendering::Software
+        );
+    }
+
+    #[test]
+    fn software_env_keeps_user_values() {
+        let linux = software_env(false, |key| {
+            (key == "LIBGL_ALWAYS_SOFTWARE").then(|| OsString::from("0"))
+        });
+        let keys: Vec<_> = linux.iter().map(|(key, _)| *key).collect();
+        assert_eq!(
+            keys,
+            [
+                "WEBKIT_DISABLE_DMABUF_RENDERER",
+                "WEBKIT_DISABLE_COMPOSITING_MODE"
+            ]
+        );
+
+        let windows = software_env(true, |_| None);
+        assert_eq!(windows[0].0, WEBVIEW2_ARGS_ENV);
+        assert_eq!(
+            windows[0].1,
+            OsString::from(format!("{WEBVIEW2_DEFAULT_ARGS} {WEBVIEW2_SOFTWARE_ARGS}"))
+        );
+        let extended = software_env(true, |_| Some(OsString::from("--lang=de")));
+        assert_eq!(
+            extended[0].1,
+            OsString::from(format!("--lang=de {WEBVIEW2_SOFTWARE_ARGS}"))
+        );
+    }
+}
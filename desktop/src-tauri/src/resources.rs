@@ -0,0 +1,174 @@
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use serde::Serialize;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Manager};
+
+use crate::i18n;
+use crate::settings;
+use crate::shutdown::ShutdownSignal;
+use crate::startup_dialog;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+// DEFAULT_MEMORY_ALERT_MB is the sidecar memory use that raises an
+// alert when memory_alert_mb is not set. Indexing even large
+// archives stays well below it.
+const DEFAULT_MEMORY_ALERT_MB: u64 = 4096;
+// REARM_RATIO is how far below the limit memory use has to fall
+// after an alert before the same sidecar can alert again, so use
+// hovering around the limit alerts once.
+const REARM_RATIO: f64 = 0.8;
+const MB: u64 = 1024 * 1024;
+
+// SidecarUsage is the sidecar's resident memory and CPU use at the
+// last poll. cpu_percent is relative to one core, so it exceeds
+// 100 when the sidecar keeps several cores busy.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub(crate) struct SidecarUsage {
+    pid: u32,
+    rss_bytes: u64,
+    cpu_percent: f32,
+}
+
+#[derive(Default)]
+pub(crate) struct ResourceState {
+    latest: Mutex<Option<SidecarUsage>>,
+}
+
+// sidecar_resources returns the sidecar's usage at the last poll,
+// or None when no local sidecar is running.
+#[tauri::command]
+pub(crate) fn sidecar_resources(app: AppHandle) -> Option<SidecarUsage> {
+    app.state::<ResourceState>()
+        .latest
+        .lock()
+        .ok()
+        .and_then(|latest| *latest)
+}
+
+// spawn_monitor samples the running sidecar every POLL_INTERVAL
+// and warns once its memory use crosses the memory_alert_mb
+// setting, offering to restart it.
+pub(crate) fn spawn_monitor(app: AppHandle) {
+    let shutdown = app.state::<ShutdownSignal>().inner().clone();
+    thread::spawn(move || {
+        let mut system = System::new();
+        let mut watch = MemoryWatch::default();
+        loop {
+            let usage = crate::current_sidecar_pid(&app).and_then(|pid| sample(&mut system, pid));
+            if let Ok(mut latest) = app.state::<ResourceState>().latest.lock() {
+                *latest = usage;
+            }
+            if let Some(usage) = usage {
+                let limit = memory_limit();
+                if watch.observe(usage.pid, usage.rss_bytes, limit) {
+                    alert(&app, usage, limit.unwrap_or_default());
+                }
+            }
+            if shutdown.wait(POLL_INTERVAL) {
+                return;
+            }
+        }
+    });
+}
+
+fn sample(system: &mut System, pid: u32) -> Option<SidecarUsage> {
+    let sys_pid = Pid::from_u32(pid);
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[sys_pid]),
+        true,
+        ProcessRefreshKind::nothing().with_memory().with_cpu(),
+    );
+    let process = system.process(sys_pid)?;
+    Some(SidecarUsage {
+        pid,
+        rss_bytes: process.memory(),
+        cpu_percent: process.cpu_usage(),
+    })
+}
+
+// memory_limit is the memory_alert_mb setting in bytes. A setting
+// of 0 turns the alert off.
+fn memory_limit() -> Option<u64> {
+    match settings::load_settings()
+        .memory_alert_mb
+        .unwrap_or(DEFAULT_MEMORY_ALERT_MB)
+    {
+        0 => None,
+        mb => Some(mb.saturating_mul(MB)),
+    }
+}
+
+fn alert(app: &AppHandle, usage: SidecarUsage, limit: u64) {
+    let used_mb = usage.rss_bytes / MB;
+    let limit_mb = limit / MB;
+    eprintln!("[agentsview] backend is using {used_mb} MB of memory, over the {limit_mb} MB limit");
+    let message = i18n::tr_args(
+        "memory-alert-message",
+        &[("used", &used_mb), ("limit", &limit_mb)],
+    );
+    let restart_app = app.clone();
+    startup_dialog::show_memory_alert(app, message, move || {
+        // The sidecar may have been restarted while the dialog was
+        // open; a new one starts with fresh memory.
+        if crate::current_sidecar_pid(&restart_app) != Some(usage.pid) {
+            return;
+        }
+        if let Some(window) = restart_app.get_webview_window("main") {
+            crate::restart_backend(&restart_app, &window);
+        }
+    });
+}
+
+// MemoryWatch decides when memory use is worth an alert: when a
+// sidecar first crosses the limit, and again only after its use
+// has fallen back below REARM_RATIO of the limit.
+#[derive(Debug, Default)]
+struct MemoryWatch {
+    alerted_pid: Option<u32>,
+}
+
+impl MemoryWatch {
+    fn observe(&mut self, pid: u32, rss_bytes: u64, limit: Option<u64>) -> bool {
+        let Some(limit) = limit else {
+            self.alerted_pid = None;
+            return false;
+        };
+        if self.alerted_pid == Some(pid) {
+            if (rss_bytes as f64) < limit as f64 * REARM_RATIO {
+                self.alerted_pid = None;
+            }
+            return false;
+        }
+        if rss_bytes >= limit {
+            self.alerted_pid = Some(pid);
+            return true;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_watch_alerts_once_per_crossing() {
+        let limit = Some(1000 * MB);
+        let mut watch = MemoryWatch::default();
+        assert!(!watch.observe(1, 900 * MB, limit));
+        assert!(watch.observe(1, 1000 * MB, limit));
+        assert!(!watch.observe(1, 1200 * MB, limit));
+        assert!(!watch.observe(1, 900 * MB, limit));
+        assert!(!watch.observe(1, 1100 * MB, limit));
+
+        assert!(!watch.observe(1, 700 * MB, limit));
+        assert!(watch.observe(1, 1100 * MB, limit));
+
+        // A restarted sidecar alerts on its own.
+        assert!(watch.observe(2, 1100 * MB, limit));
+        assert!(!watch.observe(2, 1100 * MB, None));
+    }
+}
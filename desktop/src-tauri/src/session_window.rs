@@ -0,0 +1,149 @@
+use tauri::{AppHandle, Manager, Url, WebviewUrl, WebviewWindowBuilder};
+
+use crate::backend_client::encode_query_component;
+use crate::downloads;
+use crate::error::DesktopError;
+use crate::hardening;
+use crate::kiosk;
+use crate::remote::RemoteState;
+use crate::socket_proxy;
+use crate::zoom;
+
+// LABEL_PREFIX starts the label of every session window; the rest
+// is the session id, so each session gets at most one.
+const LABEL_PREFIX: &str = "session-";
+
+// open_session_window opens a session in a window of its own, next
+// to the main one, for reviewing sessions side by side.
+#[tauri::command]
+pub(crate) fn open_session_window(app: AppHandle, session_id: String) -> Result<(), DesktopError> {
+    open(&app, &session_id)
+}
+
+// open shows session_id in a new window on the backend the main
+// window uses, sharing its sidecar. A session that already has a
+// window is brought forward instead.
+pub(crate) fn open(app: &AppHandle, session_id: &str) -> Result<(), DesktopError> {
+    let unavailable = |reason: &str| DesktopError::ActionUnavailable {
+        action: "open_session_window".to_string(),
+        reason: reason.to_string(),
+    };
+    if session_id.trim().is_empty() {
+        return Err(unavailable("no session id was given"));
+    }
+    if kiosk::config().is_some() {
+        return Err(unavailable("kiosk mode shows a single window"));
+    }
+    let label = window_label(session_id);
+    if let Some(window) = app.get_webview_window(&label) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+        return Ok(());
+    }
+    let base = backend_base(app).ok_or_else(|| unavailable("the backend is not running"))?;
+    let url = session_url(&base, session_id)
+        .ok_or_else(|| unavailable(&format!("invalid backend URL {base}")))?;
+
+    // The main window's entry in tauri.conf.json supplies the size
+    // limits and the webview data directory, whose cookies admit the
+    // window to a proxied sidecar.
+    let mut config = app
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|w| w.label == "main")
+        .cloned()
+        .ok_or(DesktopError::MissingWindow { label: "main" })?;
+    config.label = label;
+    config.url = WebviewUrl::External(url);
+    config.create = true;
+    config.visible = true;
+    let window = WebviewWindowBuilder::from_config(app, &config)
+        .and_then(|builder| {
+            builder
+                .devtools(hardening::devtools_enabled())
+                .user_agent(hardening::user_agent())
+                .on_download(downloads::on_download)
+                .build()
+        })
+        .map_err(|err| unavailable(&err.to_string()))?;
+    zoom::apply_saved(&window);
+    Ok(())
+}
+
+// is_session_window reports whether label names a window opened by
+// open_session_window.
+pub(crate) fn is_session_window(label: &str) -> bool {
+    label.starts_with(LABEL_PREFIX)
+}
+
+// backend_base is the page that opens the backend the main window
+// uses: the attached remote server, or else the local sidecar,
+// through the proxy's login URL when it has one.
+fn backend_base(app: &AppHandle) -> Option<String> {
+    if let Some(origin) = app.state::<RemoteState>().origin() {
+        return Some(origin.to_string());
+    }
+    let port = crate::current_sidecar_port(app)?;
+    Some(socket_proxy::login_url(crate::local_url(port)))
+}
+
+// session_url points base at the SPA route of a session. The proxy
+// keeps the fragment when it trades its login URL for a cookie.
+fn session_url(base: &str, session_id: &str) -> Option<Url> {
+    let mut url = Url::parse(base).ok()?;
+    url.set_fragment(Some(&format!(
+        "/sessions?session={}",
+        encode_query_component(session_id)
+    )));
+    Some(url)
+}
+
+// window_label derives a window label from a session id, replacing
+// the characters labels do not allow.
+fn window_label(session_id: &str) -> String {
+    let id: String = session_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{LABEL_PREFIX}{id}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_url_opens_the_session_route_on_the_backend() {
+        assert_eq!(
+            session_url("http://127.0.0.1:8080", "codex:a/b")
+                .unwrap()
+                .as_str(),
+            "http://127.0.0.1:8080/#/sessions?session=codex%3Aa%2Fb"
+        );
+        assert_eq!(
+            session_url("http://127.0.0.1:8080/?agentsview_proxy=s3cret", "abc")
+                .unwrap()
+                .as_str(),
+            "http://127.0.0.1:8080/?agentsview_proxy=s3cret#/sessions?session=abc"
+        );
+        assert_eq!(
+            session_url("https://agents.example.com/", "abc")
+                .unwrap()
+                .as_str(),
+            "https://agents.example.com/#/sessions?session=abc"
+        );
+
+        assert_eq!(window_label("codex:a.b/c"), "session-codex:a_b_c");
+        assert!(is_session_window(&window_label("abc")));
+        assert!(!is_session_window("main"));
+    }
+}
@@ -0,0 +1,437 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::DesktopError;
+use crate::permissions::{Capability, Grant};
+use crate::ports::PortRange;
+
+const SETTINGS_FILE: &str = "desktop-settings.json";
+// CONFIG_DIR_NAME is the shell's directory in the platform config
+// directory, the bundle identifier as Tauri's app_config_dir uses.
+const CONFIG_DIR_NAME: &str = "io.agentsview.desktop";
+// CONFIG_ENTRIES are what the shell keeps in its config directory.
+const CONFIG_ENTRIES: &[&str] = &[SETTINGS_FILE, "desktop.env", "desktop.env.d"];
+const MAX_REMOTE_CONNECTIONS: usize = 8;
+
+// DesktopSettings holds shell preferences persisted across
+// launches in desktop-settings.json in config_dir. Unknown or
+// missing fields fall back to defaults so older files keep
+// loading as settings are added.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct DesktopSettings {
+    // sidecar_path overrides the bundled agentsview binary, set
+    // when the user locates it manually after a failed launch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) sidecar_path: Option<PathBuf>,
+    // sidecar_args are extra arguments appended to the sidecar's
+    // serve command line; see sidecar::serve_args.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) sidecar_args: Vec<String>,
+    // sidecar_data_dir moves the backend's data directory, passed
+    // on as AGENT_VIEWER_DATA_DIR; see crate::data_dir.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) sidecar_data_dir: Option<PathBuf>,
+    // direnv_dir is a project directory whose .envrc is evaluated
+    // with direnv and merged into the sidecar environment; see
+    // env::read_direnv_env.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) direnv_dir: Option<PathBuf>,
+    // login_env_cache_secs is how long the cached login-shell env
+    // is used before the shell is probed again (0 disables); see
+    // env::read_login_shell_env.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) login_env_cache_secs: Option<u64>,
+    // env_redact_patterns adds key patterns whose values are hidden
+    // in env diagnostics; see env::Redactor.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) env_redact_patterns: Vec<String>,
+    // sidecar_ports limits the ports the sidecar may listen on;
+    // see ports::range.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) sidecar_ports: Option<PortRange>,
+    // last_sidecar_port is the port the sidecar last served on,
+    // tried first on the next launch; see ports::allocate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) last_sidecar_port: Option<u16>,
+    // sidecar_low_priority runs the sidecar below normal
+    // scheduling priority; see process_tree::spawn.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) sidecar_low_priority: bool,
+    // sidecar_socket serves the sidecar on a private Unix socket,
+    // reached through socket_proxy, instead of a loopback port.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) sidecar_socket: bool,
+    // share_on_lan lets a -host in sidecar_args put the backend on
+    // all interfaces, guarded by lan_auth_token; see
+    // sidecar::lan_host.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) share_on_lan: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) lan_auth_token: Option<String>,
+    // auth_proxy_port opens an authenticating proxy to the backend
+    // on that port, on auth_proxy_host (loopback by default), that
+    // admits clients presenting auth_proxy_token; see
+    // auth_proxy::start.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) auth_proxy_port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) auth_proxy_host: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) auth_proxy_token: Option<String>,
+    // always_spawn_backend starts the bundled backend even when an
+    // agentsview server already answers on the preferred port; see
+    // crate::attach_running_server.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) always_spawn_backend: bool,
+    // memory_alert_mb is the sidecar memory use, in megabytes, that
+    // raises a warning; 0 turns it off. See resources::spawn_monitor.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) memory_alert_mb: Option<u64>,
+    // remote_connections lists agentsview servers the mobile shell
+    // has attached to, most recent first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) remote_connections: Vec<String>,
+    // last_remote_route is the page last shown on the most recent
+    // remote connection, restored when the OS relaunches the app.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) last_remote_route: Option<String>,
+    // remote_ca_bundle is a PEM file of extra CAs trusted when
+    // probing https remotes; see tls::client_config.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) remote_ca_bundle: Option<PathBuf>,
+    // remote_cert_pins are SHA-256 digests of remote server
+    // certificates, honored by development builds only.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) remote_cert_pins: Vec<String>,
+    // capability_grants records the user's answer to each
+    // capability explanation; absent capabilities are undecided.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) capability_grants: BTreeMap<Capability, Grant>,
+    // kiosk_mode locks the shell down for demo machines and
+    // mounted dashboards; see kiosk::config.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) kiosk_mode: bool,
+    // kiosk_idle_secs is how long kiosk mode waits without input
+    // before returning to the dashboard (0 disables).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) kiosk_idle_secs: Option<u64>,
+    // software_rendering draws the webview without the GPU on
+    // machines where it renders black; see rendering::rendering.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) software_rendering: bool,
+    // linux_app_id, linux_decorations, and linux_force_x11 set up
+    // the Linux window before GTK starts; see linux::apply.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) linux_app_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) linux_decorations: Option<Decorations>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) linux_force_x11: bool,
+    // emulation_warning_dismissed stops the warning about running
+    // an x64 build on ARM64 Windows; see arch::warn_if_emulated.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) emulation_warning_dismissed: bool,
+    // weekly_summary opts into the weekly usage notification; see
+    // weekly::spawn_scheduler.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) weekly_summary: bool,
+    // last_weekly_summary is when the weekly summary was last
+    // posted, in seconds since the Unix epoch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) last_weekly_summary: Option<u64>,
+    // daily_cost_limit and weekly_cost_limit are spending limits in
+    // US dollars; see costs::spawn_poller.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) daily_cost_limit: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) weekly_cost_limit: Option<f64>,
+    // acknowledged_cost_alerts lists the periods whose cost alert
+    // the user has acknowledged, e.g. "daily:2026-03-14".
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) acknowledged_cost_alerts: Vec<String>,
+    // cost_alerts_snoozed_until silences cost alerts until this
+    // time, in seconds since the Unix epoch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) cost_alerts_snoozed_until: Option<u64>,
+    // allowed_origins are origins, such as a dev server, the window
+    // may navigate to besides the backend; see navigation::allowlist.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) allowed_origins: Vec<String>,
+    // external_allow_domains and external_deny_domains limit which
+    // links open in the system browser; see navigation::ExternalUrlLists.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) external_allow_domains: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) external_deny_domains: Vec<String>,
+    // window_states holds each window's placement when it was last
+    // closed, by window label; see window_state::restore.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) window_states: BTreeMap<String, WindowGeometry>,
+    // zoom is the webview zoom factor set with Cmd/Ctrl and +, - or
+    // 0, when it is not 100%; see zoom::change_zoom.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) zoom: Option<f64>,
+}
+
+// Decorations picks who draws a Linux window's frame: GTK inside
+// the window (client) or the window manager or compositor
+// (server).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Decorations {
+    Client,
+    Server,
+}
+
+// WindowGeometry is where a window was and how big, in physical
+// pixels, the display it was on, and whether it was maximized. The
+// position and size are those it returns to from maximized.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct WindowGeometry {
+    pub(crate) x: i32,
+    pub(crate) y: i32,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) maximized: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) monitor: Option<String>,
+}
+
+impl DesktopSettings {
+    // remember_connection moves url to the front of the recent
+    // connections list, dropping duplicates and the oldest
+    // entries beyond MAX_REMOTE_CONNECTIONS.
+    pub(crate) fn remember_connection(&mut self, url: &str) {
+        self.remote_connections.retain(|existing| existing != url);
+        self.remote_connections.insert(0, url.to_string());
+        self.remote_connections.truncate(MAX_REMOTE_CONNECTIONS);
+    }
+}
+
+pub(crate) fn settings_path() -> Option<PathBuf> {
+    Some(config_dir()?.join(SETTINGS_FILE))
+}
+
+// config_dir holds desktop-settings.json, desktop.env and
+// desktop.env.d: $XDG_CONFIG_HOME on Linux, Application Support
+// on macOS and %APPDATA% on Windows. Setups that only have config
+// in the legacy ~/.agentsview keep using it.
+pub(crate) fn config_dir() -> Option<PathBuf> {
+    let home = crate::resolve_home_dir()?;
+    let platform = platform_config_dir(&home, std::env::consts::OS, |key| std::env::var_os(key));
+    Some(choose_config_dir(
+        platform,
+        home.join(".agentsview"),
+        |dir| CONFIG_ENTRIES.iter().any(|name| dir.join(name).exists()),
+    ))
+}
+
+fn platform_config_dir<F>(home: &Path, os: &str, lookup: F) -> PathBuf
+where
+    F: Fn(&str) -> Option<OsString>,
+{
+    let var = |key: &str| {
+        lookup(key)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    };
+    let base = match os {
+        "macos" => home.join("Library").join("Application Support"),
+        "windows" => var("APPDATA").unwrap_or_else(|| home.join("AppData").join("Roaming")),
+        // The XDG spec says relative paths are to be ignored.
+        _ => var("XDG_CONFIG_HOME")
+            .filter(|dir| dir.is_absolute())
+            .unwrap_or_else(|| home.join(".config")),
+    };
+    base.join(CONFIG_DIR_NAME)
+}
+
+// choose_config_dir keeps legacy while it has config and platform
+// has none, so upgrading never loses settings; moving the files
+// over switches to platform.
+fn choose_config_dir<F>(platform: PathBuf, legacy: PathBuf, has_config: F) -> PathBuf
+where
+    F: Fn(&Path) -> bool,
+{
+    if !has_config(&platform) && has_config(&legacy) {
+        return legacy;
+    }
+    platform
+}
+
+// load_settings reads the settings file, returning defaults when
+// it is missing or unreadable so a bad file never blocks startup.
+pub(crate) fn load_settings() -> DesktopSettings {
+    settings_path()
+        .map(|path| load_settings_from(&path))
+        .unwrap_or_default()
+}
+
+fn load_settings_from(path: &Path) -> DesktopSettings {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return DesktopSettings::default(),
+        Err(err) => {
+            eprintln!(
+                "[agentsview] failed to read {}: {err}; using defaults",
+                path.display()
+            );
+            return DesktopSettings::default();
+        }
+    };
+    serde_json::from_str(&content).unwrap_or_else(|err| {
+        eprintln!(
+            "[agentsview] ignoring invalid settings file {}: {err}",
+            path.display()
+        );
+        DesktopSettings::default()
+    })
+}
+
+// update_settings applies a change to the current settings and
+// persists the result atomically.
+pub(crate) fn update_settings<F>(change: F) -> Result<DesktopSettings, DesktopError>
+where
+    F: FnOnce(&mut DesktopSettings),
+{
+    let path = settings_path().ok_or_else(|| {
+        DesktopError::Io(io::Error::new(
+            io::ErrorKind::NotFound,
+            "could not resolve home directory for desktop settings",
+        ))
+    })?;
+    let mut settings = load_settings_from(&path);
+    change(&mut settings);
+    save_settings_to(&path, &settings)?;
+    Ok(settings)
+}
+
+// save_settings_to writes to a temp file in the same directory
+// and renames it into place so a crash never leaves a truncated
+// settings file behind.
+fn save_settings_to(path: &Path, settings: &DesktopSettings) -> io::Result<()> {
+    let dir = path
+        .parent()
+        .ok_or_else(|| io::Error::other("settings path has no parent directory"))?;
+    fs::create_dir_all(dir)?;
+
+    let json = serde_json::to_vec_pretty(settings).map_err(io::Error::other)?;
+    let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+    tmp.write_all(&json)?;
+    tmp.write_all(b"\n")?;
+    tmp.as_file().sync_all()?;
+    tmp.persist(path).map_err(|err| err.error)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_dir_follows_platform_and_honors_legacy_config() {
+        let home = Path::new("/home/me");
+        let env = |key: &str| match key {
+            "XDG_CONFIG_HOME" => Some(OsString::from("/xdg")),
+            "APPDATA" => Some(OsString::from("/roaming")),
+            _ => None,
+        };
+        assert_eq!(
+            platform_config_dir(home, "linux", env),
+            Path::new("/xdg/io.agentsview.desktop")
+        );
+        assert_eq!(
+            platform_config_dir(home, "linux", |_| Some(OsString::from("rel"))),
+            Path::new("/home/me/.config/io.agentsview.desktop")
+        );
+        assert_eq!(
+            platform_config_dir(home, "macos", env),
+            Path::new("/home/me/Library/Application Support/io.agentsview.desktop")
+        );
+        assert_eq!(
+            platform_config_dir(home, "windows", env),
+            Path::new("/roaming/io.agentsview.desktop")
+        );
+
+        let platform = PathBuf::from("/xdg/io.agentsview.desktop");
+        let legacy = PathBuf::from("/home/me/.agentsview");
+        let choose = |with_config: &[&Path]| {
+            choose_config_dir(platform.clone(), legacy.clone(), |dir| {
+                with_config.contains(&dir)
+            })
+        };
+        assert_eq!(choose(&[]), platform);
+        assert_eq!(choose(&[&legacy]), legacy);
+        assert_eq!(choose(&[&legacy, &platform]), platform);
+    }
+
+    #[test]
+    fn load_settings_from_defaults_when_missing_or_invalid() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join(SETTINGS_FILE);
+        assert_eq!(load_settings_from(&path), DesktopSettings::default());
+
+        fs::write(&path, "{not json").expect("write invalid settings");
+        assert_eq!(load_settings_from(&path), DesktopSettings::default());
+
+        fs::write(&path, r#"{"unknown_field": true}"#).expect("write settings");
+        assert_eq!(load_settings_from(&path), DesktopSettings::default());
+    }
+
+    #[test]
+    fn save_settings_to_round_trips_and_creates_parent() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("nested").join(SETTINGS_FILE);
+        let settings = DesktopSettings {
+            sidecar_path: Some(PathBuf::from("/opt/agentsview/bin/agentsview")),
+            sidecar_args: vec!["-verbose".to_string()],
+            sidecar_data_dir: Some(PathBuf::from("/data/agentsview")),
+            remote_connections: vec!["http://192.168.1.20:8080/".to_string()],
+            last_remote_route: Some("http://192.168.1.20:8080/#/sessions/abc".to_string()),
+            capability_grants: BTreeMap::from([(Capability::Notifications, Grant::Denied)]),
+            kiosk_mode: true,
+            kiosk_idle_secs: Some(120),
+            ..Default::default()
+        };
+        save_settings_to(&path, &settings).expect("save settings");
+        assert_eq!(load_settings_from(&path), settings);
+
+        save_settings_to(&path, &DesktopSettings::default()).expect("overwrite settings");
+        let raw = fs::read_to_string(&path).expect("read settings");
+        assert!(!raw.contains("sidecar_path"));
+        assert!(!raw.contains("sidecar_args"));
+        assert!(!raw.contains("remote_connections"));
+        assert!(!raw.contains("capability_grants"));
+        assert!(!raw.contains("kiosk_mode"));
+    }
+
+    #[test]
+    fn remember_connection_dedupes_and_caps_recent_list() {
+        let mut settings = DesktopSettings::default();
+        for i in 0..MAX_REMOTE_CONNECTIONS + 2 {
+            settings.remember_connection(&format!("http://host-{i}:8080/"));
+        }
+        settings.remember_connection("http://host-5:8080/");
+
+        assert_eq!(settings.remote_connections.len(), MAX_REMOTE_CONNECTIONS);
+        assert_eq!(settings.remote_connections[0], "http://host-5:8080/");
+        assert_eq!(
+            settings
+                .remote_connections
+                .iter()
+                .filter(|url| url.as_str() == "http://host-5:8080/")
+                .count(),
+            1
+        );
+        assert!(!settings
+            .remote_connections
+            .contains(&"http://host-0:8080/".to_string()));
+    }
+}
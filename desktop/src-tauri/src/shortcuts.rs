@@ -0,0 +1,139 @@
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder, Wry};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::kiosk;
+use crate::permissions::{self, Capability};
+use crate::{annotate, palette};
+
+// BINDINGS maps each global shortcut to the popup it opens. They
+// work while any app is focused.
+const BINDINGS: [(&str, fn(&AppHandle)); 2] = [
+    ("CmdOrCtrl+Shift+K", palette::open_palette),
+    ("CmdOrCtrl+Alt+N", annotate::open_annotate),
+];
+
+// POPUPS lists every popup window, so focus handling can find them
+// by label.
+const POPUPS: [&Popup; 2] = [&palette::POPUP, &annotate::POPUP];
+
+// Popup describes a small undecorated window opened by a global
+// shortcut. It stays above other apps and is hidden, not closed,
+// when it loses focus so reopening is instant.
+pub(crate) struct Popup {
+    pub(crate) label: &'static str,
+    pub(crate) page: &'static str,
+    pub(crate) width: f64,
+    pub(crate) height: f64,
+}
+
+// init_plugin installs the global shortcut handler. Shortcuts are
+// registered by register_all once the user has allowed global
+// shortcuts.
+pub(crate) fn init_plugin() -> TauriPlugin<Wry> {
+    tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, shortcut, event| {
+            if event.state() != ShortcutState::Pressed {
+                return;
+            }
+            if let Some((_, open)) = BINDINGS
+                .iter()
+                .find(|(accelerator, _)| matches_accelerator(shortcut, accelerator))
+            {
+                open(app);
+            }
+        })
+        .build()
+}
+
+fn matches_accelerator(shortcut: &Shortcut, accelerator: &str) -> bool {
+    accelerator
+        .parse::<Shortcut>()
+        .is_ok_and(|bound| bound.id() == shortcut.id())
+}
+
+// register_all binds every shortcut after the global shortcuts
+// explanation has been accepted. Kiosk machines have none.
+pub(crate) fn register_all(app: &AppHandle) {
+    if kiosk::config().is_some() {
+        return;
+    }
+    let handle = app.clone();
+    permissions::ensure_capability(app, Capability::GlobalShortcuts, move |granted| {
+        if !granted {
+            return;
+        }
+        for (accelerator, _) in BINDINGS {
+            if let Err(err) = handle.global_shortcut().register(accelerator) {
+                eprintln!("[agentsview] failed to register {accelerator}: {err}");
+            }
+        }
+    });
+}
+
+// show_popup centers and focuses popup, creating its window on
+// first use.
+pub(crate) fn show_popup(app: &AppHandle, popup: &Popup) {
+    let window = match app.get_webview_window(popup.label) {
+        Some(window) => window,
+        None => {
+            let built =
+                WebviewWindowBuilder::new(app, popup.label, WebviewUrl::App(popup.page.into()))
+                    .title("AgentsView")
+                    .inner_size(popup.width, popup.height)
+                    .resizable(false)
+                    .decorations(false)
+                    .always_on_top(true)
+                    .skip_taskbar(true)
+                    .visible(false)
+                    .devtools(crate::hardening::devtools_enabled())
+                    .build();
+            match built {
+                Ok(window) => window,
+                Err(err) => {
+                    eprintln!("[agentsview] failed to open {} window: {err}", popup.label);
+                    return;
+                }
+            }
+        }
+    };
+    let _ = window.center();
+    let _ = window.show();
+    let _ = window.set_focus();
+}
+
+pub(crate) fn hide_popup(app: &AppHandle, popup: &Popup) {
+    if let Some(window) = app.get_webview_window(popup.label) {
+        let _ = window.hide();
+    }
+}
+
+// is_popup reports whether label names a popup window.
+pub(crate) fn is_popup(label: &str) -> bool {
+    POPUPS.iter().any(|popup| popup.label == label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bindings_parse_and_are_distinct() {
+        for (index, (accelerator, _)) in BINDINGS.iter().enumerate() {
+            let shortcut = accelerator
+                .parse::<Shortcut>()
+                .unwrap_or_else(|err| panic!("invalid shortcut {accelerator}: {err}"));
+            assert!(matches_accelerator(&shortcut, accelerator));
+            for (other, _) in &BINDINGS[index + 1..] {
+                assert!(!matches_accelerator(&shortcut, other));
+            }
+        }
+    }
+
+    #[test]
+    fn is_popup_matches_popup_labels() {
+        assert!(is_popup("palette"));
+        assert!(is_popup("annotate"));
+        assert!(!is_popup("main"));
+    }
+}
@@ -0,0 +1,118 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+// ShutdownSignal is set once the app starts exiting. Startup and
+// keep-alive threads wait on it instead of sleeping so they stop
+// immediately rather than touching a destroyed window. Clones
+// share the same signal.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ShutdownSignal(Arc<(Mutex<bool>, Condvar)>);
+
+impl ShutdownSignal {
+    pub(crate) fn trigger(&self) {
+        let (lock, cvar) = &*self.0;
+        if let Ok(mut triggered) = lock.lock() {
+            *triggered = true;
+        }
+        cvar.notify_all();
+    }
+
+    pub(crate) fn is_triggered(&self) -> bool {
+        self.0 .0.lock().map(|triggered| *triggered).unwrap_or(true)
+    }
+
+    // wait blocks for up to timeout and returns true if shutdown
+    // was signalled, either before or during the wait.
+    pub(crate) fn wait(&self, timeout: Duration) -> bool {
+        let (lock, cvar) = &*self.0;
+        let deadline = Instant::now() + timeout;
+        let Ok(mut triggered) = lock.lock() else {
+            return true;
+        };
+        while !*triggered {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return false;
+            }
+            triggered = match cvar.wait_timeout(triggered, remaining) {
+                Ok((guard, _)) => guard,
+                Err(_) => return true,
+            };
+        }
+        true
+    }
+}
+
+// ExitGate holds the app's exit until the backend has been asked
+// to stop and has had time to finish writing.
+#[derive(Debug, Default)]
+pub(crate) struct ExitGate {
+    stopping: AtomicBool,
+    open: AtomicBool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum ExitHold {
+    // Open lets the exit proceed; the backend has stopped.
+    Open,
+    // Start holds the exit; the caller must stop the backend and
+    // then exit again.
+    Start,
+    // Pending holds the exit while an earlier request stops the
+    // backend.
+    Pending,
+}
+
+impl ExitGate {
+    pub(crate) fn request(&self) -> ExitHold {
+        if self.open.load(Ordering::SeqCst) {
+            ExitHold::Open
+        } else if !self.stopping.swap(true, Ordering::SeqCst) {
+            ExitHold::Start
+        } else {
+            ExitHold::Pending
+        }
+    }
+
+    pub(crate) fn open(&self) {
+        self.open.store(true, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn wait_times_out_when_not_triggered() {
+        let signal = ShutdownSignal::default();
+        assert!(!signal.wait(Duration::from_millis(20)));
+        assert!(!signal.is_triggered());
+    }
+
+    #[test]
+    fn trigger_wakes_waiters_early() {
+        let signal = ShutdownSignal::default();
+        let waiter = signal.clone();
+        let started = Instant::now();
+        let handle = thread::spawn(move || waiter.wait(Duration::from_secs(10)));
+        thread::sleep(Duration::from_millis(20));
+        signal.trigger();
+
+        assert!(handle.join().expect("waiter thread"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(signal.is_triggered());
+        assert!(signal.wait(Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn exit_gate_holds_until_opened() {
+        let gate = ExitGate::default();
+        assert_eq!(gate.request(), ExitHold::Start);
+        assert_eq!(gate.request(), ExitHold::Pending);
+        gate.open();
+        assert_eq!(gate.request(), ExitHold::Open);
+    }
+}
@@ -0,0 +1,529 @@
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n;
+
+const SIDECAR_NAME: &str = "agentsview";
+// RESERVED_FLAGS are the serve flags the shell sets itself: it
+// needs the backend on loopback, on the port or socket it picked,
+// without opening a browser.
+const RESERVED_FLAGS: [&str; 4] = ["host", "port", "no-browser", "socket"];
+// READY_EVENT_ENV asks the sidecar to announce the port it bound
+// as a JSON line on stdout; see parse_ready_event.
+pub(crate) const READY_EVENT_ENV: &str = "AGENT_VIEWER_READY_EVENT";
+// AUTH_TOKEN_ENV hands lan_auth_token to a sidecar shared on the
+// LAN, which then requires it from clients not on this machine.
+pub(crate) const AUTH_TOKEN_ENV: &str = "AGENT_VIEWER_AUTH_TOKEN";
+// MIN_AUTH_TOKEN_LEN is the shortest lan_auth_token or
+// auth_proxy_token accepted.
+pub(crate) const MIN_AUTH_TOKEN_LEN: usize = 16;
+
+// CandidateStatus describes what the shell found at one of the
+// locations it searched for the sidecar binary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CandidateStatus {
+    Missing,
+    NotExecutable,
+    Present,
+}
+
+impl CandidateStatus {
+    fn label(self) -> String {
+        i18n::tr(match self {
+            Self::Missing => "sidecar-status-missing",
+            Self::NotExecutable => "sidecar-status-not-executable",
+            Self::Present => "sidecar-status-found",
+        })
+    }
+}
+
+// sidecar_candidate_paths lists where the sidecar may live: next
+// to the desktop executable (how tauri bundles externalBin), the
+// user's persisted override, and in dev builds the target-triple
+// suffixed copy produced by prepare-sidecar.
+pub(crate) fn sidecar_candidate_paths(
+    exe_dir: Option<&Path>,
+    override_path: Option<&Path>,
+    dev_binaries_dir: Option<&Path>,
+    target_triple: &str,
+    is_windows: bool,
+) -> Vec<PathBuf> {
+    let ext = if is_windows { ".exe" } else { "" };
+    let mut paths = Vec::new();
+    if let Some(path) = override_path {
+        paths.push(path.to_path_buf());
+    }
+    if let Some(dir) = exe_dir {
+        paths.push(dir.join(format!("{SIDECAR_NAME}{ext}")));
+    }
+    if let Some(dir) = dev_binaries_dir {
+        paths.push(dir.join(format!("{SIDECAR_NAME}-{target_triple}{ext}")));
+    }
+    paths
+}
+
+pub(crate) fn candidate_status(path: &Path) -> CandidateStatus {
+    let Ok(meta) = fs::metadata(path) else {
+        return CandidateStatus::Missing;
+    };
+    if !meta.is_file() {
+        return CandidateStatus::Missing;
+    }
+    if is_executable(&meta) {
+        CandidateStatus::Present
+    } else {
+        CandidateStatus::NotExecutable
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(meta: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    meta.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_meta: &fs::Metadata) -> bool {
+    true
+}
+
+// checked_sidecar_paths resolves the candidate list for this
+// process and reports the status of each path.
+pub(crate) fn checked_sidecar_paths(
+    override_path: Option<&Path>,
+) -> Vec<(PathBuf, CandidateStatus)> {
+    let exe_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf));
+    let dev_dir = if cfg!(dev) {
+        Some(Path::new(env!("CARGO_MANIFEST_DIR")).join("binaries"))
+    } else {
+        None
+    };
+    sidecar_candidate_paths(
+        exe_dir.as_deref(),
+        override_path,
+        dev_dir.as_deref(),
+        env!("TAURI_ENV_TARGET_TRIPLE"),
+        cfg!(target_os = "windows"),
+    )
+    .into_iter()
+    .map(|path| {
+        let status = candidate_status(&path);
+        (path, status)
+    })
+    .collect()
+}
+
+// MissingSidecar is what the splash page shows when the sidecar
+// could not be started: why, on which platform, every path that
+// was checked and what was found there, and what to try next.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct MissingSidecar {
+    intro: String,
+    platform: String,
+    checked: Vec<CheckedPath>,
+    fixes: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct CheckedPath {
+    path: String,
+    status: String,
+}
+
+// missing_sidecar builds the report for a failed launch. A binary
+// that exists but would not run is likely damaged or built for
+// another platform; one without the execute bit can be fixed with
+// chmod. The platform's security software advice comes before the
+// generic reinstall and locate fixes.
+pub(crate) fn missing_sidecar(
+    reason: &str,
+    checked: &[(PathBuf, CandidateStatus)],
+    os: &str,
+    arch: &str,
+) -> MissingSidecar {
+    let mut fixes = Vec::new();
+    let first = |wanted: CandidateStatus| {
+        checked
+            .iter()
+            .find(|(_, status)| *status == wanted)
+            .map(|(path, _)| path.display().to_string())
+    };
+    if let Some(path) = first(CandidateStatus::Present) {
+        fixes.push(i18n::tr_args("sidecar-fix-damaged", &[("path", &path)]));
+    }
+    if let Some(path) = first(CandidateStatus::NotExecutable) {
+        if os != "windows" {
+            fixes.push(i18n::tr_args("sidecar-fix-chmod", &[("path", &path)]));
+        }
+    }
+    match os {
+        "macos" => fixes.push(i18n::tr("sidecar-fix-macos")),
+        "windows" => fixes.push(i18n::tr("sidecar-fix-windows")),
+        _ => {}
+    }
+    fixes.push(i18n::tr("sidecar-fix-reinstall"));
+    fixes.push(i18n::tr("sidecar-fix-locate"));
+
+    let os_name = match os {
+        "macos" => "macOS",
+        "windows" => "Windows",
+        "linux" => "Linux",
+        other => other,
+    };
+    MissingSidecar {
+        intro: i18n::tr_args("sidecar-missing-intro", &[("reason", &reason)]),
+        platform: i18n::tr_args(
+            "sidecar-missing-platform",
+            &[("platform", &format!("{os_name} ({arch})"))],
+        ),
+        checked: checked
+            .iter()
+            .map(|(path, status)| CheckedPath {
+                path: path.display().to_string(),
+                status: status.label(),
+            })
+            .collect(),
+        fixes,
+    }
+}
+
+// serve_args is the sidecar's command line: the shell's serve
+// flags, then the extra arguments from settings. With a socket the
+// backend listens there, and host and port only name the address
+// the shell's proxy serves it on. Extra arguments
+// that would override a reserved flag are dropped along with
+// their value; anything after "--" is passed on untouched.
+pub(crate) fn serve_args(
+    host: &str,
+    port: u16,
+    socket: Option<&Path>,
+    extra: &[String],
+) -> Vec<String> {
+    let mut args = vec![
+        "serve".to_string(),
+        "-no-browser".to_string(),
+        "-host".to_string(),
+        host.to_string(),
+        "-port".to_string(),
+        port.to_string(),
+    ];
+    if let Some(socket) = socket {
+        args.push("-socket".to_string());
+        args.push(socket.display().to_string());
+    }
+    let mut extra = extra.iter();
+    while let Some(arg) = extra.next() {
+        if arg == "--" {
+            args.push(arg.clone());
+            args.extend(extra.cloned());
+            break;
+        }
+        let Some((name, inline_value)) = flag_name(arg) else {
+            args.push(arg.clone());
+            continue;
+        };
+        if !RESERVED_FLAGS.contains(&name) {
+            args.push(arg.clone());
+            continue;
+        }
+        // Boolean flags only take a value after "=".
+        let value = if inline_value || name == "no-browser" {
+            None
+        } else {
+            extra.next()
+        };
+        eprintln!(
+            "[agentsview] ignoring sidecar argument {arg}{}: the shell sets -{name} itself",
+            value.map(|value| format!(" {value}")).unwrap_or_default()
+        );
+    }
+    args
+}
+
+// lan_host returns the non-loopback -host sidecar_args asks for,
+// if it may be used: share_on_lan must be on, lan_auth_token set,
+// and the host must be all interfaces, which keeps 127.0.0.1
+// reachable for the shell. Otherwise the reason is returned and
+// the sidecar stays on loopback. Loopback hosts give None, since
+// the shell's own host already is one.
+pub(crate) fn lan_host(
+    extra: &[String],
+    share_on_lan: bool,
+    auth_token: Option<&str>,
+) -> Result<Option<String>, &'static str> {
+    let Some(host) = flag_value(extra, "host") else {
+        return Ok(None);
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if host == "localhost" || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback()) {
+        return Ok(None);
+    }
+    if !share_on_lan {
+        return Err("share_on_lan is not enabled");
+    }
+    if auth_token.map_or(0, |token| token.trim().len()) < MIN_AUTH_TOKEN_LEN {
+        return Err("share_on_lan needs a lan_auth_token of at least 16 characters");
+    }
+    if !matches!(host, "0.0.0.0" | "::") {
+        return Err("only 0.0.0.0 or :: can be shared, so the app can still reach 127.0.0.1");
+    }
+    Ok(Some(host.to_string()))
+}
+
+// flag_value returns the value of the last -name flag in args,
+// ignoring anything after "--".
+fn flag_value(args: &[String], wanted: &str) -> Option<String> {
+    let mut value = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        }
+        match flag_name(arg) {
+            Some((name, true)) if name == wanted => {
+                value = arg.split_once('=').map(|(_, value)| value.to_string());
+            }
+            Some((name, false)) if name == wanted => value = args.next().cloned(),
+            _ => {}
+        }
+    }
+    value
+}
+
+// flag_name parses a Go-style flag ("-name", "--name" or
+// "-name=value") into its name and whether it carries its value.
+fn flag_name(arg: &str) -> Option<(&str, bool)> {
+    let flag = arg.strip_prefix("--").or_else(|| arg.strip_prefix('-'))?;
+    if flag.is_empty() || flag.starts_with('-') {
+        return None;
+    }
+    Some(match flag.split_once('=') {
+        Some((name, _)) => (name, true),
+        None => (flag, false),
+    })
+}
+
+// ReadyEvent is the sidecar's startup handshake, printed as one
+// line of JSON such as {"event":"ready","port":8080}. Fields the
+// shell does not need are ignored.
+#[derive(Deserialize)]
+struct ReadyEvent {
+    event: String,
+    port: u16,
+}
+
+// parse_ready_event returns the port announced by a ready event,
+// or None for any other line.
+pub(crate) fn parse_ready_event(line: &str) -> Option<u16> {
+    let line = line.trim();
+    if !line.starts_with('{') {
+        return None;
+    }
+    let event: ReadyEvent = serde_json::from_str(line).ok()?;
+    (event.event == "ready").then_some(event.port)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(unix)]
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn sidecar_candidate_paths_orders_override_bundle_and_dev() {
+        let paths = sidecar_candidate_paths(
+            Some(Path::new("/Applications/AgentsView.app/Contents/MacOS")),
+            Some(Path::new("/opt/agentsview")),
+            Some(Path::new("/src/desktop/src-tauri/binaries")),
+            "aarch64-apple-darwin",
+            false,
+        );
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("/opt/agentsview"),
+                PathBuf::from("/Applications/AgentsView.app/Contents/MacOS/agentsview"),
+                PathBuf::from("/src/desktop/src-tauri/binaries/agentsview-aarch64-apple-darwin"),
+            ]
+        );
+
+        let windows = sidecar_candidate_paths(
+            Some(Path::new("C:/Program Files/AgentsView")),
+            None,
+            None,
+            "x86_64-pc-windows-msvc",
+            true,
+        );
+        assert_eq!(
+            windows,
+            vec![PathBuf::from("C:/Program Files/AgentsView/agentsview.exe")]
+        );
+    }
+
+    #[test]
+    fn candidate_status_distinguishes_missing_and_present() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        assert_eq!(
+            candidate_status(&dir.path().join("agentsview")),
+            CandidateStatus::Missing
+        );
+        assert_eq!(candidate_status(dir.path()), CandidateStatus::Missing);
+
+        let bin = dir.path().join("agentsview");
+        fs::write(&bin, "#!/bin/sh\n").expect("write binary");
+        #[cfg(unix)]
+        {
+            let mut perms = fs::metadata(&bin).expect("stat binary").permissions();
+            perms.set_mode(0o644);
+            fs::set_permissions(&bin, perms.clone()).expect("chmod 644");
+            assert_eq!(candidate_status(&bin), CandidateStatus::NotExecutable);
+            perms.set_mode(0o755);
+            fs::set_permissions(&bin, perms).expect("chmod 755");
+        }
+        assert_eq!(candidate_status(&bin), CandidateStatus::Present);
+    }
+
+    #[test]
+    fn serve_args_appends_extra_arguments_but_keeps_reserved_flags() {
+        let extra: Vec<String> = [
+            "-port",
+            "9000",
+            "--host=0.0.0.0",
+            "-no-browser=false",
+            "-verbose",
+            "-log-level",
+            "debug",
+            "--",
+            "-port",
+        ]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+        assert_eq!(
+            serve_args("127.0.0.1", 8080, None, &extra),
+            [
+                "serve",
+                "-no-browser",
+                "-host",
+                "127.0.0.1",
+                "-port",
+                "8080",
+                "-verbose",
+                "-log-level",
+                "debug",
+                "--",
+                "-port",
+            ]
+        );
+
+        let socket_extra = vec!["-socket=/tmp/elsewhere.sock".to_string()];
+        assert_eq!(
+            serve_args(
+                "127.0.0.1",
+                8080,
+                Some(Path::new("/tmp/agentsview-x/backend.sock")),
+                &socket_extra
+            ),
+            [
+                "serve",
+                "-no-browser",
+                "-host",
+                "127.0.0.1",
+                "-port",
+                "8080",
+                "-socket",
+                "/tmp/agentsview-x/backend.sock",
+            ]
+        );
+    }
+
+    #[test]
+    fn lan_host_requires_opt_in_and_a_token() {
+        let args = |host: &str| vec!["-verbose".to_string(), format!("--host={host}")];
+        let token = Some("0123456789abcdef");
+        assert_eq!(lan_host(&[], true, token), Ok(None));
+        assert_eq!(lan_host(&args("127.0.0.1"), false, None), Ok(None));
+        assert_eq!(lan_host(&args("[::1]"), false, None), Ok(None));
+        assert!(lan_host(&args("0.0.0.0"), false, token).is_err());
+        assert!(lan_host(&args("0.0.0.0"), true, None).is_err());
+        assert!(lan_host(&args("0.0.0.0"), true, Some("short")).is_err());
+        assert!(lan_host(&args("192.168.1.20"), true, token).is_err());
+        assert_eq!(
+            lan_host(&args("0.0.0.0"), true, token),
+            Ok(Some("0.0.0.0".to_string()))
+        );
+        let separate = ["-host".to_string(), "::".to_string()];
+        assert_eq!(lan_host(&separate, true, token), Ok(Some("::".to_string())));
+    }
+
+    #[test]
+    fn missing_sidecar_lists_paths_and_fixes() {
+        let checked = [
+            (PathBuf::from("/a/agentsview"), CandidateStatus::Missing),
+            (
+                PathBuf::from("/b/agentsview"),
+                CandidateStatus::NotExecutable,
+            ),
+        ];
+        let report = missing_sidecar("No such file or directory", &checked, "macos", "aarch64");
+        assert!(report.intro.contains("No such file or directory"));
+        assert!(report.platform.contains("macOS (aarch64)"));
+        assert_eq!(
+            report.checked[1],
+            CheckedPath {
+                path: "/b/agentsview".to_string(),
+                status: CandidateStatus::NotExecutable.label(),
+            }
+        );
+        assert_eq!(
+            report.fixes,
+            [
+                i18n::tr_args("sidecar-fix-chmod", &[("path", &"/b/agentsview")]),
+                i18n::tr("sidecar-fix-macos"),
+                i18n::tr("sidecar-fix-reinstall"),
+                i18n::tr("sidecar-fix-locate"),
+            ]
+        );
+
+        let damaged = [(
+            PathBuf::from("C:/AgentsView/agentsview.exe"),
+            CandidateStatus::Present,
+        )];
+        let report = missing_sidecar(
+            "%1 is not a valid Win32 application",
+            &damaged,
+            "windows",
+            "x86_64",
+        );
+        assert_eq!(
+            report.fixes[..2],
+            [
+                i18n::tr_args(
+                    "sidecar-fix-damaged",
+                    &[("path", &"C:/AgentsView/agentsview.exe")]
+                ),
+                i18n::tr("sidecar-fix-windows"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_ready_event_reads_the_handshake_line() {
+        let line = r#"{"event":"ready","host":"127.0.0.1","port":8081,"version":"0.9.0"}"#;
+        assert_eq!(parse_ready_event(line), Some(8081));
+        assert_eq!(
+            parse_ready_event(r#"{"event":"stopping","port":8081}"#),
+            None
+        );
+        assert_eq!(
+            parse_ready_event(r#"{"event":"ready","port":"8081"}"#),
+            None
+        );
+        assert_eq!(parse_ready_event("Port 8080 in use, using 8081"), None);
+    }
+}
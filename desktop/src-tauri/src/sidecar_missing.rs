@@ -0,0 +1,93 @@
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager, Url, WebviewWindow};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::i18n;
+use crate::settings;
+use crate::sidecar::{self, MissingSidecar};
+
+// SPLASH_URL is where the bundled splash page is served from.
+// Windows and Android serve app assets over http.
+const SPLASH_URL: &str = if cfg!(any(windows, target_os = "android")) {
+    "http://tauri.localhost/index.html"
+} else {
+    "tauri://localhost/index.html"
+};
+
+// MissingSidecarState holds the report for the last launch that
+// could not start the sidecar, until the user retries.
+#[derive(Default)]
+pub(crate) struct MissingSidecarState(Mutex<Option<MissingSidecar>>);
+
+// show replaces the splash page's progress with the missing-binary
+// report. A window already showing the backend is sent back to the
+// splash page, which asks for the report when it loads.
+pub(crate) fn show(app: &AppHandle, window: &WebviewWindow, reason: &str) {
+    let override_path = settings::load_settings().sidecar_path;
+    let checked = sidecar::checked_sidecar_paths(override_path.as_deref());
+    let report = sidecar::missing_sidecar(
+        reason,
+        &checked,
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+    );
+    if let Ok(mut missing) = app.state::<MissingSidecarState>().0.lock() {
+        *missing = Some(report);
+    }
+    let on_splash = window
+        .url()
+        .is_ok_and(|url| url.scheme() == "tauri" || url.host_str() == Some("tauri.localhost"));
+    if on_splash {
+        let _ = window.eval("window.agentsviewSidecarMissing?.();");
+    } else if let Ok(url) = Url::parse(SPLASH_URL) {
+        let _ = window.navigate(url);
+    }
+}
+
+fn clear(app: &AppHandle) {
+    if let Ok(mut missing) = app.state::<MissingSidecarState>().0.lock() {
+        *missing = None;
+    }
+}
+
+// missing_sidecar returns the report for a failed launch, or None
+// while no launch has failed to find the sidecar.
+#[tauri::command]
+pub(crate) fn missing_sidecar(app: AppHandle) -> Option<MissingSidecar> {
+    app.state::<MissingSidecarState>()
+        .0
+        .lock()
+        .ok()
+        .and_then(|missing| missing.clone())
+}
+
+// retry_sidecar starts the backend again, for a binary the user
+// restored or reinstalled.
+#[tauri::command]
+pub(crate) fn retry_sidecar(app: AppHandle, window: WebviewWindow) {
+    clear(&app);
+    crate::restart_backend(&app, &window);
+}
+
+// locate_sidecar lets the user pick the agentsview binary. The
+// choice is remembered for future launches and the backend is
+// started with it; a binary that fails too reports again.
+#[tauri::command]
+pub(crate) fn locate_sidecar(app: AppHandle, window: WebviewWindow) {
+    app.dialog()
+        .file()
+        .set_title(i18n::tr("sidecar-locate-title"))
+        .pick_file(move |picked| {
+            let Some(path) = picked.and_then(|picked| picked.into_path().ok()) else {
+                return;
+            };
+            if let Err(err) = settings::update_settings(|s| {
+                s.sidecar_path = Some(path);
+            }) {
+                eprintln!("[agentsview] failed to save sidecar override: {err}");
+            }
+            clear(&app);
+            crate::restart_backend(&app, &window);
+        });
+}
@@ -0,0 +1,64 @@
+use serde::Serialize;
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Emitter, Manager, Wry};
+
+// LAUNCH_EVENT is emitted to the main window when the app is
+// launched again, carrying that launch's arguments.
+pub(crate) const LAUNCH_EVENT: &str = "agentsview://launch";
+
+// Launch is a second launch of the app, forwarded by the instance
+// that was already running.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct Launch {
+    args: Vec<String>,
+    cwd: String,
+}
+
+// init_plugin makes later launches hand their arguments to this
+// instance and exit before they build a window or start a sidecar.
+// It has to be the first plugin registered.
+pub(crate) fn init_plugin() -> TauriPlugin<Wry> {
+    tauri_plugin_single_instance::init(|app, argv, cwd| {
+        let launch = Launch {
+            args: forwarded_args(argv),
+            cwd,
+        };
+        eprintln!(
+            "[agentsview] forwarded a second launch with arguments {:?}",
+            launch.args
+        );
+        focus_main_window(app);
+        let _ = app.emit_to("main", LAUNCH_EVENT, launch);
+    })
+}
+
+// forwarded_args drops the program path from a launch's argv.
+fn forwarded_args(argv: Vec<String>) -> Vec<String> {
+    argv.into_iter().skip(1).collect()
+}
+
+// focus_main_window brings the main window forward, including when
+// it was minimized or hidden to the tray.
+fn focus_main_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let _ = window.unminimize();
+    let _ = window.show();
+    let _ = window.set_focus();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forwarded_args_drop_the_program_path() {
+        let argv = vec![
+            "/Applications/AgentsView.app/Contents/MacOS/agentsview-desktop".to_string(),
+            "--safe-mode".to_string(),
+        ];
+        assert_eq!(forwarded_args(argv), ["--safe-mode"]);
+        assert!(forwarded_args(Vec::new()).is_empty());
+    }
+}
@@ -0,0 +1,303 @@
+use std::io::{self, Read};
+use std::net::TcpStream;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+// COOKIE carries the per-run secret that admits a client to the
+// proxy. The webview gets it from login_url; the shell's own
+// requests send it with cookie.
+const COOKIE: &str = "agentsview_proxy";
+
+// MAX_HEAD_BYTES bounds the request head read before relaying.
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+
+static SECRET: OnceLock<String> = OnceLock::new();
+// ACTIVE is set while a Proxy serves the sidecar.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+// cookie is the Cookie header value local requests to the backend
+// need while the sidecar is behind the proxy.
+pub(crate) fn cookie() -> Option<String> {
+    if !ACTIVE.load(Ordering::SeqCst) {
+        return None;
+    }
+    SECRET.get().map(|secret| format!("{COOKIE}={secret}"))
+}
+
+// login_url is where the webview goes to open a local backend at
+// url. Behind the proxy it carries the secret once, and the proxy
+// trades it for a cookie.
+pub(crate) fn login_url(url: String) -> String {
+    match (ACTIVE.load(Ordering::SeqCst), SECRET.get()) {
+        (true, Some(secret)) => format!("{url}/?{COOKIE}={secret}"),
+        _ => url,
+    }
+}
+
+#[cfg(unix)]
+pub(crate) use unix::Proxy;
+
+#[cfg(unix)]
+mod unix {
+    use std::fs;
+    use std::io::{self, Read, Write};
+    use std::net::{Shutdown, TcpListener, TcpStream};
+    use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::net::UnixStream;
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    use super::{ACTIVE, COOKIE, SECRET};
+
+    const FORBIDDEN: &[u8] =
+        b"HTTP/1.1 403 Forbidden\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+    // Proxy exposes a sidecar serving on a Unix socket to the
+    // webview, on a loopback port. The socket sits in a directory
+    // only this user can open, and the port admits only clients
+    // holding the run's secret, so other local users and processes
+    // cannot reach the API.
+    pub(crate) struct Proxy {
+        port: u16,
+        socket: PathBuf,
+        stop: Arc<AtomicBool>,
+        _dir: tempfile::TempDir,
+    }
+
+    impl Proxy {
+        // start listens on port and forwards admitted connections to
+        // socket(), where the sidecar is told to listen.
+        pub(crate) fn start(port: u16) -> io::Result<Self> {
+            let secret = secret()?;
+            let dir = tempfile::Builder::new().prefix("agentsview-").tempdir()?;
+            fs::set_permissions(dir.path(), fs::Permissions::from_mode(0o700))?;
+            let socket = dir.path().join("backend.sock");
+            let listener = TcpListener::bind(crate::local_addr(port))?;
+            let stop = Arc::new(AtomicBool::new(false));
+
+            let accept_socket = socket.clone();
+            let accept_stop = stop.clone();
+            thread::spawn(move || {
+                for conn in listener.incoming() {
+                    if accept_stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    let Ok(conn) = conn else {
+                        continue;
+                    };
+                    let socket = accept_socket.clone();
+                    thread::spawn(move || {
+                        if let Err(err) = forward(conn, &socket, secret) {
+                            eprintln!("[agentsview] backend proxy request failed: {err}");
+                        }
+                    });
+                }
+            });
+            ACTIVE.store(true, Ordering::SeqCst);
+            Ok(Self {
+                port,
+                socket,
+                stop,
+                _dir: dir,
+            })
+        }
+
+        pub(crate) fn socket(&self) -> &Path {
+            &self.socket
+        }
+    }
+
+    impl Drop for Proxy {
+        fn drop(&mut self) {
+            ACTIVE.store(false, Ordering::SeqCst);
+            self.stop.store(true, Ordering::SeqCst);
+            // Wake the accept loop so it sees stop and frees the port.
+            let _ = TcpStream::connect(crate::local_addr(self.port));
+        }
+    }
+
+    fn secret() -> io::Result<&'static str> {
+        if let Some(secret) = SECRET.get() {
+            return Ok(secret);
+        }
+        let mut bytes = [0u8; 16];
+        fs::File::open("/dev/urandom")?.read_exact(&mut bytes)?;
+        let hex = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+        Ok(SECRET.get_or_init(|| hex))
+    }
+
+    // forward checks the first request on conn, then relays the
+    // connection to the sidecar in both directions until either side
+    // closes it.
+    fn forward(mut conn: TcpStream, socket: &Path, secret: &str) -> io::Result<()> {
+        let head = super::read_head(&mut conn)?;
+        match super::admit(&head, COOKIE, COOKIE, secret) {
+            super::Admission::Allowed => {}
+            super::Admission::Login(location) => {
+                let response = format!(
+                    "HTTP/1.1 302 Found\r\nLocation: {location}\r\nSet-Cookie: {COOKIE}={secret}; Path=/; HttpOnly; SameSite=Strict\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                );
+                return conn.write_all(response.as_bytes());
+            }
+            super::Admission::Denied => return conn.write_all(FORBIDDEN),
+        }
+
+        let mut backend = UnixStream::connect(socket)?;
+        backend.write_all(&head)?;
+        let mut upstream = backend.try_clone()?;
+        let mut downstream = conn.try_clone()?;
+        let pump = thread::spawn(move || {
+            let _ = io::copy(&mut downstream, &mut upstream);
+            let _ = upstream.shutdown(Shutdown::Write);
+        });
+        let _ = io::copy(&mut backend, &mut conn);
+        let _ = conn.shutdown(Shutdown::Both);
+        let _ = pump.join();
+        Ok(())
+    }
+}
+
+// read_head reads until the end of the first request's head.
+// Whatever body bytes arrived with it are returned too.
+pub(crate) fn read_head(conn: &mut TcpStream) -> io::Result<Vec<u8>> {
+    let mut head = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 4096];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+        if head.len() > MAX_HEAD_BYTES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "request head too large",
+            ));
+        }
+        let n = conn.read(&mut chunk)?;
+        if n == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        head.extend_from_slice(&chunk[..n]);
+    }
+    Ok(head)
+}
+
+// Admission is what a proxy does with a connection's first
+// request: relay it, trade a secret in the URL for a cookie and
+// redirect to the same page without it, or refuse it.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Admission {
+    Allowed,
+    Login(String),
+    Denied,
+}
+
+// admit accepts secret as a bearer token, in the cookie named
+// cookie, or as the URL parameter param. It is shared with the auth
+// proxy, which names its own cookie and parameter.
+pub(crate) fn admit(head: &[u8], cookie: &str, param: &str, secret: &str) -> Admission {
+    let head = String::from_utf8_lossy(head);
+    let mut lines = head.split("\r\n");
+    let Some(target) = lines.next().and_then(|line| line.split(' ').nth(1)) else {
+        return Admission::Denied;
+    };
+    let headers: Vec<(&str, &str)> = lines
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim(), value.trim()))
+        .collect();
+    let bearer = headers.iter().any(|(name, value)| {
+        name.eq_ignore_ascii_case("authorization")
+            && value
+                .strip_prefix("Bearer ")
+                .is_some_and(|value| matches(value.trim(), secret))
+    });
+    let has_cookie = headers
+        .iter()
+        .filter(|(name, _)| name.eq_ignore_ascii_case("cookie"))
+        .flat_map(|(_, value)| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .any(|(name, value)| name == cookie && matches(value, secret));
+    if bearer || has_cookie {
+        return Admission::Allowed;
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let presented = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .any(|(name, value)| name == param && matches(value, secret));
+    if !presented {
+        return Admission::Denied;
+    }
+    let rest: Vec<&str> = query
+        .split('&')
+        .filter(|pair| !pair.is_empty() && pair.split('=').next() != Some(param))
+        .collect();
+    if rest.is_empty() {
+        Admission::Login(path.to_string())
+    } else {
+        Admission::Login(format!("{path}?{}", rest.join("&")))
+    }
+}
+
+// matches compares a presented secret with the real one in time
+// independent of where they differ.
+fn matches(presented: &str, secret: &str) -> bool {
+    presented.len() == secret.len()
+        && presented
+            .bytes()
+            .zip(secret.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn admit(head: &[u8], secret: &str) -> Admission {
+        super::admit(head, COOKIE, COOKIE, secret)
+    }
+
+    #[test]
+    fn admit_requires_the_secret_in_a_cookie_or_the_url() {
+        let request = |target: &str, cookie: &str| {
+            format!("GET {target} HTTP/1.1\r\nHost: 127.0.0.1:8080\r\n{cookie}\r\n")
+        };
+        assert_eq!(
+            admit(
+                request(
+                    "/api/v1/version",
+                    "Cookie: theme=dark; agentsview_proxy=s3cret\r\n"
+                )
+                .as_bytes(),
+                "s3cret"
+            ),
+            Admission::Allowed
+        );
+        assert_eq!(
+            admit(
+                request("/?agentsview_proxy=s3cret", "").as_bytes(),
+                "s3cret"
+            ),
+            Admission::Login("/".to_string())
+        );
+        assert_eq!(
+            admit(
+                request("/sessions?q=1&agentsview_proxy=s3cret", "").as_bytes(),
+                "s3cret"
+            ),
+            Admission::Login("/sessions?q=1".to_string())
+        );
+        assert_eq!(
+            admit(
+                request("/api/v1/version", "Cookie: agentsview_proxy=guess\r\n").as_bytes(),
+                "s3cret"
+            ),
+            Admission::Denied
+        );
+        assert_eq!(
+            admit(request("/?agentsview_proxy=", "").as_bytes(), "s3cret"),
+            Admission::Denied
+        );
+    }
+}
@@ -0,0 +1,379 @@
+use std::path::{Path, PathBuf};
+
+use tauri::AppHandle;
+use tauri_plugin_dialog::{
+    DialogExt, MessageDialogButtons, MessageDialogKind, MessageDialogResult,
+};
+
+#[cfg(not(feature = "no-network"))]
+use crate::backend_client::encode_query_component;
+#[cfg(desktop)]
+use crate::doctor;
+use crate::error::DesktopError;
+use crate::i18n;
+
+#[cfg(not(feature = "no-network"))]
+const NEW_ISSUE_URL: &str = "https://github.com/wesm/agentsview/issues/new";
+
+#[derive(Debug, PartialEq, Eq)]
+enum Choice {
+    Retry,
+    OpenLogs,
+    ReportIssue,
+    Dismiss,
+}
+
+// PortConflictChoice is how the user resolved a port conflict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PortConflictChoice {
+    StopOwner,
+    UseAnotherPort,
+    Dismiss,
+}
+
+// Failure is a startup error rendered for the dialog and for the
+// prefilled issue report.
+struct Failure {
+    retry_label: String,
+    message: String,
+    issue_title: String,
+    issue_body: String,
+}
+
+// show_startup_failure explains a classified startup failure in a
+// native modal dialog offering Retry, Open Logs, and Report Issue.
+// After opening the logs or the issue form the dialog returns, so
+// the user can still retry. no-network builds leave out Report
+// Issue.
+pub(crate) fn show_startup_failure<F>(
+    app: &AppHandle,
+    err: &DesktopError,
+    log_file: Option<PathBuf>,
+    retry: F,
+) where
+    F: FnOnce() + Send + 'static,
+{
+    present(app, failure(err, "dialog-retry"), log_file, retry);
+}
+
+// show_connection_blocked is the startup failure dialog for a
+// backend that listens but cannot be reached, with Re-test in
+// place of Retry; the hint carries this platform's firewall steps.
+pub(crate) fn show_connection_blocked<F>(
+    app: &AppHandle,
+    err: &DesktopError,
+    log_file: Option<PathBuf>,
+    retest: F,
+) where
+    F: FnOnce() + Send + 'static,
+{
+    present(app, failure(err, "dialog-retest"), log_file, retest);
+}
+
+// show_backend_unresponsive tells the user the backend stopped
+// answering after startup and offers to restart it. Closing the
+// dialog keeps waiting.
+pub(crate) fn show_backend_unresponsive<F>(app: &AppHandle, restart: F)
+where
+    F: FnOnce() + Send + 'static,
+{
+    app.dialog()
+        .message(i18n::tr("backend-unresponsive-message"))
+        .title(i18n::tr("backend-unresponsive-title"))
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            i18n::tr("backend-unresponsive-restart"),
+            i18n::tr("backend-unresponsive-wait"),
+        ))
+        .show(move |restart_now| {
+            if restart_now {
+                restart();
+            }
+        });
+}
+
+// show_memory_alert warns that the backend's memory use crossed
+// the configured limit and offers to restart it. Closing the
+// dialog leaves it running.
+pub(crate) fn show_memory_alert<F>(app: &AppHandle, message: String, restart: F)
+where
+    F: FnOnce() + Send + 'static,
+{
+    app.dialog()
+        .message(message)
+        .title(i18n::tr("memory-alert-title"))
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            i18n::tr("memory-alert-restart"),
+            i18n::tr("memory-alert-ignore"),
+        ))
+        .show(move |restart_now| {
+            if restart_now {
+                restart();
+            }
+        });
+}
+
+// show_version_mismatch warns that the backend's version is outside
+// the range the shell supports, offering the download page. No-network
+// builds only acknowledge it.
+pub(crate) fn show_version_mismatch<F>(app: &AppHandle, message: String, download: F)
+where
+    F: FnOnce() + Send + 'static,
+{
+    let buttons = if cfg!(feature = "no-network") {
+        MessageDialogButtons::OkCustom(i18n::tr("backend-version-continue"))
+    } else {
+        MessageDialogButtons::OkCancelCustom(
+            i18n::tr("backend-version-download"),
+            i18n::tr("backend-version-continue"),
+        )
+    };
+    app.dialog()
+        .message(message)
+        .title(i18n::tr("backend-version-title"))
+        .kind(MessageDialogKind::Warning)
+        .buttons(buttons)
+        .show(move |chose_download| {
+            if chose_download && !cfg!(feature = "no-network") {
+                download();
+            }
+        });
+}
+
+// show_sidecar_crash reports a sidecar that exited on its own with
+// its exit status and last output, offering to restart it or quit.
+// Without a backend the window is of no use, so closing the dialog
+// quits too.
+pub(crate) fn show_sidecar_crash<F>(app: &AppHandle, message: String, restart: F)
+where
+    F: FnOnce() + Send + 'static,
+{
+    let handle = app.clone();
+    app.dialog()
+        .message(message)
+        .title(i18n::tr("backend-crash-title"))
+        .kind(MessageDialogKind::Error)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            i18n::tr("backend-crash-restart"),
+            i18n::tr("backend-crash-quit"),
+        ))
+        .show(move |restart_now| {
+            if restart_now {
+                restart();
+            } else {
+                handle.exit(0);
+            }
+        });
+}
+
+// show_port_conflict explains that the sidecar's port is held by
+// another process. It offers another port, and with stop_owner
+// also stopping the stale agentsview that holds it.
+pub(crate) fn show_port_conflict<F>(app: &AppHandle, message: String, stop_owner: bool, choose: F)
+where
+    F: FnOnce(PortConflictChoice) + Send + 'static,
+{
+    let stop_label = i18n::tr("port-conflict-stop");
+    let other_label = i18n::tr("port-conflict-other-port");
+    let buttons = if stop_owner {
+        MessageDialogButtons::YesNoCancelCustom(
+            stop_label.clone(),
+            other_label.clone(),
+            i18n::tr("port-conflict-cancel"),
+        )
+    } else {
+        MessageDialogButtons::OkCancelCustom(other_label.clone(), i18n::tr("port-conflict-cancel"))
+    };
+    app.dialog()
+        .message(message)
+        .title(i18n::tr("port-conflict-title"))
+        .kind(MessageDialogKind::Warning)
+        .buttons(buttons)
+        .show_with_result(move |result| {
+            let choice = match result {
+                MessageDialogResult::Yes if stop_owner => PortConflictChoice::StopOwner,
+                MessageDialogResult::Custom(label) if label == stop_label => {
+                    PortConflictChoice::StopOwner
+                }
+                MessageDialogResult::Ok | MessageDialogResult::No => {
+                    PortConflictChoice::UseAnotherPort
+                }
+                MessageDialogResult::Custom(label) if label == other_label => {
+                    PortConflictChoice::UseAnotherPort
+                }
+                _ => PortConflictChoice::Dismiss,
+            };
+            choose(choice);
+        });
+}
+
+fn failure(err: &DesktopError, retry_key: &str) -> Failure {
+    let (issue_title, issue_body) = issue_report(err);
+    Failure {
+        retry_label: i18n::tr(retry_key),
+        message: format!("{}\n\n{}", err.user_message(), err.hint()),
+        issue_title,
+        issue_body,
+    }
+}
+
+fn present<F>(app: &AppHandle, failure: Failure, log_file: Option<PathBuf>, retry: F)
+where
+    F: FnOnce() + Send + 'static,
+{
+    let retry_label = failure.retry_label.clone();
+    let logs_label = i18n::tr("dialog-open-logs");
+    let report_label = i18n::tr("dialog-report-issue");
+    // The dismiss slot holds Open Logs, the harmless choice, since
+    // some platforms report closing the dialog as that button.
+    let buttons = if cfg!(feature = "no-network") {
+        MessageDialogButtons::OkCancelCustom(retry_label.clone(), logs_label.clone())
+    } else {
+        MessageDialogButtons::YesNoCancelCustom(
+            retry_label.clone(),
+            report_label.clone(),
+            logs_label.clone(),
+        )
+    };
+    let handle = app.clone();
+    app.dialog()
+        .message(failure.message.clone())
+        .title(i18n::tr("startup-failed-title"))
+        .kind(MessageDialogKind::Error)
+        .buttons(buttons)
+        .show_with_result(move |result| {
+            match classify(&result, &retry_label, &logs_label, &report_label) {
+                Choice::Retry => retry(),
+                Choice::OpenLogs => {
+                    if let Some(path) = &log_file {
+                        reveal(path);
+                    }
+                    present(&handle, failure, log_file, retry);
+                }
+                Choice::ReportIssue => {
+                    let body = with_diagnostics(&handle, &failure.issue_body);
+                    open_issue_form(&handle, &failure.issue_title, &body);
+                    present(&handle, failure, log_file, retry);
+                }
+                Choice::Dismiss => {}
+            }
+        });
+}
+
+fn classify(result: &MessageDialogResult, retry: &str, logs: &str, report: &str) -> Choice {
+    match result {
+        MessageDialogResult::Yes | MessageDialogResult::Ok => Choice::Retry,
+        MessageDialogResult::No => Choice::ReportIssue,
+        MessageDialogResult::Custom(label) if label == retry => Choice::Retry,
+        MessageDialogResult::Custom(label) if label == logs => Choice::OpenLogs,
+        MessageDialogResult::Custom(label) if label == report => Choice::ReportIssue,
+        _ => Choice::Dismiss,
+    }
+}
+
+// issue_report is the title and body prefilled in a new issue:
+// the failure kind and details plus the app version and platform.
+fn issue_report(err: &DesktopError) -> (String, String) {
+    let title = format!("Desktop startup failure: {}", err.kind());
+    let body = format!(
+        "{}\n\nError: {err}\nVersion: {}\nPlatform: {} {}\n",
+        err.user_message(),
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+    );
+    (title, body)
+}
+
+// with_diagnostics appends the doctor report to an issue body.
+#[cfg(desktop)]
+fn with_diagnostics(app: &AppHandle, body: &str) -> String {
+    format!(
+        "{body}\nDiagnostics:\n{}\n",
+        doctor::diagnose(app).to_text()
+    )
+}
+
+#[cfg(not(desktop))]
+fn with_diagnostics(_app: &AppHandle, body: &str) -> String {
+    body.to_string()
+}
+
+#[cfg(not(feature = "no-network"))]
+fn issue_url(title: &str, body: &str) -> String {
+    format!(
+        "{NEW_ISSUE_URL}?title={}&body={}",
+        encode_query_component(title),
+        encode_query_component(body)
+    )
+}
+
+#[cfg(not(feature = "no-network"))]
+fn open_issue_form(app: &AppHandle, title: &str, body: &str) {
+    use tauri_plugin_shell::ShellExt;
+
+    #[allow(deprecated)]
+    if let Err(err) = app.shell().open(issue_url(title, body), None) {
+        eprintln!("[agentsview] failed to open issue form: {err}");
+    }
+}
+
+#[cfg(feature = "no-network")]
+fn open_issue_form(_app: &AppHandle, _title: &str, _body: &str) {}
+
+// reveal shows the log file in the system file manager, or its
+// directory when the backend has not written one yet.
+pub(crate) fn reveal(path: &Path) {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = std::process::Command::new("open");
+        if path.exists() {
+            command.arg("-R");
+        }
+        command
+    } else if cfg!(windows) {
+        let mut command = std::process::Command::new("explorer");
+        if path.exists() {
+            command.arg("/select,");
+        }
+        command
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+    let target = if path.exists() && !cfg!(target_os = "linux") {
+        path
+    } else {
+        path.parent().unwrap_or(path)
+    };
+    if let Err(err) = command.arg(target).spawn() {
+        eprintln!("[agentsview] failed to open {}: {err}", target.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_maps_buttons_to_choices() {
+        let custom = |label: &str| MessageDialogResult::Custom(label.to_string());
+        let choose = |result| classify(&result, "Retry", "Open Logs", "Report Issue");
+        assert_eq!(choose(custom("Retry")), Choice::Retry);
+        assert_eq!(choose(custom("Open Logs")), Choice::OpenLogs);
+        assert_eq!(choose(custom("Report Issue")), Choice::ReportIssue);
+        assert_eq!(choose(MessageDialogResult::Yes), Choice::Retry);
+        assert_eq!(choose(MessageDialogResult::Cancel), Choice::Dismiss);
+    }
+
+    #[cfg(not(feature = "no-network"))]
+    #[test]
+    fn issue_url_prefills_the_failure() {
+        let (title, body) = issue_report(&DesktopError::SidecarExited { code: Some(2) });
+        assert_eq!(title, "Desktop startup failure: sidecar_exited");
+        assert!(body.contains(&format!("Version: {}", env!("CARGO_PKG_VERSION"))));
+        let url = issue_url(&title, &body);
+        assert!(url.starts_with(
+            "https://github.com/wesm/agentsview/issues/new?title=Desktop%20startup%20failure%3A%20sidecar_exited&body="
+        ));
+    }
+}
@@ -0,0 +1,133 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// unix_now returns the current time in seconds since the Unix
+// epoch.
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs())
+}
+
+// rfc3339_utc formats seconds since the Unix epoch as an RFC 3339
+// UTC timestamp, the form the backend's query parameters expect.
+pub(crate) fn rfc3339_utc(secs: u64) -> String {
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let rem = secs % 86_400;
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
+
+// date_utc formats seconds since the Unix epoch as a UTC
+// YYYY-MM-DD date, the form the backend's date filters expect.
+pub(crate) fn date_utc(secs: u64) -> String {
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+// parse_rfc3339 reads the timestamps the backend stores for
+// messages and sessions, such as 2026-03-14T09:26:53.120Z or
+// 2026-03-14T10:26:53+01:00, into seconds since the Unix epoch.
+// Fractional seconds are dropped.
+pub(crate) fn parse_rfc3339(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let (date, time) = value.split_once(['T', 't', ' '])?;
+    let mut date_parts = date.splitn(3, '-');
+    let year: i64 = date_parts.next()?.parse().ok()?;
+    let month: u32 = date_parts.next()?.parse().ok()?;
+    let day: u32 = date_parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let (clock, offset_secs) = if let Some(clock) = time.strip_suffix(['Z', 'z']) {
+        (clock, 0)
+    } else {
+        let sign_at = time.rfind(['+', '-'])?;
+        let (clock, offset) = time.split_at(sign_at);
+        let (hours, minutes) = offset[1..].split_once(':')?;
+        let secs = hours.parse::<i64>().ok()? * 3_600 + minutes.parse::<i64>().ok()? * 60;
+        (clock, if offset.starts_with('-') { -secs } else { secs })
+    };
+    let clock = clock.split_once('.').map_or(clock, |(whole, _)| whole);
+    let mut clock_parts = clock.splitn(3, ':');
+    let hour: i64 = clock_parts.next()?.parse().ok()?;
+    let minute: i64 = clock_parts.next()?.parse().ok()?;
+    let second: i64 = clock_parts.next()?.parse().ok()?;
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let secs = days_from_civil(year, month, day) * 86_400 + hour * 3_600 + minute * 60 + second
+        - offset_secs;
+    u64::try_from(secs).ok()
+}
+
+// civil_from_days and days_from_civil convert between days since
+// the Unix epoch and proleptic Gregorian dates, following Howard
+// Hinnant's date algorithms.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (yoe + era * 400 + i64::from(month <= 2), month, day)
+}
+
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let mp = i64::from((month + 9) % 12);
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rfc3339_utc_formats_civil_dates() {
+        assert_eq!(rfc3339_utc(0), "1970-01-01T00:00:00Z");
+        assert_eq!(rfc3339_utc(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(rfc3339_utc(1_773_480_413), "2026-03-14T09:26:53Z");
+        assert_eq!(date_utc(1_773_480_413), "2026-03-14");
+    }
+
+    #[test]
+    fn parse_rfc3339_reads_backend_timestamps() {
+        assert_eq!(parse_rfc3339("2026-03-14T09:26:53Z"), Some(1_773_480_413));
+        assert_eq!(
+            parse_rfc3339("2026-03-14T09:26:53.987Z"),
+            Some(1_773_480_413)
+        );
+        assert_eq!(
+            parse_rfc3339("2026-03-14T10:26:53+01:00"),
+            Some(1_773_480_413)
+        );
+        assert_eq!(
+            parse_rfc3339("2026-03-14T04:56:53.5-04:30"),
+            Some(1_773_480_413)
+        );
+        assert_eq!(parse_rfc3339("2000-02-29T00:00:00Z"), Some(951_782_400));
+        assert_eq!(parse_rfc3339("2026-03-14"), None);
+        assert_eq!(parse_rfc3339("2026-13-14T09:26:53Z"), None);
+        assert_eq!(parse_rfc3339("not a time"), None);
+    }
+
+    #[test]
+    fn timestamps_round_trip() {
+        for secs in [0, 86_399, 951_782_400, 1_773_480_413, 4_102_444_800] {
+            assert_eq!(parse_rfc3339(&rfc3339_utc(secs)), Some(secs));
+        }
+    }
+}
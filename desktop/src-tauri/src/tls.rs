@@ -0,0 +1,178 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::WebPkiServerVerifier;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{
+    CertificateError, ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme,
+};
+use sha2::{Digest, Sha256};
+
+use crate::settings::DesktopSettings;
+
+// client_config builds the TLS configuration the shell uses to
+// probe https remotes. It trusts the OS certificate store, as the
+// webview does, plus the CAs in remote_ca_bundle. In development
+// builds the server's certificate must also match one of
+// remote_cert_pins, if any are set.
+pub(crate) fn client_config(settings: &DesktopSettings) -> Result<Arc<ClientConfig>, String> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let mut roots = RootCertStore::empty();
+    let native = rustls_native_certs::load_native_certs();
+    for err in &native.errors {
+        eprintln!("[agentsview] skipping unreadable system certificates: {err}");
+    }
+    roots.add_parsable_certificates(native.certs);
+    if let Some(path) = &settings.remote_ca_bundle {
+        let (added, _) = roots.add_parsable_certificates(read_bundle(path)?);
+        if added == 0 {
+            return Err(format!("{} has no usable CA certificates", path.display()));
+        }
+    }
+
+    let builder = ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|err| err.to_string())?;
+    let config = match pins(&settings.remote_cert_pins) {
+        None => builder.with_root_certificates(roots).with_no_client_auth(),
+        Some(pins) => {
+            let inner = WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider)
+                .build()
+                .map_err(|err| err.to_string())?;
+            builder
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(PinnedVerifier { inner, pins }))
+                .with_no_client_auth()
+        }
+    };
+    Ok(Arc::new(config))
+}
+
+fn read_bundle(path: &Path) -> Result<Vec<CertificateDer<'static>>, String> {
+    CertificateDer::pem_file_iter(path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|err| format!("could not read {}: {err}", path.display()))
+}
+
+// pins parses remote_cert_pins, or returns None when pinning is
+// off. Pinning is meant for development servers: a pin stops
+// matching as soon as the certificate is renewed, so release
+// builds ignore it. Pins that do not parse are dropped, which with
+// pinning on leaves fewer certificates accepted, never more.
+fn pins(configured: &[String]) -> Option<Vec<[u8; 32]>> {
+    if configured.is_empty() {
+        return None;
+    }
+    if !cfg!(debug_assertions) {
+        eprintln!("[agentsview] ignoring remote_cert_pins: pinning is only available in development builds");
+        return None;
+    }
+    let pins = configured
+        .iter()
+        .filter_map(|pin| {
+            let parsed = parse_pin(pin);
+            if parsed.is_none() {
+                eprintln!(
+                    "[agentsview] ignoring remote_cert_pins entry {pin:?}: not a SHA-256 digest"
+                );
+            }
+            parsed
+        })
+        .collect();
+    Some(pins)
+}
+
+// parse_pin reads a certificate's SHA-256 digest written as hex,
+// optionally prefixed with "sha256:" and separated by colons as
+// `openssl x509 -fingerprint -sha256` prints it.
+fn parse_pin(pin: &str) -> Option<[u8; 32]> {
+    let pin = pin.trim();
+    let hex: Vec<u8> = pin
+        .strip_prefix("sha256:")
+        .unwrap_or(pin)
+        .bytes()
+        .filter(|byte| *byte != b':')
+        .collect();
+    if hex.len() != 64 {
+        return None;
+    }
+    let mut digest = [0u8; 32];
+    for (byte, pair) in digest.iter_mut().zip(hex.chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()?;
+    }
+    Some(digest)
+}
+
+// PinnedVerifier accepts a server only if the usual chain checks
+// pass and its leaf certificate's SHA-256 is one of the pins.
+#[derive(Debug)]
+struct PinnedVerifier {
+    inner: Arc<WebPkiServerVerifier>,
+    pins: Vec<[u8; 32]>,
+}
+
+impl ServerCertVerifier for PinnedVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        self.inner.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            ocsp_response,
+            now,
+        )?;
+        let digest: [u8; 32] = Sha256::digest(end_entity.as_ref()).into();
+        if self.pins.contains(&digest) {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::InvalidCertificate(
+                CertificateError::ApplicationVerificationFailure,
+            ))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_pin_accepts_hex_and_openssl_fingerprints() {
+        let hex = "a1".repeat(32);
+        assert_eq!(parse_pin(&hex), Some([0xa1; 32]));
+        let openssl = vec!["A1"; 32].join(":");
+        assert_eq!(parse_pin(&format!("sha256:{openssl}")), Some([0xa1; 32]));
+        assert_eq!(parse_pin("a1a1"), None);
+        assert_eq!(parse_pin(&"zz".repeat(32)), None);
+    }
+}
@@ -0,0 +1,351 @@
+use std::sync::Mutex;
+
+#[cfg(not(feature = "no-network"))]
+use tauri::menu::Submenu;
+use tauri::menu::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager};
+
+use crate::always_on_top;
+use crate::api_url;
+use crate::costs;
+#[cfg(not(feature = "no-network"))]
+use crate::discovery::{self, DiscoveryState};
+use crate::i18n;
+use crate::kiosk;
+use crate::live::LiveSession;
+use crate::notify;
+use crate::rendering::{self, Rendering};
+
+const TRAY_ID: &str = "main";
+const TOOLTIP: &str = "AgentsView";
+const ALERT_MARK: &str = "⚠";
+const ACKNOWLEDGE_ID: &str = "cost-acknowledge";
+const SNOOZE_ID: &str = "cost-snooze";
+const CLEAN_RESTART_ID: &str = "clean-restart";
+const REFRESH_ENV_ID: &str = "refresh-env";
+const COPY_API_URL_ID: &str = "copy-api-url";
+const ALWAYS_ON_TOP_ID: &str = "always-on-top";
+const RENDERING_ID: &str = "rendering";
+// CONNECT_PREFIX starts the id of a discovered server's menu item;
+// the server's URL follows it.
+#[cfg(not(feature = "no-network"))]
+const CONNECT_PREFIX: &str = "connect:";
+
+// TrayState holds what the tray shows besides the icon. The turn
+// timer and the cost alert are updated independently and share
+// the title and tooltip.
+#[derive(Default)]
+pub(crate) struct TrayState {
+    text: Mutex<TrayText>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+struct TrayText {
+    // turn is the running turn's project and elapsed time.
+    turn: Option<(String, String)>,
+    cost_alert: Option<String>,
+}
+
+// install adds the tray icon. Clicking it brings the main window
+// to the front, on the session waiting for input if there is one;
+// its menu holds troubleshooting actions.
+pub(crate) fn install(app: &AppHandle) {
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip(TOOLTIP)
+        .show_menu_on_left_click(false)
+        .on_menu_event(on_menu_event)
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                let app = tray.app_handle();
+                if !notify::open_pending(app) {
+                    if let Some(window) = app.get_webview_window("main") {
+                        let _ = window.unminimize();
+                        let _ = window.show();
+                        let _ = window.set_focus();
+                    }
+                }
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    if let Some(menu) = tray_menu(app, false) {
+        builder = builder.menu(&menu);
+    }
+    if let Err(err) = builder.build(app) {
+        eprintln!("[agentsview] failed to create tray icon: {err}");
+    }
+}
+
+// show_turn puts the running turn's elapsed time next to the tray
+// icon (macOS and some Linux panels) and in its tooltip, or
+// removes it when session is None.
+pub(crate) fn show_turn(app: &AppHandle, session: Option<&LiveSession>, now: u64) {
+    let turn = session.and_then(|session| {
+        let started_at = session.turn_started_at?;
+        let elapsed = format_elapsed(now.saturating_sub(started_at));
+        Some((session.project.clone(), elapsed))
+    });
+    update(app, |text| text.turn = turn);
+}
+
+// show_cost_alert marks the tray while a spending limit is
+// exceeded and offers to acknowledge or snooze the alert from the
+// tray menu. None clears the mark and those menu items.
+pub(crate) fn show_cost_alert(app: &AppHandle, alert: Option<String>) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let _ = tray.set_menu(tray_menu(app, alert.is_some()));
+    update(app, |text| text.cost_alert = alert);
+}
+
+// refresh_menu rebuilds the tray menu, for changes such as newly
+// discovered servers.
+pub(crate) fn refresh_menu(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let cost_alert = app
+        .state::<TrayState>()
+        .text
+        .lock()
+        .is_ok_and(|text| text.cost_alert.is_some());
+    let _ = tray.set_menu(tray_menu(app, cost_alert));
+}
+
+// tray_menu builds the tray menu: the cost alert actions while an
+// alert is active, servers found on the LAN when discovery is on,
+// the main window's keep on top toggle, then the troubleshooting
+// actions.
+fn tray_menu(app: &AppHandle, cost_alert: bool) -> Option<Menu<tauri::Wry>> {
+    let menu = Menu::new(app).ok()?;
+    if cost_alert {
+        for item in cost_alert_items(app)? {
+            menu.append(&item).ok()?;
+        }
+        let separator = PredefinedMenuItem::separator(app).ok()?;
+        menu.append(&separator).ok()?;
+    }
+    #[cfg(not(feature = "no-network"))]
+    if discovery::enabled() {
+        menu.append(&connect_menu(app)?).ok()?;
+    }
+    if kiosk::config().is_none() {
+        let always_on_top = CheckMenuItem::with_id(
+            app,
+            ALWAYS_ON_TOP_ID,
+            i18n::tr("tray-always-on-top"),
+            true,
+            always_on_top::main_pinned(app),
+            None::<&str>,
+        )
+        .ok()?;
+        menu.append(&always_on_top).ok()?;
+        let separator = PredefinedMenuItem::separator(app).ok()?;
+        menu.append(&separator).ok()?;
+    }
+    let clean_restart = MenuItem::with_id(
+        app,
+        CLEAN_RESTART_ID,
+        i18n::tr("tray-clean-restart"),
+        true,
+        None::<&str>,
+    )
+    .ok()?;
+    menu.append(&clean_restart).ok()?;
+    let refresh_env = MenuItem::with_id(
+        app,
+        REFRESH_ENV_ID,
+        i18n::tr("tray-refresh-environment"),
+        true,
+        None::<&str>,
+    )
+    .ok()?;
+    menu.append(&refresh_env).ok()?;
+    let copy_api_url = MenuItem::with_id(
+        app,
+        COPY_API_URL_ID,
+        i18n::tr("tray-copy-api-url"),
+        true,
+        None::<&str>,
+    )
+    .ok()?;
+    menu.append(&copy_api_url).ok()?;
+    if rendering::supported() {
+        menu.append(&rendering_item(app)?).ok()?;
+    }
+    Some(menu)
+}
+
+fn cost_alert_items(app: &AppHandle) -> Option<[MenuItem<tauri::Wry>; 2]> {
+    let acknowledge = MenuItem::with_id(
+        app,
+        ACKNOWLEDGE_ID,
+        i18n::tr("tray-cost-acknowledge"),
+        true,
+        None::<&str>,
+    )
+    .ok()?;
+    let snooze = MenuItem::with_id(
+        app,
+        SNOOZE_ID,
+        i18n::tr("tray-cost-snooze"),
+        true,
+        None::<&str>,
+    )
+    .ok()?;
+    Some([acknowledge, snooze])
+}
+
+// connect_menu lists the servers discovery found, each attaching
+// the main window to it.
+#[cfg(not(feature = "no-network"))]
+fn connect_menu(app: &AppHandle) -> Option<Submenu<tauri::Wry>> {
+    let servers = app.state::<DiscoveryState>().servers();
+    let submenu = Submenu::new(app, i18n::tr("tray-connect"), true).ok()?;
+    if servers.is_empty() {
+        let none = MenuItem::new(app, i18n::tr("tray-connect-none"), false, None::<&str>).ok()?;
+        submenu.append(&none).ok()?;
+    }
+    for server in servers {
+        let label = format!("{} ({})", server.name, server.url.trim_end_matches('/'));
+        let id = format!("{CONNECT_PREFIX}{}", server.url);
+        let item = MenuItem::with_id(app, id, label, true, None::<&str>).ok()?;
+        submenu.append(&item).ok()?;
+    }
+    Some(submenu)
+}
+
+// rendering_item relaunches with the other rendering mode.
+fn rendering_item(app: &AppHandle) -> Option<MenuItem<tauri::Wry>> {
+    let key = match rendering::rendering() {
+        Rendering::Hardware => "tray-software-rendering",
+        Rendering::Software | Rendering::Trial => "tray-hardware-rendering",
+    };
+    MenuItem::with_id(app, RENDERING_ID, i18n::tr(key), true, None::<&str>).ok()
+}
+
+fn on_menu_event(app: &AppHandle, event: MenuEvent) {
+    match event.id().as_ref() {
+        ACKNOWLEDGE_ID => costs::acknowledge(app),
+        SNOOZE_ID => costs::snooze(app),
+        CLEAN_RESTART_ID => crate::restart_backend_clean(app),
+        REFRESH_ENV_ID => crate::refresh_environment(app),
+        ALWAYS_ON_TOP_ID => always_on_top::toggle_main(app),
+        COPY_API_URL_ID => {
+            if let Err(err) = api_url::copy(app) {
+                eprintln!("[agentsview] could not copy the API URL: {err}");
+            }
+        }
+        RENDERING_ID => rendering::relaunch(app, rendering::rendering() == Rendering::Hardware),
+        #[cfg(not(feature = "no-network"))]
+        id => {
+            if let Some(url) = id.strip_prefix(CONNECT_PREFIX) {
+                discovery::connect(app, url);
+            }
+        }
+        #[cfg(feature = "no-network")]
+        _ => {}
+    }
+}
+
+fn update(app: &AppHandle, change: impl FnOnce(&mut TrayText)) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let Ok(mut text) = app.state::<TrayState>().text.lock() else {
+        return;
+    };
+    let before = text.clone();
+    change(&mut text);
+    if *text == before {
+        return;
+    }
+    let (title, tooltip) = render(&text);
+    let _ = tray.set_title(title);
+    let _ = tray.set_tooltip(Some(tooltip));
+}
+
+// render builds the tray title and tooltip: the turn timer,
+// prefixed with a warning mark while a cost alert is active.
+fn render(text: &TrayText) -> (Option<String>, String) {
+    let elapsed = text.turn.as_ref().map(|(_, elapsed)| elapsed.as_str());
+    let title = match (&text.cost_alert, elapsed) {
+        (Some(_), Some(elapsed)) => Some(format!("{ALERT_MARK} {elapsed}")),
+        (Some(_), None) => Some(ALERT_MARK.to_string()),
+        (None, elapsed) => elapsed.map(str::to_string),
+    };
+    let mut lines = Vec::new();
+    if let Some((project, elapsed)) = &text.turn {
+        lines.push(i18n::tr_args(
+            "tray-turn-running",
+            &[("project", project), ("elapsed", elapsed)],
+        ));
+    }
+    if let Some(alert) = &text.cost_alert {
+        lines.push(alert.clone());
+    }
+    let tooltip = if lines.is_empty() {
+        TOOLTIP.to_string()
+    } else {
+        lines.join("\n")
+    };
+    (title, tooltip)
+}
+
+// format_elapsed renders a duration as m:ss, or h:mm:ss from an
+// hour on, like a stopwatch.
+fn format_elapsed(secs: u64) -> String {
+    let (hours, minutes, seconds) = (secs / 3_600, secs % 3_600 / 60, secs % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_elapsed_reads_like_a_stopwatch() {
+        assert_eq!(format_elapsed(0), "0:00");
+        assert_eq!(format_elapsed(65), "1:05");
+        assert_eq!(format_elapsed(3_599), "59:59");
+        assert_eq!(format_elapsed(3_600), "1:00:00");
+        assert_eq!(format_elapsed(45_296), "12:34:56");
+    }
+
+    #[test]
+    fn render_marks_cost_alerts() {
+        let alert = TrayText {
+            turn: None,
+            cost_alert: Some("Daily spend $12.00 is over $10.00".to_string()),
+        };
+        assert_eq!(
+            render(&alert),
+            (
+                Some(ALERT_MARK.to_string()),
+                "Daily spend $12.00 is over $10.00".to_string()
+            )
+        );
+
+        let both = TrayText {
+            turn: Some(("agentsview".to_string(), "1:05".to_string())),
+            ..alert
+        };
+        let (title, tooltip) = render(&both);
+        assert_eq!(title.as_deref(), Some("⚠ 1:05"));
+        assert!(tooltip.ends_with("\nDaily spend $12.00 is over $10.00"));
+
+        assert_eq!(render(&TrayText::default()), (None, TOOLTIP.to_string()));
+    }
+}
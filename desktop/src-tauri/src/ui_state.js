@@ -0,0 +1,70 @@
+// UI state sync, injected into every page. The local backend's
+// origin includes the sidecar's port, so localStorage starts empty
+// whenever that port changes. This mirrors localStorage into the
+// shell and restores the mirror into an empty store.
+(() => {
+  const SAVE_INTERVAL_MS = 15000;
+  const RESTORED_FLAG = "agentsview-ui-state-restored";
+
+  const invoke = window.__TAURI_INTERNALS__?.invoke;
+  if (!invoke || window.location.hostname !== "127.0.0.1" || window !== window.top) {
+    return;
+  }
+
+  const snapshot = () => {
+    const entries = {};
+    for (let i = 0; i < localStorage.length; i += 1) {
+      const key = localStorage.key(i);
+      entries[key] = localStorage.getItem(key);
+    }
+    return entries;
+  };
+
+  let saved = JSON.stringify(snapshot());
+  let restoring = false;
+  const save = () => {
+    if (restoring) {
+      return;
+    }
+    const entries = snapshot();
+    const json = JSON.stringify(entries);
+    if (json === saved) {
+      return;
+    }
+    saved = json;
+    invoke("save_ui_state", { entries }).catch(() => {});
+  };
+
+  // The UI has already read its defaults by the time the snapshot
+  // arrives, so a restore overwrites them and reloads once. Saving
+  // waits for the restore so those defaults never replace the
+  // snapshot. The session flag keeps a snapshot that fails to
+  // stick from reloading forever.
+  if (localStorage.length === 0 && !sessionStorage.getItem(RESTORED_FLAG)) {
+    restoring = true;
+    invoke("load_ui_state")
+      .then((entries) => {
+        const keys = Object.keys(entries ?? {});
+        if (keys.length === 0) {
+          return;
+        }
+        for (const key of keys) {
+          localStorage.setItem(key, entries[key]);
+        }
+        sessionStorage.setItem(RESTORED_FLAG, "1");
+        window.location.reload();
+      })
+      .catch(() => {})
+      .finally(() => {
+        restoring = false;
+      });
+  }
+
+  window.setInterval(save, SAVE_INTERVAL_MS);
+  window.addEventListener("pagehide", save);
+  document.addEventListener("visibilitychange", () => {
+    if (document.visibilityState === "hidden") {
+      save();
+    }
+  });
+})();
@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use tauri::plugin::{Builder as PluginBuilder, TauriPlugin};
+use tauri::Runtime;
+
+use crate::error::DesktopError;
+use crate::user;
+
+const UI_STATE_FILE: &str = "ui-state.json";
+const SYNC_SCRIPT: &str = include_str!("ui_state.js");
+// MAX_UI_STATE_BYTES caps the snapshot well under the webview's
+// own localStorage quota; UI preferences are a few kilobytes.
+const MAX_UI_STATE_BYTES: usize = 1 << 20;
+
+// init_plugin injects the script that mirrors the backend UI's
+// localStorage into ui-state.json. localStorage is keyed by origin,
+// and the origin changes whenever the sidecar lands on a different
+// port, so the script restores the snapshot into an empty store.
+pub(crate) fn init_plugin<R: Runtime>() -> TauriPlugin<R> {
+    PluginBuilder::new("ui-state")
+        .js_init_script(SYNC_SCRIPT.to_string())
+        .build()
+}
+
+// load_ui_state returns the last localStorage snapshot, or an
+// empty one when there is none.
+#[tauri::command]
+pub(crate) fn load_ui_state() -> BTreeMap<String, String> {
+    ui_state_path()
+        .map(|path| load_from(&path))
+        .unwrap_or_default()
+}
+
+// save_ui_state replaces the localStorage snapshot. Snapshots over
+// MAX_UI_STATE_BYTES are dropped rather than truncated, so a
+// restore never brings back half of the UI's state.
+#[tauri::command]
+pub(crate) fn save_ui_state(entries: BTreeMap<String, String>) -> Result<(), DesktopError> {
+    let Some(path) = ui_state_path() else {
+        return Ok(());
+    };
+    save_to(&path, &entries)?;
+    Ok(())
+}
+
+fn ui_state_path() -> Option<PathBuf> {
+    let dir = crate::data_dir()?;
+    let name = user::scoped_file_name(UI_STATE_FILE, &dir);
+    Some(dir.join(name))
+}
+
+fn load_from(path: &Path) -> BTreeMap<String, String> {
+    let Ok(content) = fs::read(path) else {
+        return BTreeMap::new();
+    };
+    serde_json::from_slice(&content).unwrap_or_else(|err| {
+        eprintln!(
+            "[agentsview] ignoring invalid UI state file {}: {err}",
+            path.display()
+        );
+        BTreeMap::new()
+    })
+}
+
+// save_to writes through a temp file in the same directory, like
+// the settings file, so a crash never leaves a truncated snapshot.
+fn save_to(path: &Path, entries: &BTreeMap<String, String>) -> io::Result<()> {
+    let json = serde_json::to_vec(entries).map_err(io::Error::other)?;
+    if json.len() > MAX_UI_STATE_BYTES {
+        eprintln!(
+            "[agentsview] not saving UI state: {} bytes exceeds the {MAX_UI_STATE_BYTES} byte limit",
+            json.len()
+        );
+        return Ok(());
+    }
+    let dir = path
+        .parent()
+        .ok_or_else(|| io::Error::other("UI state path has no parent directory"))?;
+    fs::create_dir_all(dir)?;
+
+    let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+    tmp.write_all(&json)?;
+    tmp.as_file().sync_all()?;
+    tmp.persist(path).map_err(|err| err.error)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_to_round_trips_and_skips_oversized_snapshots() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("nested").join(UI_STATE_FILE);
+        assert!(load_from(&path).is_empty());
+
+        let entries = BTreeMap::from([
+            ("theme".to_string(), "dark".to_string()),
+            ("sidebar-collapsed".to_string(), "true".to_string()),
+        ]);
+        save_to(&path, &entries).expect("save UI state");
+        assert_eq!(load_from(&path), entries);
+
+        let huge = BTreeMap::from([("cache".to_string(), "x".repeat(MAX_UI_STATE_BYTES))]);
+        save_to(&path, &huge).expect("skip oversized UI state");
+        assert_eq!(load_from(&path), entries);
+
+        fs::write(&path, "{not json").expect("write invalid UI state");
+        assert!(load_from(&path).is_empty());
+    }
+}
@@ -0,0 +1,127 @@
+use std::path::Path;
+
+// BASE_PORT is where per-user port slots start. Slots are
+// PORT_STRIDE apart, and ports::range scans only its own user's
+// slot.
+const BASE_PORT: u16 = 8080;
+const PORT_SLOTS: u32 = 500;
+pub(crate) const PORT_STRIDE: u16 = 10;
+
+// preferred_port is the first port of the account's slot. It is
+// derived from the OS account so users sharing a workstation do not
+// all race for 8080.
+pub(crate) fn preferred_port() -> u16 {
+    preferred_port_for(user_slot())
+}
+
+fn preferred_port_for(slot: u32) -> u16 {
+    BASE_PORT + (slot % PORT_SLOTS) as u16 * PORT_STRIDE
+}
+
+// user_key identifies the OS account in file names.
+pub(crate) fn user_key() -> String {
+    if cfg!(unix) {
+        return format!("uid-{}", user_slot());
+    }
+    let name = std::env::var("USERNAME").unwrap_or_default();
+    let key = sanitize_user_name(&name);
+    if key.is_empty() {
+        return "user".to_string();
+    }
+    key
+}
+
+fn sanitize_user_name(name: &str) -> String {
+    name.trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+// scoped_file_name returns name unchanged when dir belongs to the
+// current user, and with the user key inserted before the
+// extension when dir is shared, such as a managed DataDir outside
+// the home directory. Locks and logs use it so users sharing a
+// data directory do not collide.
+pub(crate) fn scoped_file_name(name: &str, dir: &Path) -> String {
+    scoped_file_name_for(name, dir, crate::resolve_home_dir().as_deref(), &user_key())
+}
+
+fn scoped_file_name_for(name: &str, dir: &Path, home: Option<&Path>, key: &str) -> String {
+    if home.is_some_and(|home| dir.starts_with(home)) {
+        return name.to_string();
+    }
+    match name.rsplit_once('.') {
+        Some((stem, ext)) => format!("{stem}-{key}.{ext}"),
+        None => format!("{name}-{key}"),
+    }
+}
+
+#[cfg(unix)]
+fn user_slot() -> u32 {
+    // SAFETY: getuid has no preconditions and cannot fail.
+    unsafe { libc::getuid() }
+}
+
+// user_slot hashes the account name with FNV-1a; Windows has no
+// small numeric user id to use directly.
+#[cfg(not(unix))]
+fn user_slot() -> u32 {
+    let name = std::env::var("USERNAME").unwrap_or_default().to_lowercase();
+    name.bytes().fold(0x811c_9dc5_u32, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preferred_port_for_spreads_users_across_slots() {
+        assert_eq!(preferred_port_for(0), 8080);
+        assert_eq!(preferred_port_for(501), 8090);
+        assert_eq!(preferred_port_for(502), 8100);
+        assert_eq!(preferred_port_for(u32::MAX), 8080 + 295 * 10);
+        assert!(preferred_port_for(PORT_SLOTS - 1) < 13080);
+    }
+
+    #[test]
+    fn scoped_file_name_only_scopes_shared_dirs() {
+        let home = Path::new("/home/alice");
+        assert_eq!(
+            scoped_file_name_for(
+                "desktop.lock",
+                Path::new("/home/alice/.agentsview"),
+                Some(home),
+                "uid-501"
+            ),
+            "desktop.lock"
+        );
+        assert_eq!(
+            scoped_file_name_for(
+                "desktop.lock",
+                Path::new("/srv/agentsview"),
+                Some(home),
+                "uid-501"
+            ),
+            "desktop-uid-501.lock"
+        );
+        assert_eq!(
+            scoped_file_name_for("debug.log", Path::new("/srv/agentsview"), None, "bob"),
+            "debug-bob.log"
+        );
+    }
+
+    #[test]
+    fn sanitize_user_name_keeps_file_safe_characters() {
+        assert_eq!(sanitize_user_name(" Jane.Doe@CORP "), "jane_doe_corp");
+        assert_eq!(sanitize_user_name("build_agent-2"), "build_agent-2");
+    }
+}
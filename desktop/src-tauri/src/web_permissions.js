@@ -0,0 +1,53 @@
+// Applies the desktop shell's web permission policy inside pages,
+// so every platform webview answers the same way. See
+// web_permissions.rs, which also answers native permission requests
+// where the webview reports them.
+(() => {
+  const notAllowed = (what) =>
+    new DOMException(`${what} is not available in AgentsView`, "NotAllowedError");
+  const refuse = (target, name, what) => {
+    if (target && typeof target[name] === "function") {
+      Object.defineProperty(target, name, {
+        value: () => Promise.reject(notAllowed(what)),
+        configurable: false,
+      });
+    }
+  };
+
+  refuse(navigator.mediaDevices, "getUserMedia", "Camera and microphone access");
+  refuse(navigator.mediaDevices, "getDisplayMedia", "Screen capture");
+  for (const legacy of ["getUserMedia", "webkitGetUserMedia"]) {
+    if (typeof navigator[legacy] === "function") {
+      navigator[legacy] = (_constraints, _success, failure) => {
+        if (failure) failure(notAllowed("Camera and microphone access"));
+      };
+    }
+  }
+
+  if (navigator.geolocation) {
+    const deny = (_success, failure) => {
+      if (failure) {
+        setTimeout(() => failure({ code: 1, message: "Location is not available in AgentsView" }));
+      }
+      return 0;
+    };
+    navigator.geolocation.getCurrentPosition = deny;
+    navigator.geolocation.watchPosition = deny;
+  }
+
+  if (window.Notification) {
+    Object.defineProperty(Notification, "permission", { get: () => "denied" });
+    Notification.requestPermission = (callback) => {
+      if (callback) callback("denied");
+      return Promise.resolve("denied");
+    };
+  }
+
+  refuse(navigator.clipboard, "read", "Reading the clipboard");
+  refuse(navigator.clipboard, "readText", "Reading the clipboard");
+
+  // Downloads are held back until the user picks where to save them,
+  // then repeated; keep blob URLs alive long enough for the repeat.
+  const revoke = URL.revokeObjectURL.bind(URL);
+  URL.revokeObjectURL = (url) => setTimeout(() => revoke(url), 60000);
+})();
@@ -0,0 +1,192 @@
+use tauri::plugin::{Builder as PluginBuilder, TauriPlugin};
+use tauri::{Runtime, Webview};
+
+const POLICY_SCRIPT: &str = include_str!("web_permissions.js");
+
+// WebPermission is something a page in the webview can ask the
+// platform for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum WebPermission {
+    ClipboardWrite,
+    ClipboardRead,
+    Camera,
+    Microphone,
+    ScreenCapture,
+    Geolocation,
+    Notifications,
+    Download,
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Verdict {
+    Allow,
+    Prompt,
+    Deny,
+}
+
+// verdict is the shell's answer to a web permission request, the
+// same on every platform rather than each webview's default. Copy
+// buttons may write the clipboard, and downloads first ask where
+// to save the file (see the downloads module). The UI needs
+// nothing else, so everything else is denied; desktop
+// notifications go through the notify module instead of the web
+// Notification API.
+pub(crate) fn verdict(permission: WebPermission) -> Verdict {
+    match permission {
+        WebPermission::ClipboardWrite => Verdict::Allow,
+        WebPermission::Download => Verdict::Prompt,
+        WebPermission::ClipboardRead
+        | WebPermission::Camera
+        | WebPermission::Microphone
+        | WebPermission::ScreenCapture
+        | WebPermission::Geolocation
+        | WebPermission::Notifications
+        | WebPermission::Other => Verdict::Deny,
+    }
+}
+
+// init_plugin applies the policy to every webview: a script that
+// refuses denied APIs inside the page, which is all macOS allows
+// (WKWebView grants media capture before the page can be asked),
+// and native handlers for the requests WebKitGTK and WebView2
+// report.
+pub(crate) fn init_plugin<R: Runtime>() -> TauriPlugin<R> {
+    PluginBuilder::new("web-permissions")
+        .js_init_script_on_all_frames(POLICY_SCRIPT.to_string())
+        .on_webview_ready(|webview| install_native_handler(&webview))
+        .build()
+}
+
+#[cfg(target_os = "linux")]
+fn install_native_handler<R: Runtime>(webview: &Webview<R>) {
+    use webkit2gtk::{PermissionRequestExt, WebViewExt};
+
+    let result = webview.with_webview(|platform| {
+        platform.inner().connect_permission_request(|_, request| {
+            match verdict(linux_permission(request)) {
+                Verdict::Allow => request.allow(),
+                Verdict::Prompt | Verdict::Deny => request.deny(),
+            }
+            true
+        });
+    });
+    if let Err(err) = result {
+        eprintln!("[agentsview] could not install the web permission handler: {err}");
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn linux_permission(request: &webkit2gtk::PermissionRequest) -> WebPermission {
+    use glib::prelude::*;
+    use webkit2gtk::{
+        GeolocationPermissionRequest, NotificationPermissionRequest, UserMediaPermissionRequest,
+        UserMediaPermissionRequestExt,
+    };
+
+    if let Some(media) = request.downcast_ref::<UserMediaPermissionRequest>() {
+        if media.is_for_video_device() {
+            WebPermission::Camera
+        } else if media.is_for_audio_device() {
+            WebPermission::Microphone
+        } else {
+            WebPermission::ScreenCapture
+        }
+    } else if request.is::<GeolocationPermissionRequest>() {
+        WebPermission::Geolocation
+    } else if request.is::<NotificationPermissionRequest>() {
+        WebPermission::Notifications
+    } else {
+        WebPermission::Other
+    }
+}
+
+// install_native_handler on Windows answers WebView2's permission
+// requests. It runs after wry's own handler, which allows clipboard
+// reads, so its answer is the one WebView2 uses.
+#[cfg(windows)]
+fn install_native_handler<R: Runtime>(webview: &Webview<R>) {
+    use webview2_com::Microsoft::Web::WebView2::Win32::{
+        COREWEBVIEW2_PERMISSION_KIND, COREWEBVIEW2_PERMISSION_STATE_ALLOW,
+        COREWEBVIEW2_PERMISSION_STATE_DENY,
+    };
+    use webview2_com::PermissionRequestedEventHandler;
+
+    let result = webview.with_webview(|platform| unsafe {
+        let core = match platform.controller().CoreWebView2() {
+            Ok(core) => core,
+            Err(err) => {
+                eprintln!("[agentsview] could not install the web permission handler: {err}");
+                return;
+            }
+        };
+        let handler = PermissionRequestedEventHandler::create(Box::new(|_, args| {
+            let Some(args) = args else {
+                return Ok(());
+            };
+            let mut kind = COREWEBVIEW2_PERMISSION_KIND::default();
+            args.PermissionKind(&mut kind)?;
+            // Each download asks where to save it in downloads.rs,
+            // so WebView2 may start as many as the page asks for.
+            let state = match verdict(windows_permission(kind)) {
+                Verdict::Allow | Verdict::Prompt => COREWEBVIEW2_PERMISSION_STATE_ALLOW,
+                Verdict::Deny => COREWEBVIEW2_PERMISSION_STATE_DENY,
+            };
+            args.SetState(state)
+        }));
+        let mut token = 0;
+        if let Err(err) = core.add_PermissionRequested(&handler, &mut token) {
+            eprintln!("[agentsview] could not install the web permission handler: {err}");
+        }
+    });
+    if let Err(err) = result {
+        eprintln!("[agentsview] could not install the web permission handler: {err}");
+    }
+}
+
+#[cfg(windows)]
+fn windows_permission(
+    kind: webview2_com::Microsoft::Web::WebView2::Win32::COREWEBVIEW2_PERMISSION_KIND,
+) -> WebPermission {
+    use webview2_com::Microsoft::Web::WebView2::Win32::{
+        COREWEBVIEW2_PERMISSION_KIND_CAMERA, COREWEBVIEW2_PERMISSION_KIND_CLIPBOARD_READ,
+        COREWEBVIEW2_PERMISSION_KIND_GEOLOCATION, COREWEBVIEW2_PERMISSION_KIND_MICROPHONE,
+        COREWEBVIEW2_PERMISSION_KIND_MULTIPLE_AUTOMATIC_DOWNLOADS,
+        COREWEBVIEW2_PERMISSION_KIND_NOTIFICATIONS,
+    };
+
+    match kind {
+        COREWEBVIEW2_PERMISSION_KIND_CAMERA => WebPermission::Camera,
+        COREWEBVIEW2_PERMISSION_KIND_MICROPHONE => WebPermission::Microphone,
+        COREWEBVIEW2_PERMISSION_KIND_CLIPBOARD_READ => WebPermission::ClipboardRead,
+        COREWEBVIEW2_PERMISSION_KIND_GEOLOCATION => WebPermission::Geolocation,
+        COREWEBVIEW2_PERMISSION_KIND_NOTIFICATIONS => WebPermission::Notifications,
+        COREWEBVIEW2_PERMISSION_KIND_MULTIPLE_AUTOMATIC_DOWNLOADS => WebPermission::Download,
+        _ => WebPermission::Other,
+    }
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn install_native_handler<R: Runtime>(_webview: &Webview<R>) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verdict_allows_clipboard_writes_and_prompts_for_downloads() {
+        assert_eq!(verdict(WebPermission::ClipboardWrite), Verdict::Allow);
+        assert_eq!(verdict(WebPermission::Download), Verdict::Prompt);
+        for denied in [
+            WebPermission::ClipboardRead,
+            WebPermission::Camera,
+            WebPermission::Microphone,
+            WebPermission::ScreenCapture,
+            WebPermission::Geolocation,
+            WebPermission::Notifications,
+            WebPermission::Other,
+        ] {
+            assert_eq!(verdict(denied), Verdict::Deny, "{denied:?}");
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use std::thread;
+use std::time::Duration;
+
+use serde::Deserialize;
+use tauri::{AppHandle, Manager};
+
+use crate::backend_client::BackendClient;
+use crate::dnd;
+use crate::error::DesktopError;
+use crate::i18n::{self, Localizer};
+use crate::notify::{self, Notice};
+use crate::settings;
+use crate::shutdown::ShutdownSignal;
+use crate::timestamps;
+
+const WEEK_SECS: u64 = 7 * 86_400;
+// CHECK_INTERVAL is how often the scheduler looks for a due
+// summary; a summary held back by Do Not Disturb goes out at the
+// first check after it ends.
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const TOP_PROJECTS: usize = 3;
+const DASHBOARD_ROUTE: &str = "#/sessions";
+
+#[derive(Debug, Default, Deserialize)]
+struct AnalyticsSummary {
+    #[serde(default)]
+    total_sessions: u64,
+    #[serde(default)]
+    total_messages: u64,
+    #[serde(default)]
+    active_days: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProjectAnalytics {
+    name: String,
+    #[serde(default)]
+    sessions: u64,
+}
+
+#[derive(Deserialize)]
+struct ProjectsResponse {
+    #[serde(default)]
+    projects: Vec<ProjectAnalytics>,
+}
+
+// spawn_scheduler posts a summary of the past week's usage once a
+// week while the user has opted in with weekly_summary. The time
+// of the last summary is saved, so restarts neither repeat nor
+// skip one.
+pub(crate) fn spawn_scheduler(app: AppHandle) {
+    let shutdown = app.state::<ShutdownSignal>().inner().clone();
+    thread::spawn(move || loop {
+        let saved = settings::load_settings();
+        let now = timestamps::unix_now();
+        if saved.weekly_summary && is_due(saved.last_weekly_summary, now) && !dnd::do_not_disturb()
+        {
+            if let Some(client) = BackendClient::for_app(&app) {
+                match post_summary(&app, &client, now) {
+                    Ok(()) => record_sent(now),
+                    Err(err) => eprintln!("[agentsview] weekly summary failed: {err}"),
+                }
+            }
+        }
+        if shutdown.wait(CHECK_INTERVAL) {
+            return;
+        }
+    });
+}
+
+fn is_due(last: Option<u64>, now: u64) -> bool {
+    last.is_none_or(|last| now.saturating_sub(last) >= WEEK_SECS)
+}
+
+fn record_sent(now: u64) {
+    if let Err(err) = settings::update_settings(|s| s.last_weekly_summary = Some(now)) {
+        eprintln!("[agentsview] failed to save weekly summary time: {err}");
+    }
+}
+
+// post_summary fetches the last seven days' totals and top
+// projects. Weeks without sessions are skipped silently.
+fn post_summary(app: &AppHandle, client: &BackendClient, now: u64) -> Result<(), DesktopError> {
+    let range = format!(
+        "from={}&to={}",
+        timestamps::date_utc(now.saturating_sub(WEEK_SECS - 86_400)),
+        timestamps::date_utc(now)
+    );
+    let summary = client
+        .get_json_until::<AnalyticsSummary>(&format!("/api/v1/analytics/summary?{range}"), &|| {
+            false
+        })?
+        .unwrap_or_default();
+    if summary.total_sessions == 0 {
+        return Ok(());
+    }
+    let projects = client
+        .get_json_until::<ProjectsResponse>(
+            &format!("/api/v1/analytics/projects?{range}"),
+            &|| false,
+        )?
+        .map(|response| response.projects)
+        .unwrap_or_default();
+    notify::post(
+        app,
+        Notice {
+            title: i18n::tr("weekly-summary-title"),
+            body: summary_body(i18n::localizer(), &summary, projects),
+            sound: None,
+            route: Some(DASHBOARD_ROUTE.to_string()),
+        },
+    );
+    Ok(())
+}
+
+fn summary_body(
+    localizer: &Localizer,
+    summary: &AnalyticsSummary,
+    mut projects: Vec<ProjectAnalytics>,
+) -> String {
+    let mut body = localizer.format(
+        "weekly-summary-totals",
+        &[
+            ("sessions", &summary.total_sessions),
+            ("messages", &summary.total_messages),
+            ("days", &summary.active_days),
+        ],
+    );
+    projects.sort_by(|a, b| {
+        b.sessions
+            .cmp(&a.sessions)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    let top: Vec<&str> = projects
+        .iter()
+        .take(TOP_PROJECTS)
+        .map(|project| project.name.as_str())
+        .collect();
+    if !top.is_empty() {
+        body.push('\n');
+        body.push_str(&localizer.format("weekly-summary-top", &[("projects", &top.join(", "))]));
+    }
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(name: &str, sessions: u64) -> ProjectAnalytics {
+        ProjectAnalytics {
+            name: name.to_string(),
+            sessions,
+        }
+    }
+
+    #[test]
+    fn is_due_waits_a_week_between_summaries() {
+        assert!(is_due(None, 0));
+        assert!(!is_due(Some(1_000), 1_000 + WEEK_SECS - 1));
+        assert!(is_due(Some(1_000), 1_000 + WEEK_SECS));
+        assert!(!is_due(Some(2_000), 1_000));
+    }
+
+    #[test]
+    fn summary_body_lists_busiest_projects() {
+        let summary = AnalyticsSummary {
+            total_sessions: 12,
+            total_messages: 340,
+            active_days: 4,
+        };
+        let english = Localizer::for_locale("en");
+        let body = summary_body(
+            &english,
+            &summary,
+            vec![
+                project("docs", 1),
+                project("agentsview", 7),
+                project("cli", 3),
+                project("api", 3),
+            ],
+        );
+        assert_eq!(
+            body,
+            "Sessions: 12 · Messages: 340 · Active days: 4\nTop projects: agentsview, api, cli"
+        );
+        assert_eq!(
+            summary_body(&english, &summary, Vec::new()),
+            "Sessions: 12 · Messages: 340 · Active days: 4"
+        );
+    }
+}
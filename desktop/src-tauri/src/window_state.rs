@@ -0,0 +1,294 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager, Monitor, PhysicalPosition, PhysicalSize, Window};
+
+use crate::kiosk;
+use crate::presentation;
+use crate::session_window;
+use crate::settings::{self, WindowGeometry};
+use crate::shortcuts;
+
+// MIN_VISIBLE_WIDTH and MIN_VISIBLE_HEIGHT are how much of a
+// window's top edge must land on a display for its saved position
+// to be used: enough of the title bar to grab and move it.
+const MIN_VISIBLE_WIDTH: i64 = 120;
+const MIN_VISIBLE_HEIGHT: i64 = 40;
+
+// NormalGeometry remembers each window's last geometry while it was
+// neither maximized, minimized nor fullscreen. The window reports
+// only its current bounds, and a maximized window should be
+// restored to the size it had before.
+#[derive(Default)]
+pub(crate) struct NormalGeometry(Mutex<HashMap<String, WindowGeometry>>);
+
+impl NormalGeometry {
+    fn get(&self, label: &str) -> Option<WindowGeometry> {
+        self.0.lock().ok()?.get(label).cloned()
+    }
+
+    fn set(&self, label: &str, geometry: WindowGeometry) {
+        if let Ok(mut windows) = self.0.lock() {
+            windows.insert(label.to_string(), geometry);
+        }
+    }
+}
+
+// Screen is the work area of a connected display, in physical
+// pixels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Screen {
+    name: Option<String>,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+impl Screen {
+    fn from_monitor(monitor: &Monitor) -> Self {
+        let area = monitor.work_area();
+        Screen {
+            name: monitor.name().cloned(),
+            x: area.position.x,
+            y: area.position.y,
+            width: area.size.width,
+            height: area.size.height,
+        }
+    }
+
+    // shows_title_bar reports whether enough of the top edge of a
+    // window at geometry lies on this screen.
+    fn shows_title_bar(&self, geometry: &WindowGeometry) -> bool {
+        let overlap = |start: i64, len: i64, screen_start: i64, screen_len: i64| {
+            (start + len).min(screen_start + screen_len) - start.max(screen_start)
+        };
+        let width = i64::from(geometry.width);
+        let strip = i64::from(geometry.height).min(MIN_VISIBLE_HEIGHT);
+        let across = overlap(geometry.x.into(), width, self.x.into(), self.width.into());
+        let down = overlap(geometry.y.into(), strip, self.y.into(), self.height.into());
+        across >= width.min(MIN_VISIBLE_WIDTH) && down >= strip
+    }
+}
+
+// Placement is how a window is restored: at position, or centered
+// when that is None.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Placement {
+    position: Option<(i32, i32)>,
+    width: u32,
+    height: u32,
+    maximized: bool,
+}
+
+// track records a window's geometry as it moves and resizes, for
+// save to use once it is maximized or minimized.
+pub(crate) fn track(window: &Window) {
+    if !remembered(window) || !is_normal(window) {
+        return;
+    }
+    if let Some(geometry) = current_geometry(window) {
+        window
+            .state::<NormalGeometry>()
+            .set(window.label(), geometry);
+    }
+}
+
+// save stores a window's geometry in the settings as it closes.
+pub(crate) fn save(window: &Window) {
+    if !remembered(window) {
+        return;
+    }
+    let normal = window.state::<NormalGeometry>().get(window.label());
+    let geometry = if is_normal(window) {
+        current_geometry(window).or(normal)
+    } else {
+        normal
+    };
+    let Some(mut geometry) = geometry else {
+        return;
+    };
+    geometry.maximized = presentation::maximized_before(window)
+        .unwrap_or_else(|| window.is_maximized().unwrap_or(false));
+    let label = window.label().to_string();
+    if let Err(err) = settings::update_settings(|s| {
+        s.window_states.insert(label, geometry);
+    }) {
+        eprintln!("[agentsview] failed to save window geometry: {err}");
+    }
+}
+
+// save_all saves every open window, for quitting from the tray or
+// the menu, which does not close windows first.
+pub(crate) fn save_all(app: &AppHandle) {
+    for window in app.windows().values() {
+        save(window);
+    }
+}
+
+// restore puts a window back where it was last closed, before it
+// is first shown. A position on a display that is no longer
+// connected, or that now leaves the title bar off screen, is
+// dropped and the window is centered instead.
+pub(crate) fn restore(window: &Window) {
+    if !remembered(window) {
+        return;
+    }
+    let Some(saved) = settings::load_settings()
+        .window_states
+        .get(window.label())
+        .cloned()
+    else {
+        return;
+    };
+    let primary = window
+        .primary_monitor()
+        .ok()
+        .flatten()
+        .map(|monitor| Screen::from_monitor(&monitor));
+    let others = window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(Screen::from_monitor)
+        .filter(|screen| primary.as_ref() != Some(screen))
+        .collect::<Vec<_>>();
+    let screens: Vec<Screen> = primary.into_iter().chain(others).collect();
+    let Some(placement) = placement(&saved, &screens) else {
+        return;
+    };
+
+    let _ = window.set_size(PhysicalSize::new(placement.width, placement.height));
+    match placement.position {
+        Some((x, y)) => {
+            let _ = window.set_position(PhysicalPosition::new(x, y));
+        }
+        None => {
+            eprintln!(
+                "[agentsview] saved position of the {} window is off screen; centering it",
+                window.label()
+            );
+            let _ = window.center();
+        }
+    }
+    if let Some(geometry) = current_geometry(window) {
+        window
+            .state::<NormalGeometry>()
+            .set(window.label(), geometry);
+    }
+    if placement.maximized {
+        let _ = window.maximize();
+    }
+}
+
+// placement checks saved against the connected screens, the
+// primary one first. The window keeps its position only on the
+// display it was saved on, or on any display when that was not
+// recorded, and is never larger than the display it lands on.
+fn placement(saved: &WindowGeometry, screens: &[Screen]) -> Option<Placement> {
+    let fallback = screens.first()?;
+    let home = match &saved.monitor {
+        Some(name) => screens
+            .iter()
+            .find(|screen| screen.name.as_ref() == Some(name)),
+        None => screens.iter().find(|screen| screen.shows_title_bar(saved)),
+    };
+    let on_screen = home.filter(|screen| screen.shows_title_bar(saved));
+    let bounds = on_screen.unwrap_or(fallback);
+    Some(Placement {
+        position: on_screen.map(|_| (saved.x, saved.y)),
+        width: saved.width.min(bounds.width),
+        height: saved.height.min(bounds.height),
+        maximized: saved.maximized,
+    })
+}
+
+// remembered leaves out the shortcut popups, which open at a fixed
+// size, session windows, which come and go with their sessions, and
+// kiosk windows, which are always fullscreen.
+fn remembered(window: &Window) -> bool {
+    !shortcuts::is_popup(window.label())
+        && !session_window::is_session_window(window.label())
+        && kiosk::config().is_none()
+}
+
+fn is_normal(window: &Window) -> bool {
+    !window.is_maximized().unwrap_or(true)
+        && !window.is_minimized().unwrap_or(true)
+        && !window.is_fullscreen().unwrap_or(true)
+}
+
+fn current_geometry(window: &Window) -> Option<WindowGeometry> {
+    let position = window.outer_position().ok()?;
+    let size = window.inner_size().ok()?;
+    let monitor = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .and_then(|monitor| monitor.name().cloned());
+    Some(WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        maximized: false,
+        monitor,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen(name: &str, x: i32, width: u32, height: u32) -> Screen {
+        Screen {
+            name: Some(name.to_string()),
+            x,
+            y: 0,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn placement_drops_positions_off_connected_displays() {
+        let laptop = screen("Built-in", 0, 2880, 1800);
+        let external = screen("DELL U2720Q", 2880, 3840, 2160);
+        let saved = WindowGeometry {
+            x: 3000,
+            y: 100,
+            width: 3000,
+            height: 2000,
+            maximized: true,
+            monitor: Some("DELL U2720Q".to_string()),
+        };
+
+        let docked = placement(&saved, &[laptop.clone(), external.clone()]).unwrap();
+        assert_eq!(docked.position, Some((3000, 100)));
+        assert_eq!((docked.width, docked.height), (3000, 2000));
+        assert!(docked.maximized);
+
+        let undocked = placement(&saved, &[laptop.clone()]).unwrap();
+        assert_eq!(undocked.position, None, "display disconnected");
+        assert_eq!((undocked.width, undocked.height), (2880, 1800));
+
+        let unnamed = WindowGeometry {
+            monitor: None,
+            ..saved.clone()
+        };
+        assert_eq!(
+            placement(&unnamed, &[laptop.clone(), external])
+                .unwrap()
+                .position,
+            Some((3000, 100))
+        );
+
+        let above = WindowGeometry {
+            y: -500,
+            ..saved.clone()
+        };
+        let moved = screen("DELL U2720Q", 2880, 1920, 1080);
+        assert_eq!(placement(&above, &[laptop, moved]).unwrap().position, None);
+        assert_eq!(placement(&saved, &[]), None);
+    }
+}
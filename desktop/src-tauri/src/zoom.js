@@ -0,0 +1,35 @@
+// Zoom shortcuts, injected into every page. Cmd/Ctrl with +, - or 0
+// asks the shell to zoom the window in, out or back to 100%, since
+// the webview has no browser menu or settings to do it from.
+(() => {
+  const invoke = window.__TAURI_INTERNALS__?.invoke;
+  if (!invoke || window !== window.top) {
+    return;
+  }
+
+  const STEPS = {
+    "=": "in",
+    "+": "in",
+    "-": "out",
+    _: "out",
+    0: "reset",
+  };
+
+  window.addEventListener(
+    "keydown",
+    (event) => {
+      const modifier = event.ctrlKey || event.metaKey;
+      if (!modifier || event.altKey) {
+        return;
+      }
+      const step = STEPS[event.key];
+      if (!step) {
+        return;
+      }
+      event.preventDefault();
+      event.stopPropagation();
+      invoke("change_zoom", { step }).catch(() => {});
+    },
+    true,
+  );
+})();
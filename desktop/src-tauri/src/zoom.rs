@@ -0,0 +1,121 @@
+use serde::Deserialize;
+use tauri::plugin::{Builder as PluginBuilder, TauriPlugin};
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+
+use crate::settings::{self, DesktopSettings};
+use crate::shortcuts;
+
+const SHORTCUT_SCRIPT: &str = include_str!("zoom.js");
+
+// ZOOM_LEVELS are the factors the zoom shortcuts step through, the
+// same stops browsers use.
+const ZOOM_LEVELS: &[f64] = &[
+    0.5, 0.67, 0.75, 0.8, 0.9, 1.0, 1.1, 1.25, 1.5, 1.75, 2.0, 2.5, 3.0,
+];
+const DEFAULT_ZOOM: f64 = 1.0;
+
+// ZoomStep is what a zoom shortcut asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ZoomStep {
+    In,
+    Out,
+    Reset,
+}
+
+// init_plugin injects the script that turns Cmd/Ctrl with +, - and
+// 0 into change_zoom calls.
+pub(crate) fn init_plugin<R: Runtime>() -> TauriPlugin<R> {
+    PluginBuilder::new("zoom")
+        .js_init_script(SHORTCUT_SCRIPT.to_string())
+        .build()
+}
+
+// change_zoom moves the zoom factor one level in or out, or back
+// to 100%, in every window but the shortcut popups, and saves it
+// for the next launch. It returns the new factor.
+#[tauri::command]
+pub(crate) fn change_zoom(app: AppHandle, step: ZoomStep) -> f64 {
+    let zoom = next_zoom(saved_zoom(&settings::load_settings()), step);
+    for window in app.webview_windows().values() {
+        set_zoom(window, zoom);
+    }
+    let stored = (zoom != DEFAULT_ZOOM).then_some(zoom);
+    if let Err(err) = settings::update_settings(|s| s.zoom = stored) {
+        eprintln!("[agentsview] failed to save zoom level: {err}");
+    }
+    zoom
+}
+
+// apply_saved zooms a newly created window to the saved factor.
+pub(crate) fn apply_saved(window: &WebviewWindow) {
+    let zoom = saved_zoom(&settings::load_settings());
+    if zoom != DEFAULT_ZOOM {
+        set_zoom(window, zoom);
+    }
+}
+
+fn set_zoom(window: &WebviewWindow, zoom: f64) {
+    if shortcuts::is_popup(window.label()) {
+        return;
+    }
+    if let Err(err) = window.set_zoom(zoom) {
+        eprintln!(
+            "[agentsview] failed to zoom the {} window: {err}",
+            window.label()
+        );
+    }
+}
+
+// saved_zoom reads the zoom setting, keeping a hand-edited value
+// within the levels the shortcuts reach.
+fn saved_zoom(settings: &DesktopSettings) -> f64 {
+    let (min, max) = (ZOOM_LEVELS[0], ZOOM_LEVELS[ZOOM_LEVELS.len() - 1]);
+    settings
+        .zoom
+        .filter(|zoom| zoom.is_finite())
+        .map_or(DEFAULT_ZOOM, |zoom| zoom.clamp(min, max))
+}
+
+// next_zoom is the level after current in the direction of step. A
+// factor between levels, from a hand-edited setting, moves to the
+// nearest level that way.
+fn next_zoom(current: f64, step: ZoomStep) -> f64 {
+    const EPSILON: f64 = 0.001;
+    let mut levels = ZOOM_LEVELS.iter().copied();
+    match step {
+        ZoomStep::Reset => DEFAULT_ZOOM,
+        ZoomStep::In => levels
+            .find(|level| *level > current + EPSILON)
+            .unwrap_or(current),
+        ZoomStep::Out => levels
+            .rev()
+            .find(|level| *level < current - EPSILON)
+            .unwrap_or(current),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_zoom_steps_through_levels_and_stops_at_the_ends() {
+        assert_eq!(next_zoom(1.0, ZoomStep::In), 1.1);
+        assert_eq!(next_zoom(1.0, ZoomStep::Out), 0.9);
+        assert_eq!(next_zoom(1.2, ZoomStep::In), 1.25);
+        assert_eq!(next_zoom(1.2, ZoomStep::Out), 1.1);
+        assert_eq!(next_zoom(3.0, ZoomStep::In), 3.0);
+        assert_eq!(next_zoom(0.5, ZoomStep::Out), 0.5);
+        assert_eq!(next_zoom(2.5, ZoomStep::Reset), 1.0);
+
+        let mut settings = DesktopSettings::default();
+        assert_eq!(saved_zoom(&settings), 1.0);
+        settings.zoom = Some(1.5);
+        assert_eq!(saved_zoom(&settings), 1.5);
+        settings.zoom = Some(40.0);
+        assert_eq!(saved_zoom(&settings), 3.0);
+        settings.zoom = Some(f64::NAN);
+        assert_eq!(saved_zoom(&settings), 1.0);
+    }
+}
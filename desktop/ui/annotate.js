@@ -0,0 +1,105 @@
+// Note-entry popup for the annotate shortcut. Each time the window
+// is focused it asks the shell for the live session the note will
+// be attached to; the shell posts the note to the backend.
+(() => {
+  const invoke = window.__TAURI_INTERNALS__?.invoke;
+  if (!invoke) {
+    return;
+  }
+
+  const label = document.getElementById("label");
+  const title = document.getElementById("title");
+  const note = document.getElementById("note");
+  const status = document.getElementById("status");
+
+  const KEYS = ["annotate-placeholder", "annotate-hint", "annotate-no-session"];
+  let strings = {};
+  let target = null;
+  let saving = false;
+
+  const text = (key, fallback) => strings[key] ?? fallback;
+
+  invoke("locale_info")
+    .then(({ locale, direction }) => {
+      document.documentElement.lang = locale;
+      document.documentElement.dir = direction;
+    })
+    .catch(() => {});
+
+  const localized = invoke("localize", { keys: KEYS })
+    .then((result) => {
+      strings = result;
+      note.placeholder = text("annotate-placeholder", note.placeholder);
+    })
+    .catch(() => {});
+
+  function showStatus(message, isError) {
+    status.textContent = message;
+    status.classList.toggle("error", isError);
+  }
+
+  function showError(err) {
+    showStatus(err.message ? `${err.message} ${err.hint}` : String(err), true);
+  }
+
+  function refresh() {
+    note.focus();
+    Promise.all([invoke("annotate_target"), localized])
+      .then(([found]) => {
+        target = found;
+        label.textContent = found?.label ?? "";
+        title.textContent = found?.title ?? "";
+        note.disabled = !found;
+        if (found) {
+          showStatus(text("annotate-hint", status.textContent), false);
+          note.focus();
+        } else {
+          showStatus(
+            text("annotate-no-session", "No session has been active in the last 10 minutes."),
+            true,
+          );
+        }
+      })
+      .catch(showError);
+  }
+
+  function close() {
+    note.value = "";
+    invoke("annotate_close").catch(() => {});
+  }
+
+  note.addEventListener("keydown", (event) => {
+    if (event.key === "Escape") {
+      event.preventDefault();
+      close();
+      return;
+    }
+    if (event.key !== "Enter" || event.shiftKey || event.isComposing) {
+      return;
+    }
+    event.preventDefault();
+    if (!target || saving) {
+      return;
+    }
+    saving = true;
+    invoke("annotate_submit", { sessionId: target.session_id, note: note.value })
+      .then(() => {
+        note.value = "";
+      })
+      .catch(showError)
+      .finally(() => {
+        saving = false;
+      });
+  });
+
+  // Escape must work even when there is no session to annotate and
+  // the textarea is disabled.
+  document.addEventListener("keydown", (event) => {
+    if (event.key === "Escape" && note.disabled) {
+      close();
+    }
+  });
+
+  window.addEventListener("focus", refresh);
+  refresh();
+})();
@@ -0,0 +1,126 @@
+// Quick-switcher palette. Queries go to the shell, which debounces
+// them and cancels superseded backend requests; a superseded query
+// resolves to null and is ignored here as well.
+(() => {
+  const invoke = window.__TAURI_INTERNALS__?.invoke;
+  if (!invoke) {
+    return;
+  }
+
+  const input = document.getElementById("query");
+  const list = document.getElementById("results");
+  const empty = document.getElementById("empty");
+
+  const KEYS = [
+    "palette-placeholder",
+    "palette-empty",
+    "palette-kind-session",
+    "palette-kind-project",
+    "palette-kind-date",
+  ];
+  let strings = {};
+  let entries = [];
+  let selected = 0;
+  let latest = 0;
+
+  invoke("locale_info")
+    .then(({ locale, direction }) => {
+      document.documentElement.lang = locale;
+      document.documentElement.dir = direction;
+    })
+    .catch(() => {});
+
+  invoke("localize", { keys: KEYS })
+    .then((result) => {
+      strings = result;
+      input.placeholder = strings["palette-placeholder"] ?? input.placeholder;
+      empty.textContent = strings["palette-empty"] ?? empty.textContent;
+      render();
+    })
+    .catch(() => {});
+
+  function render() {
+    list.replaceChildren(
+      ...entries.map((entry, index) => {
+        const item = document.createElement("li");
+        item.setAttribute("role", "option");
+        item.setAttribute("aria-selected", String(index === selected));
+        const kind = document.createElement("span");
+        kind.className = "kind";
+        kind.textContent = strings[`palette-kind-${entry.kind}`] ?? entry.kind;
+        const title = document.createElement("span");
+        title.className = "title";
+        title.textContent = entry.title;
+        const detail = document.createElement("span");
+        detail.className = "detail";
+        detail.textContent = entry.detail;
+        item.append(kind, title, detail);
+        item.addEventListener("mousemove", () => select(index));
+        item.addEventListener("click", () => choose(entry));
+        return item;
+      }),
+    );
+    empty.hidden = entries.length > 0 || input.value.trim() === "";
+  }
+
+  function select(index) {
+    if (index === selected || entries.length === 0) {
+      return;
+    }
+    selected = (index + entries.length) % entries.length;
+    render();
+    list.children[selected]?.scrollIntoView({ block: "nearest" });
+  }
+
+  function choose(entry) {
+    invoke("palette_open", { route: entry.route }).catch(() => {});
+    reset();
+  }
+
+  function reset() {
+    latest++;
+    input.value = "";
+    entries = [];
+    selected = 0;
+    render();
+  }
+
+  input.addEventListener("input", () => {
+    const request = ++latest;
+    invoke("palette_search", { query: input.value })
+      .then((result) => {
+        if (result === null || request !== latest) {
+          return;
+        }
+        entries = result;
+        selected = 0;
+        render();
+      })
+      .catch(() => {});
+  });
+
+  input.addEventListener("keydown", (event) => {
+    switch (event.key) {
+      case "ArrowDown":
+        select(selected + 1);
+        break;
+      case "ArrowUp":
+        select(selected - 1);
+        break;
+      case "Enter":
+        if (entries[selected]) {
+          choose(entries[selected]);
+        }
+        break;
+      case "Escape":
+        invoke("palette_close").catch(() => {});
+        reset();
+        break;
+      default:
+        return;
+    }
+    event.preventDefault();
+  });
+
+  window.addEventListener("focus", () => input.focus());
+})();
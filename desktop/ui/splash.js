@@ -0,0 +1,202 @@
+// Splash screen behavior that needs the shell: locale and text
+// direction, localized text for elements marked with data-i18n,
+// and the remote connection picker for shells that cannot run a
+// local backend (mobile builds), or the diagnostics report and
+// the missing-binary report for those that can.
+(() => {
+  const invoke = window.__TAURI_INTERNALS__?.invoke;
+  if (!invoke) {
+    return;
+  }
+
+  const status = document.getElementById("status");
+  const form = document.getElementById("connect");
+  const input = document.getElementById("connect-url");
+  const recent = document.getElementById("connect-recent");
+  const button = form.querySelector("button");
+
+  invoke("locale_info")
+    .then(({ locale, direction }) => {
+      document.documentElement.lang = locale;
+      document.documentElement.dir = direction;
+    })
+    .catch(() => {});
+
+  const CONNECT_KEYS = [
+    "connect-heading",
+    "connect-status",
+    "connect-foot",
+    "connect-status-connecting",
+  ];
+  const DOCTOR_KEYS = ["doctor-running"];
+  const MISSING_KEYS = ["sidecar-missing-title"];
+  let strings = {};
+
+  // The shell may already have replaced a status with an error by
+  // the time translations arrive; only untouched text is replaced.
+  const translatable = [...document.querySelectorAll("[data-i18n]")].map((el) => ({
+    el,
+    original: el.textContent,
+  }));
+  const localized = invoke("localize", {
+    keys: [
+      ...translatable.map(({ el }) => el.dataset.i18n),
+      ...CONNECT_KEYS,
+      ...DOCTOR_KEYS,
+      ...MISSING_KEYS,
+    ],
+  })
+    .then((result) => {
+      strings = result;
+      for (const { el, original } of translatable) {
+        if (el.textContent === original && strings[el.dataset.i18n]) {
+          el.textContent = strings[el.dataset.i18n];
+        }
+      }
+    })
+    .catch(() => {});
+
+  const text = (key, fallback) => strings[key] ?? fallback;
+
+  // The shell calls this when a launch could not start the
+  // sidecar; it is also checked on load, since the shell may send
+  // the window back here from the backend's page.
+  window.agentsviewSidecarMissing = () => {
+    localized.then(showMissingSidecar);
+  };
+
+  Promise.all([invoke("connection_picker"), localized]).then(([picker]) => {
+    if (!picker.enabled) {
+      showDoctor();
+      showMissingSidecar();
+      return;
+    }
+    document.querySelector("h1").textContent = text("connect-heading", "Connect to AgentsView");
+    status.textContent = text(
+      "connect-status",
+      "Enter the address of an agentsview server on your network.",
+    );
+    document.querySelector(".meter").hidden = true;
+    document.querySelector(".stage-list").hidden = true;
+    document.querySelector(".foot").textContent = text(
+      "connect-foot",
+      "Sessions stay on the machine running agentsview serve.",
+    );
+
+    for (const url of picker.connections) {
+      const option = document.createElement("option");
+      option.value = url;
+      recent.append(option);
+    }
+    input.value = picker.connections[0] ?? "";
+    form.hidden = false;
+    if (picker.resume) {
+      // Relaunched by the OS (e.g. after iPad multitasking evicted
+      // the app): reopen the last page without asking again.
+      connect(input.value, picker.resume);
+    } else {
+      input.focus();
+    }
+  });
+
+  function connect(url, route) {
+    button.disabled = true;
+    status.textContent = text("connect-status-connecting", "Connecting to { $url }...").replace(
+      /\{\s*\$url\s*\}/,
+      url.trim(),
+    );
+    invoke("connect_remote", { url, route: route ?? null })
+      .catch((err) => {
+        status.textContent = err.message ? `${err.message} ${err.hint}` : String(err);
+      })
+      .finally(() => {
+        button.disabled = false;
+      });
+  }
+
+  // showDoctor offers the run_doctor report, which helps when the
+  // backend fails to start.
+  function showDoctor() {
+    const doctor = document.getElementById("doctor");
+    const run = doctor.querySelector("button");
+    const list = doctor.querySelector("ul");
+    doctor.hidden = false;
+    const item = (content, className = "") => {
+      const li = document.createElement("li");
+      li.className = className;
+      li.textContent = content;
+      return li;
+    };
+    run.addEventListener("click", () => {
+      run.disabled = true;
+      list.replaceChildren(item(text("doctor-running", "Running diagnostics...")));
+      invoke("run_doctor")
+        .then((report) => {
+          list.replaceChildren(
+            ...report.checks.map((check) =>
+              item(`${check.name}: ${check.detail}`, check.status),
+            ),
+          );
+        })
+        .catch((err) => {
+          list.replaceChildren(item(err.message ?? String(err), "fail"));
+        })
+        .finally(() => {
+          run.disabled = false;
+        });
+    });
+  }
+
+  // showMissingSidecar replaces the startup progress with the
+  // shell's report on a sidecar binary that is missing or would not
+  // run, if there is one, and offers to locate it or retry.
+  function showMissingSidecar() {
+    invoke("missing_sidecar")
+      .then((report) => {
+        if (!report) {
+          return;
+        }
+        const missing = document.getElementById("sidecar-missing");
+        const heading = document.querySelector("h1");
+        const item = (content) => {
+          const li = document.createElement("li");
+          li.textContent = content;
+          return li;
+        };
+        heading.dataset.startingText ??= heading.textContent;
+        heading.textContent = text("sidecar-missing-title", "AgentsView backend not found");
+        status.textContent = report.intro;
+        document.querySelector(".meter").hidden = true;
+        document.querySelector(".stage-list").hidden = true;
+        missing.querySelector(".sidecar-platform").textContent = report.platform;
+        const paths = report.checked.map(({ path, status: found }) => item(`${path} (${found})`));
+        missing.querySelector(".sidecar-paths").replaceChildren(...paths);
+        missing.querySelector(".sidecar-fixes").replaceChildren(...report.fixes.map(item));
+        missing.hidden = false;
+      })
+      .catch(() => {});
+  }
+
+  function hideMissingSidecar() {
+    const heading = document.querySelector("h1");
+    heading.textContent = heading.dataset.startingText ?? heading.textContent;
+    document.getElementById("sidecar-missing").hidden = true;
+    document.querySelector(".meter").hidden = false;
+    document.querySelector(".stage-list").hidden = false;
+  }
+
+  document.getElementById("sidecar-missing").addEventListener("click", (event) => {
+    const action = event.target.closest("button")?.dataset.action;
+    if (action === "locate") {
+      invoke("locate_sidecar").catch(() => {});
+    } else if (action === "retry") {
+      hideMissingSidecar();
+      invoke("retry_sidecar").catch(() => {});
+    }
+  });
+
+  form.addEventListener("submit", (event) => {
+    event.preventDefault();
+    connect(input.value);
+  });
+})();
@@ -30,6 +30,7 @@ const { mockUi, mockSessions, mockSearchStore } = vi.hoisted(
         created_at: string;
       }>,
       filters: { project: "" },
+      activeSessionId: null as string | null,
       selectSession: vi.fn(),
     },
     mockSearchStore: {
@@ -81,6 +82,7 @@ describe("CommandPalette", () => {
     mockSearchStore.results = [];
     mockSearchStore.isSearching = false;
     mockSessions.filters.project = "";
+    mockSessions.activeSessionId = null;
     mockSessions.sessions = [
       makeSession("s1", "cursor"),
       makeSession("s2", "unknown"),
@@ -107,4 +109,49 @@ describe("CommandPalette", () => {
 
     unmount(component);
   });
+
+  it("lists available desktop actions from the shell", async () => {
+    const invoke = vi.fn().mockResolvedValue([
+      {
+        id: "restart_backend",
+        title: "Restart Backend",
+        needs_session: false,
+        available: true,
+        reason: null,
+      },
+      {
+        id: "reveal_file",
+        title: "Reveal Transcript File",
+        needs_session: true,
+        available: true,
+        reason: null,
+      },
+      {
+        id: "resume_session",
+        title: "Resume Session in Terminal",
+        needs_session: false,
+        available: false,
+        reason: "no terminal emulator was found",
+      },
+    ]);
+    Object.assign(window, { __TAURI_INTERNALS__: { invoke } });
+
+    const component = mount(CommandPalette, {
+      target: document.body,
+    });
+    await vi.waitFor(() => {
+      expect(
+        document.querySelectorAll(".palette-section-label"),
+      ).toHaveLength(2);
+    });
+
+    const items = Array.from(
+      document.querySelectorAll(".palette-item .item-text"),
+    ).map((el) => el.textContent?.trim());
+    expect(items.slice(2)).toEqual(["Restart Backend"]);
+
+    unmount(component);
+    delete (window as { __TAURI_INTERNALS__?: unknown })
+      .__TAURI_INTERNALS__;
+  });
 });
@@ -135,7 +135,9 @@ class SessionsStore {
         ? minUserMsgs
         : 0,
     };
-    this.activeSessionId = null;
+    // session opens a session directly, e.g. from the desktop
+    // quick-switcher palette.
+    this.activeSessionId = params["session"] || null;
     this.resetPagination();
   }
 
@@ -95,6 +95,12 @@ describe("SessionsStore", () => {
       expect(sessions.filters.date).toBe("");
       expect(sessions.filters.minMessages).toBe(0);
       expect(sessions.filters.maxMessages).toBe(0);
+      expect(sessions.activeSessionId).toBeNull();
+    });
+
+    it("should select the session param", () => {
+      sessions.initFromParams({ session: "abc-123" });
+      expect(sessions.activeSessionId).toBe("abc-123");
     });
   });
 
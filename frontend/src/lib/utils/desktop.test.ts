@@ -0,0 +1,84 @@
+import { describe, it, expect, vi, afterEach } from "vitest";
+import {
+  listDesktopActions,
+  invokeDesktopAction,
+  openPath,
+  restartBackend,
+  reloadEnvironment,
+  backendOutput,
+} from "./desktop.js";
+
+const restart = {
+  id: "restart_backend",
+  title: "Restart Backend",
+  needs_session: false,
+  available: true,
+  reason: null,
+};
+
+describe("desktop actions", () => {
+  afterEach(() => {
+    vi.unstubAllGlobals();
+  });
+
+  it("lists nothing outside the desktop app", async () => {
+    vi.stubGlobal("window", {});
+    expect(await listDesktopActions()).toEqual([]);
+    await expect(invokeDesktopAction("export_pdf", null)).rejects.toThrow();
+  });
+
+  it("lists and invokes actions through the shell", async () => {
+    const invoke = vi.fn().mockResolvedValue([restart]);
+    vi.stubGlobal("window", { __TAURI_INTERNALS__: { invoke } });
+    expect(await listDesktopActions()).toEqual([restart]);
+    expect(invoke).toHaveBeenCalledWith("list_desktop_actions");
+
+    invoke.mockResolvedValue(undefined);
+    await invokeDesktopAction("reveal_file", "s1");
+    expect(invoke).toHaveBeenCalledWith("invoke_desktop_action", {
+      id: "reveal_file",
+      sessionId: "s1",
+    });
+  });
+
+  it("treats shells without the registry as having no actions", async () => {
+    const invoke = vi.fn().mockRejectedValue("unknown command");
+    vi.stubGlobal("window", { __TAURI_INTERNALS__: { invoke } });
+    expect(await listDesktopActions()).toEqual([]);
+  });
+
+  it("opens transcript files through the shell", async () => {
+    const invoke = vi.fn().mockResolvedValue(undefined);
+    vi.stubGlobal("window", { __TAURI_INTERNALS__: { invoke } });
+    await openPath("s1", "src/main.rs");
+    expect(invoke).toHaveBeenCalledWith("open_path", {
+      sessionId: "s1",
+      path: "src/main.rs",
+    });
+  });
+
+  it("restarts the backend through the shell", async () => {
+    const status = { port: 8081, url: "http://127.0.0.1:8081" };
+    const invoke = vi.fn().mockResolvedValue(status);
+    vi.stubGlobal("window", { __TAURI_INTERNALS__: { invoke } });
+    expect(await restartBackend()).toEqual(status);
+    expect(invoke).toHaveBeenCalledWith("restart_backend");
+    expect(await reloadEnvironment()).toEqual(status);
+    expect(invoke).toHaveBeenCalledWith("reload_environment");
+
+    vi.stubGlobal("window", {});
+    await expect(restartBackend()).rejects.toThrow();
+    await expect(reloadEnvironment()).rejects.toThrow();
+  });
+
+  it("reads recent backend output through the shell", async () => {
+    const lines = [{ attempt: 1, stream: "stderr", text: "panic: boom" }];
+    const invoke = vi.fn().mockResolvedValue(lines);
+    vi.stubGlobal("window", { __TAURI_INTERNALS__: { invoke } });
+    expect(await backendOutput()).toEqual(lines);
+    expect(invoke).toHaveBeenCalledWith("backend_output");
+
+    vi.stubGlobal("window", {});
+    expect(await backendOutput()).toEqual([]);
+  });
+});
@@ -0,0 +1,126 @@
+/**
+ * A capability of the desktop shell, as listed by its
+ * `list_desktop_actions` command.
+ */
+export interface DesktopAction {
+  id: string;
+  title: string;
+  needs_session: boolean;
+  available: boolean;
+  reason: string | null;
+}
+
+/**
+ * Where the local backend serves after `restartBackend`.
+ */
+export interface BackendStatus {
+  port: number;
+  url: string;
+}
+
+/**
+ * A line of local backend output, as kept by the shell.
+ */
+export interface BackendOutputLine {
+  attempt: number;
+  stream: "stdout" | "stderr";
+  text: string;
+}
+
+type Invoke = (
+  cmd: string,
+  args?: Record<string, unknown>,
+) => Promise<unknown>;
+
+function shellInvoke(): Invoke | undefined {
+  const internals = (
+    window as { __TAURI_INTERNALS__?: { invoke?: Invoke } }
+  ).__TAURI_INTERNALS__;
+  return internals?.invoke;
+}
+
+/**
+ * Lists the actions the desktop shell can run on this platform.
+ * Resolves to an empty list in a browser or an older shell.
+ */
+export async function listDesktopActions(): Promise<DesktopAction[]> {
+  const invoke = shellInvoke();
+  if (!invoke) return [];
+  try {
+    return (await invoke("list_desktop_actions")) as DesktopAction[];
+  } catch {
+    return [];
+  }
+}
+
+/**
+ * Runs a desktop action, on `sessionId` for actions that need a
+ * session. Rejects with the shell's error when the action fails.
+ */
+export async function invokeDesktopAction(
+  id: string,
+  sessionId: string | null,
+): Promise<void> {
+  const invoke = shellInvoke();
+  if (!invoke) {
+    throw new Error("not running in the desktop app");
+  }
+  await invoke("invoke_desktop_action", { id, sessionId });
+}
+
+/**
+ * Opens a file referenced in a session's transcript with the
+ * system's default app. The shell only opens files under the
+ * session's working directories or an agent log directory.
+ */
+export async function openPath(
+  sessionId: string,
+  path: string,
+): Promise<void> {
+  const invoke = shellInvoke();
+  if (!invoke) {
+    throw new Error("not running in the desktop app");
+  }
+  await invoke("open_path", { sessionId, path });
+}
+
+/**
+ * Restarts the local backend and resolves once it answers again.
+ * The page stays loaded unless the backend comes back on another
+ * port. Rejects with the shell's error when the restart fails or
+ * the app is connected to a remote server.
+ */
+export async function restartBackend(): Promise<BackendStatus> {
+  const invoke = shellInvoke();
+  if (!invoke) {
+    throw new Error("not running in the desktop app");
+  }
+  return (await invoke("restart_backend")) as BackendStatus;
+}
+
+/**
+ * Like `restartBackend`, but first reloads the environment: the
+ * login shell is probed again and desktop.env re-read, so newly
+ * installed agent CLIs are found.
+ */
+export async function reloadEnvironment(): Promise<BackendStatus> {
+  const invoke = shellInvoke();
+  if (!invoke) {
+    throw new Error("not running in the desktop app");
+  }
+  return (await invoke("reload_environment")) as BackendStatus;
+}
+
+/**
+ * Returns the local backend's recent output, oldest line first.
+ * Resolves to an empty list in a browser or an older shell.
+ */
+export async function backendOutput(): Promise<BackendOutputLine[]> {
+  const invoke = shellInvoke();
+  if (!invoke) return [];
+  try {
+    return (await invoke("backend_output")) as BackendOutputLine[];
+  } catch {
+    return [];
+  }
+}
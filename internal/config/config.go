@@ -12,6 +12,7 @@ import (
 	"strconv"
 	"time"
 
+	"github.com/wesm/agentsview/internal/db"
 	"github.com/wesm/agentsview/internal/parser"
 )
 
@@ -26,6 +27,15 @@ type Config struct {
 	GithubToken  string        `json:"github_token,omitempty"`
 	WriteTimeout time.Duration `json:"-"`
 
+	// AuthToken, when set, is required from clients that are
+	// not on this machine. It comes only from the environment,
+	// so it never lands in the config file.
+	AuthToken string `json:"-"`
+
+	// Socket, when set, is a Unix socket path served instead of
+	// Host and Port, for the desktop app's private transport.
+	Socket string `json:"-"`
+
 	// AgentDirs maps each AgentType to its configured
 	// directories. Single-dir agents store a one-element
 	// slice; unconfigured agents use nil.
@@ -36,6 +46,11 @@ type Config struct {
 	agentDirSource map[parser.AgentType]dirSource
 
 	ResultContentBlockedCategories []string `json:"result_content_blocked_categories,omitempty"`
+
+	// ModelPrices price models for cost analytics ahead of
+	// db.DefaultModelPrices, so prices can be updated or added
+	// without a new release.
+	ModelPrices []db.ModelPrice `json:"model_prices,omitempty"`
 }
 
 type dirSource int
@@ -141,9 +156,10 @@ func (c *Config) loadFile() error {
 	}
 
 	var file struct {
-		GithubToken                    string   `json:"github_token"`
-		CursorSecret                   string   `json:"cursor_secret"`
-		ResultContentBlockedCategories []string `json:"result_content_blocked_categories"`
+		GithubToken                    string          `json:"github_token"`
+		CursorSecret                   string          `json:"cursor_secret"`
+		ResultContentBlockedCategories []string        `json:"result_content_blocked_categories"`
+		ModelPrices                    []db.ModelPrice `json:"model_prices"`
 	}
 	if err := json.Unmarshal(data, &file); err != nil {
 		return fmt.Errorf("parsing config: %w", err)
@@ -157,6 +173,13 @@ func (c *Config) loadFile() error {
 	if file.ResultContentBlockedCategories != nil {
 		c.ResultContentBlockedCategories = file.ResultContentBlockedCategories
 	}
+	for _, p := range file.ModelPrices {
+		if p.Model == "" {
+			log.Printf("config: model_prices: entry without a model")
+			continue
+		}
+		c.ModelPrices = append(c.ModelPrices, p)
+	}
 
 	// Parse config-file dir arrays for agents that have a
 	// ConfigKey. Only apply when not already set by env var.
@@ -240,6 +263,9 @@ func (c *Config) loadEnv() {
 	if v := os.Getenv("AGENT_VIEWER_DATA_DIR"); v != "" {
 		c.DataDir = v
 	}
+	if v := os.Getenv("AGENT_VIEWER_AUTH_TOKEN"); v != "" {
+		c.AuthToken = v
+	}
 }
 
 // RegisterServeFlags registers serve-command flags on fs.
@@ -251,6 +277,10 @@ func RegisterServeFlags(fs *flag.FlagSet) {
 		"no-browser", false,
 		"Don't open browser on startup",
 	)
+	fs.String(
+		"socket", "",
+		"Serve on this Unix socket instead of host:port",
+	)
 }
 
 // applyFlags copies explicitly-set flags from fs into cfg.
@@ -267,6 +297,8 @@ func applyFlags(cfg *Config, fs *flag.FlagSet) {
 			cfg.Port, _ = strconv.Atoi(f.Value.String())
 		case "no-browser":
 			cfg.NoBrowser = f.Value.String() == "true"
+		case "socket":
+			cfg.Socket = f.Value.String()
 		}
 	})
 }
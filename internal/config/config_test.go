@@ -11,6 +11,7 @@ import (
 	"strings"
 	"testing"
 
+	"github.com/wesm/agentsview/internal/db"
 	"github.com/wesm/agentsview/internal/parser"
 )
 
@@ -482,3 +483,25 @@ func TestLoadFile_ResultContentBlockedCategories(t *testing.T) {
 		})
 	}
 }
+
+func TestLoadFile_ModelPrices(t *testing.T) {
+	dir := setupTestEnv(t)
+	writeConfig(t, dir, map[string]any{
+		"model_prices": []map[string]any{
+			{"model": "claude-sonnet-4-5", "input": 2.5, "output": 12},
+			{"input": 1},
+		},
+	})
+
+	cfg, err := LoadMinimal()
+	if err != nil {
+		t.Fatal(err)
+	}
+	want := []db.ModelPrice{
+		{Model: "claude-sonnet-4-5", Input: 2.5, Output: 12},
+	}
+	if len(cfg.ModelPrices) != len(want) ||
+		cfg.ModelPrices[0] != want[0] {
+		t.Errorf("ModelPrices = %+v, want %+v", cfg.ModelPrices, want)
+	}
+}
@@ -0,0 +1,108 @@
+package db
+
+import (
+	"context"
+	"fmt"
+)
+
+// Annotation represents a row in the annotations table.
+type Annotation struct {
+	ID        int64  `json:"id"`
+	SessionID string `json:"session_id"`
+	Body      string `json:"body"`
+	Source    string `json:"source"`
+	CreatedAt string `json:"created_at"`
+}
+
+const annotationBaseCols = `id, session_id, body, source, created_at`
+
+func scanAnnotationRow(rs rowScanner) (Annotation, error) {
+	var a Annotation
+	err := rs.Scan(
+		&a.ID, &a.SessionID, &a.Body, &a.Source, &a.CreatedAt,
+	)
+	return a, err
+}
+
+// InsertAnnotation inserts an annotation and returns it as
+// stored, with its ID and created_at filled in.
+func (db *DB) InsertAnnotation(a Annotation) (*Annotation, error) {
+	db.mu.Lock()
+	defer db.mu.Unlock()
+
+	row := db.getWriter().QueryRow(`
+		INSERT INTO annotations (session_id, body, source)
+		VALUES (?, ?, ?)
+		RETURNING `+annotationBaseCols,
+		a.SessionID, a.Body, a.Source,
+	)
+	stored, err := scanAnnotationRow(row)
+	if err != nil {
+		return nil, fmt.Errorf("inserting annotation: %w", err)
+	}
+	return &stored, nil
+}
+
+// ListAnnotations returns the annotations on a session,
+// oldest first.
+func (db *DB) ListAnnotations(
+	ctx context.Context, sessionID string,
+) ([]Annotation, error) {
+	rows, err := db.getReader().QueryContext(ctx,
+		"SELECT "+annotationBaseCols+
+			" FROM annotations WHERE session_id = ?"+
+			" ORDER BY created_at, id",
+		sessionID,
+	)
+	if err != nil {
+		return nil, fmt.Errorf("querying annotations: %w", err)
+	}
+	defer rows.Close()
+
+	var annotations []Annotation
+	for rows.Next() {
+		a, err := scanAnnotationRow(rows)
+		if err != nil {
+			return nil, fmt.Errorf("scanning annotation: %w", err)
+		}
+		annotations = append(annotations, a)
+	}
+	return annotations, rows.Err()
+}
+
+// CopyAnnotationsFrom copies all annotations from the database
+// at sourcePath into this database using ATTACH/DETACH.
+func (db *DB) CopyAnnotationsFrom(sourcePath string) error {
+	db.mu.Lock()
+	defer db.mu.Unlock()
+
+	// ATTACH is connection-scoped; pin one connection for the
+	// whole sequence, as CopyInsightsFrom does.
+	ctx := context.Background()
+	conn, err := db.getWriter().Conn(ctx)
+	if err != nil {
+		return fmt.Errorf("acquiring connection: %w", err)
+	}
+	defer conn.Close()
+
+	if _, err := conn.ExecContext(
+		ctx, "ATTACH DATABASE ? AS old_db", sourcePath,
+	); err != nil {
+		return fmt.Errorf("attaching source db: %w", err)
+	}
+	defer func() {
+		_, _ = conn.ExecContext(
+			ctx, "DETACH DATABASE old_db",
+		)
+	}()
+
+	_, err = conn.ExecContext(ctx, `
+		INSERT OR IGNORE INTO annotations
+			(id, session_id, body, source, created_at)
+		SELECT id, session_id, body, source, created_at
+		FROM old_db.annotations`)
+	if err != nil {
+		return fmt.Errorf("copying annotations: %w", err)
+	}
+	return nil
+}
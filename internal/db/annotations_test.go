@@ -0,0 +1,68 @@
+package db
+
+import (
+	"context"
+	"path/filepath"
+	"testing"
+)
+
+func TestAnnotations_InsertAndList(t *testing.T) {
+	d := testDB(t)
+	ctx := context.Background()
+	insertSession(t, d, "s1", "my-app")
+
+	first, err := d.InsertAnnotation(Annotation{
+		SessionID: "s1", Body: "first note", Source: "desktop",
+	})
+	requireNoError(t, err, "InsertAnnotation")
+	if first.ID <= 0 || first.CreatedAt == "" {
+		t.Fatalf("expected ID and created_at, got %+v", first)
+	}
+	_, err = d.InsertAnnotation(Annotation{
+		SessionID: "s1", Body: "second note", Source: "api",
+	})
+	requireNoError(t, err, "InsertAnnotation")
+	_, err = d.InsertAnnotation(Annotation{
+		SessionID: "s2", Body: "elsewhere", Source: "api",
+	})
+	requireNoError(t, err, "InsertAnnotation")
+
+	got, err := d.ListAnnotations(ctx, "s1")
+	requireNoError(t, err, "ListAnnotations")
+	if len(got) != 2 {
+		t.Fatalf("expected 2 annotations, got %d", len(got))
+	}
+	if got[0] != *first {
+		t.Errorf("first annotation = %+v, want %+v", got[0], *first)
+	}
+	if got[1].Body != "second note" || got[1].Source != "api" {
+		t.Errorf("second annotation = %+v", got[1])
+	}
+
+	none, err := d.ListAnnotations(ctx, "missing")
+	requireNoError(t, err, "ListAnnotations")
+	if len(none) != 0 {
+		t.Errorf("expected no annotations, got %d", len(none))
+	}
+}
+
+func TestAnnotations_CopyFrom(t *testing.T) {
+	src := testDB(t)
+	want, err := src.InsertAnnotation(Annotation{
+		SessionID: "s1", Body: "keep me", Source: "desktop",
+	})
+	requireNoError(t, err, "InsertAnnotation")
+	srcPath := src.Path()
+	requireNoError(t, src.Close(), "closing source db")
+
+	dst, err := Open(filepath.Join(t.TempDir(), "dst.db"))
+	requireNoError(t, err, "opening destination db")
+	t.Cleanup(func() { dst.Close() })
+
+	requireNoError(t, dst.CopyAnnotationsFrom(srcPath), "CopyAnnotationsFrom")
+	got, err := dst.ListAnnotations(context.Background(), "s1")
+	requireNoError(t, err, "ListAnnotations")
+	if len(got) != 1 || got[0] != *want {
+		t.Fatalf("copied annotations = %+v, want [%+v]", got, *want)
+	}
+}
@@ -7,8 +7,8 @@ import (
 	"time"
 )
 
-// CopyOrphanedDataFrom copies sessions (and their messages
-// and tool_calls) that exist in the source database but not
+// CopyOrphanedDataFrom copies sessions (and their messages,
+// tool_calls and token usage) that exist in the source database but not
 // in this database. This preserves archived sessions whose
 // source files no longer exist on disk.
 //
@@ -81,7 +81,7 @@ func (d *DB) CopyOrphanedDataFrom(
 
 	t := time.Now()
 
-	// Use a transaction so all three inserts are atomic.
+	// Use a transaction so all the inserts are atomic.
 	// Partial orphan copies would leave dangling sessions
 	// without messages or tool_calls.
 	tx, err := conn.BeginTx(ctx, nil)
@@ -161,6 +161,26 @@ func (d *DB) CopyOrphanedDataFrom(
 		)
 	}
 
+	// Copy token usage, which keys on the session alone.
+	if _, err := tx.ExecContext(ctx, `
+		INSERT INTO token_usage
+			(session_id, timestamp, model,
+			 input_tokens, output_tokens,
+			 cache_creation_tokens, cache_read_tokens)
+		SELECT
+			session_id, timestamp, model,
+			input_tokens, output_tokens,
+			cache_creation_tokens, cache_read_tokens
+		FROM old_db.token_usage
+		WHERE session_id IN (
+			SELECT id FROM _orphaned_ids
+		)`,
+	); err != nil {
+		return 0, fmt.Errorf(
+			"copying orphaned token usage: %w", err,
+		)
+	}
+
 	if err := tx.Commit(); err != nil {
 		return 0, fmt.Errorf(
 			"committing orphaned data: %w", err,
@@ -113,6 +113,24 @@ CREATE INDEX IF NOT EXISTS idx_tool_calls_skill
     ON tool_calls(skill_name)
     WHERE skill_name IS NOT NULL;
 
+-- Token usage of model responses, for cost analytics
+CREATE TABLE IF NOT EXISTS token_usage (
+    id                    INTEGER PRIMARY KEY,
+    session_id            TEXT NOT NULL
+        REFERENCES sessions(id) ON DELETE CASCADE,
+    timestamp             TEXT NOT NULL,
+    model                 TEXT NOT NULL,
+    input_tokens          INTEGER NOT NULL DEFAULT 0,
+    output_tokens         INTEGER NOT NULL DEFAULT 0,
+    cache_creation_tokens INTEGER NOT NULL DEFAULT 0,
+    cache_read_tokens     INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_token_usage_session
+    ON token_usage(session_id);
+CREATE INDEX IF NOT EXISTS idx_token_usage_timestamp
+    ON token_usage(timestamp);
+
 -- Insights table for AI-generated activity insights
 CREATE TABLE IF NOT EXISTS insights (
     id          INTEGER PRIMARY KEY,
@@ -134,6 +152,19 @@ CREATE INDEX IF NOT EXISTS idx_insights_lookup
 CREATE INDEX IF NOT EXISTS idx_insights_created
     ON insights(created_at DESC);
 
+-- Annotations table for notes users attach to sessions
+CREATE TABLE IF NOT EXISTS annotations (
+    id          INTEGER PRIMARY KEY,
+    session_id  TEXT NOT NULL,
+    body        TEXT NOT NULL,
+    source      TEXT NOT NULL,
+    created_at  TEXT NOT NULL
+        DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_annotations_session
+    ON annotations(session_id, created_at);
+
 -- Skipped files cache: persists skip decisions for files that
 -- produced no session (non-interactive, parse errors) so they
 -- survive process restarts without re-parsing.
@@ -0,0 +1,212 @@
+package db
+
+import (
+	"context"
+	"fmt"
+	"strings"
+)
+
+// TokenUsage represents a row in the token_usage table: the
+// tokens one model response consumed.
+type TokenUsage struct {
+	SessionID           string
+	Timestamp           string
+	Model               string
+	InputTokens         int
+	OutputTokens        int
+	CacheCreationTokens int
+	CacheReadTokens     int
+}
+
+// ReplaceSessionUsage replaces the token usage recorded for a
+// session.
+func (db *DB) ReplaceSessionUsage(
+	sessionID string, usage []TokenUsage,
+) error {
+	db.mu.Lock()
+	defer db.mu.Unlock()
+
+	tx, err := db.getWriter().Begin()
+	if err != nil {
+		return fmt.Errorf("beginning tx: %w", err)
+	}
+	defer func() { _ = tx.Rollback() }()
+
+	if _, err := tx.Exec(
+		"DELETE FROM token_usage WHERE session_id = ?", sessionID,
+	); err != nil {
+		return fmt.Errorf("deleting old token usage: %w", err)
+	}
+
+	if len(usage) > 0 {
+		stmt, err := tx.Prepare(`
+			INSERT INTO token_usage (
+				session_id, timestamp, model,
+				input_tokens, output_tokens,
+				cache_creation_tokens, cache_read_tokens
+			) VALUES (?, ?, ?, ?, ?, ?, ?)`)
+		if err != nil {
+			return fmt.Errorf("preparing insert: %w", err)
+		}
+		defer stmt.Close()
+		for _, u := range usage {
+			if _, err := stmt.Exec(
+				sessionID, u.Timestamp, u.Model,
+				u.InputTokens, u.OutputTokens,
+				u.CacheCreationTokens, u.CacheReadTokens,
+			); err != nil {
+				return fmt.Errorf("inserting token usage: %w", err)
+			}
+		}
+	}
+
+	return tx.Commit()
+}
+
+// ModelPrice is the price of a model family in US dollars per
+// million tokens. Model is matched against the start of a model
+// name.
+type ModelPrice struct {
+	Model         string  `json:"model"`
+	Input         float64 `json:"input"`
+	Output        float64 `json:"output"`
+	CacheCreation float64 `json:"cache_creation"`
+	CacheRead     float64 `json:"cache_read"`
+}
+
+// DefaultModelPrices are list prices for models the model_prices
+// config setting does not cover. They are checked in order, so
+// specific versions come before their family.
+var DefaultModelPrices = []ModelPrice{
+	{"claude-opus-4-5", 5, 25, 6.25, 0.50},
+	{"claude-opus-4-6", 5, 25, 6.25, 0.50},
+	{"claude-opus-4", 15, 75, 18.75, 1.50},
+	{"claude-3-opus", 15, 75, 18.75, 1.50},
+	{"claude-sonnet-4", 3, 15, 3.75, 0.30},
+	{"claude-3-7-sonnet", 3, 15, 3.75, 0.30},
+	{"claude-3-5-sonnet", 3, 15, 3.75, 0.30},
+	{"claude-haiku-4", 1, 5, 1.25, 0.10},
+	{"claude-3-5-haiku", 0.80, 4, 1.00, 0.08},
+	{"claude-3-haiku", 0.25, 1.25, 0.30, 0.03},
+}
+
+func priceFor(
+	model string, prices []ModelPrice,
+) (ModelPrice, bool) {
+	for _, p := range prices {
+		if p.Model != "" && strings.HasPrefix(model, p.Model) {
+			return p, true
+		}
+	}
+	return ModelPrice{}, false
+}
+
+// ModelCost is the spending on one model.
+type ModelCost struct {
+	InputTokens         int     `json:"input_tokens"`
+	OutputTokens        int     `json:"output_tokens"`
+	CacheCreationTokens int     `json:"cache_creation_tokens"`
+	CacheReadTokens     int     `json:"cache_read_tokens"`
+	TotalUSD            float64 `json:"total_usd"`
+	Priced              bool    `json:"priced"`
+}
+
+// AnalyticsCost is the response for the cost endpoint.
+type AnalyticsCost struct {
+	TotalUSD float64               `json:"total_usd"`
+	Models   map[string]*ModelCost `json:"models"`
+}
+
+// GetAnalyticsCost estimates spending from recorded token
+// usage, pricing each model by the first entry of prices that
+// matches it. Responses are bucketed by their own local date,
+// and subagent sessions count like any other. Models without a
+// price report tokens but no cost.
+func (db *DB) GetAnalyticsCost(
+	ctx context.Context, f AnalyticsFilter, prices []ModelPrice,
+) (AnalyticsCost, error) {
+	loc := f.location()
+	utcFrom, utcTo := f.utcRange()
+	preds := []string{"u.timestamp >= ?", "u.timestamp <= ?"}
+	args := []any{utcFrom, utcTo}
+	if f.Machine != "" {
+		preds = append(preds, "s.machine = ?")
+		args = append(args, f.Machine)
+	}
+	if f.Project != "" {
+		preds = append(preds, "s.project = ?")
+		args = append(args, f.Project)
+	}
+	if f.Agent != "" {
+		preds = append(preds, "s.agent = ?")
+		args = append(args, f.Agent)
+	}
+
+	query := `SELECT u.timestamp, u.model,
+			u.input_tokens, u.output_tokens,
+			u.cache_creation_tokens, u.cache_read_tokens
+		FROM token_usage u
+		JOIN sessions s ON s.id = u.session_id
+		WHERE ` + strings.Join(preds, " AND ")
+
+	rows, err := db.getReader().QueryContext(ctx, query, args...)
+	if err != nil {
+		return AnalyticsCost{}, fmt.Errorf(
+			"querying token usage: %w", err,
+		)
+	}
+	defer rows.Close()
+
+	result := AnalyticsCost{Models: map[string]*ModelCost{}}
+	for rows.Next() {
+		var ts string
+		var u TokenUsage
+		if err := rows.Scan(
+			&ts, &u.Model,
+			&u.InputTokens, &u.OutputTokens,
+			&u.CacheCreationTokens, &u.CacheReadTokens,
+		); err != nil {
+			return AnalyticsCost{}, fmt.Errorf(
+				"scanning token usage: %w", err,
+			)
+		}
+		t, ok := localTime(ts, loc)
+		if !ok {
+			continue
+		}
+		date := t.Format("2006-01-02")
+		if !inDateRange(date, f.From, f.To) ||
+			!f.matchesTimeFilter(t) {
+			continue
+		}
+
+		mc := result.Models[u.Model]
+		if mc == nil {
+			mc = &ModelCost{}
+			result.Models[u.Model] = mc
+		}
+		mc.InputTokens += u.InputTokens
+		mc.OutputTokens += u.OutputTokens
+		mc.CacheCreationTokens += u.CacheCreationTokens
+		mc.CacheReadTokens += u.CacheReadTokens
+	}
+	if err := rows.Err(); err != nil {
+		return AnalyticsCost{}, fmt.Errorf(
+			"iterating token usage: %w", err,
+		)
+	}
+
+	for model, mc := range result.Models {
+		p, ok := priceFor(model, prices)
+		if !ok {
+			continue
+		}
+		mc.Priced = true
+		mc.TotalUSD = (float64(mc.InputTokens)*p.Input +
+			float64(mc.OutputTokens)*p.Output +
+			float64(mc.CacheCreationTokens)*p.CacheCreation +
+			float64(mc.CacheReadTokens)*p.CacheRead) / 1e6
+		result.TotalUSD += mc.TotalUSD
+	}
+	return result, nil
+}
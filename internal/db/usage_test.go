@@ -0,0 +1,83 @@
+package db
+
+import (
+	"context"
+	"testing"
+)
+
+func TestReplaceSessionUsage(t *testing.T) {
+	d := testDB(t)
+	ctx := context.Background()
+	insertSession(t, d, "s1", "my-app")
+	f := AnalyticsFilter{From: "2024-06-01", To: "2024-06-30"}
+
+	first := []TokenUsage{{
+		Timestamp:   "2024-06-01T09:00:00Z",
+		Model:       "claude-opus-4-1",
+		InputTokens: 1_000_000,
+	}}
+	requireNoError(t, d.ReplaceSessionUsage("s1", first), "first replace")
+	second := []TokenUsage{{
+		Timestamp:       "2024-06-01T09:00:00Z",
+		Model:           "claude-opus-4-5",
+		CacheReadTokens: 2_000_000,
+	}}
+	requireNoError(t, d.ReplaceSessionUsage("s1", second), "second replace")
+
+	got, err := d.GetAnalyticsCost(ctx, f, DefaultModelPrices)
+	requireNoError(t, err, "GetAnalyticsCost")
+	if len(got.Models) != 1 || got.Models["claude-opus-4-5"] == nil {
+		t.Fatalf("models = %v, want only claude-opus-4-5", got.Models)
+	}
+	// Opus 4.5 cache reads cost $0.50/M, not Opus 4's $1.50/M.
+	if got.TotalUSD != 1.0 {
+		t.Errorf("TotalUSD = %v, want 1", got.TotalUSD)
+	}
+
+	// Responses are bucketed by the filter time zone's date.
+	f.Timezone = "America/Los_Angeles"
+	f.From, f.To = "2024-06-01", "2024-06-01"
+	got, err = d.GetAnalyticsCost(ctx, f, DefaultModelPrices)
+	requireNoError(t, err, "GetAnalyticsCost")
+	if got.TotalUSD != 1.0 {
+		t.Errorf("LA TotalUSD = %v, want 1", got.TotalUSD)
+	}
+	f.Timezone = "Asia/Tokyo"
+	f.From, f.To = "2024-05-31", "2024-05-31"
+	got, err = d.GetAnalyticsCost(ctx, f, DefaultModelPrices)
+	requireNoError(t, err, "GetAnalyticsCost")
+	if got.TotalUSD != 0 {
+		t.Errorf("Tokyo TotalUSD = %v, want 0", got.TotalUSD)
+	}
+}
+
+func TestGetAnalyticsCost_ConfiguredPrices(t *testing.T) {
+	d := testDB(t)
+	ctx := context.Background()
+	insertSession(t, d, "s1", "my-app")
+	f := AnalyticsFilter{From: "2024-06-01", To: "2024-06-30"}
+	usage := []TokenUsage{{
+		Timestamp:   "2024-06-01T09:00:00Z",
+		Model:       "claude-sonnet-4-5-20250929",
+		InputTokens: 1_000_000,
+	}}
+	requireNoError(t, d.ReplaceSessionUsage("s1", usage), "replace")
+
+	// An earlier entry wins over the defaults behind it.
+	prices := append([]ModelPrice{
+		{Model: "claude-sonnet-4-5", Input: 2},
+	}, DefaultModelPrices...)
+	got, err := d.GetAnalyticsCost(ctx, f, prices)
+	requireNoError(t, err, "GetAnalyticsCost")
+	if got.TotalUSD != 2.0 {
+		t.Errorf("TotalUSD = %v, want 2", got.TotalUSD)
+	}
+
+	got, err = d.GetAnalyticsCost(ctx, f, nil)
+	requireNoError(t, err, "GetAnalyticsCost")
+	mc := got.Models["claude-sonnet-4-5-20250929"]
+	if got.TotalUSD != 0 || mc == nil || mc.Priced {
+		t.Errorf("without prices: total = %v, model = %+v",
+			got.TotalUSD, mc)
+	}
+}
@@ -232,7 +232,11 @@ func parseLinear(
 		File:             fileInfo,
 	}
 
-	return []ParseResult{{Session: sess, Messages: messages}}, nil
+	return []ParseResult{{
+		Session:  sess,
+		Messages: messages,
+		Usage:    extractUsage(entries),
+	}}, nil
 }
 
 // parseDAG builds a parent->children adjacency map and walks the
@@ -410,6 +414,7 @@ func parseDAG(
 		results = append(results, ParseResult{
 			Session:  sess,
 			Messages: messages,
+			Usage:    extractUsage(branchEntries),
 		})
 	}
 
@@ -497,6 +502,44 @@ func extractMessages(entries []dagEntry) (
 	return messages, startedAt, endedAt
 }
 
+// extractUsage collects the token usage of the assistant
+// entries. Claude Code writes one entry per content block of a
+// response, each repeating the response's usage, so entries are
+// folded by message id and the last one, which carries the final
+// output count, wins.
+func extractUsage(entries []dagEntry) []ParsedUsage {
+	var usage []ParsedUsage
+	seen := make(map[string]int)
+	for _, e := range entries {
+		if e.entryType != "assistant" {
+			continue
+		}
+		u := gjson.Get(e.line, "message.usage")
+		if !u.Exists() {
+			continue
+		}
+		parsed := ParsedUsage{
+			Timestamp:           e.timestamp,
+			Model:               gjson.Get(e.line, "message.model").Str,
+			InputTokens:         int(u.Get("input_tokens").Int()),
+			OutputTokens:        int(u.Get("output_tokens").Int()),
+			CacheCreationTokens: int(u.Get("cache_creation_input_tokens").Int()),
+			CacheReadTokens:     int(u.Get("cache_read_input_tokens").Int()),
+		}
+		id := gjson.Get(e.line, "message.id").Str
+		if i, ok := seen[id]; ok && id != "" {
+			parsed.Timestamp = usage[i].Timestamp
+			usage[i] = parsed
+			continue
+		}
+		if id != "" {
+			seen[id] = len(usage)
+		}
+		usage = append(usage, parsed)
+	}
+	return usage
+}
+
 // annotateSubagentSessions sets SubagentSessionID on Task tool calls
 // whose ToolUseID appears in the subagentMap.
 func annotateSubagentSessions(
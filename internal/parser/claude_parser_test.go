@@ -3,7 +3,9 @@ package parser
 import (
 	"os"
 	"path/filepath"
+	"strconv"
 	"testing"
+	"time"
 
 	"github.com/stretchr/testify/assert"
 	"github.com/stretchr/testify/require"
@@ -198,6 +200,46 @@ func TestParseClaudeSession_ParentSessionID(t *testing.T) {
 	})
 }
 
+func TestParseClaudeSession_Usage(t *testing.T) {
+	assistant := func(id, ts string, output int) string {
+		return `{"type":"assistant","timestamp":"` + ts + `",` +
+			`"message":{"id":"` + id + `","model":"claude-sonnet-4-5",` +
+			`"content":[{"type":"text","text":"ok"}],` +
+			`"usage":{"input_tokens":10,"output_tokens":` +
+			strconv.Itoa(output) + `,` +
+			`"cache_creation_input_tokens":100,` +
+			`"cache_read_input_tokens":1000}}}`
+	}
+	content := testjsonl.JoinJSONL(
+		testjsonl.ClaudeUserJSON("hello", tsZero),
+		// Two entries of one streamed response repeat its usage.
+		assistant("msg_1", tsZeroS1, 5),
+		assistant("msg_1", tsZeroS2, 40),
+		assistant("msg_2", tsZeroS2, 7),
+		testjsonl.ClaudeAssistantJSON("no usage", tsZeroS2),
+	)
+	path := createTestFile(t, "test.jsonl", content)
+	results, err := ParseClaudeSession(path, "my_app", "local")
+	require.NoError(t, err)
+	require.Len(t, results, 1)
+
+	usage := results[0].Usage
+	require.Len(t, usage, 2)
+	// The folded response keeps the time of its first entry.
+	wantTS, err := time.Parse(time.RFC3339, tsZeroS1)
+	require.NoError(t, err)
+	assert.True(t, usage[0].Timestamp.Equal(wantTS))
+	usage[0].Timestamp = time.Time{}
+	assert.Equal(t, ParsedUsage{
+		Model:               "claude-sonnet-4-5",
+		InputTokens:         10,
+		OutputTokens:        40,
+		CacheCreationTokens: 100,
+		CacheReadTokens:     1000,
+	}, usage[0])
+	assert.Equal(t, 7, usage[1].OutputTokens)
+}
+
 func loadFixture(t *testing.T, name string) string {
 	t.Helper()
 	path := filepath.Join("testdata", name)
@@ -266,10 +266,22 @@ type ParsedMessage struct {
 	ToolResults   []ParsedToolResult
 }
 
-// ParseResult pairs a parsed session with its messages.
+// ParsedUsage holds the token usage of one model response.
+type ParsedUsage struct {
+	Timestamp           time.Time
+	Model               string
+	InputTokens         int
+	OutputTokens        int
+	CacheCreationTokens int
+	CacheReadTokens     int
+}
+
+// ParseResult pairs a parsed session with its messages and the
+// token usage of its model responses, when the agent records it.
 type ParseResult struct {
 	Session  ParsedSession
 	Messages []ParsedMessage
+	Usage    []ParsedUsage
 }
 
 // InferRelationshipTypes sets RelationshipType on results that have
@@ -143,6 +143,32 @@ func (s *Server) handleAnalyticsSummary(
 	writeJSON(w, http.StatusOK, result)
 }
 
+func (s *Server) handleAnalyticsCost(
+	w http.ResponseWriter, r *http.Request,
+) {
+	f, ok := parseAnalyticsFilter(w, r)
+	if !ok {
+		return
+	}
+
+	prices := append(
+		append([]db.ModelPrice{}, s.cfg.ModelPrices...),
+		db.DefaultModelPrices...,
+	)
+	result, err := s.db.GetAnalyticsCost(r.Context(), f, prices)
+	if err != nil {
+		if handleContextError(w, err) {
+			return
+		}
+		log.Printf("analytics error: %v", err)
+		writeError(w, http.StatusInternalServerError,
+			"internal server error")
+		return
+	}
+
+	writeJSON(w, http.StatusOK, result)
+}
+
 func (s *Server) handleAnalyticsActivity(
 	w http.ResponseWriter, r *http.Request,
 ) {
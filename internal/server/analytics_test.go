@@ -7,6 +7,7 @@ import (
 	"strings"
 	"testing"
 
+	"github.com/wesm/agentsview/internal/config"
 	"github.com/wesm/agentsview/internal/db"
 	"github.com/wesm/agentsview/internal/dbtest"
 )
@@ -153,6 +154,91 @@ func TestAnalyticsSummary(t *testing.T) {
 	})
 }
 
+func TestAnalyticsCost(t *testing.T) {
+	te := setup(t)
+	seedAnalyticsEnv(t, te)
+
+	err := te.db.ReplaceSessionUsage("a1", []db.TokenUsage{
+		{
+			Timestamp:    "2024-06-01T09:00:00Z",
+			Model:        "claude-sonnet-4-5-20250929",
+			InputTokens:  1_000_000,
+			OutputTokens: 100_000,
+		},
+		{
+			Timestamp:    "2024-06-10T09:00:00Z",
+			Model:        "claude-sonnet-4-5-20250929",
+			InputTokens:  1_000_000,
+			OutputTokens: 1_000_000,
+		},
+		{
+			Timestamp:   "2024-06-02T09:00:00Z",
+			Model:       "<synthetic>",
+			InputTokens: 500,
+		},
+	})
+	if err != nil {
+		t.Fatalf("seeding token usage: %v", err)
+	}
+
+	w := te.get(t, buildURLWithRange("cost", nil))
+	assertStatus(t, w, http.StatusOK)
+	resp := decode[db.AnalyticsCost](t, w)
+	// $3/M input and $15/M output for Sonnet.
+	if resp.TotalUSD < 4.4999 || resp.TotalUSD > 4.5001 {
+		t.Errorf("TotalUSD = %v, want 4.5", resp.TotalUSD)
+	}
+	sonnet := resp.Models["claude-sonnet-4-5-20250929"]
+	if sonnet == nil || !sonnet.Priced ||
+		sonnet.InputTokens != 1_000_000 {
+		t.Errorf("sonnet cost = %+v", sonnet)
+	}
+	unpriced := resp.Models["<synthetic>"]
+	if unpriced == nil || unpriced.Priced ||
+		unpriced.TotalUSD != 0 || unpriced.InputTokens != 500 {
+		t.Errorf("unpriced cost = %+v", unpriced)
+	}
+
+	w = te.get(t, buildURLWithRange("cost",
+		map[string]string{"project": "beta"}))
+	assertStatus(t, w, http.StatusOK)
+	if got := decode[db.AnalyticsCost](t, w).TotalUSD; got != 0 {
+		t.Errorf("beta TotalUSD = %v, want 0", got)
+	}
+}
+
+func TestAnalyticsCost_ConfiguredPrices(t *testing.T) {
+	te := setup(t, func(c *config.Config) {
+		c.ModelPrices = []db.ModelPrice{
+			{Model: "claude-sonnet-4-5", Input: 2, Output: 10},
+		}
+	})
+	seedAnalyticsEnv(t, te)
+
+	err := te.db.ReplaceSessionUsage("a1", []db.TokenUsage{{
+		Timestamp:    "2024-06-01T09:00:00Z",
+		Model:        "claude-sonnet-4-5-20250929",
+		InputTokens:  1_000_000,
+		OutputTokens: 100_000,
+	}, {
+		Timestamp:   "2024-06-02T09:00:00Z",
+		Model:       "claude-haiku-4-5-20251001",
+		InputTokens: 1_000_000,
+	}})
+	if err != nil {
+		t.Fatalf("seeding token usage: %v", err)
+	}
+
+	w := te.get(t, buildURLWithRange("cost", nil))
+	assertStatus(t, w, http.StatusOK)
+	resp := decode[db.AnalyticsCost](t, w)
+	// The configured $2/M + $10/M for Sonnet, and the default $1/M
+	// input for Haiku.
+	if resp.TotalUSD < 3.9999 || resp.TotalUSD > 4.0001 {
+		t.Errorf("TotalUSD = %v, want 4", resp.TotalUSD)
+	}
+}
+
 func TestAnalyticsSummary_DateValidation(t *testing.T) {
 	te := setup(t)
 
@@ -199,6 +285,7 @@ func TestAnalyticsErrorRedaction(t *testing.T) {
 
 	endpoints := []string{
 		"summary",
+		"cost",
 		"activity",
 		"heatmap",
 		"projects",
@@ -226,6 +313,7 @@ func TestAnalyticsEndpoints_DefaultParams(t *testing.T) {
 
 	endpoints := []string{
 		"summary",
+		"cost",
 		"activity",
 		"heatmap",
 		"projects",
@@ -0,0 +1,93 @@
+package server
+
+import (
+	"encoding/json"
+	"net/http"
+	"strings"
+	"unicode/utf8"
+
+	"github.com/wesm/agentsview/internal/db"
+)
+
+const (
+	maxAnnotationBody   = 10_000
+	maxAnnotationSource = 64
+)
+
+type createAnnotationRequest struct {
+	Body   string `json:"body"`
+	Source string `json:"source"`
+}
+
+func (s *Server) handleListAnnotations(
+	w http.ResponseWriter, r *http.Request,
+) {
+	id := r.PathValue("id")
+	annotations, err := s.db.ListAnnotations(r.Context(), id)
+	if err != nil {
+		if handleContextError(w, err) {
+			return
+		}
+		writeError(w, http.StatusInternalServerError, err.Error())
+		return
+	}
+	if annotations == nil {
+		annotations = []db.Annotation{}
+	}
+	writeJSON(w, http.StatusOK, map[string]any{
+		"annotations": annotations,
+	})
+}
+
+func (s *Server) handleCreateAnnotation(
+	w http.ResponseWriter, r *http.Request,
+) {
+	var req createAnnotationRequest
+	if err := json.NewDecoder(r.Body).Decode(&req); err != nil {
+		writeError(w, http.StatusBadRequest, "invalid JSON body")
+		return
+	}
+
+	body := strings.TrimSpace(req.Body)
+	if body == "" {
+		writeError(w, http.StatusBadRequest, "body is required")
+		return
+	}
+	if utf8.RuneCountInString(body) > maxAnnotationBody {
+		writeError(w, http.StatusBadRequest, "body is too long")
+		return
+	}
+	source := strings.TrimSpace(req.Source)
+	if source == "" {
+		source = "api"
+	}
+	if len(source) > maxAnnotationSource {
+		writeError(w, http.StatusBadRequest, "source is too long")
+		return
+	}
+
+	id := r.PathValue("id")
+	session, err := s.db.GetSession(r.Context(), id)
+	if err != nil {
+		if handleContextError(w, err) {
+			return
+		}
+		writeError(w, http.StatusInternalServerError, err.Error())
+		return
+	}
+	if session == nil {
+		writeError(w, http.StatusNotFound, "session not found")
+		return
+	}
+
+	annotation, err := s.db.InsertAnnotation(db.Annotation{
+		SessionID: id,
+		Body:      body,
+		Source:    source,
+	})
+	if err != nil {
+		writeError(w, http.StatusInternalServerError, err.Error())
+		return
+	}
+	writeJSON(w, http.StatusCreated, annotation)
+}
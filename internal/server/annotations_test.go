@@ -0,0 +1,101 @@
+package server_test
+
+import (
+	"net/http"
+	"strings"
+	"testing"
+
+	"github.com/wesm/agentsview/internal/db"
+)
+
+type listAnnotationsResponse struct {
+	Annotations []db.Annotation `json:"annotations"`
+}
+
+func TestCreateAnnotation(t *testing.T) {
+	te := setup(t)
+	te.seedSession(t, "s1", "my-app", 4)
+
+	w := te.post(t, "/api/v1/sessions/s1/annotations",
+		`{"body":"  check the retry loop  ","source":"desktop"}`)
+	assertStatus(t, w, http.StatusCreated)
+	created := decode[db.Annotation](t, w)
+	if created.ID <= 0 || created.SessionID != "s1" ||
+		created.Body != "check the retry loop" ||
+		created.Source != "desktop" {
+		t.Fatalf("unexpected annotation: %+v", created)
+	}
+
+	w = te.post(t, "/api/v1/sessions/s1/annotations",
+		`{"body":"no source given"}`)
+	assertStatus(t, w, http.StatusCreated)
+	if got := decode[db.Annotation](t, w).Source; got != "api" {
+		t.Errorf("default source = %q, want api", got)
+	}
+
+	w = te.get(t, "/api/v1/sessions/s1/annotations")
+	assertStatus(t, w, http.StatusOK)
+	list := decode[listAnnotationsResponse](t, w)
+	if len(list.Annotations) != 2 {
+		t.Fatalf("expected 2 annotations, got %d", len(list.Annotations))
+	}
+	if list.Annotations[0] != created {
+		t.Errorf("first annotation = %+v, want %+v",
+			list.Annotations[0], created)
+	}
+}
+
+func TestCreateAnnotation_Errors(t *testing.T) {
+	te := setup(t)
+	te.seedSession(t, "s1", "my-app", 4)
+
+	tests := []struct {
+		name       string
+		path       string
+		body       string
+		wantStatus int
+		wantError  string
+	}{
+		{
+			name:       "InvalidJSON",
+			path:       "/api/v1/sessions/s1/annotations",
+			body:       `{`,
+			wantStatus: http.StatusBadRequest,
+			wantError:  "invalid JSON body",
+		},
+		{
+			name:       "EmptyBody",
+			path:       "/api/v1/sessions/s1/annotations",
+			body:       `{"body":"   "}`,
+			wantStatus: http.StatusBadRequest,
+			wantError:  "body is required",
+		},
+		{
+			name: "BodyTooLong",
+			path: "/api/v1/sessions/s1/annotations",
+			body: `{"body":"` +
+				strings.Repeat("x", 10_001) + `"}`,
+			wantStatus: http.StatusBadRequest,
+			wantError:  "body is too long",
+		},
+		{
+			name:       "UnknownSession",
+			path:       "/api/v1/sessions/missing/annotations",
+			body:       `{"body":"note"}`,
+			wantStatus: http.StatusNotFound,
+			wantError:  "session not found",
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			w := te.post(t, tt.path, tt.body)
+			assertStatus(t, w, tt.wantStatus)
+			assertErrorResponse(t, w, tt.wantError)
+		})
+	}
+
+	w := te.get(t, "/api/v1/sessions/s1/annotations")
+	assertStatus(t, w, http.StatusOK)
+	assertBodyContains(t, w, `"annotations":[]`)
+}
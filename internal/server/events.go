@@ -158,6 +158,42 @@ func (s *Server) handleWatchSession(
 	}
 }
 
+// handleEvents streams a sessions_changed event each time a
+// sync writes sessions, so clients such as the desktop shell can
+// wait for changes instead of polling.
+func (s *Server) handleEvents(
+	w http.ResponseWriter, r *http.Request,
+) {
+	stream, err := NewSSEStream(w)
+	if err != nil {
+		writeError(w, http.StatusInternalServerError,
+			"streaming not supported")
+		return
+	}
+
+	heartbeat := time.NewTicker(
+		pollInterval * heartbeatTicks,
+	)
+	defer heartbeat.Stop()
+
+	for {
+		changed := s.engine.Changed()
+		select {
+		case <-r.Context().Done():
+			return
+		case <-changed:
+			if !stream.Send("sessions_changed", "") {
+				return
+			}
+		case <-heartbeat.C:
+			if !stream.Send("heartbeat",
+				time.Now().Format(time.RFC3339)) {
+				return
+			}
+		}
+	}
+}
+
 func (s *Server) handleTriggerSync(
 	w http.ResponseWriter, r *http.Request,
 ) {
@@ -1,11 +1,18 @@
 package server
 
 import (
+	"crypto/subtle"
 	"encoding/json"
+	"net"
 	"net/http"
+	"strings"
 	"time"
 )
 
+// authCookie holds the auth token for browsers that signed in
+// with ?token=.
+const authCookie = "agentsview_token"
+
 // jsonError is the standard JSON error response.
 type jsonError struct {
 	Error string `json:"error"`
@@ -47,6 +54,58 @@ func (s *Server) withTimeout(
 	)
 }
 
+// authMiddleware requires token from clients that are not on this
+// machine, as a bearer token, the auth cookie, or a ?token= query
+// parameter that sets the cookie so a LAN browser only has to
+// open one link. Loopback clients, including the desktop app,
+// are not asked for it. An empty token turns the check off.
+func authMiddleware(token string, next http.Handler) http.Handler {
+	if token == "" {
+		return next
+	}
+	matches := func(got string) bool {
+		return subtle.ConstantTimeCompare([]byte(got), []byte(token)) == 1
+	}
+	return http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if isLoopbackRemote(r.RemoteAddr) {
+			next.ServeHTTP(w, r)
+			return
+		}
+		if got := r.URL.Query().Get("token"); got != "" && matches(got) {
+			http.SetCookie(w, &http.Cookie{
+				Name:     authCookie,
+				Value:    token,
+				Path:     "/",
+				HttpOnly: true,
+				SameSite: http.SameSiteStrictMode,
+			})
+			next.ServeHTTP(w, r)
+			return
+		}
+		if c, err := r.Cookie(authCookie); err == nil && matches(c.Value) {
+			next.ServeHTTP(w, r)
+			return
+		}
+		bearer, ok := strings.CutPrefix(r.Header.Get("Authorization"), "Bearer ")
+		if ok && matches(bearer) {
+			next.ServeHTTP(w, r)
+			return
+		}
+		writeError(w, http.StatusUnauthorized, "authentication required")
+	})
+}
+
+// isLoopbackRemote reports whether a request's RemoteAddr is on
+// this machine.
+func isLoopbackRemote(remoteAddr string) bool {
+	host, _, err := net.SplitHostPort(remoteAddr)
+	if err != nil {
+		return false
+	}
+	ip := net.ParseIP(host)
+	return ip != nil && ip.IsLoopback()
+}
+
 // contentTypeWrapper intercepts WriteHeader to set Content-Type on specific status codes.
 type contentTypeWrapper struct {
 	http.ResponseWriter
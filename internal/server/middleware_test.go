@@ -187,3 +187,53 @@ func TestCORSMiddlewareMergesVaryHeader(t *testing.T) {
 		t.Fatalf("expected Vary to include Origin, got %q", vary)
 	}
 }
+
+func TestAuthMiddleware(t *testing.T) {
+	t.Parallel()
+
+	const token = "0123456789abcdef"
+	h := authMiddleware(token, http.HandlerFunc(
+		func(w http.ResponseWriter, r *http.Request) {
+			w.WriteHeader(http.StatusOK)
+		},
+	))
+
+	tests := []struct {
+		name       string
+		remote     string
+		target     string
+		header     string
+		cookie     string
+		wantStatus int
+		wantCookie bool
+	}{
+		{"LoopbackNeedsNoToken", "127.0.0.1:5000", "/api/v1/sessions", "", "", http.StatusOK, false},
+		{"IPv6LoopbackNeedsNoToken", "[::1]:5000", "/api/v1/sessions", "", "", http.StatusOK, false},
+		{"LANWithoutToken", "192.168.1.20:5000", "/api/v1/sessions", "", "", http.StatusUnauthorized, false},
+		{"LANWrongBearer", "192.168.1.20:5000", "/api/v1/sessions", "Bearer nope", "", http.StatusUnauthorized, false},
+		{"LANBearer", "192.168.1.20:5000", "/api/v1/sessions", "Bearer " + token, "", http.StatusOK, false},
+		{"LANCookie", "192.168.1.20:5000", "/", "", token, http.StatusOK, false},
+		{"LANQuerySetsCookie", "192.168.1.20:5000", "/?token=" + token, "", "", http.StatusOK, true},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			req := httptest.NewRequest(http.MethodGet, tt.target, nil)
+			req.RemoteAddr = tt.remote
+			if tt.header != "" {
+				req.Header.Set("Authorization", tt.header)
+			}
+			if tt.cookie != "" {
+				req.AddCookie(&http.Cookie{Name: authCookie, Value: tt.cookie})
+			}
+			rec := httptest.NewRecorder()
+			h.ServeHTTP(rec, req)
+			if rec.Code != tt.wantStatus {
+				t.Errorf("status = %d, want %d", rec.Code, tt.wantStatus)
+			}
+			gotCookie := strings.Contains(rec.Header().Get("Set-Cookie"), authCookie)
+			if gotCookie != tt.wantCookie {
+				t.Errorf("set cookie = %v, want %v", gotCookie, tt.wantCookie)
+			}
+		})
+	}
+}
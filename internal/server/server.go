@@ -2,12 +2,14 @@ package server
 
 import (
 	"context"
+	"errors"
 	"fmt"
 	"io/fs"
 	"log"
 	"net"
 	"net/http"
 	"net/url"
+	"os"
 	"strconv"
 	"strings"
 	gosync "sync"
@@ -130,11 +132,18 @@ func (s *Server) routes() {
 	s.mux.Handle(
 		"POST /api/v1/sessions/{id}/publish", s.withTimeout(s.handlePublishSession),
 	)
+	s.mux.Handle(
+		"GET /api/v1/sessions/{id}/annotations", s.withTimeout(s.handleListAnnotations),
+	)
+	s.mux.Handle(
+		"POST /api/v1/sessions/{id}/annotations", s.withTimeout(s.handleCreateAnnotation),
+	)
 	s.mux.Handle(
 		"POST /api/v1/sessions/upload", s.withTimeout(s.handleUploadSession),
 	)
 	s.mux.Handle("GET /api/v1/analytics/summary", s.withTimeout(s.handleAnalyticsSummary))
 	s.mux.Handle("GET /api/v1/analytics/activity", s.withTimeout(s.handleAnalyticsActivity))
+	s.mux.Handle("GET /api/v1/analytics/cost", s.withTimeout(s.handleAnalyticsCost))
 	s.mux.Handle("GET /api/v1/analytics/heatmap", s.withTimeout(s.handleAnalyticsHeatmap))
 	s.mux.Handle("GET /api/v1/analytics/projects", s.withTimeout(s.handleAnalyticsProjects))
 	s.mux.Handle("GET /api/v1/analytics/hour-of-week", s.withTimeout(s.handleAnalyticsHourOfWeek))
@@ -154,6 +163,8 @@ func (s *Server) routes() {
 	s.mux.Handle("GET /api/v1/agents", s.withTimeout(s.handleListAgents))
 	s.mux.Handle("GET /api/v1/stats", s.withTimeout(s.handleGetStats))
 	s.mux.Handle("GET /api/v1/version", s.withTimeout(s.handleGetVersion))
+	// SSE: long-lived like watch, so no timeout either.
+	s.mux.HandleFunc("GET /api/v1/events", s.handleEvents)
 	s.mux.HandleFunc("POST /api/v1/sync", s.handleTriggerSync)
 	s.mux.HandleFunc("POST /api/v1/resync", s.handleTriggerResync)
 	s.mux.Handle("GET /api/v1/sync/status", s.withTimeout(s.handleSyncStatus))
@@ -222,10 +233,13 @@ func (s *Server) Handler() http.Handler {
 	if bindAll {
 		bindAllIPs = localInterfaceIPs()
 	}
-	return hostCheckMiddleware(
-		allowedHosts, bindAll, s.cfg.Port, bindAllIPs,
-		corsMiddleware(
-			allowedOrigins, bindAll, s.cfg.Port, bindAllIPs, logMiddleware(s.mux),
+	return authMiddleware(
+		s.cfg.AuthToken,
+		hostCheckMiddleware(
+			allowedHosts, bindAll, s.cfg.Port, bindAllIPs,
+			corsMiddleware(
+				allowedOrigins, bindAll, s.cfg.Port, bindAllIPs, logMiddleware(s.mux),
+			),
 		),
 	)
 }
@@ -432,7 +446,7 @@ func localInterfaceIPs() map[string]bool {
 
 // ListenAndServe starts the HTTP server.
 func (s *Server) ListenAndServe() error {
-	addr := fmt.Sprintf("%s:%d", s.cfg.Host, s.cfg.Port)
+	addr := net.JoinHostPort(s.cfg.Host, strconv.Itoa(s.cfg.Port))
 	srv := &http.Server{
 		Addr:        addr,
 		Handler:     s.Handler(),
@@ -457,6 +471,60 @@ func (s *Server) Shutdown(ctx context.Context) error {
 	return srv.Shutdown(ctx)
 }
 
+// ListenAvailable binds the first free port of the 100 starting at
+// start on host and returns the open listener with its port. Unlike
+// FindAvailablePort it never releases the port between checking and
+// serving, so another process cannot take it in between. A start of
+// 0 binds an ephemeral port. If every port is taken, the error is
+// the one for start.
+func ListenAvailable(host string, start int) (net.Listener, int, error) {
+	if start == 0 {
+		ln, err := net.Listen("tcp", net.JoinHostPort(host, "0"))
+		if err != nil {
+			return nil, 0, err
+		}
+		return ln, ln.Addr().(*net.TCPAddr).Port, nil
+	}
+	var firstErr error
+	for port := start; port < start+100 && port <= 65535; port++ {
+		addr := net.JoinHostPort(host, strconv.Itoa(port))
+		ln, err := net.Listen("tcp", addr)
+		if err == nil {
+			return ln, port, nil
+		}
+		if firstErr == nil {
+			firstErr = err
+		}
+	}
+	return nil, 0, firstErr
+}
+
+// ListenSocket serves on a Unix socket at path instead of TCP, so
+// only processes that can open path reach the server. A socket
+// left behind by an earlier run is replaced; any other file there
+// is an error. The socket is made private to the current user.
+func ListenSocket(path string) (net.Listener, error) {
+	if info, err := os.Lstat(path); err == nil {
+		if info.Mode()&fs.ModeSocket == 0 {
+			return nil, fmt.Errorf("%s exists and is not a socket", path)
+		}
+		if err := os.Remove(path); err != nil {
+			return nil, err
+		}
+	} else if !errors.Is(err, fs.ErrNotExist) {
+		return nil, err
+	}
+	ln, err := net.Listen("unix", path)
+	if err != nil {
+		return nil, err
+	}
+	if err := os.Chmod(path, 0o600); err != nil {
+		ln.Close()
+		return nil, err
+	}
+	return ln, nil
+}
+
 // FindAvailablePort finds an available port starting from the
 // given port, binding to the specified host.
 func FindAvailablePort(host string, start int) int {
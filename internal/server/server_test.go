@@ -13,6 +13,7 @@ import (
 	"net/http/httptest"
 	"os"
 	"path/filepath"
+	"runtime"
 	"strings"
 	stdlibsync "sync"
 	"testing"
@@ -2081,6 +2082,42 @@ func TestWatchSession_FileDisappearAndResolve(t *testing.T) {
 	<-done
 }
 
+func TestEvents_SessionsChanged(t *testing.T) {
+	te := setup(t)
+
+	ctx, cancel := context.WithTimeout(
+		context.Background(), 5*time.Second,
+	)
+	defer cancel()
+
+	req := httptest.NewRequest(
+		http.MethodGet, "/api/v1/events", nil,
+	).WithContext(ctx)
+	w := &flushRecorder{ResponseRecorder: httptest.NewRecorder()}
+
+	done := make(chan struct{})
+	go func() {
+		te.handler.ServeHTTP(w, req)
+		close(done)
+	}()
+
+	time.Sleep(200 * time.Millisecond)
+
+	te.writeSessionFile(t, "events-proj", "events-sess.jsonl",
+		testjsonl.NewSessionBuilder().
+			AddClaudeUser(tsZero, "hello"),
+	)
+	syncReq := httptest.NewRequest(
+		http.MethodPost, "/api/v1/sync", nil,
+	)
+	syncW := &flushRecorder{ResponseRecorder: httptest.NewRecorder()}
+	te.handler.ServeHTTP(syncW, syncReq)
+
+	te.waitForSSEEvent(t, w, "sessions_changed", 5*time.Second)
+	cancel()
+	<-done
+}
+
 func TestTriggerSync_SSEEvents(t *testing.T) {
 	te := setup(t)
 
@@ -2517,3 +2554,78 @@ func TestFindAvailablePortSkipsOccupied(t *testing.T) {
 	}
 	ln2.Close()
 }
+
+func TestListenAvailableKeepsPortBound(t *testing.T) {
+	ln, err := net.Listen("tcp", "127.0.0.1:0")
+	if err != nil {
+		t.Fatalf("listen: %v", err)
+	}
+	defer ln.Close()
+
+	occupied := ln.Addr().(*net.TCPAddr).Port
+
+	got, port, err := server.ListenAvailable("127.0.0.1", occupied)
+	if err != nil {
+		t.Fatalf("ListenAvailable: %v", err)
+	}
+	defer got.Close()
+	if port == occupied {
+		t.Errorf("ListenAvailable bound occupied port %d", occupied)
+	}
+	if addrPort := got.Addr().(*net.TCPAddr).Port; addrPort != port {
+		t.Errorf("listener on %d, reported %d", addrPort, port)
+	}
+
+	// The returned port stays bound until the listener closes.
+	if ln2, err := net.Listen(
+		"tcp", fmt.Sprintf("127.0.0.1:%d", port),
+	); err == nil {
+		ln2.Close()
+		t.Errorf("port %d was released", port)
+	}
+
+	eph, ephPort, err := server.ListenAvailable("127.0.0.1", 0)
+	if err != nil {
+		t.Fatalf("ListenAvailable(0): %v", err)
+	}
+	defer eph.Close()
+	if ephPort == 0 {
+		t.Error("ListenAvailable(0) reported port 0")
+	}
+}
+
+func TestListenSocketReplacesStaleSocket(t *testing.T) {
+	if runtime.GOOS == "windows" {
+		t.Skip("the desktop app uses socket transport on Unix only")
+	}
+	path := filepath.Join(t.TempDir(), "backend.sock")
+	stale, err := server.ListenSocket(path)
+	if err != nil {
+		t.Fatalf("ListenSocket: %v", err)
+	}
+	// Closing a unix listener removes its file; keep it to
+	// simulate a crashed run.
+	stale.(*net.UnixListener).SetUnlinkOnClose(false)
+	stale.Close()
+
+	ln, err := server.ListenSocket(path)
+	if err != nil {
+		t.Fatalf("ListenSocket over stale socket: %v", err)
+	}
+	defer ln.Close()
+	info, err := os.Stat(path)
+	if err != nil {
+		t.Fatalf("stat socket: %v", err)
+	}
+	if perm := info.Mode().Perm(); perm != 0o600 {
+		t.Errorf("socket mode = %o, want 600", perm)
+	}
+
+	plain := filepath.Join(t.TempDir(), "not-a-socket")
+	if err := os.WriteFile(plain, nil, 0o600); err != nil {
+		t.Fatalf("write file: %v", err)
+	}
+	if _, err := server.ListenSocket(plain); err == nil {
+		t.Error("ListenSocket replaced a regular file")
+	}
+}
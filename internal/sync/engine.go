@@ -40,6 +40,9 @@ type Engine struct {
 	mu                      gosync.RWMutex
 	lastSync                time.Time
 	lastSyncStats           SyncStats
+	// changed is closed and replaced each time a sync writes
+	// sessions; see Changed.
+	changed chan struct{}
 	// skipCache tracks paths that should be skipped on
 	// subsequent syncs, keyed by path with the file mtime
 	// at time of caching. Covers parse errors and
@@ -72,6 +75,7 @@ func NewEngine(
 		agentDirs:               dirs,
 		machine:                 cfg.Machine,
 		blockedResultCategories: blockedCategorySet(cfg.BlockedResultCategories),
+		changed:                 make(chan struct{}),
 		skipCache:               skipCache,
 	}
 }
@@ -109,6 +113,23 @@ func (e *Engine) LastSyncStats() SyncStats {
 	return e.lastSyncStats
 }
 
+// Changed returns a channel that is closed the next time a sync
+// writes sessions. Callers call Changed again after each close to
+// keep watching.
+func (e *Engine) Changed() <-chan struct{} {
+	e.mu.RLock()
+	defer e.mu.RUnlock()
+	return e.changed
+}
+
+// notifyChanged wakes everyone waiting on Changed.
+func (e *Engine) notifyChanged() {
+	e.mu.Lock()
+	close(e.changed)
+	e.changed = make(chan struct{})
+	e.mu.Unlock()
+}
+
 type syncJob struct {
 	processResult
 	path string
@@ -142,6 +163,7 @@ func (e *Engine) SyncPaths(paths []string) {
 		log.Printf(
 			"sync: %d file(s) updated", stats.Synced,
 		)
+		e.notifyChanged()
 	}
 }
 
@@ -649,6 +671,27 @@ func (e *Engine) ResyncAll(
 		time.Since(tInsights).Round(time.Millisecond),
 	)
 
+	// Annotations are user-written like insights and must
+	// survive the swap.
+	if err := newDB.CopyAnnotationsFrom(origPath); err != nil {
+		log.Printf("resync: copy annotations: %v", err)
+		stats.Aborted = true
+		stats.Warnings = append(stats.Warnings,
+			"annotations copy failed, aborting swap: "+
+				err.Error(),
+		)
+		newDB.Close()
+		removeTempDB(tempPath)
+		restoreSkipCache()
+		if rerr := origDB.Reopen(); rerr != nil {
+			log.Printf("resync: recovery reopen: %v", rerr)
+		}
+		e.mu.Lock()
+		e.lastSyncStats = stats
+		e.mu.Unlock()
+		return stats
+	}
+
 	// Copy orphaned sessions (source files gone) from the
 	// old DB so archived data is preserved. Failure aborts
 	// the swap to avoid losing archived sessions.
@@ -710,6 +753,7 @@ func (e *Engine) ResyncAll(
 	e.mu.Lock()
 	e.lastSyncStats = stats
 	e.mu.Unlock()
+	e.notifyChanged()
 
 	return stats
 }
@@ -727,6 +771,14 @@ func removeWAL(path string) {
 	os.Remove(path + "-shm")
 }
 
+// Stop waits for a running sync to finish and keeps later
+// ones from starting, so the database can be closed without
+// cutting a write short. The engine must not be used after
+// Stop.
+func (e *Engine) Stop() {
+	e.syncMu.Lock()
+}
+
 // SyncAll discovers and syncs all session files from all agents.
 func (e *Engine) SyncAll(onProgress ProgressFunc) SyncStats {
 	e.syncMu.Lock()
@@ -830,6 +882,9 @@ func (e *Engine) syncAllLocked(
 	e.lastSync = time.Now()
 	e.lastSyncStats = stats
 	e.mu.Unlock()
+	if stats.Synced > 0 {
+		e.notifyChanged()
+	}
 	return stats
 }
 
@@ -973,8 +1028,9 @@ func (e *Engine) collectAndBatch(
 
 		for _, pr := range r.results {
 			pending = append(pending, pendingWrite{
-				sess: pr.Session,
-				msgs: pr.Messages,
+				sess:  pr.Session,
+				msgs:  pr.Messages,
+				usage: pr.Usage,
 			})
 		}
 
@@ -1439,8 +1495,9 @@ func validateCursorContainment(
 }
 
 type pendingWrite struct {
-	sess parser.ParsedSession
-	msgs []parser.ParsedMessage
+	sess  parser.ParsedSession
+	msgs  []parser.ParsedMessage
+	usage []parser.ParsedUsage
 }
 
 func (e *Engine) writeBatch(batch []pendingWrite) {
@@ -1454,6 +1511,7 @@ func (e *Engine) writeBatch(batch []pendingWrite) {
 			continue
 		}
 		e.writeMessages(pw.sess.ID, msgs)
+		e.writeUsage(pw)
 	}
 }
 
@@ -1521,6 +1579,31 @@ func (e *Engine) writeSessionFull(pw pendingWrite) {
 			pw.sess.ID, err,
 		)
 	}
+	e.writeUsage(pw)
+}
+
+// writeUsage replaces the session's recorded token usage with
+// the usage parsed from its file.
+func (e *Engine) writeUsage(pw pendingWrite) {
+	usage := make([]db.TokenUsage, len(pw.usage))
+	for i, u := range pw.usage {
+		usage[i] = db.TokenUsage{
+			Timestamp:           timeutil.Format(u.Timestamp),
+			Model:               u.Model,
+			InputTokens:         u.InputTokens,
+			OutputTokens:        u.OutputTokens,
+			CacheCreationTokens: u.CacheCreationTokens,
+			CacheReadTokens:     u.CacheReadTokens,
+		}
+	}
+	if err := e.db.ReplaceSessionUsage(
+		pw.sess.ID, usage,
+	); err != nil {
+		log.Printf(
+			"replace token usage for %s: %v",
+			pw.sess.ID, err,
+		)
+	}
 }
 
 // toDBSession converts a pendingWrite to a db.Session.
@@ -1698,9 +1781,14 @@ func (e *Engine) SyncSingleSession(sessionID string) error {
 
 	for _, pr := range res.results {
 		e.writeSessionFull(
-			pendingWrite{sess: pr.Session, msgs: pr.Messages},
+			pendingWrite{
+				sess:  pr.Session,
+				msgs:  pr.Messages,
+				usage: pr.Usage,
+			},
 		)
 	}
+	e.notifyChanged()
 	return nil
 }
 
@@ -1753,6 +1841,7 @@ func (e *Engine) syncSingleOpenCode(
 		e.writeSessionFull(
 			pendingWrite{sess: *sess, msgs: msgs},
 		)
+		e.notifyChanged()
 		return nil
 	}
 
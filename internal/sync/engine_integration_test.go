@@ -1043,6 +1043,37 @@ func TestSyncPathsStatsUpdated(t *testing.T) {
 	}
 }
 
+func TestSyncPathsNotifiesChanged(t *testing.T) {
+	env := setupTestEnv(t)
+
+	changed := env.engine.Changed()
+	env.engine.SyncPaths([]string{
+		filepath.Join(env.claudeDir, "proj", "missing.jsonl"),
+	})
+	select {
+	case <-changed:
+		t.Fatal("Changed closed without any session written")
+	default:
+	}
+
+	content := testjsonl.NewSessionBuilder().
+		AddClaudeUser(tsZero, "Hello").
+		String()
+	path := env.writeClaudeSession(
+		t, "proj", "changed-test.jsonl", content,
+	)
+	env.engine.SyncPaths([]string{path})
+
+	select {
+	case <-changed:
+	default:
+		t.Fatal("Changed should close after SyncPaths writes")
+	}
+	if env.engine.Changed() == changed {
+		t.Error("Changed should return a fresh channel")
+	}
+}
+
 func TestSyncPathsClaudeParentSessionID(t *testing.T) {
 	env := setupTestEnv(t)
 
@@ -1505,6 +1536,46 @@ func TestSyncEngineConcurrentSerialization(t *testing.T) {
 	wg.Wait()
 }
 
+// TestSyncEngineStopWaitsForSync verifies that Stop does
+// not return while a sync holds syncMu.
+func TestSyncEngineStopWaitsForSync(t *testing.T) {
+	env := setupTestEnv(t)
+	content := testjsonl.NewSessionBuilder().
+		AddClaudeUser(tsZero, "msg").
+		String()
+	env.writeClaudeSession(t, "proj", "stop.jsonl", content)
+
+	barrier := make(chan struct{})
+	syncAllEntered := make(chan struct{})
+	var syncOnce gosync.Once
+	go env.engine.SyncAll(func(_ sync.Progress) {
+		syncOnce.Do(func() {
+			close(syncAllEntered)
+			<-barrier
+		})
+	})
+	<-syncAllEntered
+
+	stopped := make(chan struct{})
+	go func() {
+		env.engine.Stop()
+		close(stopped)
+	}()
+
+	select {
+	case <-stopped:
+		t.Fatal("Stop returned while SyncAll held mutex")
+	case <-time.After(50 * time.Millisecond):
+	}
+
+	close(barrier)
+	select {
+	case <-stopped:
+	case <-time.After(5 * time.Second):
+		t.Fatal("Stop never returned after SyncAll finished")
+	}
+}
+
 // TestSyncEnginePostFilterCounts verifies that writeBatch
 // stores post-filter message counts (after pairAndFilter
 // removes empty user+tool_result messages), not the raw